@@ -4,8 +4,9 @@
 //! in the original source text, supporting both exact and fuzzy matching.
 
 use crate::{
-    data::{AlignmentStatus, CharInterval, Extraction},
+    data::{AlignmentStatus, CharInterval, Extraction, TokenInterval},
     exceptions::LangExtractResult,
+    tokenizer,
 };
 use std::cmp::min;
 
@@ -14,8 +15,11 @@ use std::cmp::min;
 pub struct AlignmentConfig {
     /// Enable fuzzy alignment when exact matching fails
     pub enable_fuzzy_alignment: bool,
-    /// Minimum overlap ratio for fuzzy alignment (0.0 to 1.0)
+    /// Minimum word coverage for fuzzy alignment (0.0 to 1.0); also a floor for
+    /// the Levenshtein pass
     pub fuzzy_alignment_threshold: f32,
+    /// Minimum normalized Levenshtein ratio for character-level fuzzy matches
+    pub levenshtein_alignment_threshold: f32,
     /// Accept partial exact matches (MATCH_LESSER status)
     pub accept_match_lesser: bool,
     /// Case-sensitive matching
@@ -29,6 +33,7 @@ impl Default for AlignmentConfig {
         Self {
             enable_fuzzy_alignment: true,
             fuzzy_alignment_threshold: 0.4, // Lower threshold for better fuzzy matching
+            levenshtein_alignment_threshold: 0.85, // Only near-identical spelling variants
             accept_match_lesser: true,
             case_sensitive: false,
             max_search_window: 100,
@@ -36,6 +41,140 @@ impl Default for AlignmentConfig {
     }
 }
 
+/// Matching mode for [`align_extractions`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlignmentMode {
+    /// Only accept exact (case-insensitive) substring matches
+    Exact,
+    /// Fall back to whitespace-normalized and word-window fuzzy matching
+    #[default]
+    Fuzzy,
+}
+
+impl AlignmentMode {
+    /// Alignment configuration used for this mode
+    pub fn config(self) -> AlignmentConfig {
+        match self {
+            AlignmentMode::Exact => AlignmentConfig {
+                enable_fuzzy_alignment: false,
+                accept_match_lesser: false,
+                ..Default::default()
+            },
+            AlignmentMode::Fuzzy => AlignmentConfig {
+                accept_match_lesser: false,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Align externally produced extractions against `text`
+///
+/// Fills `char_interval` and `alignment_status` for each extraction. Extractions
+/// that cannot be located get no interval and a `None` status. Returns the
+/// number of extractions that were aligned.
+pub fn align_extractions(
+    text: &str,
+    extractions: &mut [Extraction],
+    mode: AlignmentMode,
+) -> LangExtractResult<usize> {
+    let aligner = TextAligner::with_config(mode.config());
+    let mut aligned_count = 0;
+
+    for extraction in extractions.iter_mut() {
+        extraction.char_interval = aligner.align_single_extraction(extraction, text, 0)?;
+        if extraction.char_interval.is_some() {
+            aligned_count += 1;
+        }
+    }
+
+    Ok(aligned_count)
+}
+
+/// Fill `token_interval` from each extraction's `char_interval`
+///
+/// Token indices refer to the tokenization of `text` and the end index is
+/// exclusive. A token only partly covered by the char interval is included.
+pub fn populate_token_intervals(text: &str, extractions: &mut [Extraction]) -> LangExtractResult<()> {
+    let tokens = tokenizer::tokenize(text)?.tokens;
+
+    for extraction in extractions.iter_mut() {
+        let Some((start, end)) = extraction
+            .char_interval
+            .as_ref()
+            .and_then(|interval| Some((interval.start_pos?, interval.end_pos?)))
+        else {
+            continue;
+        };
+
+        let start_token = tokens.partition_point(|token| token.char_interval.end_pos <= start);
+        let end_token = tokens.partition_point(|token| token.char_interval.start_pos < end);
+        if start_token < end_token {
+            extraction.token_interval = Some(TokenInterval::new(Some(start_token), Some(end_token)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Correct extractions whose `char_interval` does not fit `text`
+///
+/// An interval is invalid when it ends past the text, is empty or reversed, or
+/// splits a UTF-8 character. Such extractions are re-aligned by searching for
+/// their text; those that still cannot be located are dropped. Returns the
+/// number of extractions corrected or dropped.
+pub fn validate_char_intervals(
+    text: &str,
+    extractions: &mut Vec<Extraction>,
+    config: &AlignmentConfig,
+) -> LangExtractResult<usize> {
+    let aligner = TextAligner::with_config(config.clone());
+    let mut corrected = 0;
+    let mut keep = Vec::with_capacity(extractions.len());
+
+    for mut extraction in extractions.drain(..) {
+        let Some((start, end)) = extraction
+            .char_interval
+            .as_ref()
+            .and_then(|interval| Some((interval.start_pos?, interval.end_pos?)))
+        else {
+            keep.push(extraction);
+            continue;
+        };
+        if start < end && end <= text.len() && text.is_char_boundary(start) && text.is_char_boundary(end) {
+            keep.push(extraction);
+            continue;
+        }
+
+        corrected += 1;
+        extraction.alignment_status = None;
+        match aligner.align_single_extraction(&mut extraction, text, 0)? {
+            Some(interval) => {
+                tracing::info!(
+                    extraction_text = %extraction.extraction_text,
+                    start, end,
+                    new_start = interval.start_pos,
+                    new_end = interval.end_pos,
+                    "Re-aligned extraction with an invalid char interval"
+                );
+                extraction.char_interval = Some(interval);
+                keep.push(extraction);
+            }
+            None => {
+                tracing::warn!(
+                    extraction_text = %extraction.extraction_text,
+                    start, end,
+                    text_length = text.len(),
+                    "Dropped extraction with an invalid char interval that could not be re-aligned"
+                );
+            }
+        }
+    }
+
+    *extractions = keep;
+    Ok(corrected)
+}
+
 /// Text aligner for mapping extractions to source text positions
 pub struct TextAligner {
     config: AlignmentConfig,
@@ -64,8 +203,8 @@ impl TextAligner {
         let mut aligned_count = 0;
 
         for extraction in extractions.iter_mut() {
-            if let Some(interval) = self.align_single_extraction(extraction, source_text, char_offset)? {
-                extraction.char_interval = Some(interval);
+            extraction.char_interval = self.align_single_extraction(extraction, source_text, char_offset)?;
+            if extraction.char_interval.is_some() {
                 aligned_count += 1;
             }
         }
@@ -80,6 +219,11 @@ impl TextAligner {
         source_text: &str,
         char_offset: usize,
     ) -> LangExtractResult<Option<CharInterval>> {
+        // Absence assertions have no text to locate
+        if !extraction.present {
+            return Ok(None);
+        }
+
         let extraction_text = if self.config.case_sensitive {
             extraction.extraction_text.clone()
         } else {
@@ -103,6 +247,22 @@ impl TextAligner {
 
         // Try fuzzy matching if enabled
         if self.config.enable_fuzzy_alignment {
+            if let Some((start, end)) = self.find_whitespace_normalized_match(&extraction_text, &search_text) {
+                extraction.alignment_status = Some(AlignmentStatus::MatchFuzzy);
+                return Ok(Some(CharInterval::new(
+                    Some(start + char_offset),
+                    Some(end + char_offset),
+                )));
+            }
+
+            if let Some((start, end)) = self.find_levenshtein_match(&extraction_text, &search_text) {
+                extraction.alignment_status = Some(AlignmentStatus::MatchFuzzy);
+                return Ok(Some(CharInterval::new(
+                    Some(start + char_offset),
+                    Some(end + char_offset),
+                )));
+            }
+
             if let Some((start, end, status)) = self.find_fuzzy_match(&extraction_text, &search_text) {
                 extraction.alignment_status = Some(status);
                 return Ok(Some(CharInterval::new(
@@ -150,10 +310,90 @@ impl TextAligner {
         None
     }
 
+    /// Find a match after collapsing whitespace runs in both texts, mapped back to source positions
+    fn find_whitespace_normalized_match(&self, extraction_text: &str, source_text: &str) -> Option<(usize, usize)> {
+        let needle = extraction_text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if needle.is_empty() {
+            return None;
+        }
+
+        // Normalized source plus, for every normalized byte, the source span it came from
+        let mut normalized = String::with_capacity(source_text.len());
+        let mut spans: Vec<(usize, usize)> = Vec::with_capacity(source_text.len());
+        let mut chars = source_text.char_indices().peekable();
+
+        while let Some((pos, c)) = chars.next() {
+            if c.is_whitespace() {
+                let mut end = pos + c.len_utf8();
+                while let Some(&(next_pos, next)) = chars.peek() {
+                    if !next.is_whitespace() {
+                        break;
+                    }
+                    end = next_pos + next.len_utf8();
+                    chars.next();
+                }
+                normalized.push(' ');
+                spans.push((pos, end));
+            } else {
+                normalized.push(c);
+                for _ in 0..c.len_utf8() {
+                    spans.push((pos, pos + c.len_utf8()));
+                }
+            }
+        }
+
+        let start = normalized.find(&needle)?;
+        let end = start + needle.len();
+        Some((spans[start].0, spans[end - 1].1))
+    }
+
+    /// Find the word-bounded span with the best normalized Levenshtein ratio above the threshold
+    fn find_levenshtein_match(&self, extraction_text: &str, source_text: &str) -> Option<(usize, usize)> {
+        let needle: Vec<char> = extraction_text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .collect();
+        let word_count = extraction_text.split_whitespace().count();
+        if needle.is_empty() {
+            return None;
+        }
+
+        let threshold = self
+            .config
+            .levenshtein_alignment_threshold
+            .max(self.config.fuzzy_alignment_threshold);
+        let source_spans = word_spans(source_text);
+        let mut best_match: Option<(usize, usize, f32)> = None;
+
+        for window_size in word_count.saturating_sub(1).max(1)..=word_count + 1 {
+            for window in source_spans.windows(window_size) {
+                let candidate: Vec<char> = window
+                    .iter()
+                    .map(|&(s, e)| &source_text[s..e])
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .chars()
+                    .collect();
+
+                let ratio = levenshtein_ratio(&needle, &candidate);
+                if ratio >= threshold
+                    && best_match.is_none_or(|(_, _, best)| ratio > best)
+                {
+                    best_match = Some((window[0].0, window[window_size - 1].1, ratio));
+                }
+            }
+        }
+
+        best_match.map(|(start, end, _)| (start, end))
+    }
+
     /// Find fuzzy matches using sliding window approach
     fn find_fuzzy_match(&self, extraction_text: &str, source_text: &str) -> Option<(usize, usize, AlignmentStatus)> {
         let extraction_words: Vec<&str> = extraction_text.split_whitespace().collect();
-        let source_words: Vec<&str> = source_text.split_whitespace().collect();
+        let source_spans = word_spans(source_text);
+        let source_words: Vec<&str> = source_spans.iter().map(|&(s, e)| &source_text[s..e]).collect();
 
         if extraction_words.is_empty() || source_words.is_empty() {
             return None;
@@ -191,17 +431,8 @@ impl TextAligner {
 
         // Convert word positions back to character positions
         if let Some((start_word_idx, end_word_idx, _)) = best_match {
-            let char_start = if start_word_idx == 0 {
-                0
-            } else {
-                source_words[..start_word_idx].join(" ").len() + 1
-            };
-
-            let char_end = if end_word_idx >= source_words.len() {
-                source_text.len()
-            } else {
-                source_words[..end_word_idx].join(" ").len()
-            };
+            let char_start = source_spans[start_word_idx].0;
+            let char_end = source_spans[end_word_idx - 1].1;
 
             return Some((char_start, char_end, AlignmentStatus::MatchFuzzy));
         }
@@ -274,6 +505,49 @@ impl TextAligner {
     }
 }
 
+/// Normalized Levenshtein similarity: 1.0 for identical sequences, 0.0 for completely different
+fn levenshtein_ratio(a: &[char], b: &[char]) -> f32 {
+    let max_len = a.len().max(b.len());
+    if max_len == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    1.0 - previous[b.len()] as f32 / max_len as f32
+}
+
+/// Byte spans of whitespace-separated words in `text`
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut word_start: Option<usize> = None;
+
+    for (pos, c) in text.char_indices() {
+        if c.is_whitespace() {
+            if let Some(start) = word_start.take() {
+                spans.push((start, pos));
+            }
+        } else if word_start.is_none() {
+            word_start = Some(pos);
+        }
+    }
+    if let Some(start) = word_start {
+        spans.push((start, text.len()));
+    }
+
+    spans
+}
+
 impl Default for TextAligner {
     fn default() -> Self {
         Self::new()
@@ -416,4 +690,168 @@ mod tests {
         assert_eq!(stats.success_rate(), 2.0 / 3.0);
         assert_eq!(stats.exact_match_rate(), 1.0 / 3.0);
     }
+
+    #[test]
+    fn test_levenshtein_ratio() {
+        let a: Vec<char> = "john smith".chars().collect();
+        let b: Vec<char> = "jon smith".chars().collect();
+        assert_eq!(levenshtein_ratio(&a, &a), 1.0);
+        assert!((levenshtein_ratio(&a, &b) - 0.9).abs() < 1e-6);
+        assert_eq!(levenshtein_ratio(&[], &[]), 1.0);
+    }
+
+    #[test]
+    fn test_fuzzy_alignment_threshold_extra_space() {
+        let aligner = TextAligner::with_config(AlignmentConfig {
+            fuzzy_alignment_threshold: 0.9,
+            accept_match_lesser: false,
+            ..Default::default()
+        });
+        let mut extraction = Extraction::new("person".to_string(), "John  Smith".to_string());
+        let source_text = "Contact John Smith for details.";
+
+        let result = aligner.align_single_extraction(&mut extraction, source_text, 0).unwrap();
+
+        assert_eq!(result, Some(CharInterval::new(Some(8), Some(18))));
+        assert_eq!(extraction.alignment_status, Some(AlignmentStatus::MatchFuzzy));
+    }
+
+    #[test]
+    fn test_levenshtein_does_not_ground_unrelated_values() {
+        let aligner = TextAligner::new();
+        let source_text = "Patient John Brown on 2024-01-05 was prescribed aspirin in Boston.";
+
+        for value in ["ibuprofen", "Mary Johnson", "Paris"] {
+            let mut extraction = Extraction::new("entity".to_string(), value.to_string());
+            let result = aligner.align_single_extraction(&mut extraction, source_text, 0).unwrap();
+            assert_eq!(result, None, "{} should stay unaligned", value);
+            assert_eq!(extraction.alignment_status, None);
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_alignment_levenshtein_threshold() {
+        let source_text = "Contact John Smith for details.";
+
+        let lenient = TextAligner::with_config(AlignmentConfig {
+            fuzzy_alignment_threshold: 0.9,
+            ..Default::default()
+        });
+        let mut extraction = Extraction::new("person".to_string(), "Jon Smith".to_string());
+        let result = lenient.align_single_extraction(&mut extraction, source_text, 0).unwrap();
+        assert_eq!(result, Some(CharInterval::new(Some(8), Some(18))));
+        assert_eq!(extraction.alignment_status, Some(AlignmentStatus::MatchFuzzy));
+
+        let strict = TextAligner::with_config(AlignmentConfig {
+            fuzzy_alignment_threshold: 0.95,
+            ..Default::default()
+        });
+        let mut extractions = vec![Extraction::with_char_interval(
+            "person".to_string(),
+            "Jon Smith".to_string(),
+            CharInterval::new(Some(0), Some(9)),
+        )];
+        let aligned = strict.align_extractions(&mut extractions, source_text, 0).unwrap();
+        assert_eq!(aligned, 0);
+        assert_eq!(extractions[0].char_interval, None);
+        assert_eq!(extractions[0].alignment_status, None);
+    }
+
+    #[test]
+    fn test_align_extractions_exact() {
+        let text = "Patient John Smith was prescribed aspirin.";
+        let mut extractions = vec![
+            Extraction::new("person".to_string(), "John Smith".to_string()),
+            Extraction::new("medication".to_string(), "Aspirin".to_string()),
+        ];
+
+        let aligned = align_extractions(text, &mut extractions, AlignmentMode::Exact).unwrap();
+
+        assert_eq!(aligned, 2);
+        assert_eq!(extractions[0].char_interval, Some(CharInterval::new(Some(8), Some(18))));
+        assert_eq!(extractions[0].alignment_status, Some(AlignmentStatus::MatchExact));
+        assert_eq!(extractions[1].char_interval, Some(CharInterval::new(Some(34), Some(41))));
+    }
+
+    #[test]
+    fn test_align_extractions_fuzzy_whitespace() {
+        let text = "Patient John\n   Smith was prescribed aspirin.";
+        let mut extractions = vec![Extraction::new("person".to_string(), "John Smith".to_string())];
+
+        let aligned = align_extractions(text, &mut extractions, AlignmentMode::Exact).unwrap();
+        assert_eq!(aligned, 0);
+
+        let aligned = align_extractions(text, &mut extractions, AlignmentMode::Fuzzy).unwrap();
+        assert_eq!(aligned, 1);
+        assert_eq!(extractions[0].alignment_status, Some(AlignmentStatus::MatchFuzzy));
+        let interval = extractions[0].char_interval.clone().unwrap();
+        assert_eq!(&text[interval.start_pos.unwrap()..interval.end_pos.unwrap()], "John\n   Smith");
+    }
+
+    #[test]
+    fn test_align_extractions_not_found() {
+        let text = "Patient John Smith was prescribed aspirin.";
+        let mut extractions = vec![Extraction::with_char_interval(
+            "medication".to_string(),
+            "ibuprofen".to_string(),
+            CharInterval::new(Some(0), Some(9)),
+        )];
+
+        let aligned = align_extractions(text, &mut extractions, AlignmentMode::Fuzzy).unwrap();
+
+        assert_eq!(aligned, 0);
+        assert_eq!(extractions[0].char_interval, None);
+        assert_eq!(extractions[0].alignment_status, None);
+    }
+
+    #[test]
+    fn test_populate_token_intervals() {
+        let text = "Dr. John Smith works at Acme.";
+        let mut extractions = vec![
+            Extraction::new("person".to_string(), "John Smith".to_string()),
+            Extraction::new("company".to_string(), "cme".to_string()),
+            Extraction::new("missing".to_string(), "Boston".to_string()),
+        ];
+        extractions[0].char_interval = Some(CharInterval::new(Some(4), Some(14)));
+        // Starts mid-token, so the whole "Acme" token is covered
+        extractions[1].char_interval = Some(CharInterval::new(Some(25), Some(28)));
+
+        populate_token_intervals(text, &mut extractions).unwrap();
+
+        let tokens = tokenizer::tokenize(text).unwrap().tokens;
+        let covered = |extraction: &Extraction| {
+            let interval = extraction.token_interval.as_ref().unwrap();
+            let (start, end) = (interval.start_token.unwrap(), interval.end_token.unwrap());
+            &text[tokens[start].char_interval.start_pos..tokens[end - 1].char_interval.end_pos]
+        };
+        assert_eq!(extractions[0].token_interval, Some(TokenInterval::new(Some(2), Some(4))));
+        assert_eq!(covered(&extractions[0]), "John Smith");
+        assert_eq!(covered(&extractions[1]), "Acme");
+        assert!(extractions[2].token_interval.is_none());
+    }
+
+    #[test]
+    fn test_validate_char_intervals_realigns_or_drops() {
+        let text = "Alice met Bob in Paris.";
+        let with_interval = |value: &str, start: usize, end: usize| {
+            let mut extraction = Extraction::new("entity".to_string(), value.to_string());
+            extraction.char_interval = Some(CharInterval::new(Some(start), Some(end)));
+            extraction
+        };
+        let mut extractions = vec![
+            with_interval("Alice", 0, 5),
+            with_interval("Bob", 40, 43),
+            with_interval("Paris", 17, 17),
+            with_interval("Zurich", 30, 60),
+            Extraction::new("entity".to_string(), "unaligned".to_string()),
+        ];
+
+        let corrected = validate_char_intervals(text, &mut extractions, &AlignmentConfig::default()).unwrap();
+        assert_eq!(corrected, 3);
+        let texts: Vec<&str> = extractions.iter().map(|e| e.extraction_text.as_str()).collect();
+        assert_eq!(texts, vec!["Alice", "Bob", "Paris", "unaligned"]);
+        assert_eq!(extractions[1].char_interval, Some(CharInterval::new(Some(10), Some(13))));
+        assert_eq!(extractions[1].alignment_status, Some(AlignmentStatus::MatchExact));
+        assert_eq!(extractions[2].char_interval, Some(CharInterval::new(Some(17), Some(22))));
+    }
 }
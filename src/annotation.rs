@@ -1,28 +1,72 @@
 //! Text annotation functionality.
 
 use crate::{
-    alignment::TextAligner,
+    alignment::{AlignmentConfig, TextAligner},
+    checkpoint::ChunkCheckpoint,
     chunking::{ChunkResult, ResultAggregator, TextChunk, TokenChunk, ChunkIterator},
-    data::{AnnotatedDocument, Extraction, FormatType, Document},
-    exceptions::LangExtractResult,
+    data::{AlignmentStatus, AnnotatedDocument, Extraction, FormatType, Document},
+    exceptions::{LangExtractError, LangExtractResult},
     inference::BaseLanguageModel,
     logging::{report_progress, ProgressEvent},
     prompting::PromptTemplateStructured,
-    resolver::Resolver,
-    tokenizer::Tokenizer,
+    resolver::{Resolver, ValidationError},
+    tokenizer::{Tokenize, Tokenizer},
 };
-use futures::future::join_all;
+use futures::stream::{self, StreamExt};
 use std::collections::HashMap;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Main annotator for processing text through language models
 pub struct Annotator {
-    language_model: Box<dyn BaseLanguageModel>,
+    language_model: Arc<dyn BaseLanguageModel>,
     prompt_template: PromptTemplateStructured,
     #[allow(dead_code)]
     format_type: FormatType,
     #[allow(dead_code)]
     fence_output: bool,
+    alignment_config: AlignmentConfig,
+    skip_chunking: bool,
+    grounding: Option<GroundingPolicy>,
+    keep_raw_outputs: bool,
+    chunk_timeout: Option<Duration>,
+    context_provider: Option<Arc<dyn ContextProvider>>,
+    auto_shrink_on_truncation: bool,
+    checkpoint: Option<ChunkCheckpoint>,
+    tokenizer: Option<Arc<dyn Tokenize>>,
+    reformat_attempts: usize,
+    context_window: Option<usize>,
+    min_chunks: Option<usize>,
+    temperature: f32,
+}
+
+/// How [`Annotator::annotate_text`] splits a text
+enum ChunkPlan {
+    /// Roughly equal chunks of a text that fits in one prompt, for `min_chunks`
+    Even(Vec<TextChunk>),
+    /// The whole text as a single chunk
+    Whole,
+    /// Token-based chunks of at most this many characters
+    Tokens(usize),
+}
+
+/// Smallest chunk size auto-shrinking halves a truncated chunk down to
+pub const MIN_AUTO_SHRINK_CHAR_BUFFER: usize = 100;
+
+/// Supplies the additional prompt context for each chunk
+///
+/// Useful for passing a section heading or a summary of the preceding chunk
+/// instead of one static `additional_context` string.
+pub trait ContextProvider: Send + Sync {
+    /// Context to include in the prompt for `chunk`, if any
+    fn context_for_chunk(&self, chunk: &TextChunk) -> Option<String>;
+}
+
+/// Which aligned extractions count as grounded in the source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroundingPolicy {
+    /// Whether fuzzy matches are kept (exact, lesser and greater matches always are)
+    pub allow_fuzzy: bool,
 }
 
 impl Annotator {
@@ -34,10 +78,271 @@ impl Annotator {
         fence_output: bool,
     ) -> Self {
         Self {
-            language_model,
+            language_model: Arc::from(language_model),
             prompt_template,
             format_type,
             fence_output,
+            alignment_config: AlignmentConfig::default(),
+            skip_chunking: false,
+            grounding: None,
+            keep_raw_outputs: false,
+            chunk_timeout: None,
+            context_provider: None,
+            auto_shrink_on_truncation: false,
+            checkpoint: None,
+            tokenizer: None,
+            reformat_attempts: 0,
+            context_window: None,
+            min_chunks: None,
+            temperature: 1.0,
+        }
+    }
+
+    /// Set the alignment configuration used to locate extractions in the source text
+    pub fn with_alignment_config(mut self, alignment_config: AlignmentConfig) -> Self {
+        self.alignment_config = alignment_config;
+        self
+    }
+
+    /// Get the alignment configuration
+    pub fn alignment_config(&self) -> &AlignmentConfig {
+        &self.alignment_config
+    }
+
+    /// Set the sampling temperature sent with every model call (defaults to 1.0)
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Get the sampling temperature sent with every model call
+    pub fn temperature(&self) -> f32 {
+        self.temperature
+    }
+
+    /// Get the id of the model the annotator prompts
+    pub fn model_id(&self) -> &str {
+        self.language_model.model_id()
+    }
+
+    /// Get the prompt description the annotator sends, with its placeholders substituted
+    pub fn prompt_description(&self) -> Option<String> {
+        self.prompt_template.description.as_ref()?;
+        self.prompt_template.render_description().ok()
+    }
+
+    /// Drop extractions that could not be located in the source text
+    pub fn with_grounding(mut self, grounding: Option<GroundingPolicy>) -> Self {
+        self.grounding = grounding;
+        self
+    }
+
+    /// Attach each chunk's raw model output to the returned document's `raw_outputs`
+    pub fn with_raw_outputs(mut self, keep_raw_outputs: bool) -> Self {
+        self.keep_raw_outputs = keep_raw_outputs;
+        self
+    }
+
+    /// Give up on a chunk that takes longer than `timeout`, recording it as failed
+    ///
+    /// The limit covers every model call for the chunk, including reformat
+    /// follow-ups. Unchunked text that times out fails the whole call.
+    pub fn with_chunk_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.chunk_timeout = timeout;
+        self
+    }
+
+    /// Build each chunk's prompt context with `provider` instead of the static `additional_context`
+    pub fn with_context_provider(mut self, provider: Option<Arc<dyn ContextProvider>>) -> Self {
+        self.context_provider = provider;
+        self
+    }
+
+    /// Split large documents into chunks with `tokenizer` instead of the default [`Tokenizer`]
+    pub fn with_tokenizer(mut self, tokenizer: Option<Arc<dyn Tokenize>>) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    /// Ask the model to reformat an unparseable response, up to `max_attempts` times per chunk
+    pub fn with_reformat_on_parse_failure(mut self, max_attempts: usize) -> Self {
+        self.reformat_attempts = max_attempts;
+        self
+    }
+
+    /// Corrective prompt asking the model to resend `response` in the expected format
+    fn reformat_prompt(&self, response: &str, error: &LangExtractError) -> String {
+        let format = match self.format_type {
+            FormatType::Json => "JSON",
+            FormatType::Yaml => "YAML",
+        };
+        format!(
+            "Your previous response could not be parsed as valid {format} ({error}). \
+             Rewrite it as valid {format} with the same content and reply with only the {format}.\n\n\
+             Previous response:\n{response}"
+        )
+    }
+
+    /// Context for `chunk`: the provider's if one is set, otherwise the static context
+    fn chunk_context(&self, chunk: &TextChunk, additional_context: Option<&str>) -> Option<String> {
+        match &self.context_provider {
+            Some(provider) => provider.context_for_chunk(chunk),
+            None => additional_context.map(String::from),
+        }
+    }
+
+    /// Re-run a chunk whose output was truncated as chunks of half its size
+    ///
+    /// Halving repeats until the output is complete or the chunk size would
+    /// drop below [`MIN_AUTO_SHRINK_CHAR_BUFFER`].
+    pub fn with_auto_shrink_on_truncation(mut self, auto_shrink: bool) -> Self {
+        self.auto_shrink_on_truncation = auto_shrink;
+        self
+    }
+
+    /// Half of `len`, if auto-shrinking is enabled and the result stays above the floor
+    fn shrunk_buffer(&self, len: usize) -> Option<usize> {
+        let half = len / 2;
+        (self.auto_shrink_on_truncation && half >= MIN_AUTO_SHRINK_CHAR_BUFFER).then_some(half)
+    }
+
+    /// Record completed chunks in a checkpoint file and skip those already recorded
+    ///
+    /// Applies to chunked texts; a text that fits in one prompt is always processed.
+    pub fn with_checkpoint(mut self, checkpoint: Option<ChunkCheckpoint>) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+
+    /// Shrink chunks so a chunk plus the rendered prompt fits in `context_window` tokens
+    pub fn with_context_window(mut self, context_window: Option<usize>) -> Self {
+        self.context_window = context_window;
+        self
+    }
+
+    /// Split every text into at least `min_chunks` chunks so they can be processed in parallel
+    ///
+    /// Texts that fit in `max_char_buffer` are cut into that many roughly equal,
+    /// overlapping chunks; longer texts get a smaller buffer.
+    pub fn with_min_chunks(mut self, min_chunks: Option<usize>) -> Self {
+        self.min_chunks = min_chunks;
+        self
+    }
+
+    /// `max_char_buffer`, reduced so a chunk of `text` plus the prompt fits in the context window
+    ///
+    /// The prompt overhead is the token count of the prompt rendered around an
+    /// empty input. The remaining token budget is converted to characters using
+    /// the text's own characters-per-token ratio.
+    fn budgeted_char_buffer(
+        &self,
+        text: &str,
+        max_char_buffer: usize,
+        additional_context: Option<&str>,
+    ) -> LangExtractResult<usize> {
+        let Some(context_window) = self.context_window else {
+            return Ok(max_char_buffer);
+        };
+        let default_tokenizer;
+        let tokenizer: &dyn Tokenize = match self.tokenizer.as_deref() {
+            Some(tokenizer) => tokenizer,
+            None => {
+                default_tokenizer = Tokenizer::new()?;
+                &default_tokenizer
+            }
+        };
+
+        let overhead = tokenizer.tokenize(&self.build_prompt("", additional_context)?)?.tokens.len();
+        if overhead >= context_window {
+            return Err(LangExtractError::configuration(format!(
+                "Prompt overhead of {} tokens leaves no room for text in a {}-token context window",
+                overhead, context_window
+            )));
+        }
+
+        let text_tokens = tokenizer.tokenize(text)?.tokens.len().max(1);
+        let chars_per_token = text.len() as f64 / text_tokens as f64;
+        let budget = ((context_window - overhead) as f64 * chars_per_token) as usize;
+        if budget < max_char_buffer {
+            tracing::debug!(overhead, context_window, budget, "Chunk size limited by the context window");
+        }
+        Ok(max_char_buffer.min(budget.max(1)))
+    }
+
+    /// Decide how `text` is split before any chunk is sent to the model
+    fn chunk_plan(
+        &self,
+        text: &str,
+        max_char_buffer: usize,
+        additional_context: Option<&str>,
+    ) -> LangExtractResult<ChunkPlan> {
+        let max_char_buffer = self.budgeted_char_buffer(text, max_char_buffer, additional_context)?;
+        let min_chunks = self.min_chunks.filter(|&count| count > 1 && !self.skip_chunking);
+
+        Ok(if let Some(count) = min_chunks.filter(|_| text.len() <= max_char_buffer) {
+            ChunkPlan::Even(even_chunks(text, count))
+        } else if self.skip_chunking || text.len() <= max_char_buffer {
+            ChunkPlan::Whole
+        } else {
+            ChunkPlan::Tokens(match min_chunks {
+                Some(count) => max_char_buffer.min(text.len().div_ceil(count)),
+                None => max_char_buffer,
+            })
+        })
+    }
+
+    /// Chunks [`Self::annotate_text`] initially sends for `text`, before any auto-shrinking
+    pub fn plan_chunks(
+        &self,
+        text: &str,
+        max_char_buffer: usize,
+        additional_context: Option<&str>,
+    ) -> LangExtractResult<Vec<TextChunk>> {
+        match self.chunk_plan(text, max_char_buffer, additional_context)? {
+            ChunkPlan::Even(chunks) => Ok(chunks),
+            ChunkPlan::Whole => Ok(vec![TextChunk::new(0, text.to_string(), 0, None)]),
+            ChunkPlan::Tokens(max_char_buffer) => token_chunks(text, max_char_buffer, self.tokenizer.as_deref()),
+        }
+    }
+
+    /// Render the prompt sent for `chunk`, including its per-chunk context
+    pub fn render_chunk_prompt(&self, chunk: &TextChunk, additional_context: Option<&str>) -> LangExtractResult<String> {
+        self.build_prompt(&chunk.text, self.chunk_context(chunk, additional_context).as_deref())
+    }
+
+    /// Get the tokenizer used for chunking and context budgeting, if one was set
+    pub fn tokenizer(&self) -> Option<&dyn Tokenize> {
+        self.tokenizer.as_deref()
+    }
+
+    /// Always send the whole text in a single prompt, ignoring `max_char_buffer`
+    pub fn with_skip_chunking(mut self, skip_chunking: bool) -> Self {
+        self.skip_chunking = skip_chunking;
+        self
+    }
+
+    /// Create an annotator sharing this one's model and settings but with another prompt description
+    pub fn for_prompt(&self, description: &str) -> Self {
+        let mut prompt_template = self.prompt_template.clone();
+        prompt_template.description = Some(description.to_string());
+        Self {
+            language_model: Arc::clone(&self.language_model),
+            prompt_template,
+            format_type: self.format_type,
+            fence_output: self.fence_output,
+            alignment_config: self.alignment_config.clone(),
+            skip_chunking: self.skip_chunking,
+            grounding: self.grounding,
+            keep_raw_outputs: self.keep_raw_outputs,
+            chunk_timeout: self.chunk_timeout,
+            context_provider: self.context_provider.clone(),
+            auto_shrink_on_truncation: self.auto_shrink_on_truncation,
+            checkpoint: self.checkpoint.clone(),
+            tokenizer: self.tokenizer.clone(),
+            reformat_attempts: self.reformat_attempts,
+            context_window: self.context_window,
+            min_chunks: self.min_chunks,
+            temperature: self.temperature,
         }
     }
 
@@ -53,41 +358,139 @@ impl Annotator {
         extraction_passes: usize,
         max_workers: usize,
     ) -> LangExtractResult<AnnotatedDocument> {
-        // Check if we need to chunk the text
-        if text.len() <= max_char_buffer {
-            // Text is small enough, process directly
-            return self.process_single_text(text, resolver, additional_context, debug).await;
+        let mut result = match self.chunk_plan(text, max_char_buffer, additional_context)? {
+            ChunkPlan::Even(chunks) => {
+                // Text fits in one prompt but should still be spread over several workers
+                report_progress(ProgressEvent::ChunkingStarted {
+                    total_chars: text.len(),
+                    chunk_count: chunks.len(),
+                    strategy: "even".to_string(),
+                });
+                self.process_text_chunks_in_batches(
+                    chunks,
+                    text,
+                    resolver,
+                    batch_length,
+                    additional_context,
+                    debug,
+                    extraction_passes,
+                    max_workers,
+                ).await?
+            }
+            ChunkPlan::Whole => {
+                // Text is small enough (or chunking is disabled), process directly as a single chunk
+                let context = match &self.context_provider {
+                    Some(provider) => provider.context_for_chunk(&TextChunk::new(0, text.to_string(), 0, None)),
+                    None => additional_context.map(String::from),
+                };
+                let (document, truncated) =
+                    self.process_single_text(text, resolver, context.as_deref(), debug).await?;
+                match self.shrunk_buffer(text.len()).filter(|_| truncated) {
+                    Some(buffer) => {
+                        tracing::info!(buffer, "Model output was truncated; re-running with smaller chunks");
+                        self.process_token_chunked_text(
+                            text,
+                            resolver,
+                            buffer,
+                            batch_length,
+                            additional_context,
+                            debug,
+                            extraction_passes,
+                            max_workers,
+                        ).await?
+                    }
+                    None => {
+                        let mut document = document;
+                        for extraction in document.extractions.iter_mut().flatten() {
+                            extraction.chunk_id = Some(0);
+                        }
+                        document
+                    }
+                }
+            }
+            ChunkPlan::Tokens(max_char_buffer) => {
+                // Text is too large, use token-based chunking
+                if debug {
+                    report_progress(ProgressEvent::Debug {
+                        operation: "chunking".to_string(),
+                        details: format!("Text length ({} chars) exceeds buffer limit ({} chars), using token-based chunking",
+                            text.len(), max_char_buffer),
+                    });
+                }
+
+                self.process_token_chunked_text(
+                    text,
+                    resolver,
+                    max_char_buffer,
+                    batch_length,
+                    additional_context,
+                    debug,
+                    extraction_passes,
+                    max_workers,
+                ).await?
+            }
+        };
+
+        if let Some(extractions) = result.extractions.as_mut() {
+            crate::alignment::validate_char_intervals(text, extractions, &self.alignment_config)?;
+            crate::alignment::populate_token_intervals(text, extractions)?;
         }
 
-        // Text is too large, use token-based chunking
-        if debug {
-            report_progress(ProgressEvent::Debug {
-                operation: "chunking".to_string(),
-                details: format!("Text length ({} chars) exceeds buffer limit ({} chars), using token-based chunking",
-                    text.len(), max_char_buffer),
-            });
+        if let Some(policy) = self.grounding {
+            self.drop_ungrounded(&mut result, policy);
         }
 
-        self.process_token_chunked_text(
-            text,
-            resolver,
-            max_char_buffer,
-            batch_length,
-            additional_context,
-            debug,
-            extraction_passes,
-            max_workers,
-        ).await
+        Ok(result)
     }
 
-    /// Process text that fits within the buffer limit
+    /// Remove extractions whose alignment does not satisfy the grounding policy
+    fn drop_ungrounded(&self, document: &mut AnnotatedDocument, policy: GroundingPolicy) {
+        let Some(extractions) = document.extractions.as_mut() else {
+            return;
+        };
+
+        let before = extractions.len();
+        extractions.retain(|extraction| match extraction.alignment_status {
+            None => !extraction.present,
+            Some(AlignmentStatus::MatchFuzzy) => policy.allow_fuzzy,
+            Some(_) => true,
+        });
+
+        let dropped = before - extractions.len();
+        if dropped > 0 {
+            tracing::debug!(dropped, total = before, "Dropped extractions not grounded in the source text");
+        }
+    }
+
+    /// Process text that fits within the buffer limit, also reporting whether the output was truncated
     async fn process_single_text(
         &self,
         text: &str,
         resolver: &Resolver,
         additional_context: Option<&str>,
         debug: bool,
-    ) -> LangExtractResult<AnnotatedDocument> {
+    ) -> LangExtractResult<(AnnotatedDocument, bool)> {
+        let Some(timeout) = self.chunk_timeout else {
+            return self.process_single_text_untimed(text, resolver, additional_context, debug).await;
+        };
+        tokio::time::timeout(timeout, self.process_single_text_untimed(text, resolver, additional_context, debug))
+            .await
+            .unwrap_or_else(|_| {
+                Err(LangExtractError::inference_simple(format!(
+                    "Chunk timed out after {} ms",
+                    timeout.as_millis()
+                )))
+            })
+    }
+
+    /// [`Self::process_single_text`] without the chunk timeout
+    async fn process_single_text_untimed(
+        &self,
+        text: &str,
+        resolver: &Resolver,
+        additional_context: Option<&str>,
+        debug: bool,
+    ) -> LangExtractResult<(AnnotatedDocument, bool)> {
         // Build the prompt
         let prompt = self.build_prompt(text, additional_context)?;
 
@@ -116,7 +519,7 @@ impl Annotator {
 
         // Create inference parameters
         let mut kwargs = HashMap::new();
-        kwargs.insert("temperature".to_string(), serde_json::json!(1));
+        kwargs.insert("temperature".to_string(), serde_json::json!(self.temperature));
         kwargs.insert("max_completion_tokens".to_string(), serde_json::json!(8000));
 
         // Call the language model
@@ -138,10 +541,11 @@ impl Annotator {
 
         // Extract the response
         let mut annotated_doc = AnnotatedDocument::with_extractions(Vec::new(), text.to_string());
+        let mut truncated = false;
 
         if let Some(batch) = results.first() {
             if let Some(output) = batch.first() {
-                let response_text = output.text();
+                let mut response_text = output.text().to_string();
 
                 if debug {
                     report_progress(ProgressEvent::Debug {
@@ -165,8 +569,38 @@ impl Annotator {
                     raw_output_length: response_text.len(),
                 });
 
-                match resolver.validate_and_parse(response_text, &expected_fields) {
+                let mut parsed = resolver.validate_and_parse(&response_text, &expected_fields);
+                let mut attempt = 0;
+                while attempt < self.reformat_attempts {
+                    let Err(error) = &parsed else { break };
+                    attempt += 1;
+                    let reformat_prompt = self.reformat_prompt(&response_text, error);
+                    tracing::info!(
+                        attempt,
+                        max_attempts = self.reformat_attempts,
+                        prompt = %reformat_prompt,
+                        "Response could not be parsed, asking the model to reformat it"
+                    );
+                    let outputs = self.language_model.infer(&[reformat_prompt], &kwargs).await?;
+                    response_text = outputs
+                        .first()
+                        .and_then(|batch| batch.first())
+                        .map(|output| output.text().to_string())
+                        .unwrap_or_default();
+                    parsed = resolver.validate_and_parse(&response_text, &expected_fields);
+                }
+                if self.keep_raw_outputs {
+                    annotated_doc.raw_outputs = Some(vec![response_text.clone()]);
+                }
+
+                match parsed {
+                    Ok((_, validation_result)) if resolver.strict_validation() && !validation_result.is_valid => {
+                        return Err(LangExtractError::ValidationFailed {
+                            errors: validation_result.errors,
+                        });
+                    }
                     Ok((mut extractions, validation_result)) => {
+                        truncated = validation_result.truncated;
                         // Report validation results
                         report_progress(ProgressEvent::ValidationCompleted {
                             extractions_found: extractions.len(),
@@ -198,7 +632,7 @@ impl Annotator {
                         }
 
                         // Align extractions with the source text
-                        let aligner = TextAligner::new();
+                        let aligner = TextAligner::with_config(self.alignment_config.clone());
                         let aligned_count = aligner.align_extractions(&mut extractions, text, 0)
                             .unwrap_or(0);
 
@@ -212,6 +646,16 @@ impl Annotator {
                             warnings: validation_result.warnings.len(),
                         });
                     }
+                    Err(e) if resolver.strict_validation() => {
+                        return Err(LangExtractError::ValidationFailed {
+                            errors: vec![ValidationError {
+                                message: format!("Failed to parse response: {}", e),
+                                field_path: None,
+                                expected: Some("Valid JSON structure".to_string()),
+                                actual: Some("Unparseable content".to_string()),
+                            }],
+                        });
+                    }
                     Err(e) => {
                         if debug {
                             report_progress(ProgressEvent::Debug {
@@ -220,14 +664,14 @@ impl Annotator {
                             });
                         }
                         // If parsing fails, create a single extraction with the raw response
-                        let extraction = Extraction::new("raw_response".to_string(), response_text.to_string());
+                        let extraction = Extraction::new("raw_response".to_string(), response_text);
                         annotated_doc.extractions = Some(vec![extraction]);
                     }
                 }
             }
         }
 
-        Ok(annotated_doc)
+        Ok((annotated_doc, truncated))
     }
 
     /// Process large text using chunking
@@ -243,42 +687,7 @@ impl Annotator {
         extraction_passes: usize,
         max_workers: usize,
     ) -> LangExtractResult<AnnotatedDocument> {
-        // Create tokenizer and tokenize the text
-        let tokenizer = Tokenizer::new()?;
-        let tokenized_text = tokenizer.tokenize(text)?;
-
-        // Create document for chunking
-        let document = Document {
-            document_id: None,
-            text: text.to_string(),
-            additional_context: None,
-        };
-
-        // Create token-based chunk iterator
-        let chunk_iter = ChunkIterator::new(&tokenized_text, &tokenizer, max_char_buffer, Some(&document))?;
-
-        // Collect chunks from iterator
-        let token_chunks: Result<Vec<TokenChunk>, _> = chunk_iter.collect();
-        let token_chunks = token_chunks?;
-
-        // Convert TokenChunks to TextChunks for compatibility with existing pipeline
-        let mut text_chunks = Vec::new();
-        for (i, token_chunk) in token_chunks.iter().enumerate() {
-            let chunk_text = token_chunk.chunk_text(&tokenizer)?;
-            let char_interval = token_chunk.char_interval(&tokenizer)?;
-            let chunk_len = chunk_text.len();
-
-            let text_chunk = TextChunk {
-                id: i,
-                text: chunk_text,
-                char_offset: char_interval.start_pos.unwrap_or(0),
-                char_length: chunk_len,
-                document_id: None,
-                has_overlap: false,
-                overlap_info: None,
-            };
-            text_chunks.push(text_chunk);
-        }
+        let text_chunks = token_chunks(text, max_char_buffer, self.tokenizer.as_deref())?;
 
         // Report chunking started
         report_progress(ProgressEvent::ChunkingStarted {
@@ -321,30 +730,67 @@ impl Annotator {
         extraction_passes: usize,
         max_workers: usize,
     ) -> LangExtractResult<AnnotatedDocument> {
-        // Process chunks in parallel batches
+        // Reuse chunks completed by an earlier run sharing the checkpoint
         let mut chunk_results = Vec::new();
+        let checkpoint_key = |chunk: &TextChunk| -> LangExtractResult<String> {
+            let context = self.chunk_context(chunk, additional_context);
+            let prompt = self.build_prompt(&chunk.text, context.as_deref())?;
+            Ok(ChunkCheckpoint::key(self.language_model.model_id(), &prompt, self.temperature, chunk))
+        };
+        let pending = match &self.checkpoint {
+            Some(checkpoint) => {
+                let mut completed = checkpoint.load()?;
+                let mut pending = Vec::new();
+                let mut resumed = 0;
+                for chunk in chunks {
+                    match completed.remove(&checkpoint_key(&chunk)?) {
+                        Some(result) => {
+                            chunk_results.push(result);
+                            resumed += 1;
+                        }
+                        None => pending.push(chunk),
+                    }
+                }
+                if resumed > 0 {
+                    tracing::info!(
+                        resumed,
+                        remaining = pending.len(),
+                        "Resuming from checkpoint '{}'",
+                        checkpoint.path().display()
+                    );
+                }
+                pending
+            }
+            None => chunks,
+        };
+
+        // Process chunks in parallel batches
         let effective_workers = std::cmp::min(max_workers, batch_length);
-        let total_chunks = chunks.len();
+        let total_chunks = pending.len();
         let mut processed_chunks = 0;
 
-        for (batch_idx, chunk_batch) in chunks.chunks(batch_length).enumerate() {
+        for (batch_idx, chunk_batch) in pending.chunks(batch_length).enumerate() {
             // Progress reporting for each batch
             report_progress(ProgressEvent::BatchProgress {
                 batch_number: batch_idx + 1,
-                total_batches: (chunks.len() + batch_length - 1) / batch_length,
+                total_batches: (pending.len() + batch_length - 1) / batch_length,
                 chunks_processed: processed_chunks,
                 total_chunks,
             });
 
-            let batch_futures: Vec<_> = chunk_batch.iter()
-                .take(effective_workers)
-                .map(|chunk| self.process_chunk(chunk, resolver, additional_context, debug))
-                .collect();
-
-            let batch_results = join_all(batch_futures).await;
-
-            for result in batch_results {
-                chunk_results.push(result?);
+            // Up to `effective_workers` chunks run at once; results arrive in completion order
+            let mut batch_results = stream::iter(chunk_batch)
+                .map(|chunk| async move {
+                    (chunk, self.process_chunk(chunk, resolver, additional_context, debug).await)
+                })
+                .buffer_unordered(effective_workers.max(1));
+
+            while let Some((chunk, result)) = batch_results.next().await {
+                let result = result?;
+                if let Some(checkpoint) = self.checkpoint.as_ref().filter(|_| result.success) {
+                    checkpoint.record(&checkpoint_key(chunk)?, &result)?;
+                }
+                chunk_results.push(result);
             }
 
             processed_chunks += chunk_batch.len();
@@ -371,9 +817,16 @@ impl Annotator {
             // For now, we just use the single pass results
         }
 
-        // Aggregate results
+        // Aggregate in document order, whatever order the chunks completed in
+        chunk_results.sort_by_key(|result| (result.char_offset, result.chunk_id));
+        for result in &mut chunk_results {
+            for extraction in result.extractions.iter_mut().flatten() {
+                extraction.chunk_id = Some(result.chunk_id);
+            }
+        }
+        let chunk_count = chunk_results.len();
         report_progress(ProgressEvent::AggregationStarted {
-            chunk_count: chunks.len(),
+            chunk_count,
         });
         let aggregator = ResultAggregator::new();
         let final_result = aggregator.aggregate_chunk_results(
@@ -391,7 +844,7 @@ impl Annotator {
             report_progress(ProgressEvent::Debug {
                 operation: "aggregation".to_string(),
                 details: format!("Aggregated {} total extractions from {} chunks",
-                    final_result.extraction_count(), chunks.len()),
+                    final_result.extraction_count(), chunk_count),
             });
         }
 
@@ -409,13 +862,22 @@ impl Annotator {
         debug: bool,
     ) -> LangExtractResult<ChunkResult> {
         let start_time = Instant::now();
+        let static_context = additional_context;
+        let context = self.chunk_context(chunk, additional_context);
+        let additional_context = context.as_deref();
 
-        match self.process_single_text(&chunk.text, resolver, additional_context, false).await {
-            Ok(annotated_doc) => {
+        let result = self.process_single_text(&chunk.text, resolver, additional_context, false).await;
+
+        match result {
+            Ok((_, true)) if self.shrunk_buffer(chunk.text.len()).is_some() => {
+                self.process_shrunk_chunk(chunk, resolver, static_context, debug).await
+            }
+            Ok((annotated_doc, _)) => {
+                let raw_output = annotated_doc.raw_outputs.and_then(|outputs| outputs.into_iter().next());
                 let mut extractions = annotated_doc.extractions.unwrap_or_default();
 
                 // Align extractions with the chunk text
-                let aligner = TextAligner::new();
+                let aligner = TextAligner::with_config(self.alignment_config.clone());
                 let aligned_count = aligner.align_chunk_extractions(
                     &mut extractions,
                     &chunk.text,
@@ -435,8 +897,11 @@ impl Annotator {
                     extractions,
                     chunk.char_offset,
                     chunk.char_length,
-                ).with_processing_time(start_time.elapsed()))
+                )
+                .with_raw_output(raw_output)
+                .with_processing_time(start_time.elapsed()))
             }
+            Err(e) if e.is_validation_error() => Err(e),
             Err(e) => {
                 if debug {
                     report_progress(ProgressEvent::Debug {
@@ -455,6 +920,49 @@ impl Annotator {
         }
     }
 
+    /// Re-run a chunk whose output was truncated as chunks of half its size, merging their results
+    async fn process_shrunk_chunk(
+        &self,
+        chunk: &TextChunk,
+        resolver: &Resolver,
+        additional_context: Option<&str>,
+        debug: bool,
+    ) -> LangExtractResult<ChunkResult> {
+        let start_time = Instant::now();
+        let buffer = self.shrunk_buffer(chunk.text.len()).unwrap_or(chunk.text.len());
+        if debug {
+            report_progress(ProgressEvent::Debug {
+                operation: "chunk_processing".to_string(),
+                details: format!("Chunk {} output was truncated; retrying with {} char chunks", chunk.id, buffer),
+            });
+        }
+
+        let mut extractions = Vec::new();
+        let mut raw_outputs = Vec::new();
+        for mut sub_chunk in token_chunks(&chunk.text, buffer, self.tokenizer.as_deref())? {
+            sub_chunk.id = chunk.id;
+            sub_chunk.char_offset += chunk.char_offset;
+            sub_chunk.document_id = chunk.document_id.clone();
+            sub_chunk.section_heading = chunk.section_heading.clone();
+
+            let result = Box::pin(self.process_chunk(&sub_chunk, resolver, additional_context, debug)).await?;
+            if !result.success {
+                return Ok(result);
+            }
+            extractions.extend(result.extractions.unwrap_or_default());
+            raw_outputs.extend(result.raw_output);
+        }
+
+        Ok(ChunkResult::success(
+            chunk.id,
+            extractions,
+            chunk.char_offset,
+            chunk.char_length,
+        )
+        .with_raw_output((!raw_outputs.is_empty()).then(|| raw_outputs.join("\n")))
+        .with_processing_time(start_time.elapsed()))
+    }
+
     /// Build the prompt using the new template system
     fn build_prompt(&self, text: &str, additional_context: Option<&str>) -> LangExtractResult<String> {
         // Use the new template system for better prompt generation
@@ -558,3 +1066,755 @@ impl Annotator {
         Ok(extractions)
     }
 }
+
+/// Split `text` into `count` roughly equal chunks that overlap their neighbours
+///
+/// Split points move forward to the next whitespace (within a quarter of a
+/// chunk) so words stay whole, and each chunk reaches about a tenth of its
+/// size into the chunks on either side.
+pub(crate) fn even_chunks(text: &str, count: usize) -> Vec<TextChunk> {
+    let target = text.len().div_ceil(count.max(1)).max(1);
+    let overlap = target / 10;
+    let ceil_boundary = |mut pos: usize| {
+        pos = pos.min(text.len());
+        while !text.is_char_boundary(pos) {
+            pos += 1;
+        }
+        pos
+    };
+    let floor_boundary = |mut pos: usize| {
+        while !text.is_char_boundary(pos) {
+            pos -= 1;
+        }
+        pos
+    };
+
+    let mut bounds = vec![0];
+    for i in 1..count {
+        let split = ceil_boundary(target * i);
+        let split = text[split..]
+            .find(char::is_whitespace)
+            .filter(|&distance| distance <= target / 4)
+            .map_or(split, |distance| split + distance);
+        if split > *bounds.last().unwrap() && split < text.len() {
+            bounds.push(split);
+        }
+    }
+    bounds.push(text.len());
+
+    bounds
+        .windows(2)
+        .enumerate()
+        .map(|(id, window)| {
+            let (start, end) = (window[0], window[1]);
+            let chunk_start = floor_boundary(start.saturating_sub(overlap));
+            let chunk_end = ceil_boundary(end + overlap);
+            TextChunk::with_overlap(
+                id,
+                text[chunk_start..chunk_end].to_string(),
+                chunk_start,
+                None,
+                start - chunk_start,
+                chunk_end - end,
+            )
+        })
+        .collect()
+}
+
+/// Split text into the token-based chunks used for documents larger than `max_char_buffer`
+///
+/// Uses the default [`Tokenizer`] when `tokenizer` is `None`.
+pub(crate) fn token_chunks(
+    text: &str,
+    max_char_buffer: usize,
+    tokenizer: Option<&dyn Tokenize>,
+) -> LangExtractResult<Vec<TextChunk>> {
+    // Create tokenizer and tokenize the text
+    let default_tokenizer;
+    let tokenizer = match tokenizer {
+        Some(tokenizer) => tokenizer,
+        None => {
+            default_tokenizer = Tokenizer::new()?;
+            &default_tokenizer
+        }
+    };
+    let tokenized_text = tokenizer.tokenize(text)?;
+
+    // Create document for chunking
+    let document = Document {
+        document_id: None,
+        text: text.to_string(),
+        additional_context: None,
+    };
+
+    // Create token-based chunk iterator
+    let chunk_iter = ChunkIterator::new(&tokenized_text, tokenizer, max_char_buffer, Some(&document))?;
+
+    // Collect chunks from iterator
+    let token_chunks: Result<Vec<TokenChunk>, _> = chunk_iter.collect();
+    let token_chunks = token_chunks?;
+
+    // Convert TokenChunks to TextChunks for compatibility with existing pipeline
+    let mut text_chunks = Vec::new();
+    for (i, token_chunk) in token_chunks.iter().enumerate() {
+        let chunk_text = token_chunk.chunk_text(tokenizer)?;
+        let char_interval = token_chunk.char_interval(tokenizer)?;
+        let chunk_len = chunk_text.len();
+
+        let text_chunk = TextChunk {
+            id: i,
+            text: chunk_text,
+            char_offset: char_interval.start_pos.unwrap_or(0),
+            char_length: chunk_len,
+            document_id: None,
+            has_overlap: false,
+            overlap_info: None,
+            section_heading: None,
+        };
+        text_chunks.push(text_chunk);
+    }
+
+    Ok(text_chunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::{CharInterval, ExampleData},
+        inference::ScoredOutput,
+        resolver::ValidationConfig,
+        visualization::{export_document, ExportConfig, ExportFormat},
+        ExtractConfig,
+    };
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Language model that returns a fixed response and counts inference calls
+    struct CountingModel {
+        response: String,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl BaseLanguageModel for CountingModel {
+        async fn infer(
+            &self,
+            batch_prompts: &[String],
+            _kwargs: &HashMap<String, serde_json::Value>,
+        ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
+            self.calls.fetch_add(batch_prompts.len(), Ordering::SeqCst);
+            Ok(batch_prompts
+                .iter()
+                .map(|_| vec![ScoredOutput::from_text(self.response.clone())])
+                .collect())
+        }
+
+        fn model_id(&self) -> &str {
+            "counting-model"
+        }
+
+        fn provider_name(&self) -> &str {
+            "test"
+        }
+    }
+
+    fn create_annotator(calls: Arc<AtomicUsize>) -> Annotator {
+        let model = CountingModel {
+            response: r#"[{"person": "Bob"}]"#.to_string(),
+            calls,
+        };
+        Annotator::new(
+            Box::new(model),
+            PromptTemplateStructured::new(Some("Extract people")),
+            FormatType::Json,
+            false,
+        )
+    }
+
+    fn create_resolver() -> Resolver {
+        let config = ExtractConfig {
+            debug: false,
+            ..Default::default()
+        };
+        Resolver::new(&config, false).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sub_buffer_document_uses_single_inference_call() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let annotator = create_annotator(calls.clone());
+        let text = "Alice met Bob in Paris.";
+
+        let result = annotator
+            .annotate_text(text, &create_resolver(), 1000, 10, None, false, 1, 1)
+            .await
+            .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let extractions = result.extractions.unwrap();
+        assert_eq!(extractions.len(), 1);
+        assert_eq!(extractions[0].char_interval, Some(CharInterval::new(Some(10), Some(13))));
+    }
+
+    #[tokio::test]
+    async fn test_skip_chunking_sends_whole_document() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let annotator = create_annotator(calls.clone()).with_skip_chunking(true);
+        let text = format!("{} Later, Alice met Bob in Paris.", "Filler sentence here.".repeat(20));
+
+        let result = annotator
+            .annotate_text(&text, &create_resolver(), 50, 10, None, false, 1, 1)
+            .await
+            .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let extractions = result.extractions.unwrap();
+        let bob_start = text.find("Bob").unwrap();
+        assert_eq!(
+            extractions[0].char_interval,
+            Some(CharInterval::new(Some(bob_start), Some(bob_start + 3)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strict_validation_fails_on_missing_required_field() {
+        let mut annotator = create_annotator(Arc::new(AtomicUsize::new(0)));
+        annotator.prompt_template.examples.push(ExampleData::new(
+            "Alice lives in Paris.".to_string(),
+            vec![
+                Extraction::new("person".to_string(), "Alice".to_string()),
+                Extraction::new("location".to_string(), "Paris".to_string()),
+            ],
+        ));
+        let validation_config = ValidationConfig {
+            require_all_fields: true,
+            save_raw_outputs: false,
+            ..Default::default()
+        };
+        let text = "Alice met Bob in Paris.";
+
+        let lenient = Resolver::with_validation_config(
+            &ExtractConfig { debug: false, ..Default::default() },
+            false,
+            validation_config.clone(),
+        )
+        .unwrap();
+        let result = annotator
+            .annotate_text(text, &lenient, 1000, 10, None, false, 1, 1)
+            .await;
+        assert!(result.is_ok());
+
+        let strict = Resolver::with_validation_config(
+            &ExtractConfig { debug: false, strict_validation: true, ..Default::default() },
+            false,
+            validation_config,
+        )
+        .unwrap();
+        let error = annotator
+            .annotate_text(text, &strict, 1000, 10, None, false, 1, 1)
+            .await
+            .unwrap_err();
+        match error {
+            LangExtractError::ValidationFailed { errors } => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].field_path.as_deref(), Some("location"));
+            }
+            other => panic!("Expected ValidationFailed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_require_grounding_drops_invented_extractions() {
+        let model = || CountingModel {
+            response: r#"[{"person": "Bob"}, {"person": "Zelda Quinnington"}]"#.to_string(),
+            calls: Arc::new(AtomicUsize::new(0)),
+        };
+        let annotator = |grounding| {
+            Annotator::new(
+                Box::new(model()),
+                PromptTemplateStructured::new(Some("Extract people")),
+                FormatType::Json,
+                false,
+            )
+            .with_grounding(grounding)
+        };
+        let text = "Alice met Bob in Paris.";
+
+        let ungated = annotator(None)
+            .annotate_text(text, &create_resolver(), 1000, 10, None, false, 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(ungated.extraction_count(), 2);
+
+        let grounded = annotator(Some(GroundingPolicy { allow_fuzzy: false }))
+            .annotate_text(text, &create_resolver(), 1000, 10, None, false, 1, 1)
+            .await
+            .unwrap();
+        let extractions = grounded.extractions.unwrap();
+        assert_eq!(extractions.len(), 1);
+        assert_eq!(extractions[0].extraction_text, "Bob");
+        assert_eq!(extractions[0].alignment_status, Some(AlignmentStatus::MatchExact));
+    }
+
+    #[tokio::test]
+    async fn test_keep_raw_outputs_returns_model_response_per_chunk() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let text = "Alice met Bob in Paris.";
+
+        let result = create_annotator(calls.clone())
+            .annotate_text(text, &create_resolver(), 1000, 10, None, false, 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(result.raw_outputs, None);
+
+        let annotator = create_annotator(calls.clone()).with_raw_outputs(true);
+        let result = annotator
+            .annotate_text(text, &create_resolver(), 1000, 10, None, false, 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(result.raw_outputs, Some(vec![r#"[{"person": "Bob"}]"#.to_string()]));
+
+        let long_text = format!("{} Later, Alice met Bob in Paris.", "Filler sentence here.".repeat(20));
+        let result = annotator
+            .annotate_text(&long_text, &create_resolver(), 100, 10, None, false, 1, 10)
+            .await
+            .unwrap();
+        let raw_outputs = result.raw_outputs.unwrap();
+        assert!(raw_outputs.len() > 1);
+        assert!(raw_outputs.iter().all(|raw| raw == r#"[{"person": "Bob"}]"#));
+    }
+
+    /// Language model that stalls on prompts mentioning "slow"
+    struct StallingModel;
+
+    #[async_trait]
+    impl BaseLanguageModel for StallingModel {
+        async fn infer(
+            &self,
+            batch_prompts: &[String],
+            _kwargs: &HashMap<String, serde_json::Value>,
+        ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
+            if batch_prompts.iter().any(|prompt| prompt.contains("slow")) {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            }
+            Ok(batch_prompts
+                .iter()
+                .map(|_| vec![ScoredOutput::from_text(r#"[{"person": "Alice"}]"#.to_string())])
+                .collect())
+        }
+
+        fn model_id(&self) -> &str {
+            "stalling-model"
+        }
+
+        fn provider_name(&self) -> &str {
+            "test"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chunk_timeout_fails_only_the_slow_chunk() {
+        let annotator = Annotator::new(
+            Box::new(StallingModel),
+            PromptTemplateStructured::new(Some("Extract people")),
+            FormatType::Json,
+            false,
+        )
+        .with_chunk_timeout(Some(Duration::from_millis(50)));
+        let resolver = create_resolver();
+        let fast = TextChunk::new(0, "Alice arrived.".to_string(), 0, None);
+        let slow = TextChunk::new(1, "A slow chunk.".to_string(), 15, None);
+
+        let results = futures::future::join_all([
+            annotator.process_chunk(&fast, &resolver, None, false),
+            annotator.process_chunk(&slow, &resolver, None, false),
+        ])
+        .await;
+
+        let fast_result = results[0].as_ref().unwrap();
+        assert!(fast_result.success);
+        assert_eq!(fast_result.extractions.as_ref().map(Vec::len), Some(1));
+
+        let slow_result = results[1].as_ref().unwrap();
+        assert!(!slow_result.success);
+        assert!(slow_result.error.as_deref().unwrap().contains("timed out after 50 ms"));
+    }
+
+    #[tokio::test]
+    async fn test_chunk_timeout_applies_to_unchunked_text() {
+        let annotator = Annotator::new(
+            Box::new(StallingModel),
+            PromptTemplateStructured::new(Some("Extract people")),
+            FormatType::Json,
+            false,
+        )
+        .with_chunk_timeout(Some(Duration::from_millis(50)));
+
+        let error = tokio::time::timeout(
+            Duration::from_secs(5),
+            annotator.annotate_text("A slow chunk.", &create_resolver(), 1000, 10, None, false, 1, 1),
+        )
+        .await
+        .expect("timeout should cut the stalled call short")
+        .unwrap_err();
+        assert!(error.to_string().contains("timed out after 50 ms"));
+    }
+
+    /// Language model that records every prompt it receives
+    struct RecordingModel {
+        prompts: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl BaseLanguageModel for RecordingModel {
+        async fn infer(
+            &self,
+            batch_prompts: &[String],
+            _kwargs: &HashMap<String, serde_json::Value>,
+        ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
+            self.prompts.lock().unwrap().extend(batch_prompts.iter().cloned());
+            Ok(batch_prompts
+                .iter()
+                .map(|_| vec![ScoredOutput::from_text("[]".to_string())])
+                .collect())
+        }
+
+        fn model_id(&self) -> &str {
+            "recording-model"
+        }
+
+        fn provider_name(&self) -> &str {
+            "test"
+        }
+    }
+
+    /// Context provider naming the chunk it was asked about
+    struct ChunkIdContext;
+
+    impl ContextProvider for ChunkIdContext {
+        fn context_for_chunk(&self, chunk: &TextChunk) -> Option<String> {
+            Some(format!("chunk #{}", chunk.id))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_context_provider_supplies_per_chunk_context() {
+        let prompts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let annotator = Annotator::new(
+            Box::new(RecordingModel { prompts: prompts.clone() }),
+            PromptTemplateStructured::new(Some("Extract people")),
+            FormatType::Json,
+            false,
+        )
+        .with_context_provider(Some(Arc::new(ChunkIdContext)));
+        let text = "Alice met Bob at the station. ".repeat(10);
+
+        annotator
+            .annotate_text(&text, &create_resolver(), 100, 10, Some("static context"), false, 1, 10)
+            .await
+            .unwrap();
+
+        let prompts = prompts.lock().unwrap();
+        assert!(prompts.len() > 1);
+        let mut ids: Vec<usize> = prompts
+            .iter()
+            .map(|prompt| {
+                assert!(!prompt.contains("static context"));
+                let start = prompt.find("chunk #").expect("chunk context in prompt") + "chunk #".len();
+                prompt[start..].chars().take_while(char::is_ascii_digit).collect::<String>().parse().unwrap()
+            })
+            .collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (0..prompts.len()).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_auto_shrink_recovers_truncated_chunk() {
+        let text = "Alice arrived at the station early in the morning and waited patiently for the long delayed regional train. \
+                    Bob left the office late in the evening after finishing every one of his quarterly sales reports for the team.";
+        assert!(text.len() / 2 >= MIN_AUTO_SHRINK_CHAR_BUFFER);
+        let build = |auto_shrink: bool| {
+            let model = crate::providers::MockModel::from_fn(|prompt| {
+                match (prompt.contains("Alice"), prompt.contains("Bob")) {
+                    (true, true) => r#"[{"person": "Alice"}, {"person": "Bo"#,
+                    (true, false) => r#"[{"person": "Alice"}]"#,
+                    (false, true) => r#"[{"person": "Bob"}]"#,
+                    (false, false) => "[]",
+                }
+                .to_string()
+            });
+            Annotator::new(
+                Box::new(model),
+                PromptTemplateStructured::new(Some("Extract people")),
+                FormatType::Json,
+                false,
+            )
+            .with_auto_shrink_on_truncation(auto_shrink)
+        };
+        let resolver = create_resolver();
+        let people = |document: &AnnotatedDocument| {
+            document.extractions.iter().flatten().map(|e| e.extraction_text.clone()).collect::<Vec<_>>()
+        };
+
+        let truncated = build(false).annotate_text(text, &resolver, 1000, 1, None, false, 1, 1).await.unwrap();
+        assert_eq!(people(&truncated), vec!["Alice"]);
+
+        let recovered = build(true).annotate_text(text, &resolver, 1000, 1, None, false, 1, 1).await.unwrap();
+        assert_eq!(people(&recovered), vec!["Alice", "Bob"]);
+
+        let chunk = TextChunk::new(3, text.to_string(), 40, None);
+        let result = build(true).process_chunk(&chunk, &resolver, None, false).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.chunk_id, 3);
+        let bob = &result.extractions.unwrap()[1];
+        assert_eq!(bob.char_interval.as_ref().and_then(|i| i.start_pos), text.find("Bob").map(|pos| pos + 40));
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_resumes_only_missing_chunks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("checkpoint.jsonl");
+        let text = "Alice went to the market. Bob stayed at home. Carol read a book. Dave fixed the car.";
+        let build = |calls: Arc<AtomicUsize>| {
+            create_annotator(calls).with_checkpoint(Some(ChunkCheckpoint::new(&path)))
+        };
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let first = build(calls.clone())
+            .annotate_text(text, &create_resolver(), 30, 2, None, false, 1, 2)
+            .await
+            .unwrap();
+        let chunk_count = calls.load(Ordering::SeqCst);
+        assert!(chunk_count >= 3);
+
+        // Simulate a crash after the first two chunks were recorded
+        let content = std::fs::read_to_string(&path).unwrap();
+        let partial: Vec<_> = content.lines().take(2).collect();
+        std::fs::write(&path, partial.join("\n") + "\n").unwrap();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let resumed = build(calls.clone())
+            .annotate_text(text, &create_resolver(), 30, 2, None, false, 1, 2)
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), chunk_count - 2);
+        assert_eq!(resumed.extraction_count(), first.extraction_count());
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), chunk_count);
+
+        // A different request setting reuses none of the recorded chunks
+        let calls = Arc::new(AtomicUsize::new(0));
+        build(calls.clone())
+            .with_temperature(0.0)
+            .annotate_text(text, &create_resolver(), 30, 2, None, false, 1, 2)
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), chunk_count);
+    }
+
+    /// Language model that answers with the person named in the prompt, slower for earlier names
+    struct DelayedModel;
+
+    #[async_trait]
+    impl BaseLanguageModel for DelayedModel {
+        async fn infer(
+            &self,
+            batch_prompts: &[String],
+            _kwargs: &HashMap<String, serde_json::Value>,
+        ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
+            let names = ["Alice", "Bob", "Carol", "Dave"];
+            let mut outputs = Vec::new();
+            for prompt in batch_prompts {
+                let (position, name) = names.iter().enumerate().find(|(_, name)| prompt.contains(*name)).unwrap();
+                tokio::time::sleep(Duration::from_millis(40 * (names.len() - position) as u64)).await;
+                outputs.push(vec![ScoredOutput::from_text(format!(r#"[{{"person": "{}"}}]"#, name))]);
+            }
+            Ok(outputs)
+        }
+
+        fn model_id(&self) -> &str {
+            "delayed-model"
+        }
+
+        fn provider_name(&self) -> &str {
+            "test"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_chunks_keep_document_order() {
+        let annotator = Annotator::new(
+            Box::new(DelayedModel),
+            PromptTemplateStructured::new(Some("Extract people")),
+            FormatType::Json,
+            false,
+        );
+        let text = "Alice went to the market. Bob stayed at home. Carol read a book. Dave fixed the car.";
+
+        let result = annotator
+            .annotate_text(text, &create_resolver(), 30, 4, None, false, 1, 4)
+            .await
+            .unwrap();
+
+        let people: Vec<_> = result.extractions.unwrap().into_iter().map(|e| e.extraction_text).collect();
+        assert_eq!(people, vec!["Alice", "Bob", "Carol", "Dave"]);
+    }
+
+    #[tokio::test]
+    async fn test_reformat_on_parse_failure_recovers_invalid_json() {
+        let reformat_requests = Arc::new(AtomicUsize::new(0));
+        let counter = reformat_requests.clone();
+        let annotator = || {
+            let counter = counter.clone();
+            let model = crate::providers::MockModel::from_fn(move |prompt| {
+                if prompt.contains("could not be parsed") {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    r#"[{"person": "Bob"}]"#.to_string()
+                } else {
+                    "Sure! person: Bob, {broken".to_string()
+                }
+            });
+            Annotator::new(
+                Box::new(model),
+                PromptTemplateStructured::new(Some("Extract people")),
+                FormatType::Json,
+                false,
+            )
+        };
+        let text = "Alice met Bob in Paris.";
+
+        let result = annotator()
+            .annotate_text(text, &create_resolver(), 1000, 10, None, false, 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(result.extractions.unwrap()[0].extraction_class, "raw_response");
+        assert_eq!(reformat_requests.load(Ordering::SeqCst), 0);
+
+        let result = annotator()
+            .with_reformat_on_parse_failure(2)
+            .with_raw_outputs(true)
+            .annotate_text(text, &create_resolver(), 1000, 10, None, false, 1, 1)
+            .await
+            .unwrap();
+        let extractions = result.extractions.unwrap();
+        assert_eq!(extractions.len(), 1);
+        assert_eq!(extractions[0].extraction_class, "person");
+        assert_eq!(extractions[0].extraction_text, "Bob");
+        assert_eq!(result.raw_outputs, Some(vec![r#"[{"person": "Bob"}]"#.to_string()]));
+        assert_eq!(reformat_requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_context_window_shrinks_chunks_for_large_prompts() {
+        let text = "Alice met Bob in Paris. ".repeat(20);
+        let chunk_calls = |example_count: usize| {
+            let text = text.clone();
+            async move {
+                let calls = Arc::new(AtomicUsize::new(0));
+                let mut annotator = create_annotator(calls.clone()).with_context_window(Some(300));
+                annotator.prompt_template.examples = (0..example_count)
+                    .map(|i| {
+                        ExampleData::new(
+                            format!("Person number {} is called Carol and lives in a small town near Berlin.", i),
+                            vec![Extraction::new("person".to_string(), "Carol".to_string())],
+                        )
+                    })
+                    .collect();
+                annotator
+                    .annotate_text(&text, &create_resolver(), 1000, 10, None, false, 1, 1)
+                    .await
+                    .unwrap();
+                calls.load(Ordering::SeqCst)
+            }
+        };
+
+        let few_examples = chunk_calls(1).await;
+        let many_examples = chunk_calls(8).await;
+        assert_eq!(few_examples, 1);
+        assert!(many_examples > few_examples);
+
+        let mut annotator = create_annotator(Arc::new(AtomicUsize::new(0))).with_context_window(Some(10));
+        annotator.prompt_template.examples = Vec::new();
+        assert!(annotator
+            .annotate_text(&text, &create_resolver(), 1000, 10, None, false, 1, 1)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_min_chunks_splits_sub_buffer_document() {
+        let text = "Alice met Bob in Paris. ".repeat(20);
+        let chunks = even_chunks(&text, 4);
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(chunks.iter().map(|chunk| chunk.core_text()).collect::<String>(), text);
+        assert!(chunks.iter().skip(1).all(|chunk| chunk.has_overlap));
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let annotator = create_annotator(calls.clone()).with_min_chunks(Some(4));
+        annotator
+            .annotate_text(&text, &create_resolver(), 1000, 10, None, false, 1, 4)
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_extractions_tagged_with_producing_chunk() {
+        let names = ["Alice", "Bob", "Carol", "Dan", "Erin", "Frank"];
+        let model = crate::providers::MockModel::from_fn(move |prompt| {
+            // Only look at the chunk, which follows the last "Input:" marker
+            let chunk = prompt.rsplit("Input:").next().unwrap_or(prompt);
+            let found: Vec<String> = names
+                .iter()
+                .filter(|name| chunk.contains(*name))
+                .map(|name| format!(r#"{{"person": "{}"}}"#, name))
+                .collect();
+            format!("[{}]", found.join(","))
+        });
+        let annotator = Annotator::new(
+            Box::new(model),
+            PromptTemplateStructured::new(Some("Extract people")),
+            FormatType::Json,
+            false,
+        );
+        let text = "Alice met Bob. Carol met Dan. Erin met Frank.";
+
+        let result = annotator
+            .annotate_text(text, &create_resolver(), 16, 10, None, false, 1, 1)
+            .await
+            .unwrap();
+
+        let chunks = token_chunks(text, 16, None).unwrap();
+        assert!(chunks.len() > 1);
+        let extractions = result.extractions.unwrap();
+        assert_eq!(extractions.len(), names.len());
+        for extraction in &extractions {
+            let chunk = &chunks[extraction.chunk_id.unwrap()];
+            assert!(chunk.text.contains(&extraction.extraction_text), "{:?} not in {:?}", extraction, chunk.text);
+        }
+
+        // Both flat exports carry the chunk id of every extraction
+        let chunk_ids: Vec<usize> = extractions.iter().map(|e| e.chunk_id.unwrap()).collect();
+        let document = AnnotatedDocument::with_extractions(extractions, text.to_string());
+        let json_config = ExportConfig { format: ExportFormat::Json, show_chunk_ids: true, ..Default::default() };
+        let exported: serde_json::Value =
+            serde_json::from_str(&export_document(&document, &json_config).unwrap()).unwrap();
+        let json_ids: Vec<usize> = exported["extractions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["chunk_id"].as_u64().unwrap() as usize)
+            .collect();
+        assert_eq!(json_ids, chunk_ids);
+
+        let csv_config = ExportConfig { format: ExportFormat::Csv, show_chunk_ids: true, ..Default::default() };
+        let csv = export_document(&document, &csv_config).unwrap();
+        let csv_ids: Vec<usize> = csv
+            .lines()
+            .skip(1)
+            .map(|line| line.rsplit(',').next().unwrap().parse().unwrap())
+            .collect();
+        assert_eq!(csv_ids, chunk_ids);
+    }
+}
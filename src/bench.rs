@@ -0,0 +1,189 @@
+//! Latency and throughput benchmarks for language model providers.
+//!
+//! A benchmark renders one extraction prompt and sends it to a single model
+//! instance a fixed number of times, `max_workers` requests at a time.
+
+use crate::{
+    build_prompt_template,
+    data::ExampleData,
+    exceptions::LangExtractResult,
+    inference::BaseLanguageModel,
+    resolver::Resolver,
+    tokenizer::Tokenizer,
+    ExtractConfig,
+};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Summary of a benchmark run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    /// Model the requests were sent to
+    pub model_id: String,
+    /// Number of requests sent
+    pub runs: usize,
+    /// Requests that failed before returning a response
+    pub errors: usize,
+    /// Mean latency of successful requests, in milliseconds
+    pub mean_latency_ms: f64,
+    /// 95th percentile latency of successful requests, in milliseconds
+    pub p95_latency_ms: f64,
+    /// Response tokens generated per second of wall-clock time
+    ///
+    /// Providers do not report token usage to the library, so response
+    /// tokens are counted with the default [`Tokenizer`].
+    pub tokens_per_sec: f64,
+    /// Fraction of requests whose response parsed into extractions (0.0 to 1.0)
+    pub parse_success_rate: f64,
+}
+
+impl BenchReport {
+    /// Render the report as a small two-column text table
+    pub fn to_table(&self) -> String {
+        let rows = [
+            ("model", self.model_id.clone()),
+            ("runs", self.runs.to_string()),
+            ("errors", self.errors.to_string()),
+            ("mean latency", format!("{:.1} ms", self.mean_latency_ms)),
+            ("p95 latency", format!("{:.1} ms", self.p95_latency_ms)),
+            ("tokens/sec", format!("{:.1}", self.tokens_per_sec)),
+            ("parse success rate", format!("{:.1}%", self.parse_success_rate * 100.0)),
+        ];
+        let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        rows.iter()
+            .map(|(label, value)| format!("{:<width$}  {}\n", label, value, width = width))
+            .collect()
+    }
+}
+
+/// Outcome of a single benchmark request
+struct BenchSample {
+    latency: Duration,
+    response_tokens: usize,
+    parsed: bool,
+}
+
+/// Send the extraction prompt for `text` to `model` `runs` times and summarize latency and parsing
+pub async fn run_bench(
+    model: &dyn BaseLanguageModel,
+    text: &str,
+    prompt_description: Option<&str>,
+    examples: &[ExampleData],
+    config: &ExtractConfig,
+    runs: usize,
+) -> LangExtractResult<BenchReport> {
+    let prompt = build_prompt_template(prompt_description, examples, config)
+        .render(text, config.additional_context.as_deref())?;
+    let resolver = Resolver::new(config, model.requires_fence_output())?;
+    let tokenizer = Tokenizer::new()?;
+    let expected_fields: Vec<String> = examples
+        .iter()
+        .flat_map(|example| example.extractions.iter())
+        .map(|extraction| extraction.extraction_class.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut kwargs = HashMap::new();
+    kwargs.insert("temperature".to_string(), serde_json::json!(config.temperature));
+
+    let started = Instant::now();
+    let outcomes: Vec<Option<BenchSample>> = stream::iter(0..runs)
+        .map(|_| async {
+            let request_started = Instant::now();
+            let outputs = match model.infer(std::slice::from_ref(&prompt), &kwargs).await {
+                Ok(outputs) => outputs,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Benchmark request failed");
+                    return None;
+                }
+            };
+            let latency = request_started.elapsed();
+            let response = outputs
+                .first()
+                .and_then(|batch| batch.first())
+                .map(|output| output.text())
+                .unwrap_or("");
+            Some(BenchSample {
+                latency,
+                response_tokens: tokenizer.tokenize(response).map(|tokens| tokens.len()).unwrap_or(0),
+                parsed: resolver.validate_and_parse(response, &expected_fields).is_ok(),
+            })
+        })
+        .buffer_unordered(config.max_workers.max(1))
+        .collect()
+        .await;
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let samples: Vec<BenchSample> = outcomes.into_iter().flatten().collect();
+    let mut latencies: Vec<f64> = samples.iter().map(|sample| sample.latency.as_secs_f64() * 1000.0).collect();
+    latencies.sort_by(|a, b| a.total_cmp(b));
+    let mean_latency_ms = if latencies.is_empty() {
+        0.0
+    } else {
+        latencies.iter().sum::<f64>() / latencies.len() as f64
+    };
+    let p95_latency_ms = latencies
+        .get(((latencies.len() as f64 * 0.95).ceil() as usize).saturating_sub(1))
+        .copied()
+        .unwrap_or(0.0);
+    let response_tokens: usize = samples.iter().map(|sample| sample.response_tokens).sum();
+    let parsed = samples.iter().filter(|sample| sample.parsed).count();
+
+    Ok(BenchReport {
+        model_id: model.model_id().to_string(),
+        runs,
+        errors: runs - samples.len(),
+        mean_latency_ms,
+        p95_latency_ms,
+        tokens_per_sec: if elapsed > 0.0 { response_tokens as f64 / elapsed } else { 0.0 },
+        parse_success_rate: if runs == 0 { 0.0 } else { parsed as f64 / runs as f64 },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{data::Extraction, providers::MockModel};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_bench_reports_latency_and_parse_success() {
+        let calls = AtomicUsize::new(0);
+        let model = MockModel::from_fn(move |_| {
+            if calls.fetch_add(1, Ordering::SeqCst) % 4 == 3 {
+                "not json {".to_string()
+            } else {
+                r#"[{"person": "Alice"}]"#.to_string()
+            }
+        });
+        let examples = vec![ExampleData::new(
+            "John met Mary".to_string(),
+            vec![Extraction::new("person".to_string(), "John".to_string())],
+        )];
+        let config = ExtractConfig {
+            max_workers: 2,
+            debug: false,
+            ..Default::default()
+        };
+
+        let report = run_bench(&model, "Alice met Bob.", Some("Extract people"), &examples, &config, 8)
+            .await
+            .unwrap();
+
+        assert_eq!(model.prompts().len(), 8);
+        assert_eq!(report.model_id, "mock");
+        assert_eq!(report.runs, 8);
+        assert_eq!(report.errors, 0);
+        assert_eq!(report.parse_success_rate, 0.75);
+        assert!(report.mean_latency_ms >= 0.0);
+        assert!(report.p95_latency_ms >= report.mean_latency_ms);
+
+        let table = report.to_table();
+        assert!(table.contains("mean latency"));
+        assert!(table.contains("p95 latency"));
+        assert!(table.contains("parse success rate  75.0%"));
+    }
+}
@@ -0,0 +1,152 @@
+//! On-disk caching of raw model outputs.
+//!
+//! Responses are keyed by a stable hash of the model id, prompt, temperature
+//! and the other request settings so that re-running an identical extraction
+//! does not call the language model again.
+
+use crate::exceptions::{LangExtractError, LangExtractResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Default directory used when no cache directory is configured
+pub const DEFAULT_CACHE_DIR: &str = "./.langextract_cache";
+
+/// A single cached model response as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Cache key the entry was stored under
+    key: String,
+    /// Model that produced the output
+    model_id: String,
+    /// Raw model output
+    output: String,
+}
+
+/// File-backed cache of raw model outputs
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    /// Directory holding one JSON file per cached response
+    dir: PathBuf,
+    /// Whether responses sampled with temperature > 0 are cached
+    cache_nonzero_temperature: bool,
+}
+
+impl ResponseCache {
+    /// Create a cache rooted at `dir`, creating the directory if needed
+    pub fn new(dir: impl Into<PathBuf>) -> LangExtractResult<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| {
+            LangExtractError::configuration(format!(
+                "Failed to create cache directory '{}': {}",
+                dir.display(),
+                e
+            ))
+        })?;
+
+        Ok(Self {
+            dir,
+            cache_nonzero_temperature: true,
+        })
+    }
+
+    /// Set whether responses sampled with temperature > 0 are cached
+    pub fn with_nonzero_temperature(mut self, enabled: bool) -> Self {
+        self.cache_nonzero_temperature = enabled;
+        self
+    }
+
+    /// Get the cache directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Whether a request with the given temperature should use the cache
+    pub fn accepts_temperature(&self, temperature: Option<f64>) -> bool {
+        self.cache_nonzero_temperature || temperature.is_none_or(|t| t <= 0.0)
+    }
+
+    /// Compute the cache key for a request
+    ///
+    /// `settings` is a serialized form of everything else that shapes the
+    /// request, such as the schema, output limits and extra body fields.
+    pub fn key(model_id: &str, prompt: &str, temperature: Option<f64>, settings: Option<&str>) -> String {
+        let temperature = temperature.map(|t| t.to_string()).unwrap_or_default();
+        let parts = [model_id, prompt, temperature.as_str(), settings.unwrap_or("")];
+
+        // Two FNV-1a passes with different offsets give a stable 128-bit key
+        let mut high: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut low: u64 = 0x6c62_272e_07bb_0142;
+        for part in parts {
+            for byte in part.len().to_le_bytes().iter().chain(part.as_bytes()) {
+                high = (high ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3);
+                low = (low ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3).rotate_left(5);
+            }
+        }
+
+        format!("{:016x}{:016x}", high, low)
+    }
+
+    /// Look up a cached output
+    pub fn get(&self, key: &str) -> Option<String> {
+        let content = fs::read_to_string(self.entry_path(key)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        (entry.key == key).then_some(entry.output)
+    }
+
+    /// Store an output under `key`
+    pub fn put(&self, key: &str, model_id: &str, output: &str) -> LangExtractResult<()> {
+        let entry = CacheEntry {
+            key: key.to_string(),
+            model_id: model_id.to_string(),
+            output: output.to_string(),
+        };
+        fs::write(self.entry_path(key), serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_key_is_stable_and_distinct() {
+        let key = ResponseCache::key("mistral", "prompt", Some(0.0), None);
+        assert_eq!(key, ResponseCache::key("mistral", "prompt", Some(0.0), None));
+        assert_eq!(key.len(), 32);
+
+        assert_ne!(key, ResponseCache::key("llama", "prompt", Some(0.0), None));
+        assert_ne!(key, ResponseCache::key("mistral", "prompt 2", Some(0.0), None));
+        assert_ne!(key, ResponseCache::key("mistral", "prompt", Some(0.5), None));
+        assert_ne!(key, ResponseCache::key("mistral", "prompt", Some(0.0), Some("{\"format\":\"json\"}")));
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ResponseCache::new(temp_dir.path().join("cache")).unwrap();
+        let key = ResponseCache::key("mistral", "prompt", None, None);
+
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, "mistral", "{\"name\": \"John\"}").unwrap();
+        assert_eq!(cache.get(&key), Some("{\"name\": \"John\"}".to_string()));
+    }
+
+    #[test]
+    fn test_cache_temperature_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ResponseCache::new(temp_dir.path()).unwrap();
+        assert!(cache.accepts_temperature(Some(0.7)));
+
+        let cache = cache.with_nonzero_temperature(false);
+        assert!(cache.accepts_temperature(None));
+        assert!(cache.accepts_temperature(Some(0.0)));
+        assert!(!cache.accepts_temperature(Some(0.7)));
+    }
+}
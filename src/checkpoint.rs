@@ -0,0 +1,148 @@
+//! Checkpointing of completed chunks for resumable extraction.
+//!
+//! Every successfully processed chunk is appended to the checkpoint file as one
+//! JSON line. When an extraction is restarted with the same checkpoint file,
+//! chunks already recorded there are loaded instead of being sent to the model.
+
+use crate::{
+    cache::ResponseCache,
+    chunking::{ChunkResult, TextChunk},
+    exceptions::{LangExtractError, LangExtractResult},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// A single completed chunk as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CheckpointEntry {
+    /// Key identifying the chunk (see [`ChunkCheckpoint::key`])
+    key: String,
+    /// Result produced for the chunk
+    result: ChunkResult,
+}
+
+/// Append-only JSON Lines file of completed chunk results
+#[derive(Debug, Clone)]
+pub struct ChunkCheckpoint {
+    path: PathBuf,
+}
+
+impl ChunkCheckpoint {
+    /// Use the checkpoint file at `path`; it is created on the first recorded chunk
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Get the checkpoint file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Key for `chunk` when sent to `model_id` as the rendered `prompt` at `temperature`
+    ///
+    /// The rendered prompt carries the chunk text along with the examples, prompt
+    /// variables, class descriptions and every other prompt setting, and the key
+    /// adds the chunk offset, so a checkpoint shared by several documents or runs
+    /// only ever resumes identical work.
+    pub fn key(model_id: &str, prompt: &str, temperature: f32, chunk: &TextChunk) -> String {
+        ResponseCache::key(
+            model_id,
+            prompt,
+            Some(f64::from(temperature)),
+            Some(&chunk.char_offset.to_string()),
+        )
+    }
+
+    /// Load the completed chunks recorded so far, keyed by [`ChunkCheckpoint::key`]
+    ///
+    /// A missing file yields no entries. Lines that do not parse, such as one
+    /// cut short by a crash, are skipped.
+    pub fn load(&self) -> LangExtractResult<HashMap<String, ChunkResult>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => {
+                return Err(LangExtractError::configuration(format!(
+                    "Failed to read checkpoint '{}': {}",
+                    self.path.display(),
+                    e
+                )))
+            }
+        };
+
+        let mut completed = HashMap::new();
+        for (line_number, line) in content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            match serde_json::from_str::<CheckpointEntry>(line) {
+                Ok(entry) => {
+                    completed.insert(entry.key, entry.result);
+                }
+                Err(e) => tracing::warn!(line = line_number + 1, error = %e, "Skipping unreadable checkpoint entry"),
+            }
+        }
+
+        Ok(completed)
+    }
+
+    /// Append a completed chunk to the checkpoint file
+    ///
+    /// A line left unterminated by a crash is closed first, so the new entry
+    /// starts on a line of its own.
+    pub fn record(&self, key: &str, result: &ChunkResult) -> LangExtractResult<()> {
+        let entry = CheckpointEntry {
+            key: key.to_string(),
+            result: result.clone(),
+        };
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(&self.path)?;
+        if file.metadata()?.len() > 0 {
+            let mut last = [0u8];
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                line.insert(0, '\n');
+            }
+        }
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Extraction;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_checkpoint_round_trip_skips_partial_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let checkpoint = ChunkCheckpoint::new(temp_dir.path().join("run.jsonl"));
+        assert!(checkpoint.load().unwrap().is_empty());
+
+        let chunk = TextChunk::new(0, "Alice met Bob.".to_string(), 0, None);
+        let key = ChunkCheckpoint::key("mock", "Extract people\nAlice met Bob.", 0.5, &chunk);
+        let result = ChunkResult::success(0, vec![Extraction::new("person".to_string(), "Alice".to_string())], 0, 14);
+        checkpoint.record(&key, &result).unwrap();
+
+        let mut file = OpenOptions::new().append(true).open(checkpoint.path()).unwrap();
+        file.write_all(b"{\"key\": \"trunc").unwrap();
+
+        let next = TextChunk::new(1, "Carol left.".to_string(), 15, None);
+        let next_key = ChunkCheckpoint::key("mock", "Extract people\nCarol left.", 0.5, &next);
+        let next_result = ChunkResult::success(1, vec![Extraction::new("person".to_string(), "Carol".to_string())], 15, 26);
+        checkpoint.record(&next_key, &next_result).unwrap();
+
+        let completed = checkpoint.load().unwrap();
+        assert_eq!(completed.len(), 2);
+        assert_eq!(completed[&key].extractions.as_ref().unwrap()[0].extraction_text, "Alice");
+        assert_eq!(completed[&next_key].extractions.as_ref().unwrap()[0].extraction_text, "Carol");
+        assert_ne!(key, ChunkCheckpoint::key("mock", "Extract places\nAlice met Bob.", 0.5, &chunk));
+        assert_ne!(key, ChunkCheckpoint::key("mock", "Extract people\nAlice met Bob.", 0.0, &chunk));
+    }
+}
@@ -7,11 +7,13 @@
 
 use crate::{
     data::{AnnotatedDocument, Document, Extraction, CharInterval},
-    exceptions::LangExtractResult,
-    tokenizer::{TokenInterval, TokenizedText, Tokenizer, SentenceIterator},
+    exceptions::{LangExtractError, LangExtractResult},
+    tokenizer::{TokenInterval, TokenizedText, Tokenize, SentenceIterator},
 };
 use regex::Regex;
 use semchunk_rs::Chunker;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
 
 /// Different strategies for chunking text
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,6 +31,8 @@ pub enum ChunkingStrategy {
     Adaptive,
     /// Semantic chunking using embeddings and content understanding (RECOMMENDED)
     Semantic,
+    /// Split Markdown at heading boundaries, never inside code fences
+    Markdown,
 }
 
 /// A chunk of text with metadata
@@ -48,6 +52,8 @@ pub struct TextChunk {
     pub has_overlap: bool,
     /// Overlap information (start and end overlap lengths)
     pub overlap_info: Option<(usize, usize)>,
+    /// Nearest preceding section heading (Markdown chunking)
+    pub section_heading: Option<String>,
 }
 
 impl TextChunk {
@@ -67,6 +73,7 @@ impl TextChunk {
             document_id,
             has_overlap: false,
             overlap_info: None,
+            section_heading: None,
         }
     }
 
@@ -88,9 +95,16 @@ impl TextChunk {
             document_id,
             has_overlap: overlap_start > 0 || overlap_end > 0,
             overlap_info: Some((overlap_start, overlap_end)),
+            section_heading: None,
         }
     }
 
+    /// Set the section heading this chunk belongs to
+    pub fn with_section_heading(mut self, heading: Option<String>) -> Self {
+        self.section_heading = heading;
+        self
+    }
+
     /// Get the character interval for this chunk in the original document
     pub fn char_interval(&self) -> CharInterval {
         CharInterval::new(
@@ -162,7 +176,7 @@ impl TokenChunk {
     }
 
     /// Get the chunk text (requires tokenizer to reconstruct)
-    pub fn chunk_text(&self, tokenizer: &Tokenizer) -> LangExtractResult<String> {
+    pub fn chunk_text(&self, tokenizer: &dyn Tokenize) -> LangExtractResult<String> {
         if let Some(ref cached) = self.chunk_text {
             return Ok(cached.clone());
         }
@@ -191,7 +205,7 @@ impl TokenChunk {
     }
 
     /// Get the sanitized chunk text (removes excess whitespace)
-    pub fn sanitized_chunk_text(&self, tokenizer: &Tokenizer) -> LangExtractResult<String> {
+    pub fn sanitized_chunk_text(&self, tokenizer: &dyn Tokenize) -> LangExtractResult<String> {
         let text = self.chunk_text(tokenizer)?;
         Ok(sanitize_text(&text)?)
     }
@@ -202,7 +216,7 @@ impl TokenChunk {
     }
 
     /// Get the character interval corresponding to the token interval
-    pub fn char_interval(&self, tokenizer: &Tokenizer) -> LangExtractResult<CharInterval> {
+    pub fn char_interval(&self, tokenizer: &dyn Tokenize) -> LangExtractResult<CharInterval> {
         if let Some(ref cached) = self.char_interval {
             return Ok(cached.clone());
         }
@@ -289,6 +303,7 @@ pub struct TextChunker {
     config: ChunkingConfig,
     sentence_regex: Regex,
     paragraph_regex: Regex,
+    tokenizer: Option<Box<dyn Tokenize>>,
 }
 
 impl TextChunker {
@@ -309,9 +324,19 @@ impl TextChunker {
             config,
             sentence_regex,
             paragraph_regex,
+            tokenizer: None,
         }
     }
 
+    /// Split semantic and adaptive chunks at the sentence boundaries found by `tokenizer`
+    ///
+    /// Needed for languages written without spaces, e.g. with a [`crate::tokenizer::CjkTokenizer`].
+    /// Chunks stay within `max_chunk_size` bytes unless a single token is longer.
+    pub fn with_tokenizer(mut self, tokenizer: Box<dyn Tokenize>) -> Self {
+        self.tokenizer = Some(tokenizer);
+        self
+    }
+
     /// Chunk a document into smaller pieces
     pub fn chunk_document(&self, document: &Document) -> LangExtractResult<Vec<TextChunk>> {
         self.chunk_text(&document.text, document.document_id.clone())
@@ -330,6 +355,7 @@ impl TextChunker {
             ChunkingStrategy::Paragraph => self.chunk_by_paragraphs(text, document_id),
             ChunkingStrategy::Adaptive => self.chunk_adaptive(text, document_id),
             ChunkingStrategy::Semantic => self.chunk_semantic(text, document_id),
+            ChunkingStrategy::Markdown => self.chunk_markdown(text, document_id),
         }
     }
 
@@ -421,6 +447,10 @@ impl TextChunker {
 
     /// Semantic chunking using embeddings and content understanding
     fn chunk_semantic(&self, text: &str, document_id: Option<String>) -> LangExtractResult<Vec<TextChunk>> {
+        if let Some(tokenizer) = &self.tokenizer {
+            return self.chunk_by_tokens(text, tokenizer.as_ref(), document_id);
+        }
+
         // Create a simple token counter (word-based for now)
         // In a real implementation, you'd use tiktoken or similar for more accurate counting
         let token_counter = Box::new(|s: &str| s.split_whitespace().count());
@@ -491,6 +521,89 @@ impl TextChunker {
         Ok(final_chunks)
     }
 
+    /// Token-based chunking at the sentence boundaries found by `tokenizer`
+    fn chunk_by_tokens(
+        &self,
+        text: &str,
+        tokenizer: &dyn Tokenize,
+        document_id: Option<String>,
+    ) -> LangExtractResult<Vec<TextChunk>> {
+        let tokenized = tokenizer.tokenize(text)?;
+        let document = Document::new(text.to_string());
+        let chunk_iter = ChunkIterator::new(&tokenized, tokenizer, self.config.max_chunk_size, Some(&document))?;
+
+        let mut chunks = Vec::new();
+        for (chunk_id, token_chunk) in chunk_iter.enumerate() {
+            let token_chunk = token_chunk?;
+            let start_pos = token_chunk.char_interval(tokenizer)?.start_pos.unwrap_or(0);
+            let chunk_text = token_chunk.chunk_text(tokenizer)?;
+            chunks.push(TextChunk::new(chunk_id, chunk_text, start_pos, document_id.clone()));
+        }
+
+        Ok(chunks)
+    }
+
+    /// Markdown chunking: one chunk per heading section, sub-splitting oversized
+    /// sections at blank lines while keeping fenced code blocks whole
+    fn chunk_markdown(&self, text: &str, document_id: Option<String>) -> LangExtractResult<Vec<TextChunk>> {
+        let max_size = self.config.max_chunk_size.max(1);
+        let mut chunks = Vec::new();
+
+        for (section_start, section_end, heading) in markdown_sections(text) {
+            let mut ranges = Vec::new();
+
+            if section_end - section_start <= max_size {
+                ranges.push((section_start, section_end));
+            } else {
+                // Greedily pack blocks into chunks no larger than max_size
+                let mut current: Option<(usize, usize)> = None;
+                for (block_start, block_end, is_code) in markdown_blocks(text, section_start, section_end) {
+                    if let Some((start, end)) = current {
+                        if block_end - start <= max_size {
+                            current = Some((start, block_end));
+                            continue;
+                        }
+                        ranges.push((start, end));
+                        current = None;
+                    }
+
+                    if block_end - block_start <= max_size || is_code {
+                        current = Some((block_start, block_end));
+                    } else {
+                        // Oversized prose block: hard split at character boundaries
+                        let mut pos = block_start;
+                        while pos < block_end {
+                            let mut split = (pos + max_size).min(block_end);
+                            while !text.is_char_boundary(split) {
+                                split -= 1;
+                            }
+                            if split == pos {
+                                split = pos + text[pos..].chars().next().map_or(1, |c| c.len_utf8());
+                            }
+                            ranges.push((pos, split));
+                            pos = split;
+                        }
+                    }
+                }
+                if let Some(range) = current {
+                    ranges.push(range);
+                }
+            }
+
+            for (start, end) in ranges {
+                let chunk = TextChunk::new(chunks.len(), text[start..end].to_string(), start, document_id.clone())
+                    .with_section_heading(heading.clone());
+                chunks.push(chunk);
+            }
+        }
+
+        if chunks.is_empty() {
+            chunks.push(TextChunk::new(0, text.to_string(), 0, document_id));
+        }
+
+        Ok(chunks)
+    }
+
     /// Chunk text based on provided boundaries
     fn chunk_by_boundaries(
         &self,
@@ -552,10 +665,260 @@ impl Default for TextChunker {
     }
 }
 
+/// Return the fence marker ("```" or "~~~") if the line opens a fenced code block
+fn markdown_fence_marker(line: &str) -> Option<&'static str> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") {
+        Some("```")
+    } else if trimmed.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+/// Return the heading text if the line is an ATX heading (`#` to `######`)
+fn markdown_heading(line: &str) -> Option<String> {
+    let trimmed = line.trim_end();
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim().to_string())
+}
+
+/// Split Markdown into (start, end, heading) sections at headings outside code fences
+fn markdown_sections(text: &str) -> Vec<(usize, usize, Option<String>)> {
+    let mut sections = Vec::new();
+    let mut section_start = 0;
+    let mut heading: Option<String> = None;
+    let mut fence: Option<&'static str> = None;
+    let mut pos = 0;
+
+    for line in text.split_inclusive('\n') {
+        let line_start = pos;
+        pos += line.len();
+
+        if let Some(marker) = fence {
+            if line.trim_start().starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(marker) = markdown_fence_marker(line) {
+            fence = Some(marker);
+            continue;
+        }
+        if let Some(title) = markdown_heading(line) {
+            // Skip an empty preamble before the first heading
+            if line_start > section_start && (heading.is_some() || !text[section_start..line_start].trim().is_empty()) {
+                sections.push((section_start, line_start, heading.take()));
+            }
+            section_start = line_start;
+            heading = Some(title);
+        }
+    }
+
+    if section_start < text.len() {
+        sections.push((section_start, text.len(), heading));
+    }
+
+    sections
+}
+
+/// Split a section into (start, end, is_code) blocks at blank lines, keeping code fences whole
+fn markdown_blocks(text: &str, start: usize, end: usize) -> Vec<(usize, usize, bool)> {
+    let mut blocks = Vec::new();
+    let mut block_start = start;
+    let mut fence: Option<&'static str> = None;
+    let mut pos = start;
+
+    for line in text[start..end].split_inclusive('\n') {
+        let line_start = pos;
+        pos += line.len();
+
+        if let Some(marker) = fence {
+            if line.trim_start().starts_with(marker) {
+                fence = None;
+                blocks.push((block_start, pos, true));
+                block_start = pos;
+            }
+            continue;
+        }
+        if let Some(marker) = markdown_fence_marker(line) {
+            if line_start > block_start {
+                blocks.push((block_start, line_start, false));
+            }
+            block_start = line_start;
+            fence = Some(marker);
+            continue;
+        }
+        if line.trim().is_empty() {
+            blocks.push((block_start, pos, false));
+            block_start = pos;
+        }
+    }
+
+    if block_start < end {
+        blocks.push((block_start, end, fence.is_some()));
+    }
+
+    blocks
+}
+
+/// Bytes requested from the reader per refill
+const STREAM_READ_SIZE: usize = 64 * 1024;
+
+/// Chunk text from a reader incrementally, without loading the whole document
+///
+/// Chunks are contiguous (no overlap) and at most `max_chunk_size` bytes. When
+/// `respect_sentences` is set each chunk ends at the last sentence boundary that
+/// fits, falling back to whitespace and finally to a hard cut. `char_offset` is
+/// the byte offset into the full stream, matching [`TextChunker`].
+pub fn stream_chunks<R: Read>(reader: R, config: ChunkingConfig) -> StreamChunks<R> {
+    StreamChunks {
+        reader,
+        sentence_regex: Regex::new(r"[.!?]+\s+").unwrap(),
+        config,
+        buffer: String::new(),
+        pending_bytes: Vec::new(),
+        offset: 0,
+        next_id: 0,
+        eof: false,
+    }
+}
+
+/// Iterator returned by [`stream_chunks`]
+pub struct StreamChunks<R: Read> {
+    reader: R,
+    config: ChunkingConfig,
+    sentence_regex: Regex,
+    /// Decoded text not yet emitted as a chunk
+    buffer: String,
+    /// Trailing bytes of an incomplete UTF-8 sequence from the last read
+    pending_bytes: Vec<u8>,
+    /// Offset of `buffer` within the whole stream
+    offset: usize,
+    next_id: usize,
+    eof: bool,
+}
+
+impl<R: Read> StreamChunks<R> {
+    /// Read until the buffer holds more than one chunk or the reader is exhausted
+    fn fill_buffer(&mut self) -> LangExtractResult<()> {
+        let mut block = vec![0u8; STREAM_READ_SIZE.max(self.config.max_chunk_size)];
+
+        while !self.eof && self.buffer.len() <= self.config.max_chunk_size {
+            let read = match self.reader.read(&mut block) {
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+
+            if read == 0 {
+                self.eof = true;
+                if !self.pending_bytes.is_empty() {
+                    return Err(LangExtractError::chunking(
+                        "Input ends with an incomplete UTF-8 sequence",
+                    ));
+                }
+                break;
+            }
+
+            self.pending_bytes.extend_from_slice(&block[..read]);
+            let valid_up_to = match std::str::from_utf8(&self.pending_bytes) {
+                Ok(_) => self.pending_bytes.len(),
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                Err(e) => {
+                    return Err(LangExtractError::chunking(format!(
+                        "Invalid UTF-8 at byte {}",
+                        self.offset + self.buffer.len() + e.valid_up_to()
+                    )))
+                }
+            };
+
+            let rest = self.pending_bytes.split_off(valid_up_to);
+            // The prefix was validated above
+            self.buffer.push_str(std::str::from_utf8(&self.pending_bytes).unwrap_or_default());
+            self.pending_bytes = rest;
+        }
+
+        Ok(())
+    }
+
+    /// Choose where the next chunk ends within the buffer
+    fn split_point(&self) -> usize {
+        let max_size = self.config.max_chunk_size.max(1);
+        if self.buffer.len() <= max_size {
+            return self.buffer.len();
+        }
+
+        let mut limit = max_size;
+        while !self.buffer.is_char_boundary(limit) {
+            limit -= 1;
+        }
+        if limit == 0 {
+            // A single character wider than the chunk size
+            return self.buffer.chars().next().map_or(0, char::len_utf8);
+        }
+
+        let window = &self.buffer[..limit];
+        let min_size = self.config.min_chunk_size.min(limit);
+
+        if self.config.respect_sentences {
+            if let Some(end) = self
+                .sentence_regex
+                .find_iter(window)
+                .map(|m| m.end())
+                .filter(|&end| end >= min_size)
+                .last()
+            {
+                return end;
+            }
+        }
+
+        match window.rfind(char::is_whitespace) {
+            Some(pos) if pos > 0 => pos + window[pos..].chars().next().map_or(1, char::len_utf8),
+            _ => limit,
+        }
+    }
+}
+
+impl<R: Read> Iterator for StreamChunks<R> {
+    type Item = LangExtractResult<TextChunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.fill_buffer() {
+            // Stop after reporting the error
+            self.eof = true;
+            self.buffer.clear();
+            self.pending_bytes.clear();
+            return Some(Err(e));
+        }
+
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let end = self.split_point();
+        let rest = self.buffer.split_off(end);
+        let text = std::mem::replace(&mut self.buffer, rest);
+
+        let chunk = TextChunk::new(self.next_id, text, self.offset, None);
+        self.offset += end;
+        self.next_id += 1;
+        Some(Ok(chunk))
+    }
+}
+
 /// Token-based chunk iterator that mimics Python's ChunkIterator behavior
 pub struct ChunkIterator<'a> {
     tokenized_text: &'a TokenizedText,
-    tokenizer: &'a Tokenizer,
+    tokenizer: &'a dyn Tokenize,
     max_char_buffer: usize,
     sentence_iter: SentenceIterator<'a>,
     broken_sentence: bool,
@@ -567,7 +930,7 @@ impl<'a> ChunkIterator<'a> {
     /// Create a new chunk iterator
     pub fn new(
         text: &'a TokenizedText,
-        tokenizer: &'a Tokenizer,
+        tokenizer: &'a dyn Tokenize,
         max_char_buffer: usize,
         document: Option<&'a Document>,
     ) -> LangExtractResult<Self> {
@@ -762,12 +1125,72 @@ impl<'a> Iterator for ChunkIterator<'a> {
     }
 }
 
+/// Signature for user-supplied duplicate predicates
+pub type DedupFn = dyn Fn(&Extraction, &Extraction) -> bool + Send + Sync;
+
+/// Signature for user-supplied similarity scores between two extractions (0.0 to 1.0)
+pub type SimilarityFn = dyn Fn(&Extraction, &Extraction) -> f32 + Send + Sync;
+
+/// Strategy used by [`ResultAggregator`] to decide whether two extractions are duplicates
+#[derive(Clone, Default)]
+pub enum DedupStrategy {
+    /// Same class and identical extraction text
+    ExactText,
+    /// Word-level Jaccard similarity above the aggregator threshold (default)
+    #[default]
+    Jaccard,
+    /// Same class and identical text after lowercasing, stripping punctuation and collapsing whitespace
+    NormalizedText,
+    /// Same class and overlapping character intervals
+    CharIntervalOverlap,
+    /// User-supplied predicate returning true when two extractions are duplicates
+    Custom(std::sync::Arc<DedupFn>),
+    /// Same class and a user-supplied similarity score at or above the aggregator threshold
+    ///
+    /// With the `embeddings` feature, `embeddings::semantic_dedup_strategy` builds
+    /// one from embedding cosine similarity.
+    Similarity(std::sync::Arc<SimilarityFn>),
+}
+
+impl DedupStrategy {
+    /// Create a custom strategy from a closure
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(&Extraction, &Extraction) -> bool + Send + Sync + 'static,
+    {
+        DedupStrategy::Custom(std::sync::Arc::new(f))
+    }
+
+    /// Create a similarity strategy from a scoring closure
+    pub fn similarity<F>(f: F) -> Self
+    where
+        F: Fn(&Extraction, &Extraction) -> f32 + Send + Sync + 'static,
+    {
+        DedupStrategy::Similarity(std::sync::Arc::new(f))
+    }
+}
+
+impl std::fmt::Debug for DedupStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DedupStrategy::ExactText => write!(f, "ExactText"),
+            DedupStrategy::Jaccard => write!(f, "Jaccard"),
+            DedupStrategy::NormalizedText => write!(f, "NormalizedText"),
+            DedupStrategy::CharIntervalOverlap => write!(f, "CharIntervalOverlap"),
+            DedupStrategy::Custom(_) => write!(f, "Custom(<fn>)"),
+            DedupStrategy::Similarity(_) => write!(f, "Similarity(<fn>)"),
+        }
+    }
+}
+
 /// Result aggregator for combining extractions from multiple chunks
 pub struct ResultAggregator {
     /// Similarity threshold for duplicate detection
     similarity_threshold: f32,
     /// Whether to merge overlapping extractions
     merge_overlaps: bool,
+    /// Strategy used to detect duplicate extractions
+    dedup_strategy: DedupStrategy,
 }
 
 impl ResultAggregator {
@@ -776,17 +1199,28 @@ impl ResultAggregator {
         Self {
             similarity_threshold: 0.8,
             merge_overlaps: true,
+            dedup_strategy: DedupStrategy::default(),
         }
     }
 
     /// Create a result aggregator with custom settings
-    pub fn with_settings(similarity_threshold: f32, merge_overlaps: bool) -> Self {
+    pub fn with_settings(
+        similarity_threshold: f32,
+        merge_overlaps: bool,
+        dedup_strategy: DedupStrategy,
+    ) -> Self {
         Self {
             similarity_threshold,
             merge_overlaps,
+            dedup_strategy,
         }
     }
 
+    /// Get the deduplication strategy in use
+    pub fn dedup_strategy(&self) -> &DedupStrategy {
+        &self.dedup_strategy
+    }
+
     /// Aggregate results from multiple chunks into a single annotated document
     pub fn aggregate_chunk_results(
         &self,
@@ -795,12 +1229,40 @@ impl ResultAggregator {
         document_id: Option<String>,
     ) -> LangExtractResult<AnnotatedDocument> {
         let mut all_extractions = Vec::new();
+        let mut group_offset = 0;
+
+        // Chunks without a kept output (e.g. a failed model call) get an empty entry so indices line up
+        let raw_outputs = chunk_results
+            .iter()
+            .any(|chunk| chunk.raw_output.is_some())
+            .then(|| {
+                chunk_results
+                    .iter()
+                    .map(|chunk| chunk.raw_output.clone().unwrap_or_default())
+                    .collect::<Vec<_>>()
+            });
 
         // Collect all extractions from chunks
         for chunk_result in chunk_results {
-            if let Some(extractions) = chunk_result.extractions {
-                // Character positions should already be adjusted by the alignment process
-                // during chunk processing, so we don't need to add the offset again here
+            if let Some(mut extractions) = chunk_result.extractions {
+                // Group indices restart in every chunk, so shift them to stay unique
+                let mut next_offset = group_offset;
+                for extraction in &mut extractions {
+                    if let Some(group_index) = extraction.group_index {
+                        extraction.group_index = Some(group_index + group_offset);
+                        next_offset = next_offset.max(group_index + group_offset + 1);
+                    }
+                }
+                group_offset = next_offset;
+
+                // Aligned chunks already carry absolute positions; relative ones are shifted here
+                // so that entities found in an overlap region land on the same span
+                if !chunk_result.intervals_absolute {
+                    for interval in extractions.iter_mut().filter_map(|e| e.char_interval.as_mut()) {
+                        interval.start_pos = interval.start_pos.map(|pos| pos + chunk_result.char_offset);
+                        interval.end_pos = interval.end_pos.map(|pos| pos + chunk_result.char_offset);
+                    }
+                }
                 all_extractions.extend(extractions);
             }
         }
@@ -815,12 +1277,13 @@ impl ResultAggregator {
         // Create the aggregated document
         let mut annotated_doc = AnnotatedDocument::with_extractions(deduplicated_extractions, original_text);
         annotated_doc.document_id = document_id;
+        annotated_doc.raw_outputs = raw_outputs;
 
         Ok(annotated_doc)
     }
 
-    /// Remove duplicate extractions based on similarity
-    fn deduplicate_extractions(&self, extractions: Vec<Extraction>) -> LangExtractResult<Vec<Extraction>> {
+    /// Remove duplicate extractions based on the dedup strategy, keeping the first of each
+    pub fn deduplicate_extractions(&self, extractions: Vec<Extraction>) -> LangExtractResult<Vec<Extraction>> {
         let mut unique_extractions = Vec::new();
 
         for extraction in extractions {
@@ -844,6 +1307,33 @@ impl ResultAggregator {
 
     /// Check if two extractions are similar enough to be considered duplicates
     fn are_similar_extractions(&self, e1: &Extraction, e2: &Extraction) -> bool {
+        match &self.dedup_strategy {
+            DedupStrategy::Jaccard => self.are_similar_jaccard(e1, e2),
+            DedupStrategy::ExactText => {
+                e1.extraction_class == e2.extraction_class && e1.extraction_text == e2.extraction_text
+            }
+            DedupStrategy::NormalizedText => {
+                e1.extraction_class == e2.extraction_class
+                    && normalize_for_dedup(&e1.extraction_text) == normalize_for_dedup(&e2.extraction_text)
+            }
+            DedupStrategy::CharIntervalOverlap => {
+                if e1.extraction_class != e2.extraction_class {
+                    return false;
+                }
+                match (&e1.char_interval, &e2.char_interval) {
+                    (Some(interval1), Some(interval2)) => interval1.overlaps_with(interval2),
+                    _ => false,
+                }
+            }
+            DedupStrategy::Custom(predicate) => predicate(e1, e2),
+            DedupStrategy::Similarity(similarity) => {
+                e1.extraction_class == e2.extraction_class && similarity(e1, e2) >= self.similarity_threshold
+            }
+        }
+    }
+
+    /// Jaccard-based duplicate check (class match or overlapping positions plus similar text)
+    fn are_similar_jaccard(&self, e1: &Extraction, e2: &Extraction) -> bool {
         // Same extraction class and similar text
         if e1.extraction_class == e2.extraction_class {
             let similarity = self.text_similarity(&e1.extraction_text, &e2.extraction_text);
@@ -885,6 +1375,17 @@ impl ResultAggregator {
     }
 }
 
+/// Normalize text for dedup comparison: lowercase, drop punctuation, collapse whitespace
+fn normalize_for_dedup(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| !c.is_ascii_punctuation())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 impl Default for ResultAggregator {
     fn default() -> Self {
         Self::new()
@@ -892,7 +1393,7 @@ impl Default for ResultAggregator {
 }
 
 /// Result from processing a single chunk
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChunkResult {
     /// ID of the chunk that was processed
     pub chunk_id: usize,
@@ -908,6 +1409,10 @@ pub struct ChunkResult {
     pub error: Option<String>,
     /// Processing time for this chunk
     pub processing_time: Option<std::time::Duration>,
+    /// Whether extraction intervals are already document-absolute (otherwise relative to the chunk)
+    pub intervals_absolute: bool,
+    /// Raw model output for this chunk, when the annotator keeps it
+    pub raw_output: Option<String>,
 }
 
 impl ChunkResult {
@@ -926,6 +1431,8 @@ impl ChunkResult {
             success: true,
             error: None,
             processing_time: None,
+            intervals_absolute: true,
+            raw_output: None,
         }
     }
 
@@ -944,6 +1451,8 @@ impl ChunkResult {
             success: false,
             error: Some(error),
             processing_time: None,
+            intervals_absolute: true,
+            raw_output: None,
         }
     }
 
@@ -952,6 +1461,18 @@ impl ChunkResult {
         self.processing_time = Some(duration);
         self
     }
+
+    /// Attach the raw model output for this chunk
+    pub fn with_raw_output(mut self, raw_output: Option<String>) -> Self {
+        self.raw_output = raw_output;
+        self
+    }
+
+    /// Mark extraction intervals as relative to the chunk start, to be shifted by `char_offset` on aggregation
+    pub fn with_relative_intervals(mut self) -> Self {
+        self.intervals_absolute = false;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -1014,6 +1535,68 @@ mod tests {
         assert_eq!(chunks[0].text, text);
     }
 
+    #[test]
+    fn test_stream_chunks_offsets_are_contiguous() {
+        let sentence = "Le café à Zürich ouvre à 8h. Patients arrive early! ";
+        let text = sentence.repeat(60_000);
+        assert!(text.len() > 3_000_000);
+
+        let config = ChunkingConfig {
+            max_chunk_size: 1000,
+            min_chunk_size: 100,
+            ..Default::default()
+        };
+        let chunks: Vec<TextChunk> = stream_chunks(std::io::Cursor::new(text.as_bytes()), config)
+            .collect::<LangExtractResult<_>>()
+            .unwrap();
+
+        let mut expected_offset = 0;
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.id, i);
+            assert_eq!(chunk.char_offset, expected_offset);
+            assert!(chunk.text.len() <= 1000);
+            assert_eq!(&text[chunk.char_offset..chunk.char_offset + chunk.char_length], chunk.text);
+            if i + 1 < chunks.len() {
+                assert!(chunk.text.ends_with(". ") || chunk.text.ends_with("! "));
+            }
+            expected_offset += chunk.char_length;
+        }
+        assert_eq!(expected_offset, text.len());
+    }
+
+    #[test]
+    fn test_stream_chunks_across_small_reads() {
+        /// Reader that hands out a few bytes at a time, splitting multi-byte characters
+        struct TrickleReader<'a>(&'a [u8]);
+
+        impl Read for TrickleReader<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = self.0.len().min(buf.len()).min(3);
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+
+        let text = "Ünïcödé sentence one. Second sentence here. Third one ends without punctuation";
+        let config = ChunkingConfig {
+            max_chunk_size: 30,
+            min_chunk_size: 5,
+            ..Default::default()
+        };
+        let chunks: Vec<TextChunk> = stream_chunks(TrickleReader(text.as_bytes()), config)
+            .collect::<LangExtractResult<_>>()
+            .unwrap();
+
+        assert_eq!(chunks[0].text, "Ünïcödé sentence one. ");
+        assert_eq!(chunks.iter().map(|c| c.text.as_str()).collect::<String>(), text);
+
+        let invalid: &[u8] = &[b'o', b'k', 0xff, b'!'];
+        let mut stream = stream_chunks(std::io::Cursor::new(invalid), ChunkingConfig::default());
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+    }
+
     #[test]
     fn test_chunk_char_interval() {
         let chunk = TextChunk::new(0, "test".to_string(), 10, None);
@@ -1068,6 +1651,47 @@ mod tests {
         assert!(chunk_text.contains("This is a longer sentence."));
     }
 
+    #[test]
+    fn test_cjk_tokenizer_chunks_japanese_sentences() {
+        use crate::tokenizer::CjkTokenizer;
+
+        let text = "東京は日本の首都です。大阪は商業の中心地です。京都には古い寺がたくさんあります。";
+        let document = create_document(text);
+
+        // The default tokenizer sees the whole text as a single token
+        assert_eq!(create_tokenizer().tokenize(text).unwrap().len(), 1);
+
+        let tokenizer = CjkTokenizer::new().unwrap();
+        let tokenized = tokenizer.tokenize(text).unwrap();
+        assert_eq!(tokenized.len(), text.chars().count());
+
+        let chunks: Vec<TokenChunk> = ChunkIterator::new(&tokenized, &tokenizer, 70, Some(&document))
+            .unwrap()
+            .collect::<LangExtractResult<_>>()
+            .unwrap();
+        let texts: Vec<String> = chunks.iter().map(|chunk| chunk.chunk_text(&tokenizer).unwrap()).collect();
+        assert_eq!(
+            texts,
+            vec!["東京は日本の首都です。大阪は商業の中心地です。", "京都には古い寺がたくさんあります。"]
+        );
+        for (chunk, chunk_text) in chunks.iter().zip(&texts) {
+            let interval = chunk.char_interval(&tokenizer).unwrap();
+            assert_eq!(&text[interval.start_pos.unwrap()..interval.end_pos.unwrap()], chunk_text);
+        }
+
+        let chunker = TextChunker::with_config(ChunkingConfig {
+            max_chunk_size: 60,
+            ..Default::default()
+        })
+        .with_tokenizer(Box::new(tokenizer));
+        let chunks = chunker.chunk_text(text, None).unwrap();
+        assert_eq!(chunks.len(), 3);
+        for chunk in &chunks {
+            assert!(chunk.text.ends_with('。'));
+            assert_eq!(&text[chunk.char_offset..chunk.char_offset + chunk.text.len()], chunk.text);
+        }
+    }
+
     #[test]
     fn test_sentence_breaking() {
         // Test: Sentence Breaking
@@ -1604,4 +2228,219 @@ mod tests {
             assert_eq!(chunk.document_id, document_id);
         }
     }
+
+    fn aggregate_with(aggregator: &ResultAggregator, extractions: Vec<Extraction>) -> Vec<Extraction> {
+        let results = vec![ChunkResult::success(0, extractions, 0, 100)];
+        aggregator
+            .aggregate_chunk_results(results, "text".to_string(), None)
+            .unwrap()
+            .extractions
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_aggregation_keeps_chunk_groups_distinct() {
+        let grouped = |class: &str, text: &str, group: usize| Extraction {
+            extraction_class: class.to_string(),
+            extraction_text: text.to_string(),
+            group_index: Some(group),
+            ..Default::default()
+        };
+
+        let results = vec![
+            ChunkResult::success(0, vec![grouped("name", "Alice", 0), grouped("name", "Bob", 1)], 0, 50),
+            ChunkResult::success(1, vec![grouped("name", "Carol", 0)], 50, 50),
+        ];
+        let doc = ResultAggregator::with_settings(0.8, false, DedupStrategy::default())
+            .aggregate_chunk_results(results, "text".to_string(), None)
+            .unwrap();
+
+        let groups: Vec<Option<usize>> = doc.extractions.unwrap().iter().map(|e| e.group_index).collect();
+        assert_eq!(groups, vec![Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_aggregation_remaps_relative_intervals_in_overlap() {
+        let text = "Alice met Bob in Paris.";
+        let located = |text: &str, start: usize, end: usize| Extraction {
+            extraction_class: "person".to_string(),
+            extraction_text: text.to_string(),
+            char_interval: Some(CharInterval::new(Some(start), Some(end))),
+            ..Default::default()
+        };
+
+        // Chunks "Alice met Bob in" (0..16) and "Bob in Paris." (10..23) overlap on "Bob in"
+        let results = vec![
+            ChunkResult::success(0, vec![located("Alice", 0, 5), located("Bob", 10, 13)], 0, 16)
+                .with_relative_intervals(),
+            ChunkResult::success(1, vec![located("Bob", 0, 3)], 10, 13).with_relative_intervals(),
+        ];
+        let doc = ResultAggregator::with_settings(0.8, true, DedupStrategy::CharIntervalOverlap)
+            .aggregate_chunk_results(results, text.to_string(), None)
+            .unwrap();
+
+        let extractions = doc.extractions.unwrap();
+        let bobs: Vec<_> = extractions.iter().filter(|e| e.extraction_text == "Bob").collect();
+        assert_eq!(extractions.len(), 2);
+        assert_eq!(bobs.len(), 1);
+        assert_eq!(bobs[0].char_interval, Some(CharInterval::new(Some(10), Some(13))));
+        assert_eq!(&text[10..13], "Bob");
+    }
+
+    #[test]
+    fn test_dedup_strategy_default_is_jaccard() {
+        let aggregator = ResultAggregator::new();
+        assert!(matches!(aggregator.dedup_strategy(), DedupStrategy::Jaccard));
+
+        let extractions = vec![
+            Extraction::new("person".to_string(), "John Smith".to_string()),
+            Extraction::new("person".to_string(), "Smith John".to_string()),
+        ];
+        assert_eq!(aggregate_with(&aggregator, extractions).len(), 1);
+    }
+
+    #[test]
+    fn test_dedup_strategy_exact_text_keeps_jaccard_duplicates() {
+        let aggregator = ResultAggregator::with_settings(0.8, true, DedupStrategy::ExactText);
+
+        let extractions = vec![
+            Extraction::new("person".to_string(), "John Smith".to_string()),
+            Extraction::new("person".to_string(), "Smith John".to_string()),
+            Extraction::new("person".to_string(), "John Smith".to_string()),
+        ];
+        let result = aggregate_with(&aggregator, extractions);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].extraction_text, "John Smith");
+        assert_eq!(result[1].extraction_text, "Smith John");
+    }
+
+    #[test]
+    fn test_dedup_strategy_normalized_text() {
+        let aggregator = ResultAggregator::with_settings(0.8, true, DedupStrategy::NormalizedText);
+
+        let extractions = vec![
+            Extraction::new("org".to_string(), "Acme, Inc.".to_string()),
+            Extraction::new("org".to_string(), "acme  inc".to_string()),
+            Extraction::new("person".to_string(), "acme inc".to_string()),
+        ];
+        assert_eq!(aggregate_with(&aggregator, extractions).len(), 2);
+    }
+
+    #[test]
+    fn test_dedup_strategy_char_interval_overlap() {
+        let aggregator = ResultAggregator::with_settings(0.8, true, DedupStrategy::CharIntervalOverlap);
+
+        let extractions = vec![
+            Extraction::with_char_interval(
+                "person".to_string(),
+                "John Smith".to_string(),
+                CharInterval::new(Some(0), Some(10)),
+            ),
+            Extraction::with_char_interval(
+                "person".to_string(),
+                "Smith".to_string(),
+                CharInterval::new(Some(5), Some(10)),
+            ),
+            Extraction::with_char_interval(
+                "person".to_string(),
+                "John Smith".to_string(),
+                CharInterval::new(Some(50), Some(60)),
+            ),
+        ];
+        assert_eq!(aggregate_with(&aggregator, extractions).len(), 2);
+    }
+
+    #[test]
+    fn test_dedup_strategy_custom_closure() {
+        let aggregator = ResultAggregator::with_settings(
+            0.8,
+            true,
+            DedupStrategy::custom(|e1, e2| {
+                let canonical = |t: &str| match t {
+                    "USA" | "United States" => "us".to_string(),
+                    other => other.to_lowercase(),
+                };
+                e1.extraction_class == e2.extraction_class
+                    && canonical(&e1.extraction_text) == canonical(&e2.extraction_text)
+            }),
+        );
+
+        let extractions = vec![
+            Extraction::new("country".to_string(), "USA".to_string()),
+            Extraction::new("country".to_string(), "United States".to_string()),
+            Extraction::new("country".to_string(), "Canada".to_string()),
+        ];
+        assert_eq!(aggregate_with(&aggregator, extractions).len(), 2);
+    }
+
+    #[test]
+    fn test_dedup_strategy_similarity_uses_threshold() {
+        let aggregator = ResultAggregator::with_settings(
+            0.9,
+            true,
+            DedupStrategy::similarity(|e1, e2| if e1.extraction_text.len() == e2.extraction_text.len() { 0.95 } else { 0.5 }),
+        );
+
+        let extractions = vec![
+            Extraction::new("code".to_string(), "AB1".to_string()),
+            Extraction::new("code".to_string(), "CD2".to_string()),
+            Extraction::new("label".to_string(), "EF3".to_string()),
+            Extraction::new("code".to_string(), "GHIJ".to_string()),
+        ];
+        assert_eq!(aggregate_with(&aggregator, extractions).len(), 3);
+    }
+
+    #[test]
+    fn test_markdown_chunking_keeps_sections_intact() {
+        let chunker = TextChunker::with_config(ChunkingConfig {
+            strategy: ChunkingStrategy::Markdown,
+            max_chunk_size: 120,
+            ..Default::default()
+        });
+
+        let text = "Intro paragraph before any heading.\n\n\
+# Overview\nThe system processes orders and tracks inventory levels.\n\n\
+## Details\nOrders are validated before shipping to customers.\n\n\
+## Limits\nAt most 100 orders per minute are accepted.\n";
+        let chunks = chunker.chunk_text(text, None).unwrap();
+
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(chunks[0].section_heading, None);
+        assert_eq!(chunks[1].section_heading.as_deref(), Some("Overview"));
+        assert_eq!(chunks[2].section_heading.as_deref(), Some("Details"));
+        assert_eq!(chunks[3].section_heading.as_deref(), Some("Limits"));
+        assert!(chunks[2].text.starts_with("## Details"));
+        assert!(chunks[2].text.contains("shipping to customers."));
+
+        // Chunks cover the document exactly with correct offsets
+        let rebuilt: String = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(rebuilt, text);
+        for chunk in &chunks {
+            assert_eq!(&text[chunk.char_offset..chunk.char_offset + chunk.char_length], chunk.text);
+        }
+    }
+
+    #[test]
+    fn test_markdown_chunking_never_splits_code_fences() {
+        let chunker = TextChunker::with_config(ChunkingConfig {
+            strategy: ChunkingStrategy::Markdown,
+            max_chunk_size: 60,
+            ..Default::default()
+        });
+
+        let code = "```rust\n# not a heading\nfn main() {\n\n    println!(\"hello\");\n}\n```\n";
+        let text = format!(
+            "# Setup\nInstall the toolchain first.\n\n{}\nThen run the binary and check the output.\n",
+            code
+        );
+        let chunks = chunker.chunk_text(&text, None).unwrap();
+
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.section_heading.as_deref() == Some("Setup")));
+        assert!(chunks.iter().any(|c| c.text.contains(code)));
+        assert!(!chunks.iter().any(|c| c.text.starts_with("# not a heading")));
+
+        let rebuilt: String = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(rebuilt, text);
+    }
 }
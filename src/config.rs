@@ -5,8 +5,10 @@
 
 use crate::{
     data::FormatType,
+    exceptions::{LangExtractError, LangExtractResult},
     logging::ProgressHandler,
     providers::ProviderConfig,
+    ExtractConfig,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -536,11 +538,178 @@ impl From<LangExtractConfig> for crate::ExtractConfig {
             enable_multipass: config.multipass.enable_multipass,
             multipass_min_extractions: config.multipass.min_extractions_per_chunk,
             multipass_quality_threshold: config.multipass.quality_threshold,
+            fuzzy_alignment_threshold: config.alignment.fuzzy_alignment_threshold,
+            class_descriptions: HashMap::new(),
+            skip_chunking: false,
+            enable_cache: false,
+            cache_dir: None,
+            cache_nonzero_temperature: true,
+            strict_validation: false,
+            system_prompt: None,
+            require_grounding: false,
+            allow_fuzzy_grounding: false,
+            example_selection: crate::prompting::ExampleSelection::All,
+            max_examples: None,
+            keep_raw_outputs: false,
+            normalize_input: true,
+            max_output_tokens: None,
+            seed: None,
+            pass_prompts: Vec::new(),
+            response_parser: None,
+            per_chunk_timeout_ms: None,
+            context_provider: None,
+            sort_order: crate::ExtractionSortOrder::AsFound,
+            merge_adjacent_same_class: false,
+            multipass_quality_scorer: None,
+            auto_shrink_on_truncation: false,
+            prompt_variables: HashMap::new(),
+            strict_prompt_variables: false,
+            json_mode: false,
+            checkpoint_path: None,
+            allowed_classes: None,
+            post_process: None,
+            tokenizer: None,
+            reformat_on_parse_failure: false,
+            max_reformat_attempts: 1,
+            min_chunks: None,
+            table_mode: false,
+            prompt_language: crate::prompting::PromptLanguage::English,
             progress_handler: config.progress.handler,
         }
     }
 }
 
+/// Fluent builder for [`ExtractConfig`] that checks its invariants on [`build`](Self::build)
+#[derive(Debug, Clone, Default)]
+pub struct ExtractConfigBuilder {
+    config: ExtractConfig,
+}
+
+impl ExtractConfigBuilder {
+    /// Start from the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the model id
+    pub fn with_model_id(mut self, model_id: impl Into<String>) -> Self {
+        self.config.model_id = model_id.into();
+        self
+    }
+
+    /// Set the API key
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.config.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Use an explicit provider configuration
+    pub fn with_provider(mut self, provider: ProviderConfig) -> Self {
+        self.config.model_id = provider.model.clone();
+        if let Ok(value) = serde_json::to_value(provider) {
+            self.config.language_model_params.insert("provider_config".to_string(), value);
+        }
+        self
+    }
+
+    /// Set the output format
+    pub fn with_format_type(mut self, format_type: FormatType) -> Self {
+        self.config.format_type = format_type;
+        self
+    }
+
+    /// Set the maximum characters per chunk
+    pub fn with_max_char_buffer(mut self, max_char_buffer: usize) -> Self {
+        self.config.max_char_buffer = max_char_buffer;
+        self
+    }
+
+    /// Set the sampling temperature (must be within 0.0 to 1.0)
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.config.temperature = temperature;
+        self
+    }
+
+    /// Set the number of chunks per batch
+    pub fn with_batch_length(mut self, batch_length: usize) -> Self {
+        self.config.batch_length = batch_length;
+        self
+    }
+
+    /// Set the number of concurrent workers (must not exceed the batch length)
+    pub fn with_max_workers(mut self, max_workers: usize) -> Self {
+        self.config.max_workers = max_workers;
+        self
+    }
+
+    /// Set additional context for the prompt
+    pub fn with_additional_context(mut self, context: impl Into<String>) -> Self {
+        self.config.additional_context = Some(context.into());
+        self
+    }
+
+    /// Set the number of extraction passes
+    pub fn with_extraction_passes(mut self, passes: usize) -> Self {
+        self.config.extraction_passes = passes;
+        self
+    }
+
+    /// Enable or disable multi-pass extraction
+    pub fn with_multipass(mut self, enabled: bool) -> Self {
+        self.config.enable_multipass = enabled;
+        self
+    }
+
+    /// Enable or disable debug mode
+    pub fn with_debug(mut self, debug: bool) -> Self {
+        self.config.debug = debug;
+        self
+    }
+
+    /// Fail when model output does not pass validation
+    pub fn with_strict_validation(mut self, strict: bool) -> Self {
+        self.config.strict_validation = strict;
+        self
+    }
+
+    /// Set the progress handler
+    pub fn with_progress_handler(mut self, handler: Arc<dyn ProgressHandler>) -> Self {
+        self.config.progress_handler = Some(handler);
+        self
+    }
+
+    /// Validate the configuration and return it
+    pub fn build(self) -> LangExtractResult<ExtractConfig> {
+        let config = self.config;
+
+        if !(0.0..=1.0).contains(&config.temperature) {
+            return Err(LangExtractError::configuration(format!(
+                "temperature must be between 0.0 and 1.0, got {}",
+                config.temperature
+            )));
+        }
+        if config.max_char_buffer == 0 {
+            return Err(LangExtractError::configuration("max_char_buffer must be greater than 0"));
+        }
+        if config.max_workers == 0 || config.batch_length == 0 {
+            return Err(LangExtractError::configuration(
+                "batch_length and max_workers must be greater than 0",
+            ));
+        }
+        if config.batch_length < config.max_workers {
+            return Err(LangExtractError::configuration(format!(
+                "batch_length ({}) must be at least max_workers ({})",
+                config.batch_length, config.max_workers
+            )));
+        }
+        if config.extraction_passes == 0 {
+            return Err(LangExtractError::configuration("extraction_passes must be at least 1"));
+        }
+
+        Ok(config)
+    }
+}
+
 impl std::fmt::Debug for LangExtractConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("LangExtractConfig")
@@ -632,4 +801,29 @@ mod tests {
         assert_eq!(config.provider.model, deserialized.provider.model);
         assert_eq!(config.processing.format_type, deserialized.processing.format_type);
     }
+
+    #[test]
+    fn test_extract_config_builder_validates() {
+        let config = ExtractConfigBuilder::new()
+            .with_provider(ProviderConfig::ollama("mistral", None))
+            .with_temperature(0.2)
+            .with_batch_length(8)
+            .with_max_workers(4)
+            .build()
+            .unwrap();
+        assert_eq!(config.model_id, "mistral");
+        assert_eq!(config.temperature, 0.2);
+        assert!(config.language_model_params.contains_key("provider_config"));
+
+        let err = ExtractConfigBuilder::new().with_temperature(2.0).build().unwrap_err();
+        assert!(err.is_configuration_error());
+        assert!(err.to_string().contains("temperature"));
+
+        let err = ExtractConfigBuilder::new()
+            .with_batch_length(2)
+            .with_max_workers(4)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("batch_length (2) must be at least max_workers (4)"));
+    }
 }
@@ -22,11 +22,16 @@ pub enum AlignmentStatus {
 }
 
 /// Represents a character interval in text
+///
+/// Serialized as `{"start": n, "end": n}`; the `start_pos`/`end_pos` and
+/// `start_char`/`end_char` spellings are also accepted when reading.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CharInterval {
     /// Starting position of the interval (inclusive)
+    #[serde(default, rename = "start", alias = "start_pos", alias = "start_char")]
     pub start_pos: Option<usize>,
     /// Ending position of the interval (exclusive)
+    #[serde(default, rename = "end", alias = "end_pos", alias = "end_char")]
     pub end_pos: Option<usize>,
 }
 
@@ -56,12 +61,12 @@ impl CharInterval {
     }
 }
 
-/// Token interval information (placeholder for future tokenizer integration)
+/// Token span of an extraction in the tokenized source text
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TokenInterval {
     /// Starting token index
     pub start_token: Option<usize>,
-    /// Ending token index
+    /// Ending token index (exclusive)
     pub end_token: Option<usize>,
 }
 
@@ -98,9 +103,26 @@ pub struct Extraction {
     pub description: Option<String>,
     /// Additional attributes as key-value pairs
     pub attributes: Option<HashMap<String, serde_json::Value>>,
+    /// Multi-pass extraction pass (1-based) that first found this extraction
+    #[serde(default)]
+    pub source_pass: Option<usize>,
+    /// Id of the chunk whose model output produced this extraction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_id: Option<usize>,
     /// Token position information
-    #[serde(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub token_interval: Option<TokenInterval>,
+    /// False for an absence assertion: the model checked for the class and found nothing
+    #[serde(default = "default_present", skip_serializing_if = "is_present")]
+    pub present: bool,
+}
+
+fn default_present() -> bool {
+    true
+}
+
+fn is_present(present: &bool) -> bool {
+    *present
 }
 
 impl Extraction {
@@ -116,6 +138,17 @@ impl Extraction {
             description: None,
             attributes: None,
             token_interval: None,
+            source_pass: None,
+            chunk_id: None,
+            present: true,
+        }
+    }
+
+    /// Create an absence assertion: `extraction_class` was looked for and not found
+    pub fn absent(extraction_class: String) -> Self {
+        Self {
+            present: false,
+            ..Self::new(extraction_class, String::new())
         }
     }
 }
@@ -132,6 +165,9 @@ impl Default for Extraction {
             description: None,
             attributes: None,
             token_interval: None,
+            source_pass: None,
+            chunk_id: None,
+            present: true,
         }
     }
 }
@@ -153,6 +189,9 @@ impl Extraction {
             description: None,
             attributes: None,
             token_interval: None,
+            source_pass: None,
+            chunk_id: None,
+            present: true,
         }
     }
 
@@ -235,6 +274,88 @@ impl Document {
     }
 }
 
+/// Settings of the extraction run that produced an [`AnnotatedDocument`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunMetadata {
+    /// Model that produced the extractions
+    pub model_id: String,
+    /// Sampling temperature
+    pub temperature: f32,
+    /// Prompt description the model was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// When the run finished, as an RFC 3339 timestamp
+    pub timestamp: String,
+}
+
+impl RunMetadata {
+    /// Record a run finishing now
+    pub fn new(model_id: &str, temperature: f32, prompt: Option<&str>) -> Self {
+        Self {
+            model_id: model_id.to_string(),
+            temperature,
+            prompt: prompt.map(str::to_string),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Translation of byte offsets in normalized text back to the original input
+///
+/// Anchors are only stored where the two texts stop lining up (a character was
+/// removed or replaced by one of a different length); between anchors offsets
+/// advance together.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffsetMap {
+    /// `(normalized, original)` offsets where a character starts
+    starts: Vec<(usize, usize)>,
+    /// `(normalized, original)` offsets where a character ends
+    ends: Vec<(usize, usize)>,
+}
+
+impl OffsetMap {
+    /// Record a kept character at `normalized` (`normalized_len` bytes) that came from `original` (`original_len` bytes)
+    pub fn record(&mut self, normalized: usize, normalized_len: usize, original: usize, original_len: usize) {
+        Self::push_anchor(&mut self.starts, normalized, original);
+        Self::push_anchor(&mut self.ends, normalized + normalized_len, original + original_len);
+    }
+
+    fn push_anchor(anchors: &mut Vec<(usize, usize)>, normalized: usize, original: usize) {
+        let expected = match anchors.last() {
+            Some(&(n, o)) => o + (normalized - n),
+            None => normalized,
+        };
+        if expected != original {
+            anchors.push((normalized, original));
+        }
+    }
+
+    fn lookup(anchors: &[(usize, usize)], pos: usize) -> usize {
+        match anchors.iter().rev().find(|(n, _)| *n <= pos) {
+            Some(&(n, o)) => o + (pos - n),
+            None => pos,
+        }
+    }
+
+    /// Original offset of the character starting at normalized offset `pos`
+    pub fn original_start(&self, pos: usize) -> usize {
+        Self::lookup(&self.starts, pos)
+    }
+
+    /// Original offset just past the character ending at normalized offset `pos`
+    pub fn original_end(&self, pos: usize) -> usize {
+        Self::lookup(&self.ends, pos)
+    }
+
+    /// Translate an interval over the normalized text into one over the original input
+    pub fn map_interval(&self, interval: &CharInterval) -> CharInterval {
+        CharInterval::new(
+            interval.start_pos.map(|start| self.original_start(start)),
+            interval.end_pos.map(|end| self.original_end(end)),
+        )
+    }
+}
+
 /// Annotated document with extractions
 ///
 /// Represents the result of processing a document through the annotation pipeline.
@@ -247,6 +368,15 @@ pub struct AnnotatedDocument {
     pub extractions: Option<Vec<Extraction>>,
     /// Original text content
     pub text: Option<String>,
+    /// Raw model output for each chunk, in chunk order (only kept when requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_outputs: Option<Vec<String>>,
+    /// Model, temperature and prompt of the run, set by [`crate::extract`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_metadata: Option<RunMetadata>,
+    /// Offsets of `text` in the unnormalized input, set when [`crate::extract`] normalized it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset_map: Option<OffsetMap>,
 }
 
 impl AnnotatedDocument {
@@ -256,6 +386,9 @@ impl AnnotatedDocument {
             document_id: None,
             extractions: None,
             text: None,
+            raw_outputs: None,
+            run_metadata: None,
+            offset_map: None,
         }
     }
 
@@ -265,6 +398,9 @@ impl AnnotatedDocument {
             document_id: None,
             extractions: Some(extractions),
             text: Some(text),
+            raw_outputs: None,
+            run_metadata: None,
+            offset_map: None,
         }
     }
 
@@ -294,6 +430,26 @@ impl AnnotatedDocument {
         }
     }
 
+    /// Copy of this document with character intervals pointing into the original, unnormalized input
+    ///
+    /// Only the normalized text is kept, so the copy has no `text`; slice the
+    /// input passed to [`crate::extract`] with its intervals instead. Documents
+    /// without an offset map are returned with their intervals unchanged.
+    pub fn map_to_original(&self) -> AnnotatedDocument {
+        let mut document = self.clone();
+        let map = match document.offset_map.take() {
+            Some(map) => map,
+            None => return document,
+        };
+        document.text = None;
+        for extraction in document.extractions.iter_mut().flatten() {
+            if let Some(interval) = extraction.char_interval.as_mut() {
+                *interval = map.map_interval(interval);
+            }
+        }
+        document
+    }
+
     /// Get the number of extractions
     pub fn extraction_count(&self) -> usize {
         self.extractions.as_ref().map_or(0, |e| e.len())
@@ -310,6 +466,35 @@ impl AnnotatedDocument {
                     .collect()
             })
     }
+
+    /// Bucket extractions by `group_index`, ordered within each group by `extraction_index`
+    ///
+    /// Groups are returned in ascending `group_index` order. Extractions without a
+    /// group index are each returned as their own single-item group at the end.
+    pub fn grouped_extractions(&self) -> Vec<Vec<&Extraction>> {
+        let extractions = match &self.extractions {
+            Some(extractions) => extractions,
+            None => return Vec::new(),
+        };
+
+        let mut groups: std::collections::BTreeMap<usize, Vec<&Extraction>> = std::collections::BTreeMap::new();
+        let mut ungrouped = Vec::new();
+
+        for extraction in extractions {
+            match extraction.group_index {
+                Some(group_index) => groups.entry(group_index).or_default().push(extraction),
+                None => ungrouped.push(vec![extraction]),
+            }
+        }
+
+        let mut result: Vec<Vec<&Extraction>> = groups.into_values().collect();
+        for group in &mut result {
+            // Stable sort keeps input order for extractions without an index
+            group.sort_by_key(|e| e.extraction_index.unwrap_or(usize::MAX));
+        }
+        result.extend(ungrouped);
+        result
+    }
 }
 
 impl Default for AnnotatedDocument {
@@ -318,6 +503,43 @@ impl Default for AnnotatedDocument {
     }
 }
 
+/// Order in which extractions are returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionSortOrder {
+    /// Keep the order the model and chunk aggregation produced
+    #[default]
+    AsFound,
+    /// Sort by start position, extractions without a position last
+    ByCharPosition,
+    /// Sort by class name, then by start position
+    ByClassThenPosition,
+}
+
+impl ExtractionSortOrder {
+    /// Sort `extractions` in place; ties keep their original order
+    pub fn sort(self, extractions: &mut [Extraction]) {
+        // `None` sorts after every position
+        let position = |extraction: &Extraction| {
+            extraction
+                .char_interval
+                .as_ref()
+                .and_then(|interval| interval.start_pos)
+                .map_or((1, 0), |start| (0, start))
+        };
+
+        match self {
+            ExtractionSortOrder::AsFound => {}
+            ExtractionSortOrder::ByCharPosition => extractions.sort_by_key(position),
+            ExtractionSortOrder::ByClassThenPosition => extractions.sort_by(|a, b| {
+                a.extraction_class
+                    .cmp(&b.extraction_class)
+                    .then_with(|| position(a).cmp(&position(b)))
+            }),
+        }
+    }
+}
+
 /// Enumeration of supported output formats
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -379,6 +601,258 @@ impl ExampleData {
     pub fn add_extraction(&mut self, extraction: Extraction) {
         self.extractions.push(extraction);
     }
+
+    /// Check the example for mistakes that would mislead the model
+    ///
+    /// Reports empty fields, extraction text that does not occur in the example
+    /// text (ignoring case), and a class whose extractions use different
+    /// attribute keys.
+    pub fn validate(&self) -> Vec<ExampleWarning> {
+        let mut warnings = Vec::new();
+        if self.text.trim().is_empty() {
+            warnings.push(ExampleWarning::EmptyText);
+        }
+
+        let lowercase_text = self.text.to_lowercase();
+        let mut shapes: HashMap<&str, Vec<&String>> = HashMap::new();
+        for (index, extraction) in self.extractions.iter().enumerate() {
+            if extraction.extraction_class.trim().is_empty() {
+                warnings.push(ExampleWarning::EmptyField { extraction_index: index, field: "extraction_class".to_string() });
+            }
+            if extraction.extraction_text.trim().is_empty() {
+                warnings.push(ExampleWarning::EmptyField { extraction_index: index, field: "extraction_text".to_string() });
+            } else if !lowercase_text.contains(&extraction.extraction_text.to_lowercase()) {
+                warnings.push(ExampleWarning::TextNotFound {
+                    extraction_index: index,
+                    extraction_text: extraction.extraction_text.clone(),
+                });
+            }
+
+            let mut keys: Vec<&String> = extraction.attributes.iter().flat_map(|a| a.keys()).collect();
+            keys.sort();
+            match shapes.get(extraction.extraction_class.as_str()) {
+                Some(expected) if *expected != keys => warnings.push(ExampleWarning::ConflictingShape {
+                    extraction_index: index,
+                    extraction_class: extraction.extraction_class.clone(),
+                }),
+                Some(_) => {}
+                None => {
+                    shapes.insert(&extraction.extraction_class, keys);
+                }
+            }
+        }
+
+        warnings
+    }
+}
+
+/// A problem found in an example by [`ExampleData::validate`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExampleWarning {
+    /// The example text is empty
+    EmptyText,
+    /// An extraction has an empty class or text
+    EmptyField { extraction_index: usize, field: String },
+    /// The extraction text does not occur in the example text
+    TextNotFound { extraction_index: usize, extraction_text: String },
+    /// An extraction uses different attribute keys than an earlier one of the same class
+    ConflictingShape { extraction_index: usize, extraction_class: String },
+}
+
+impl std::fmt::Display for ExampleWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExampleWarning::EmptyText => write!(f, "example text is empty"),
+            ExampleWarning::EmptyField { extraction_index, field } => {
+                write!(f, "extraction {} has an empty {}", extraction_index, field)
+            }
+            ExampleWarning::TextNotFound { extraction_index, extraction_text } => write!(
+                f,
+                "extraction {} text '{}' does not appear in the example text",
+                extraction_index, extraction_text
+            ),
+            ExampleWarning::ConflictingShape { extraction_index, extraction_class } => write!(
+                f,
+                "extraction {} of class '{}' has different attributes than an earlier '{}' extraction",
+                extraction_index, extraction_class, extraction_class
+            ),
+        }
+    }
+}
+
+/// An extraction matched in both documents whose text or attributes differ
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangedExtraction {
+    /// The extraction in the first document
+    pub before: Extraction,
+    /// The matching extraction in the second document
+    pub after: Extraction,
+}
+
+/// Differences between the extractions of two annotated documents
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExtractionDiff {
+    /// Extractions only found in the second document
+    pub added: Vec<Extraction>,
+    /// Extractions only found in the first document
+    pub removed: Vec<Extraction>,
+    /// Matched extractions whose text or attributes changed
+    pub changed: Vec<ChangedExtraction>,
+}
+
+impl ExtractionDiff {
+    /// Whether the two documents have the same extractions
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Render the diff as pretty-printed JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl std::fmt::Display for ExtractionDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences");
+        }
+        for extraction in &self.removed {
+            writeln!(f, "- [{}] {}", extraction.extraction_class, extraction.extraction_text)?;
+        }
+        for extraction in &self.added {
+            writeln!(f, "+ [{}] {}", extraction.extraction_class, extraction.extraction_text)?;
+        }
+        for change in &self.changed {
+            writeln!(
+                f,
+                "~ [{}] {} -> {}",
+                change.before.extraction_class, change.before.extraction_text, change.after.extraction_text
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Merge fragments of one entity, e.g. "New", "York", "City" tagged as separate locations
+///
+/// Extractions of the same class whose char intervals touch or are separated only
+/// by whitespace in `text` become one extraction spanning the combined range.
+/// String attributes that differ are joined with a space. Extractions without an
+/// interval are left as they are.
+pub fn merge_adjacent_same_class(text: &str, extractions: &mut Vec<Extraction>) {
+    let span = |e: &Extraction| e.char_interval.as_ref().and_then(|i| Some((i.start_pos?, i.end_pos?)));
+
+    let mut order: Vec<usize> = (0..extractions.len()).filter(|&i| span(&extractions[i]).is_some()).collect();
+    order.sort_by_key(|&i| span(&extractions[i]));
+
+    let mut merged_away = vec![false; extractions.len()];
+    let mut current: Option<usize> = None;
+    for index in order {
+        let Some(target) = current else {
+            current = Some(index);
+            continue;
+        };
+        let (start, end) = span(&extractions[target]).unwrap_or_default();
+        let (next_start, next_end) = span(&extractions[index]).unwrap_or_default();
+        let gap_is_whitespace = end <= next_start
+            && text.get(end..next_start).is_some_and(|gap| gap.chars().all(char::is_whitespace));
+
+        if extractions[target].extraction_class != extractions[index].extraction_class || !gap_is_whitespace {
+            current = Some(index);
+            continue;
+        }
+
+        let Some(combined_text) = text.get(start..next_end) else {
+            current = Some(index);
+            continue;
+        };
+        let fragment = extractions[index].clone();
+        let target_extraction = &mut extractions[target];
+        target_extraction.extraction_text = combined_text.to_string();
+        target_extraction.char_interval = Some(CharInterval::new(Some(start), Some(next_end)));
+        target_extraction.token_interval = None;
+        if let Some(attributes) = fragment.attributes {
+            let merged = target_extraction.attributes.get_or_insert_with(HashMap::new);
+            for (key, value) in attributes {
+                match (merged.get_mut(&key), &value) {
+                    (Some(serde_json::Value::String(existing)), serde_json::Value::String(extra)) if existing != extra => {
+                        existing.push(' ');
+                        existing.push_str(extra);
+                    }
+                    (Some(_), _) => {}
+                    (None, _) => {
+                        merged.insert(key, value);
+                    }
+                }
+            }
+        }
+        merged_away[index] = true;
+    }
+
+    let mut index = 0;
+    extractions.retain(|_| {
+        index += 1;
+        !merged_away[index - 1]
+    });
+}
+
+/// Compare the extractions of two documents, e.g. runs before and after a prompt change
+///
+/// Extractions are matched by class plus either identical text or overlapping
+/// character intervals. Exact text matches are paired across the whole
+/// document before any overlap is considered. Matched pairs whose text or
+/// attributes differ are reported as changed.
+pub fn diff_documents(a: &AnnotatedDocument, b: &AnnotatedDocument) -> ExtractionDiff {
+    let before = a.extractions.as_deref().unwrap_or_default();
+    let after = b.extractions.as_deref().unwrap_or_default();
+    let mut matched = vec![false; before.len()];
+    let mut pairs: Vec<Option<usize>> = vec![None; after.len()];
+
+    for same_text in [true, false] {
+        for (pair, extraction) in pairs.iter_mut().zip(after) {
+            if pair.is_some() {
+                continue;
+            }
+            *pair = before.iter().enumerate().position(|(i, other)| {
+                !matched[i]
+                    && other.extraction_class == extraction.extraction_class
+                    && if same_text {
+                        other.extraction_text == extraction.extraction_text
+                    } else {
+                        other.overlaps_with(extraction)
+                    }
+            });
+            if let Some(index) = *pair {
+                matched[index] = true;
+            }
+        }
+    }
+
+    let mut diff = ExtractionDiff::default();
+    for (pair, extraction) in pairs.into_iter().zip(after) {
+        match pair {
+            Some(index) => {
+                let other = &before[index];
+                if other.extraction_text != extraction.extraction_text || other.attributes != extraction.attributes {
+                    diff.changed.push(ChangedExtraction {
+                        before: other.clone(),
+                        after: extraction.clone(),
+                    });
+                }
+            }
+            None => diff.added.push(extraction.clone()),
+        }
+    }
+
+    diff.removed = before
+        .iter()
+        .zip(&matched)
+        .filter(|(_, &matched)| !matched)
+        .map(|(extraction, _)| extraction.clone())
+        .collect();
+    diff
 }
 
 #[cfg(test)]
@@ -497,6 +971,39 @@ mod tests {
         assert_eq!(example.extractions.len(), 2);
     }
 
+    #[test]
+    fn test_grouped_extractions() {
+        let make = |class: &str, text: &str, group: Option<usize>, index: usize| Extraction {
+            extraction_class: class.to_string(),
+            extraction_text: text.to_string(),
+            group_index: group,
+            extraction_index: Some(index),
+            ..Default::default()
+        };
+
+        let doc = AnnotatedDocument::with_extractions(
+            vec![
+                make("age", "25", Some(1), 3),
+                make("name", "Alice", Some(0), 0),
+                make("note", "standalone", None, 4),
+                make("name", "Bob", Some(1), 2),
+                make("age", "30", Some(0), 1),
+            ],
+            "Alice is 30. Bob is 25.".to_string(),
+        );
+
+        let groups = doc.grouped_extractions();
+        assert_eq!(groups.len(), 3);
+
+        let texts: Vec<Vec<&str>> = groups
+            .iter()
+            .map(|g| g.iter().map(|e| e.extraction_text.as_str()).collect())
+            .collect();
+        assert_eq!(texts, vec![vec!["Alice", "30"], vec!["Bob", "25"], vec!["standalone"]]);
+
+        assert!(AnnotatedDocument::new().grouped_extractions().is_empty());
+    }
+
     #[test]
     fn test_serialization() {
         let extraction = Extraction::new("person".to_string(), "John Doe".to_string());
@@ -509,4 +1016,218 @@ mod tests {
         let deserialized: Document = serde_json::from_str(&json_str).unwrap();
         assert_eq!(doc, deserialized);
     }
+
+    #[test]
+    fn test_char_interval_round_trip() {
+        let mut located = Extraction::new("person".to_string(), "John".to_string());
+        located.char_interval = Some(CharInterval::new(Some(0), Some(4)));
+        let mut partial = Extraction::new("city".to_string(), "Boston".to_string());
+        partial.char_interval = Some(CharInterval::new(Some(13), None));
+        let doc = AnnotatedDocument::with_extractions(vec![located, partial], "John lives in Boston".to_string());
+
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["extractions"][0]["char_interval"], serde_json::json!({"start": 0, "end": 4}));
+        assert_eq!(json["extractions"][1]["char_interval"], serde_json::json!({"start": 13, "end": null}));
+        assert_eq!(serde_json::from_value::<AnnotatedDocument>(json).unwrap(), doc);
+
+        let yaml = serde_yaml::to_string(&doc).unwrap();
+        assert_eq!(serde_yaml::from_str::<AnnotatedDocument>(&yaml).unwrap(), doc);
+
+        let legacy: CharInterval = serde_json::from_str(r#"{"start_pos": 2, "end_pos": 5}"#).unwrap();
+        assert_eq!(legacy, CharInterval::new(Some(2), Some(5)));
+        let exported: CharInterval = serde_json::from_str(r#"{"start_char": 2}"#).unwrap();
+        assert_eq!(exported, CharInterval::new(Some(2), None));
+    }
+
+    #[test]
+    fn test_diff_documents() {
+        let located = |class: &str, text: &str, start: usize| {
+            let mut extraction = Extraction::new(class.to_string(), text.to_string());
+            extraction.char_interval = Some(CharInterval::new(Some(start), Some(start + text.len())));
+            extraction
+        };
+        let before = AnnotatedDocument::with_extractions(
+            vec![
+                located("person", "John Smith", 0),
+                located("age", "30", 20),
+                located("city", "Boston", 40),
+            ],
+            "text".to_string(),
+        );
+        let after = AnnotatedDocument::with_extractions(
+            vec![
+                located("person", "John", 0),
+                located("city", "Boston", 40),
+                located("employer", "Acme", 60),
+            ],
+            "text".to_string(),
+        );
+
+        let diff = diff_documents(&before, &after);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].extraction_text, "Acme");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].extraction_class, "age");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].before.extraction_text, "John Smith");
+        assert_eq!(diff.changed[0].after.extraction_text, "John");
+
+        let text = diff.to_string();
+        assert!(text.contains("- [age] 30"));
+        assert!(text.contains("+ [employer] Acme"));
+        assert!(text.contains("~ [person] John Smith -> John"));
+
+        let json: serde_json::Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+        assert_eq!(json["changed"][0]["after"]["extraction_text"], "John");
+        assert!(diff_documents(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_diff_documents_prefers_later_exact_match_over_earlier_overlap() {
+        let located = |text: &str, start: usize| {
+            let mut extraction = Extraction::new("person".to_string(), text.to_string());
+            extraction.char_interval = Some(CharInterval::new(Some(start), Some(start + text.len())));
+            extraction
+        };
+        let before = AnnotatedDocument::with_extractions(vec![located("John Smith", 0)], "text".to_string());
+        let after = AnnotatedDocument::with_extractions(
+            vec![located("John", 0), located("John Smith", 0)],
+            "text".to_string(),
+        );
+
+        let diff = diff_documents(&before, &after);
+        assert!(diff.changed.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].extraction_text, "John");
+    }
+
+    #[test]
+    fn test_extraction_sort_orders() {
+        let at = |class: &str, text: &str, start: Option<usize>| {
+            let mut extraction = Extraction::new(class.to_string(), text.to_string());
+            extraction.char_interval = start.map(|s| CharInterval::new(Some(s), Some(s + text.len())));
+            extraction
+        };
+        let extractions = vec![
+            at("person", "Bob", Some(20)),
+            at("city", "Paris", None),
+            at("person", "Alice", Some(0)),
+            at("city", "Rome", Some(40)),
+            at("person", "Carol", None),
+        ];
+        let sorted = |order: ExtractionSortOrder| {
+            let mut extractions = extractions.clone();
+            order.sort(&mut extractions);
+            extractions.into_iter().map(|e| e.extraction_text).collect::<Vec<_>>()
+        };
+
+        assert_eq!(sorted(ExtractionSortOrder::AsFound), vec!["Bob", "Paris", "Alice", "Rome", "Carol"]);
+        assert_eq!(sorted(ExtractionSortOrder::ByCharPosition), vec!["Alice", "Bob", "Rome", "Paris", "Carol"]);
+        assert_eq!(
+            sorted(ExtractionSortOrder::ByClassThenPosition),
+            vec!["Rome", "Paris", "Alice", "Bob", "Carol"]
+        );
+    }
+
+    #[test]
+    fn test_example_validate() {
+        let mut well_formed = ExampleData::new(
+            "John Smith is 30 years old.".to_string(),
+            vec![
+                Extraction::new("person".to_string(), "John Smith".to_string()),
+                Extraction::new("age".to_string(), "30".to_string()),
+            ],
+        );
+        assert!(well_formed.validate().is_empty());
+
+        let mut aged = Extraction::new("age".to_string(), "30".to_string());
+        aged.attributes = Some(HashMap::from([("unit".to_string(), serde_json::json!("years"))]));
+        well_formed.add_extraction(aged);
+        assert_eq!(
+            well_formed.validate(),
+            vec![ExampleWarning::ConflictingShape { extraction_index: 2, extraction_class: "age".to_string() }]
+        );
+
+        let misaligned = ExampleData::new(
+            "John Smith is 30 years old.".to_string(),
+            vec![
+                Extraction::new("person".to_string(), "Jane Doe".to_string()),
+                Extraction::new("".to_string(), "30".to_string()),
+            ],
+        );
+        let warnings = misaligned.validate();
+        assert_eq!(
+            warnings,
+            vec![
+                ExampleWarning::TextNotFound { extraction_index: 0, extraction_text: "Jane Doe".to_string() },
+                ExampleWarning::EmptyField { extraction_index: 1, field: "extraction_class".to_string() },
+            ]
+        );
+        assert_eq!(warnings[0].to_string(), "extraction 0 text 'Jane Doe' does not appear in the example text");
+    }
+
+    #[test]
+    fn test_merge_adjacent_same_class() {
+        let text = "She moved to New York  City last May.";
+        let located = |class: &str, value: &str, attributes: Option<(&str, &str)>| {
+            let start = text.find(value).unwrap();
+            let mut extraction = Extraction::new(class.to_string(), value.to_string());
+            extraction.char_interval = Some(CharInterval::new(Some(start), Some(start + value.len())));
+            extraction.attributes = attributes
+                .map(|(key, value)| HashMap::from([(key.to_string(), serde_json::json!(value))]));
+            extraction
+        };
+        let mut extractions = vec![
+            located("location", "York", Some(("note", "state"))),
+            located("location", "New", Some(("note", "prefix"))),
+            located("location", "City", None),
+            located("date", "May", None),
+        ];
+
+        merge_adjacent_same_class(text, &mut extractions);
+
+        assert_eq!(extractions.len(), 2);
+        let location = extractions.iter().find(|e| e.extraction_class == "location").unwrap();
+        assert_eq!(location.extraction_text, "New York  City");
+        assert_eq!(location.char_interval, Some(CharInterval::new(Some(13), Some(27))));
+        assert_eq!(
+            location.attributes.as_ref().unwrap().get("note"),
+            Some(&serde_json::json!("prefix state"))
+        );
+        assert_eq!(extractions[1].extraction_text, "May");
+    }
+
+    #[test]
+    fn test_map_to_original_skips_removed_characters() {
+        let raw = "Dr.\u{200B} Alice\u{00A0}Smith\u{FEFF} met Bob.";
+        let (normalized, offsets) = crate::io::normalize_input_text_with_offsets(raw);
+        assert_eq!(normalized, "Dr. Alice Smith met Bob.");
+
+        let located = |value: &str| {
+            let start = normalized.find(value).unwrap();
+            let mut extraction = Extraction::new("person".to_string(), value.to_string());
+            extraction.char_interval = Some(CharInterval::new(Some(start), Some(start + value.len())));
+            extraction
+        };
+        let mut document = AnnotatedDocument::with_extractions(
+            vec![located("Alice Smith"), located("Bob")],
+            normalized.clone(),
+        );
+        document.offset_map = Some(offsets);
+
+        let mapped = document.map_to_original();
+        assert!(mapped.text.is_none());
+        let spans: Vec<&str> = mapped
+            .extractions
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|e| {
+                let interval = e.char_interval.as_ref().unwrap();
+                &raw[interval.start_pos.unwrap()..interval.end_pos.unwrap()]
+            })
+            .collect();
+        assert_eq!(spans, vec!["Alice\u{00A0}Smith", "Bob"]);
+    }
 }
@@ -0,0 +1,186 @@
+//! Embedding-based semantic similarity for deduplicating extractions.
+//!
+//! Word overlap misses duplicates such as "USA" and "United States". An
+//! [`Embedder`] turns extraction texts into vectors whose cosine similarity
+//! drives a [`DedupStrategy::Similarity`].
+
+use crate::{
+    chunking::{DedupStrategy, ResultAggregator},
+    data::Extraction,
+    exceptions::{LangExtractError, LangExtractResult},
+    providers::{ProviderConfig, ProviderType},
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Turns texts into embedding vectors
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Embed each text, returning one vector per input in order
+    async fn embed(&self, texts: &[String]) -> LangExtractResult<Vec<Vec<f32>>>;
+}
+
+/// Embedder calling an OpenAI-compatible (`/embeddings`) or Ollama (`/api/embed`) endpoint
+pub struct HttpEmbedder {
+    client: reqwest::Client,
+    config: ProviderConfig,
+}
+
+impl HttpEmbedder {
+    /// Create an embedder for an OpenAI or Ollama provider config, using its model
+    pub fn new(config: ProviderConfig) -> LangExtractResult<Self> {
+        if !matches!(config.provider_type, ProviderType::OpenAI | ProviderType::Ollama) {
+            return Err(LangExtractError::configuration(format!(
+                "Embeddings are not supported for the {} provider",
+                config.provider_type
+            )));
+        }
+        Ok(Self {
+            client: reqwest::Client::new(),
+            config,
+        })
+    }
+}
+
+#[async_trait]
+impl Embedder for HttpEmbedder {
+    async fn embed(&self, texts: &[String]) -> LangExtractResult<Vec<Vec<f32>>> {
+        let base_url = self.config.base_url.trim_end_matches('/');
+        let url = match self.config.provider_type {
+            ProviderType::Ollama => format!("{}/api/embed", base_url),
+            _ => format!("{}/embeddings", base_url),
+        };
+
+        let mut request = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({ "model": self.config.model, "input": texts }));
+        if let Some(api_key) = &self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        for (key, value) in &self.config.headers {
+            request = request.header(key, value);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            return Err(LangExtractError::inference_simple(format!(
+                "Embedding request to {} returned HTTP {}: {}",
+                url, status, message
+            )));
+        }
+        let body: serde_json::Value = response.json().await?;
+
+        let vectors: Option<Vec<Vec<f32>>> = match self.config.provider_type {
+            ProviderType::Ollama => body["embeddings"].as_array().map(|vectors| vectors.iter().map(to_vector).collect()),
+            _ => body["data"].as_array().map(|data| data.iter().map(|item| to_vector(&item["embedding"])).collect()),
+        };
+        match vectors {
+            Some(vectors) if vectors.len() == texts.len() => Ok(vectors),
+            _ => Err(LangExtractError::parsing(format!(
+                "Embedding response from {} did not contain {} vectors",
+                url,
+                texts.len()
+            ))),
+        }
+    }
+}
+
+/// Read a JSON array of numbers as a vector
+fn to_vector(value: &serde_json::Value) -> Vec<f32> {
+    value
+        .as_array()
+        .map(|values| values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect())
+        .unwrap_or_default()
+}
+
+/// Cosine similarity of two vectors (0.0 when either is empty or zero)
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Build a similarity dedup strategy from the embeddings of `extractions`
+///
+/// Each distinct extraction text is embedded once. Pairs involving a text
+/// that was not embedded score 0.0 unless the texts are identical.
+pub async fn semantic_dedup_strategy(
+    embedder: &dyn Embedder,
+    extractions: &[Extraction],
+) -> LangExtractResult<DedupStrategy> {
+    let mut texts: Vec<String> = extractions.iter().map(|e| e.extraction_text.clone()).collect();
+    texts.sort_unstable();
+    texts.dedup();
+
+    let vectors = if texts.is_empty() { Vec::new() } else { embedder.embed(&texts).await? };
+    let cache: Arc<HashMap<String, Vec<f32>>> = Arc::new(texts.into_iter().zip(vectors).collect());
+
+    Ok(DedupStrategy::similarity(move |e1, e2| {
+        if e1.extraction_text == e2.extraction_text {
+            return 1.0;
+        }
+        match (cache.get(&e1.extraction_text), cache.get(&e2.extraction_text)) {
+            (Some(a), Some(b)) => cosine_similarity(a, b),
+            _ => 0.0,
+        }
+    }))
+}
+
+/// Remove extractions of the same class whose embeddings have cosine similarity of at least `threshold`
+pub async fn deduplicate_semantic(
+    embedder: &dyn Embedder,
+    extractions: Vec<Extraction>,
+    threshold: f32,
+) -> LangExtractResult<Vec<Extraction>> {
+    let strategy = semantic_dedup_strategy(embedder, &extractions).await?;
+    ResultAggregator::with_settings(threshold, true, strategy).deduplicate_extractions(extractions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Embedder returning fixed vectors and counting embedded texts
+    struct StubEmbedder {
+        embedded: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Embedder for StubEmbedder {
+        async fn embed(&self, texts: &[String]) -> LangExtractResult<Vec<Vec<f32>>> {
+            self.embedded.fetch_add(texts.len(), Ordering::SeqCst);
+            Ok(texts
+                .iter()
+                .map(|text| match text.as_str() {
+                    "USA" => vec![1.0, 0.0],
+                    "United States" => vec![0.98, 0.2],
+                    _ => vec![0.0, 1.0],
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_dedup_merges_synonyms() {
+        let embedder = StubEmbedder { embedded: AtomicUsize::new(0) };
+        let country = |text: &str| Extraction::new("country".to_string(), text.to_string());
+        let extractions = vec![country("USA"), country("United States"), country("Canada"), country("USA")];
+
+        let unique = deduplicate_semantic(&embedder, extractions, 0.9).await.unwrap();
+        let texts: Vec<&str> = unique.iter().map(|e| e.extraction_text.as_str()).collect();
+        assert_eq!(texts, vec!["USA", "Canada"]);
+        assert_eq!(embedder.embedded.load(Ordering::SeqCst), 3);
+
+        assert!((cosine_similarity(&[1.0, 0.0], &[0.0, 1.0])).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,192 @@
+//! Offline token and cost estimates for planning extraction jobs.
+//!
+//! Estimates plan chunks and render prompts exactly as [`crate::extract`]
+//! would, but never call a language model. Chunks auto-shrunk after a
+//! truncated response are not counted.
+
+use crate::{
+    build_annotator, build_prompt_template,
+    data::ExampleData,
+    exceptions::LangExtractResult,
+    factory::create_provider_config,
+    io::normalize_input_text_with_offsets,
+    providers::MockModel,
+    tokenizer::{Tokenize, Tokenizer},
+    ExtractConfig,
+};
+use serde::{Deserialize, Serialize};
+
+/// Planned size and cost of an extraction run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtractionEstimate {
+    /// Number of chunks the text is split into
+    pub chunk_count: usize,
+    /// Number of prompts sent to the model (chunks times extraction passes)
+    pub prompt_count: usize,
+    /// Total prompt tokens across all prompts
+    pub prompt_tokens: usize,
+    /// Estimated cost in dollars, if the provider config has `price_per_1k_tokens`
+    pub estimated_cost: Option<f64>,
+}
+
+/// Estimate the prompt tokens and cost of extracting from `text` without calling the model
+pub fn extract_estimate(
+    text: &str,
+    prompt_description: Option<&str>,
+    examples: &[ExampleData],
+    config: &ExtractConfig,
+) -> LangExtractResult<ExtractionEstimate> {
+    let normalized;
+    let text = if config.normalize_input {
+        normalized = normalize_input_text_with_offsets(text).0;
+        normalized.as_str()
+    } else {
+        text
+    };
+
+    // The model is never called; it only carries the prompt template and chunking settings
+    let prompt_template = build_prompt_template(prompt_description, examples, config);
+    let annotator = build_annotator(Box::new(MockModel::new()), prompt_template, config, false);
+    let additional_context = config.additional_context.as_deref();
+    let chunks = annotator.plan_chunks(text, config.max_char_buffer, additional_context)?;
+
+    let default_tokenizer;
+    let tokenizer: &dyn Tokenize = match annotator.tokenizer() {
+        Some(tokenizer) => tokenizer,
+        None => {
+            default_tokenizer = Tokenizer::new()?;
+            &default_tokenizer
+        }
+    };
+    let mut tokens_per_pass = 0;
+    for chunk in &chunks {
+        let prompt = annotator.render_chunk_prompt(chunk, additional_context)?;
+        tokens_per_pass += tokenizer.tokenize(&prompt)?.tokens.len();
+    }
+
+    // Only multi-pass extraction re-sends chunks; single-pass runs ignore extra passes
+    let passes = if config.enable_multipass {
+        config.extraction_passes.max(1)
+    } else {
+        1
+    };
+    let prompt_tokens = tokens_per_pass * passes;
+    let estimated_cost = create_provider_config(config)
+        .ok()
+        .and_then(|provider| provider.price_per_1k_tokens)
+        .map(|price| prompt_tokens as f64 / 1000.0 * price);
+
+    Ok(ExtractionEstimate {
+        chunk_count: chunks.len(),
+        prompt_count: chunks.len() * passes,
+        prompt_tokens,
+        estimated_cost,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{data::Extraction, providers::ProviderConfig, tokenizer::CjkTokenizer};
+
+    fn examples() -> Vec<ExampleData> {
+        vec![ExampleData::new(
+            "John Doe is 30 years old".to_string(),
+            vec![Extraction::new("person".to_string(), "John Doe".to_string())],
+        )]
+    }
+
+    #[test]
+    fn test_estimate_single_prompt() {
+        let provider = ProviderConfig::ollama("mistral", None).with_price_per_1k_tokens(0.5);
+        let mut config = ExtractConfig {
+            max_char_buffer: 1000,
+            ..Default::default()
+        };
+        config.language_model_params.insert(
+            "provider_config".to_string(),
+            serde_json::to_value(&provider).unwrap(),
+        );
+        let text = "Alice met Bob in Paris.";
+
+        let estimate = extract_estimate(text, Some("Extract people"), &examples(), &config).unwrap();
+
+        let prompt = build_prompt_template(Some("Extract people"), &examples(), &config)
+            .render(text, None)
+            .unwrap();
+        let expected_tokens = Tokenizer::new().unwrap().tokenize(&prompt).unwrap().len();
+        assert_eq!(estimate.chunk_count, 1);
+        assert_eq!(estimate.prompt_tokens, expected_tokens);
+        assert_eq!(estimate.estimated_cost, Some(expected_tokens as f64 / 1000.0 * 0.5));
+    }
+
+    #[test]
+    fn test_estimate_chunked_text_with_passes() {
+        let config = ExtractConfig {
+            max_char_buffer: 40,
+            enable_multipass: true,
+            extraction_passes: 2,
+            ..Default::default()
+        };
+        let text = "Alice met Bob in Paris. Carol called Dave. Erin wrote to Frank. Grace visited Heidi.";
+
+        let single_pass = extract_estimate(
+            text,
+            None,
+            &examples(),
+            &ExtractConfig { extraction_passes: 1, ..config.clone() },
+        )
+        .unwrap();
+        let estimate = extract_estimate(text, None, &examples(), &config).unwrap();
+
+        assert_eq!(estimate.chunk_count, 3);
+        assert_eq!(estimate.prompt_count, 6);
+        assert_eq!(estimate.prompt_tokens, single_pass.prompt_tokens * 2);
+        assert_eq!(estimate.estimated_cost, None);
+    }
+
+    #[test]
+    fn test_estimate_follows_min_chunks_and_context_window() {
+        let text = "Alice met Bob in Paris. Carol called Dave. Erin wrote to Frank. Grace visited Heidi.";
+        let config = ExtractConfig {
+            max_char_buffer: 1000,
+            min_chunks: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(extract_estimate(text, None, &examples(), &config).unwrap().chunk_count, 3);
+
+        let overhead = Tokenizer::new()
+            .unwrap()
+            .tokenize(&build_prompt_template(None, &examples(), &config).render("", None).unwrap())
+            .unwrap()
+            .tokens
+            .len();
+        let provider = ProviderConfig::ollama("mistral", None).with_context_window(overhead + 8);
+        let mut config = ExtractConfig {
+            max_char_buffer: 1000,
+            ..Default::default()
+        };
+        config.language_model_params.insert(
+            "provider_config".to_string(),
+            serde_json::to_value(&provider).unwrap(),
+        );
+        assert!(extract_estimate(text, None, &examples(), &config).unwrap().chunk_count > 1);
+    }
+
+    #[test]
+    fn test_estimate_counts_tokens_with_configured_tokenizer() {
+        let config = ExtractConfig {
+            max_char_buffer: 1000,
+            ..Default::default()
+        }
+        .with_tokenizer(std::sync::Arc::new(CjkTokenizer::new().unwrap()));
+        let text = "张伟在北京见到了李娜。";
+
+        let estimate = extract_estimate(text, None, &examples(), &config).unwrap();
+
+        let prompt = build_prompt_template(None, &examples(), &config).render(text, None).unwrap();
+        let cjk_tokens = CjkTokenizer::new().unwrap().tokenize(&prompt).unwrap().tokens.len();
+        assert_eq!(estimate.prompt_tokens, cjk_tokens);
+        assert_ne!(estimate.prompt_tokens, Tokenizer::new().unwrap().tokenize(&prompt).unwrap().len());
+    }
+}
@@ -63,6 +63,12 @@ pub enum LangExtractError {
     #[error("Visualization error: {0}")]
     VisualizationError(String),
 
+    /// Model output failed validation while strict validation was enabled
+    #[error("Validation failed: {}", .errors.iter().map(|e| e.message.as_str()).collect::<Vec<_>>().join("; "))]
+    ValidationFailed {
+        errors: Vec<crate::resolver::ValidationError>,
+    },
+
     /// Generic error for unexpected conditions
     #[error("Unexpected error: {0}")]
     UnexpectedError(String),
@@ -163,6 +169,19 @@ impl LangExtractError {
     pub fn is_parsing_error(&self) -> bool {
         matches!(self, Self::ParsingError(_))
     }
+
+    /// Check if this error is a strict validation failure
+    pub fn is_validation_error(&self) -> bool {
+        matches!(self, Self::ValidationFailed { .. })
+    }
+
+    /// Get the specific inference failure if this error wraps one
+    pub fn inference_kind(&self) -> Option<&InferenceError> {
+        match self {
+            Self::InferenceError { source: Some(source), .. } => source.downcast_ref::<InferenceError>(),
+            _ => None,
+        }
+    }
 }
 
 // Convert from serde JSON errors
@@ -217,6 +236,10 @@ pub enum InferenceError {
     #[error("Service unavailable for provider {provider}")]
     ServiceUnavailable { provider: String },
 
+    /// Server could not be reached at all (connection refused, DNS failure, timeout)
+    #[error("Server unreachable for provider {provider}: {message}")]
+    ServerUnreachable { provider: String, message: String },
+
     /// Generic inference failure
     #[error("Inference failed: {message}")]
     InferenceFailed { message: String },
@@ -229,6 +252,7 @@ impl From<InferenceError> for LangExtractError {
             InferenceError::AuthenticationFailed { provider, .. } => Some(provider.clone()),
             InferenceError::QuotaExceeded { provider } => Some(provider.clone()),
             InferenceError::ServiceUnavailable { provider } => Some(provider.clone()),
+            InferenceError::ServerUnreachable { provider, .. } => Some(provider.clone()),
             _ => None,
         };
 
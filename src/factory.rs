@@ -1,16 +1,15 @@
 //! Factory for creating language model instances.
 
 use crate::{
-    data::ExampleData,
+    cache::{ResponseCache, DEFAULT_CACHE_DIR},
+    data::{ExampleData, FormatType},
     exceptions::{LangExtractError, LangExtractResult},
     inference::BaseLanguageModel,
-    providers::{create_provider, ProviderConfig},
+    providers::{create_provider, MockModel, ProviderConfig, ProviderType},
+    schema::{gbnf_grammar, json_output_schema},
     ExtractConfig,
 };
 
-#[cfg(test)]
-use crate::providers::ProviderType;
-
 /// Create a language model based on configuration
 pub async fn create_model(
     config: &ExtractConfig,
@@ -18,18 +17,64 @@ pub async fn create_model(
 ) -> LangExtractResult<Box<dyn BaseLanguageModel>> {
     // Determine provider type and configuration from the ExtractConfig
     let provider_config = create_provider_config(config)?;
+
+    if provider_config.provider_type == ProviderType::Mock {
+        let mut model = MockModel::from_config(&provider_config)?;
+        model.set_fence_output(config.fence_output);
+        return Ok(Box::new(model));
+    }
     
     // Create the provider
     let mut provider = create_provider(provider_config)?;
+
+    // Attach the response cache if enabled
+    if config.enable_cache {
+        let cache_dir = config
+            .cache_dir
+            .clone()
+            .unwrap_or_else(|| std::path::PathBuf::from(DEFAULT_CACHE_DIR));
+        let cache = ResponseCache::new(cache_dir)?
+            .with_nonzero_temperature(config.cache_nonzero_temperature);
+        provider = provider.with_cache(cache);
+    }
+
+    if let Some(system_prompt) = &config.system_prompt {
+        provider = provider.with_system_prompt(system_prompt.clone());
+    }
+
+    if let Some(max_output_tokens) = config.max_output_tokens {
+        provider = provider.with_max_output_tokens(max_output_tokens);
+    }
+
+    if let Some(seed) = config.seed {
+        provider = provider.with_seed(seed);
+    }
+
+    provider = provider.with_json_mode(config.json_mode);
     
-    // Apply schema if examples are provided
+    // Apply schema if examples are provided and the provider can enforce one
     if let Some(example_data) = examples {
-        if config.use_schema_constraints && !example_data.is_empty() {
+        if config.use_schema_constraints && !example_data.is_empty() && provider.capabilities().json_schema {
             if let Some(schema_class) = provider.get_schema_class() {
                 // For now, we'll use a basic schema
                 provider.apply_schema(Some(schema_class));
             }
         }
+
+        // Hold local models to the example output shape: llama.cpp-style servers take a
+        // GBNF grammar, Ollama a JSON schema in `format`
+        if config.use_schema_constraints && config.format_type == FormatType::Json {
+            let capabilities = provider.capabilities();
+            if capabilities.grammar {
+                if let Some(grammar) = gbnf_grammar(example_data) {
+                    provider = provider.with_grammar(grammar);
+                }
+            } else if capabilities.json_schema {
+                if let Some(output_schema) = json_output_schema(example_data) {
+                    provider = provider.with_output_schema(output_schema);
+                }
+            }
+        }
     }
     
     // Set fence output preference
@@ -39,7 +84,7 @@ pub async fn create_model(
 }
 
 /// Create provider configuration from ExtractConfig
-fn create_provider_config(config: &ExtractConfig) -> LangExtractResult<ProviderConfig> {
+pub(crate) fn create_provider_config(config: &ExtractConfig) -> LangExtractResult<ProviderConfig> {
     // Check if provider configuration is already specified in language_model_params
     if let Some(provider_config_value) = config.language_model_params.get("provider_config") {
         if let Ok(provider_config) = serde_json::from_value::<ProviderConfig>(provider_config_value.clone()) {
@@ -78,6 +78,30 @@ impl HttpClient {
         ).await
     }
 
+    /// GET request returning the response body, with retry logic
+    pub async fn get_bytes_with_retry(&self, url: &str, operation_name: &str) -> LangExtractResult<Vec<u8>> {
+        self.retry_with_backoff(|| self.get_bytes_single(url), operation_name).await
+    }
+
+    /// Single GET request returning the response body
+    async fn get_bytes_single(&self, url: &str) -> LangExtractResult<Vec<u8>> {
+        let mut request = self.client.get(url);
+        for (key, value) in &self.config.headers {
+            request = request.header(key, value);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(LangExtractError::invalid_input(format!(
+                "HTTP error: {} for URL: {}",
+                response.status(),
+                url
+            )));
+        }
+
+        Ok(response.bytes().await?.to_vec())
+    }
+
     /// Single POST request with JSON body
     async fn post_json_single<T>(&self, url: &str, body: &T) -> LangExtractResult<Value>
     where
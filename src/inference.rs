@@ -3,8 +3,13 @@
 //! This module provides the core abstraction for language model inference,
 //! including the base trait that all providers must implement.
 
-use crate::{data::FormatType, exceptions::LangExtractResult, schema::BaseSchema};
+use crate::{
+    data::FormatType,
+    exceptions::{LangExtractError, LangExtractResult},
+    schema::BaseSchema,
+};
 use async_trait::async_trait;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -66,6 +71,35 @@ impl fmt::Display for ScoredOutput {
     }
 }
 
+/// An image sent alongside a prompt to a multimodal model
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageInput {
+    /// Encoded image bytes (PNG, JPEG, ...)
+    pub data: Vec<u8>,
+    /// MIME type of the image, e.g. `image/png`
+    pub mime_type: String,
+}
+
+impl ImageInput {
+    /// Create an image input from encoded bytes and their MIME type
+    pub fn new(data: impl Into<Vec<u8>>, mime_type: &str) -> Self {
+        Self {
+            data: data.into(),
+            mime_type: mime_type.to_string(),
+        }
+    }
+
+    /// Base64 encoding of the image bytes
+    pub fn to_base64(&self) -> String {
+        base64::engine::general_purpose::STANDARD.encode(&self.data)
+    }
+
+    /// The image as a `data:` URL
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.to_base64())
+    }
+}
+
 /// Abstract base trait for language model inference
 ///
 /// All language model providers must implement this trait to be compatible
@@ -92,6 +126,16 @@ pub trait BaseLanguageModel: Send + Sync {
         true // Conservative default
     }
 
+    /// Cheaply check that the provider is reachable, authorized and serves the model
+    ///
+    /// Failures are reported as [`crate::exceptions::InferenceError`] variants
+    /// (`ServerUnreachable`, `AuthenticationFailed`, `ModelNotFound`), available
+    /// through [`crate::LangExtractError::inference_kind`]. Providers without a
+    /// cheap ping report healthy.
+    async fn health_check(&self) -> LangExtractResult<()> {
+        Ok(())
+    }
+
     /// Perform inference on a batch of prompts
     ///
     /// # Arguments
@@ -118,6 +162,22 @@ pub trait BaseLanguageModel: Send + Sync {
         Ok(results.into_iter().next().unwrap_or_default())
     }
 
+    /// Run a single prompt together with an image
+    ///
+    /// Providers that cannot read images return a configuration error.
+    async fn infer_with_image(
+        &self,
+        prompt: &str,
+        image: &ImageInput,
+        kwargs: &std::collections::HashMap<String, serde_json::Value>,
+    ) -> LangExtractResult<Vec<ScoredOutput>> {
+        let _ = (prompt, image, kwargs);
+        Err(LangExtractError::configuration(format!(
+            "Provider '{}' does not support image input",
+            self.provider_name()
+        )))
+    }
+
     /// Parse model output as JSON or YAML
     ///
     /// This expects raw JSON/YAML without code fences.
@@ -1,6 +1,9 @@
 //! I/O utilities for loading text from various sources.
 
+use crate::data::{ExampleData, OffsetMap};
 use crate::exceptions::{LangExtractError, LangExtractResult};
+use crate::http_client::{HttpClient, HttpConfig};
+use futures::stream::{self, StreamExt};
 use regex::Regex;
 
 /// Check if a string is a URL (starts with http:// or https://)
@@ -26,8 +29,48 @@ pub async fn download_text_from_url(url: &str) -> LangExtractResult<String> {
         ));
     }
 
-    let content = response.text().await?;
-    Ok(content)
+    let bytes = response.bytes().await?;
+    decode_download(&bytes)
+}
+
+/// Download several URLs, at most `max_concurrent` at a time
+///
+/// Results are returned in the order of `urls`. Each request uses the timeout
+/// and retry settings of [`HttpConfig::default`]; use
+/// [`download_many_with_config`] to change them.
+pub async fn download_many(urls: &[&str], max_concurrent: usize) -> Vec<LangExtractResult<String>> {
+    download_many_with_config(urls, max_concurrent, HttpConfig::default()).await
+}
+
+/// Download several URLs with the given timeout, retry and header settings
+pub async fn download_many_with_config(
+    urls: &[&str],
+    max_concurrent: usize,
+    config: HttpConfig,
+) -> Vec<LangExtractResult<String>> {
+    let client = HttpClient::with_config(config);
+    stream::iter(urls)
+        .map(|url| {
+            let client = &client;
+            async move {
+                if !is_url(url) {
+                    return Err(LangExtractError::invalid_input(format!("Invalid URL: {}", url)));
+                }
+                let bytes = client.get_bytes_with_retry(url, &format!("Download {}", url)).await?;
+                decode_download(&bytes)
+            }
+        })
+        .buffered(max_concurrent.max(1))
+        .collect()
+        .await
+}
+
+/// Text of a downloaded body, decompressing gzip data
+fn decode_download(bytes: &[u8]) -> LangExtractResult<String> {
+    if is_gzip_data(bytes) {
+        return decompress_gzip(bytes);
+    }
+    Ok(String::from_utf8_lossy(bytes).into_owned())
 }
 
 /// Clean and normalize text content
@@ -38,6 +81,81 @@ pub fn normalize_text(text: &str) -> String {
     normalized.to_string()
 }
 
+/// Drop invisible characters that throw off offsets and tokenization
+///
+/// Removes zero-width spaces, word joiners, byte-order marks, soft hyphens and
+/// control characters other than tab, newline and carriage return, and turns
+/// no-break spaces into plain spaces. Unlike [`normalize_text`], line breaks and
+/// runs of whitespace are kept. Unicode NFC composition is not applied.
+pub fn normalize_input_text(text: &str) -> String {
+    normalize_input_text_with_offsets(text).0
+}
+
+/// Normalize text like [`normalize_input_text`], also returning where each part came from in `text`
+pub fn normalize_input_text_with_offsets(text: &str) -> (String, OffsetMap) {
+    let mut normalized = String::with_capacity(text.len());
+    let mut offsets = OffsetMap::default();
+    for (original, c) in text.char_indices() {
+        if matches!(c, '\u{200B}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}')
+            || (c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+        {
+            continue;
+        }
+        let kept = match c {
+            '\u{00A0}' | '\u{2007}' | '\u{202F}' => ' ',
+            other => other,
+        };
+        offsets.record(normalized.len(), kept.len_utf8(), original, c.len_utf8());
+        normalized.push(kept);
+    }
+    (normalized, offsets)
+}
+
+/// An examples file holds either a list of examples or a single one
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum ExamplesFile {
+    Many(Vec<ExampleData>),
+    One(ExampleData),
+}
+
+/// Load the few-shot examples from every `.json`, `.yaml` and `.yml` file in `dir`
+///
+/// Files are read in file name order and their examples concatenated. Each file
+/// holds one example or a list of them; files that cannot be read or parsed are
+/// skipped with a warning.
+pub fn load_examples_dir(dir: impl AsRef<std::path::Path>) -> LangExtractResult<Vec<ExampleData>> {
+    let dir = dir.as_ref();
+    let mut paths: Vec<std::path::PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| {
+            LangExtractError::configuration(format!("Failed to read examples directory '{}': {}", dir.display(), e))
+        })?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && matches!(path.extension().and_then(|ext| ext.to_str()), Some("json" | "yaml" | "yml"))
+        })
+        .collect();
+    paths.sort();
+
+    let mut examples = Vec::new();
+    for path in paths {
+        let parsed = std::fs::read_to_string(&path).map_err(LangExtractError::from).and_then(|content| {
+            if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                Ok(serde_json::from_str::<ExamplesFile>(&content)?)
+            } else {
+                Ok(serde_yaml::from_str::<ExamplesFile>(&content)?)
+            }
+        });
+        match parsed {
+            Ok(ExamplesFile::Many(file_examples)) => examples.extend(file_examples),
+            Ok(ExamplesFile::One(example)) => examples.push(example),
+            Err(e) => tracing::warn!(path = %path.display(), error = %e, "Skipping unreadable examples file"),
+        }
+    }
+    Ok(examples)
+}
+
 /// Extract plain text from HTML content (basic implementation)
 pub fn extract_text_from_html(html: &str) -> String {
     // This is a very basic HTML tag removal
@@ -47,18 +165,150 @@ pub fn extract_text_from_html(html: &str) -> String {
     normalize_text(&text)
 }
 
-/// Load text from a file path
+/// Load text from a file path, decompressing `.gz` files
 pub async fn load_text_from_file(file_path: &str) -> LangExtractResult<String> {
+    if is_gzip_path(file_path) {
+        return decompress_gzip(&tokio::fs::read(file_path).await?);
+    }
     let content = tokio::fs::read_to_string(file_path).await?;
     Ok(content)
 }
 
+/// Read text from a file path, decompressing `.gz` files
+pub fn read_text_file(file_path: &str) -> LangExtractResult<String> {
+    if is_gzip_path(file_path) {
+        return decompress_gzip(&std::fs::read(file_path)?);
+    }
+    Ok(std::fs::read_to_string(file_path)?)
+}
+
+/// Whether `path` names a gzip-compressed file (`.gz`)
+pub fn is_gzip_path(path: &str) -> bool {
+    std::path::Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+/// Whether `data` starts with the gzip magic bytes
+fn is_gzip_data(data: &[u8]) -> bool {
+    data.starts_with(&[0x1f, 0x8b])
+}
+
+/// Decompress gzip data into UTF-8 text
+#[cfg(feature = "gzip")]
+pub fn decompress_gzip(data: &[u8]) -> LangExtractResult<String> {
+    use std::io::Read;
+
+    let mut text = String::new();
+    flate2::read::MultiGzDecoder::new(data)
+        .read_to_string(&mut text)
+        .map_err(|e| LangExtractError::parsing(format!("Failed to decompress gzip input: {}", e)))?;
+    Ok(text)
+}
+
+/// Decompress gzip data into UTF-8 text
+#[cfg(not(feature = "gzip"))]
+pub fn decompress_gzip(_data: &[u8]) -> LangExtractResult<String> {
+    Err(LangExtractError::configuration(
+        "Reading gzip-compressed input requires the 'gzip' feature",
+    ))
+}
+
 /// Save text to a file path
 pub async fn save_text_to_file(file_path: &str, content: &str) -> LangExtractResult<()> {
     tokio::fs::write(file_path, content).await?;
     Ok(())
 }
 
+/// Whether `path` names a Word document (`.docx`, or the unsupported legacy `.doc`)
+pub fn is_word_document(path: &str) -> bool {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    matches!(extension.as_deref(), Some("docx") | Some("doc"))
+}
+
+/// Extract plain text from a Word `.docx` file
+///
+/// Paragraphs are returned in document order, one per line. Table rows are
+/// flattened to one line each with cells separated by tabs. Legacy binary
+/// `.doc` files are rejected.
+#[cfg(feature = "docx")]
+pub fn extract_text_from_docx(path: impl AsRef<std::path::Path>) -> LangExtractResult<String> {
+    use quick_xml::events::Event;
+    use std::io::Read;
+
+    let path = path.as_ref();
+    let mut file = std::fs::File::open(path)?;
+    let mut magic = [0u8; 4];
+    let is_ole = file.read(&mut magic)? == 4 && magic == [0xD0, 0xCF, 0x11, 0xE0];
+    let is_doc = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("doc"));
+    if is_ole || is_doc {
+        return Err(LangExtractError::invalid_input(format!(
+            "Legacy Word .doc files are not supported: {} (save it as .docx first)",
+            path.display()
+        )));
+    }
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)
+        .map_err(|e| LangExtractError::parsing(format!("Failed to open DOCX archive: {}", e)))?;
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")
+        .map_err(|e| LangExtractError::parsing(format!("DOCX is missing word/document.xml: {}", e)))?
+        .read_to_string(&mut xml)?;
+
+    let mut reader = quick_xml::Reader::from_str(&xml);
+    let mut lines = Vec::new();
+    let mut paragraph = String::new();
+    let mut cell_paragraphs: Vec<String> = Vec::new();
+    let mut row_cells: Vec<String> = Vec::new();
+    let mut table_depth = 0;
+    let mut in_text = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| LangExtractError::parsing(format!("Invalid DOCX XML: {}", e)))?;
+        match event {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"t" => in_text = true,
+                b"tbl" => table_depth += 1,
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"tab" => paragraph.push('\t'),
+                b"br" | b"cr" => paragraph.push('\n'),
+                _ => {}
+            },
+            Event::Text(text) if in_text => {
+                let text = text
+                    .unescape()
+                    .map_err(|e| LangExtractError::parsing(format!("Invalid DOCX text: {}", e)))?;
+                paragraph.push_str(&text);
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" if table_depth > 0 => cell_paragraphs.push(std::mem::take(&mut paragraph)),
+                b"p" => lines.push(std::mem::take(&mut paragraph)),
+                // Nested tables are folded into the enclosing cell
+                b"tc" if table_depth == 1 => {
+                    let cell: Vec<String> = cell_paragraphs.drain(..).filter(|p| !p.is_empty()).collect();
+                    row_cells.push(cell.join(" "));
+                }
+                b"tr" if table_depth == 1 => lines.push(std::mem::take(&mut row_cells).join("\t")),
+                b"tbl" => table_depth -= 1,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(lines.join("\n"))
+}
+
 /// Detect the content type of text (plain text, HTML, etc.)
 #[derive(Debug, Clone, PartialEq)]
 pub enum ContentType {
@@ -97,10 +347,439 @@ pub fn detect_content_type(content: &str) -> ContentType {
     ContentType::PlainText
 }
 
+/// Rows of a CSV or TSV file, with the header row split out
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelimitedTable {
+    /// Column names from the first row
+    pub headers: Vec<String>,
+    /// Data rows, each padded to the header width
+    pub rows: Vec<Vec<String>>,
+}
+
+impl DelimitedTable {
+    /// Turn one column into documents with ids `row-1`, `row-2`, ... in row order
+    pub fn column_documents(&self, column: &str) -> LangExtractResult<Vec<JsonlDocument>> {
+        let index = self.headers.iter().position(|header| header == column).ok_or_else(|| {
+            LangExtractError::invalid_input(format!(
+                "Column '{}' not found; available columns: {}",
+                column,
+                self.headers.join(", ")
+            ))
+        })?;
+
+        Ok(self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(row, fields)| JsonlDocument {
+                id: format!("row-{}", row + 1),
+                text: fields[index].clone(),
+            })
+            .collect())
+    }
+}
+
+/// Delimiter implied by a file name: tab for `.tsv`/`.tab`, comma otherwise
+pub fn delimiter_for_path(path: &str) -> char {
+    let lower = path.to_lowercase();
+    if lower.ends_with(".tsv") || lower.ends_with(".tab") {
+        '\t'
+    } else {
+        ','
+    }
+}
+
+/// Parse CSV/TSV content with RFC 4180 quoting into a table
+///
+/// Quoted fields may contain the delimiter, doubled quotes and line breaks.
+/// Blank lines are skipped and short rows are padded with empty fields.
+pub fn parse_delimited(content: &str, delimiter: char) -> LangExtractResult<DelimitedTable> {
+    let content = content.strip_prefix('\u{FEFF}').unwrap_or(content);
+    let mut records: Vec<Vec<String>> = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c != '"' {
+                field.push(c);
+            } else if chars.peek() == Some(&'"') {
+                field.push('"');
+                chars.next();
+            } else {
+                in_quotes = false;
+            }
+        } else if c == '"' && field.is_empty() {
+            in_quotes = true;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            record.push(std::mem::take(&mut field));
+            records.push(std::mem::take(&mut record));
+        } else {
+            field.push(c);
+        }
+    }
+
+    if in_quotes {
+        return Err(LangExtractError::invalid_input("Unterminated quoted field in delimited input"));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|record| record.len() > 1 || record.first().is_some_and(|f| !f.is_empty()));
+
+    let mut records = records.into_iter();
+    let headers = records
+        .next()
+        .ok_or_else(|| LangExtractError::invalid_input("Delimited input has no header row"))?;
+    let rows = records
+        .map(|mut row| {
+            if row.len() < headers.len() {
+                row.resize(headers.len(), String::new());
+            }
+            row
+        })
+        .collect();
+
+    Ok(DelimitedTable { headers, rows })
+}
+
+/// Render a header row and data rows as CSV/TSV, quoting fields where needed
+pub fn write_delimited(headers: &[String], rows: &[Vec<String>], delimiter: char) -> String {
+    let escape = |field: &String| {
+        if field.contains(delimiter) || field.contains('"') || field.contains('\n') || field.contains('\r') {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.clone()
+        }
+    };
+
+    let mut output = String::new();
+    for row in std::iter::once(headers).chain(rows.iter().map(Vec::as_slice)) {
+        let fields: Vec<String> = row.iter().map(escape).collect();
+        output.push_str(&fields.join(&delimiter.to_string()));
+        output.push('\n');
+    }
+    output
+}
+
+/// A single document read from a JSON Lines input file
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonlDocument {
+    /// Caller-supplied document id, preserved as the result's `document_id`
+    pub id: String,
+    /// Text to extract from
+    pub text: String,
+}
+
+/// Parse JSON Lines content where each line is `{"id": ..., "text": ...}`
+///
+/// Blank lines are ignored. Malformed lines are skipped and reported as
+/// warnings (with their 1-based line number) instead of failing the whole input.
+pub fn parse_jsonl_documents(content: &str) -> (Vec<JsonlDocument>, Vec<String>) {
+    let mut documents = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let value: serde_json::Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => {
+                warnings.push(format!("line {}: invalid JSON: {}", index + 1, e));
+                continue;
+            }
+        };
+
+        let id = match value.get("id") {
+            Some(serde_json::Value::String(id)) => id.clone(),
+            Some(serde_json::Value::Number(id)) => id.to_string(),
+            _ => {
+                warnings.push(format!("line {}: missing string or numeric \"id\"", index + 1));
+                continue;
+            }
+        };
+
+        match value.get("text").and_then(|text| text.as_str()) {
+            Some(text) => documents.push(JsonlDocument { id, text: text.to_string() }),
+            None => warnings.push(format!("line {}: missing string \"text\"", index + 1)),
+        }
+    }
+
+    (documents, warnings)
+}
+
+/// One timed segment of a transcript
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptSegment {
+    /// Start time in milliseconds
+    pub start_ms: u64,
+    /// End time in milliseconds (for `[HH:MM:SS]` lines, the next segment's start)
+    pub end_ms: Option<u64>,
+    /// Speaker label, from a `<v Name>` tag or a `Name:` prefix
+    pub speaker: Option<String>,
+    /// Spoken text with timestamp and speaker prefixes removed
+    pub text: String,
+}
+
+/// A parsed transcript: its segments joined into one text for extraction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    /// Segments in order
+    pub segments: Vec<TranscriptSegment>,
+    /// Segment texts joined with newlines
+    pub text: String,
+    /// Byte range of each segment within `text`
+    ranges: Vec<(usize, usize)>,
+}
+
+impl Transcript {
+    /// Parse an SRT, WebVTT or `[HH:MM:SS] speaker: text` transcript
+    pub fn parse(content: &str) -> LangExtractResult<Self> {
+        let segments = parse_transcript(content)?;
+        let mut text = String::new();
+        let mut ranges = Vec::with_capacity(segments.len());
+        for segment in &segments {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            let start = text.len();
+            text.push_str(&segment.text);
+            ranges.push((start, text.len()));
+        }
+        Ok(Self { segments, text, ranges })
+    }
+
+    /// Segments overlapping the byte range `start..end` of [`Transcript::text`]
+    pub fn segments_in(&self, start: usize, end: usize) -> impl Iterator<Item = &TranscriptSegment> {
+        self.segments
+            .iter()
+            .zip(&self.ranges)
+            .filter(move |(_, (s, e))| *s < end.max(start + 1) && start < *e)
+            .map(|(segment, _)| segment)
+    }
+
+    /// Add `start_time`, `end_time` and `speaker` attributes from the segments each extraction spans
+    ///
+    /// Extractions must be aligned against [`Transcript::text`]; those without a
+    /// char interval are left unchanged.
+    pub fn annotate_timestamps(&self, extractions: &mut [crate::data::Extraction]) {
+        for extraction in extractions {
+            let Some((start, end)) = extraction
+                .char_interval
+                .as_ref()
+                .and_then(|interval| Some((interval.start_pos?, interval.end_pos?)))
+            else {
+                continue;
+            };
+            let spanned: Vec<&TranscriptSegment> = self.segments_in(start, end).collect();
+            let (Some(first), Some(last)) = (spanned.first(), spanned.last()) else {
+                continue;
+            };
+
+            let attributes = extraction.attributes.get_or_insert_with(Default::default);
+            attributes.insert("start_time".to_string(), format_timestamp(first.start_ms).into());
+            attributes.insert(
+                "end_time".to_string(),
+                format_timestamp(last.end_ms.unwrap_or(last.start_ms)).into(),
+            );
+            if let Some(speaker) = &first.speaker {
+                attributes.insert("speaker".to_string(), speaker.clone().into());
+            }
+        }
+    }
+}
+
+/// Format milliseconds as `HH:MM:SS.mmm`
+pub fn format_timestamp(ms: u64) -> String {
+    format!("{:02}:{:02}:{:02}.{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000)
+}
+
+/// Parse `HH:MM:SS`, `MM:SS` and either with a `,mmm` or `.mmm` fraction into milliseconds
+fn parse_timestamp(value: &str) -> Option<u64> {
+    let (clock, fraction) = match value.trim().split_once(['.', ',']) {
+        Some((clock, fraction)) => (clock, fraction),
+        None => (value.trim(), ""),
+    };
+    let parts: Vec<u64> = clock.split(':').map(|part| part.parse().ok()).collect::<Option<_>>()?;
+    let seconds = match parts[..] {
+        [h, m, s] => h * 3600 + m * 60 + s,
+        [m, s] => m * 60 + s,
+        _ => return None,
+    };
+    let millis = match fraction.len() {
+        0 => 0,
+        1..=3 => fraction.parse::<u64>().ok()? * 10u64.pow(3 - fraction.len() as u32),
+        _ => return None,
+    };
+    Some(seconds * 1000 + millis)
+}
+
+/// Patterns for splitting speaker labels off transcript segments, compiled once per transcript
+struct SpeakerPatterns {
+    voice: Regex,
+    label: Regex,
+    tags: Regex,
+}
+
+impl SpeakerPatterns {
+    fn new() -> Self {
+        Self {
+            voice: Regex::new(r"^<v(?:\.[^ >]*)? ([^>]+)>").expect("valid voice tag pattern"),
+            // Up to three capitalized words ("Bob", "Dr. Smith", "Speaker 2"), so prose with an early colon stays intact
+            label: Regex::new(r"^([A-Z][\w.'-]*(?: [A-Z0-9][\w.'-]*){0,2}):\s+").expect("valid speaker pattern"),
+            tags: Regex::new(r"</?[^>]+>").expect("valid tag pattern"),
+        }
+    }
+
+    /// Split a leading speaker label off segment text
+    fn split_speaker(&self, text: &str) -> (Option<String>, String) {
+        let (speaker, rest) = if let Some(caps) = self.voice.captures(text) {
+            (Some(caps[1].trim().to_string()), &text[caps[0].len()..])
+        } else if let Some(caps) = self.label.captures(text) {
+            (Some(caps[1].trim().to_string()), &text[caps[0].len()..])
+        } else {
+            (None, text)
+        };
+        (speaker, self.tags.replace_all(rest, "").trim().to_string())
+    }
+}
+
+/// Parse transcript segments from SRT, WebVTT or `[HH:MM:SS] speaker: text` lines
+///
+/// Cue numbers, the `WEBVTT` header and cue settings are ignored. In the
+/// bracketed format, lines without a timestamp continue the previous segment.
+pub fn parse_transcript(content: &str) -> LangExtractResult<Vec<TranscriptSegment>> {
+    let content = content.strip_prefix('\u{FEFF}').unwrap_or(content);
+    let speakers = SpeakerPatterns::new();
+    let mut segments = Vec::new();
+
+    if content.contains("-->") {
+        let mut lines = content.lines();
+        while let Some(line) = lines.next() {
+            let Some((start, rest)) = line.split_once("-->") else {
+                continue;
+            };
+            let end = rest.split_whitespace().next().unwrap_or_default();
+            let (Some(start_ms), Some(end_ms)) = (parse_timestamp(start), parse_timestamp(end)) else {
+                return Err(LangExtractError::invalid_input(format!("Invalid cue timing: {}", line)));
+            };
+            let cue: Vec<&str> = lines.by_ref().map(str::trim).take_while(|l| !l.is_empty()).collect();
+            let (speaker, text) = speakers.split_speaker(&cue.join(" "));
+            segments.push(TranscriptSegment { start_ms, end_ms: Some(end_ms), speaker, text });
+        }
+    } else {
+        let timed = Regex::new(r"^\s*\[([\d:.,]+)\]\s*(.*)$").expect("valid timestamp pattern");
+        for line in content.lines() {
+            if let Some(caps) = timed.captures(line) {
+                let start_ms = parse_timestamp(&caps[1])
+                    .ok_or_else(|| LangExtractError::invalid_input(format!("Invalid timestamp: {}", &caps[1])))?;
+                if let Some(previous) = segments.last_mut() {
+                    previous.end_ms = Some(start_ms);
+                }
+                let (speaker, text) = speakers.split_speaker(&caps[2]);
+                segments.push(TranscriptSegment { start_ms, end_ms: None, speaker, text });
+            } else if let Some(previous) = segments.last_mut().filter(|_| !line.trim().is_empty()) {
+                previous.text.push(' ');
+                previous.text.push_str(line.trim());
+            }
+        }
+    }
+
+    if segments.is_empty() {
+        return Err(LangExtractError::invalid_input("No timestamped transcript segments found"));
+    }
+    Ok(segments)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Build a minimal `.docx` archive around the given `<w:body>` content
+    #[cfg(feature = "docx")]
+    fn write_docx(path: &std::path::Path, body: &str) {
+        use std::io::Write;
+
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("[Content_Types].xml", options).unwrap();
+        zip.write_all(br#"<?xml version="1.0" encoding="UTF-8"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"/>"#).unwrap();
+        zip.start_file("word/document.xml", options).unwrap();
+        write!(
+            zip,
+            r#"<?xml version="1.0" encoding="UTF-8"?><w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}</w:body></w:document>"#,
+            body
+        )
+        .unwrap();
+        zip.finish().unwrap();
+    }
+
+    #[cfg(feature = "docx")]
+    #[test]
+    fn test_extract_text_from_docx() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("sample.docx");
+        write_docx(
+            &path,
+            concat!(
+                r#"<w:p><w:r><w:t>Patient </w:t></w:r><w:r><w:t>report</w:t></w:r></w:p>"#,
+                r#"<w:tbl>"#,
+                r#"<w:tr><w:tc><w:p><w:r><w:t>Drug</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Dose</w:t></w:r></w:p></w:tc></w:tr>"#,
+                r#"<w:tr><w:tc><w:p><w:r><w:t>Aspirin</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>81 mg</w:t></w:r></w:p></w:tc></w:tr>"#,
+                r#"</w:tbl>"#,
+                r#"<w:p><w:r><w:t>Follow up &amp; review</w:t><w:tab/><w:t>in 2 weeks.</w:t></w:r></w:p>"#,
+            ),
+        );
+
+        let text = extract_text_from_docx(&path).unwrap();
+        assert_eq!(
+            text,
+            "Patient report\nDrug\tDose\nAspirin\t81 mg\nFollow up & review\tin 2 weeks."
+        );
+
+        let legacy = temp_dir.path().join("old.doc");
+        std::fs::write(&legacy, [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]).unwrap();
+        let error = extract_text_from_docx(&legacy).unwrap_err();
+        assert!(error.to_string().contains("Legacy Word .doc files are not supported"));
+    }
+
+    #[test]
+    fn test_parse_jsonl_documents_skips_malformed_lines() {
+        let content = r#"{"id": "doc-1", "text": "Alice met Bob."}
+{"id": 2, "text": "Carol lives in Paris."}
+
+not json
+{"text": "no id"}
+{"id": "doc-4", "text": 42}
+{"id": "doc-5", "text": "Dave works at ACME."}
+"#;
+
+        let (documents, warnings) = parse_jsonl_documents(content);
+        assert_eq!(
+            documents,
+            vec![
+                JsonlDocument { id: "doc-1".to_string(), text: "Alice met Bob.".to_string() },
+                JsonlDocument { id: "2".to_string(), text: "Carol lives in Paris.".to_string() },
+                JsonlDocument { id: "doc-5".to_string(), text: "Dave works at ACME.".to_string() },
+            ]
+        );
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].starts_with("line 4:"));
+        assert!(warnings[1].starts_with("line 5:"));
+        assert!(warnings[2].starts_with("line 6:"));
+    }
+
     #[test]
     fn test_is_url() {
         assert!(is_url("https://example.com"));
@@ -117,6 +796,40 @@ mod tests {
         assert_eq!(normalize_text(input), expected);
     }
 
+    #[test]
+    fn test_normalize_input_text() {
+        let input = "\u{FEFF}Dr.\u{00A0}Smith\u{200B} saw\u{0007} the\tpatient\n\non\u{202F}Monday";
+        assert_eq!(normalize_input_text(input), "Dr. Smith saw the\tpatient\n\non Monday");
+        assert_eq!(normalize_input_text("plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_parse_delimited_rows_in_order() {
+        let csv = "id,notes,owner\r\n\
+                   1,\"Alice met Bob, then left\",ops\r\n\
+                   2,\"Quote \"\"here\"\"\nand a new line\",\r\n\
+                   \r\n\
+                   3,Carol signed,legal";
+        let table = parse_delimited(csv, delimiter_for_path("notes.csv")).unwrap();
+        assert_eq!(table.headers, vec!["id", "notes", "owner"]);
+        assert_eq!(table.rows.len(), 3);
+        assert_eq!(table.rows[1], vec!["2", "Quote \"here\"\nand a new line", ""]);
+
+        let documents = table.column_documents("notes").unwrap();
+        let ids: Vec<&str> = documents.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["row-1", "row-2", "row-3"]);
+        assert_eq!(documents[0].text, "Alice met Bob, then left");
+        assert_eq!(documents[2].text, "Carol signed");
+        assert!(table.column_documents("missing").is_err());
+
+        let written = write_delimited(&table.headers, &table.rows, ',');
+        assert_eq!(parse_delimited(&written, ',').unwrap(), table);
+
+        let tsv = parse_delimited("id\ttext\n7\tx, y\n", delimiter_for_path("rows.TSV")).unwrap();
+        assert_eq!(tsv.rows, vec![vec!["7", "x, y"]]);
+        assert!(parse_delimited("a,b\n\"open", ',').is_err());
+    }
+
     #[test]
     fn test_extract_text_from_html() {
         let html = "<html><body><h1>Hello</h1><p>World</p></body></html>";
@@ -173,4 +886,157 @@ mod tests {
             ContentType::PlainText
         );
     }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_read_gzipped_text_file() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("note.txt.gz");
+        let text = "Patient took 400 mg ibuprofen.\n";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+        let path = path.to_str().unwrap();
+        assert!(is_gzip_path(path));
+        assert_eq!(read_text_file(path).unwrap(), text);
+        assert_eq!(tokio_test::block_on(load_text_from_file(path)).unwrap(), text);
+    }
+
+    const SRT_FIXTURE: &str = "1
+00:00:01,000 --> 00:00:04,500
+Alice: Welcome to the quarterly review.
+
+2
+00:01:23,250 --> 00:01:27,000
+Bob: Revenue grew
+to 4.2 million dollars.
+";
+
+    #[test]
+    fn test_parse_srt_transcript_and_map_timestamps() {
+        let transcript = Transcript::parse(SRT_FIXTURE).unwrap();
+        assert_eq!(transcript.segments.len(), 2);
+        assert_eq!(transcript.segments[1].start_ms, 83_250);
+        assert_eq!(transcript.segments[1].speaker.as_deref(), Some("Bob"));
+        assert_eq!(transcript.text, "Welcome to the quarterly review.\nRevenue grew to 4.2 million dollars.");
+
+        let start = transcript.text.find("4.2 million").unwrap();
+        let mut extraction = crate::data::Extraction::new("amount".to_string(), "4.2 million".to_string());
+        extraction.char_interval = Some(crate::data::CharInterval::new(Some(start), Some(start + 11)));
+        let mut extractions = vec![extraction];
+        transcript.annotate_timestamps(&mut extractions);
+
+        let attributes = extractions[0].attributes.as_ref().unwrap();
+        assert_eq!(attributes["start_time"], "00:01:23.250");
+        assert_eq!(attributes["end_time"], "00:01:27.000");
+        assert_eq!(attributes["speaker"], "Bob");
+    }
+
+    #[test]
+    fn test_parse_vtt_and_bracketed_transcripts() {
+        let vtt = "WEBVTT\n\nintro\n00:05.000 --> 00:07.000 align:start\n<v Carol>Hello <b>there</b>\n";
+        let segments = parse_transcript(vtt).unwrap();
+        assert_eq!(segments[0].start_ms, 5000);
+        assert_eq!(segments[0].speaker.as_deref(), Some("Carol"));
+        assert_eq!(segments[0].text, "Hello there");
+
+        let plain = "[00:00:05] Dave: First point\ncontinued here\n[00:00:09] Second point\n";
+        let segments = parse_transcript(plain).unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "First point continued here");
+        assert_eq!(segments[0].end_ms, Some(9000));
+        assert_eq!(segments[1].speaker, None);
+
+        assert!(parse_transcript("no timestamps here").is_err());
+    }
+
+    #[test]
+    fn test_colon_in_prose_is_not_a_speaker() {
+        let segments = parse_transcript("[00:00:01] The answer is simple: we ship.\n[00:00:04] Dr. Smith: Agreed.\n").unwrap();
+        assert_eq!(segments[0].speaker, None);
+        assert_eq!(segments[0].text, "The answer is simple: we ship.");
+        assert_eq!(segments[1].speaker.as_deref(), Some("Dr. Smith"));
+        assert_eq!(segments[1].text, "Agreed.");
+    }
+
+    #[tokio::test]
+    async fn test_download_many_preserves_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Serves "/<n>" with body "doc <n>" after (4 - n) * 50ms, so later URLs answer first
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (server_in_flight, server_peak) = (in_flight.clone(), peak.clone());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let (in_flight, peak) = (server_in_flight.clone(), server_peak.clone());
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                    let index: u64 = request.split_whitespace().nth(1).unwrap_or("/0")[1..].parse().unwrap_or(0);
+
+                    let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(4u64.saturating_sub(index) * 50)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let body = format!("doc {}", index);
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        let urls: Vec<String> = (1..=4).map(|i| format!("{}/{}", base, i)).collect();
+        let mut url_refs: Vec<&str> = urls.iter().map(String::as_str).collect();
+        url_refs.push("not a url");
+
+        let results = download_many(&url_refs, 3).await;
+        assert_eq!(results.len(), 5);
+        let bodies: Vec<&str> = results[..4].iter().map(|r| r.as_deref().unwrap()).collect();
+        assert_eq!(bodies, vec!["doc 1", "doc 2", "doc 3", "doc 4"]);
+        assert!(results[4].as_ref().unwrap_err().to_string().contains("Invalid URL"));
+        assert!(peak.load(Ordering::SeqCst) > 1);
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn test_load_examples_dir_merges_files_in_name_order() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("b_places.yaml"),
+            "text: Paris is in France\nextractions:\n  - extraction_class: city\n    extraction_text: Paris\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("a_people.json"),
+            r#"[
+                {"text": "Alice met Bob", "extractions": [{"extraction_class": "person", "extraction_text": "Alice"}]},
+                {"text": "Carol called", "extractions": [{"extraction_class": "person", "extraction_text": "Carol"}]}
+            ]"#,
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join("broken.json"), "{not json").unwrap();
+        std::fs::write(temp_dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let examples = load_examples_dir(temp_dir.path()).unwrap();
+
+        let texts: Vec<&str> = examples.iter().map(|example| example.text.as_str()).collect();
+        assert_eq!(texts, vec!["Alice met Bob", "Carol called", "Paris is in France"]);
+        assert_eq!(examples[2].extractions[0].extraction_class, "city");
+        assert!(load_examples_dir(temp_dir.path().join("missing")).is_err());
+    }
 }
@@ -52,7 +52,11 @@ pub mod schema;
 // Processing modules
 pub mod alignment;
 pub mod annotation;
+pub mod bench;
 pub mod chunking;
+#[cfg(feature = "embeddings")]
+pub mod embeddings;
+pub mod estimate;
 pub mod inference;
 pub mod multipass;
 pub mod tokenizer;
@@ -62,6 +66,8 @@ pub mod providers;
 pub mod factory;
 
 // Utility modules
+pub mod cache;
+pub mod checkpoint;
 pub mod http_client;
 pub mod io;
 pub mod logging;
@@ -77,22 +83,28 @@ pub use config::{
     LangExtractConfig, ProcessingConfig, ValidationConfig as NewValidationConfig, 
     ChunkingConfig, AlignmentConfig as NewAlignmentConfig, MultiPassConfig as NewMultiPassConfig, 
     VisualizationConfig, InferenceConfig as NewInferenceConfig, ProgressConfig, 
-    ChunkingStrategy, ExportFormat as NewExportFormat
+    ChunkingStrategy, ExportFormat as NewExportFormat, ExtractConfigBuilder
 };
+pub use annotation::ContextProvider;
+pub use multipass::{DefaultQualityScorer, QualityScorer};
 pub use data::{
-    AlignmentStatus, AnnotatedDocument, CharInterval, Document, ExampleData, Extraction,
-    FormatType,
+    diff_documents, merge_adjacent_same_class, AlignmentStatus, AnnotatedDocument, CharInterval, Document, ExampleData,
+    ExampleWarning, Extraction, ExtractionDiff, ExtractionSortOrder, FormatType, OffsetMap, RunMetadata,
 };
+pub use estimate::{extract_estimate, ExtractionEstimate};
 pub use exceptions::{LangExtractError, LangExtractResult};
-pub use inference::{BaseLanguageModel, ScoredOutput};
+pub use inference::{BaseLanguageModel, ImageInput, ScoredOutput};
 pub use logging::{ProgressHandler, ProgressEvent, ConsoleProgressHandler, SilentProgressHandler, LogProgressHandler};
-pub use providers::{ProviderConfig, ProviderType, UniversalProvider};
-pub use resolver::{ValidationConfig, ValidationResult, ValidationError, ValidationWarning, CoercionSummary, CoercionDetail, CoercionTargetType};
-pub use visualization::{ExportFormat, ExportConfig, export_document};
+pub use providers::{ProviderCapabilities, ProviderConfig, ProviderType, UniversalProvider};
+pub use resolver::{ResponseParser, ValidationConfig, DateOrder, NumberLocale, ValidationResult, ValidationError, ValidationWarning, CoercionSummary, CoercionDetail, CoercionTargetType};
+pub use visualization::{CsvAttributeMode, ExportFormat, ExportConfig, OverlapPolicy, export_diff_html, export_document};
 pub use pipeline::{PipelineConfig, PipelineStep, PipelineResult, PipelineExecutor};
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Hook transforming an extraction before it is returned; `None` drops it
+pub type ExtractionPostProcessor = dyn Fn(Extraction) -> Option<Extraction> + Send + Sync;
 
 /// Configuration for the extract function
 #[derive(Clone, Serialize, Deserialize)]
@@ -133,6 +145,120 @@ pub struct ExtractConfig {
     pub multipass_min_extractions: usize,
     /// Quality threshold for keeping extractions (0.0 to 1.0)
     pub multipass_quality_threshold: f32,
+    /// Minimum similarity (0.0 to 1.0) for accepting a fuzzy alignment
+    #[serde(default = "default_fuzzy_alignment_threshold")]
+    pub fuzzy_alignment_threshold: f32,
+    /// Guidance for individual extraction classes, rendered as a section of the prompt
+    #[serde(default)]
+    pub class_descriptions: HashMap<String, String>,
+    /// Send the whole document in a single prompt regardless of `max_char_buffer`
+    #[serde(default)]
+    pub skip_chunking: bool,
+    /// Cache raw model outputs on disk and reuse them for identical requests
+    #[serde(default)]
+    pub enable_cache: bool,
+    /// Directory for the response cache (defaults to "./.langextract_cache")
+    #[serde(default)]
+    pub cache_dir: Option<std::path::PathBuf>,
+    /// Whether responses sampled with temperature > 0 are cached
+    #[serde(default = "default_true")]
+    pub cache_nonzero_temperature: bool,
+    /// Fail with `LangExtractError::ValidationFailed` when model output does not pass validation
+    #[serde(default)]
+    pub strict_validation: bool,
+    /// System message sent ahead of the extraction prompt (prepended for non-chat providers)
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Drop extractions whose text could not be located in the source document
+    #[serde(default)]
+    pub require_grounding: bool,
+    /// Whether fuzzy alignments count as grounded when `require_grounding` is set
+    #[serde(default)]
+    pub allow_fuzzy_grounding: bool,
+    /// How few-shot examples are chosen for each chunk's prompt
+    #[serde(default)]
+    pub example_selection: prompting::ExampleSelection,
+    /// Maximum number of few-shot examples per prompt (`None` keeps all)
+    #[serde(default)]
+    pub max_examples: Option<usize>,
+    /// Return each chunk's raw model output in `AnnotatedDocument::raw_outputs` (single-pass extraction)
+    #[serde(default)]
+    pub keep_raw_outputs: bool,
+    /// Strip zero-width/control characters and no-break spaces before extraction (default: true)
+    ///
+    /// Character intervals then refer to the normalized text returned in `AnnotatedDocument::text`;
+    /// `AnnotatedDocument::map_to_original` translates them back to the input.
+    #[serde(default = "default_true")]
+    pub normalize_input: bool,
+    /// Maximum completion tokens per model request (`None` leaves the provider default)
+    #[serde(default)]
+    pub max_output_tokens: Option<u32>,
+    /// Seed for reproducible sampling and random example selection
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Prompt for each multi-pass pass (pass `i` uses entry `i - 1`, falling back to the base prompt)
+    ///
+    /// Requires `enable_multipass`; runs at least as many passes as there are prompts.
+    #[serde(default)]
+    pub pass_prompts: Vec<String>,
+    /// Custom parser for model output, replacing the built-in JSON/YAML parsing (not serialized)
+    #[serde(skip)]
+    pub response_parser: Option<std::sync::Arc<dyn ResponseParser>>,
+    /// Time limit per chunk in milliseconds, covering reformat retries; a chunk that exceeds it is
+    /// recorded as failed and skipped, and unchunked text that exceeds it fails the extraction
+    #[serde(default)]
+    pub per_chunk_timeout_ms: Option<u64>,
+    /// Per-chunk prompt context, replacing the static `additional_context` (not serialized)
+    #[serde(skip)]
+    pub context_provider: Option<std::sync::Arc<dyn ContextProvider>>,
+    /// Order of the returned extractions
+    #[serde(default)]
+    pub sort_order: ExtractionSortOrder,
+    /// Merge same-class extractions that are adjacent or separated only by whitespace
+    #[serde(default)]
+    pub merge_adjacent_same_class: bool,
+    /// Chunk scorer deciding which chunks multi-pass extraction re-processes (not serialized)
+    #[serde(skip)]
+    pub multipass_quality_scorer: Option<std::sync::Arc<dyn QualityScorer>>,
+    /// Re-run chunks whose model output was truncated as chunks of half the size (down to a floor)
+    #[serde(default)]
+    pub auto_shrink_on_truncation: bool,
+    /// Values for `{key}` placeholders in the prompt description
+    #[serde(default)]
+    pub prompt_variables: HashMap<String, String>,
+    /// Fail when the prompt description has a placeholder missing from `prompt_variables`
+    #[serde(default)]
+    pub strict_prompt_variables: bool,
+    /// Request a JSON object response from the provider even without schema constraints
+    #[serde(default)]
+    pub json_mode: bool,
+    /// JSON Lines file recording completed chunks, so an interrupted extraction resumes where it stopped
+    #[serde(default)]
+    pub checkpoint_path: Option<std::path::PathBuf>,
+    /// Closed set of extraction classes; listed in the prompt, and extractions of any other class are dropped
+    #[serde(default)]
+    pub allowed_classes: Option<Vec<String>>,
+    /// Hook run on every extraction before it is returned; returning `None` drops it (not serialized)
+    #[serde(skip)]
+    pub post_process: Option<std::sync::Arc<ExtractionPostProcessor>>,
+    /// Tokenizer used to split large documents into chunks, e.g. a [`tokenizer::CjkTokenizer`] (not serialized)
+    #[serde(skip)]
+    pub tokenizer: Option<std::sync::Arc<dyn tokenizer::Tokenize>>,
+    /// When a response cannot be parsed, ask the model to reformat it before giving up
+    #[serde(default)]
+    pub reformat_on_parse_failure: bool,
+    /// Maximum reformat requests per chunk when `reformat_on_parse_failure` is enabled
+    #[serde(default = "default_max_reformat_attempts")]
+    pub max_reformat_attempts: usize,
+    /// Split texts into at least this many chunks, even ones that fit in `max_char_buffer` (`None` disables)
+    #[serde(default)]
+    pub min_chunks: Option<usize>,
+    /// Read a "table" key of row objects as grouped extractions (one group per row) and hint the row shape in the prompt
+    #[serde(default)]
+    pub table_mode: bool,
+    /// Language of the instruction text generated around the prompt description (the description itself is sent as written)
+    #[serde(default)]
+    pub prompt_language: prompting::PromptLanguage,
     /// Progress handler for reporting extraction progress (not serialized)
     #[serde(skip)]
     pub progress_handler: Option<std::sync::Arc<dyn ProgressHandler>>,
@@ -159,11 +285,59 @@ impl Default for ExtractConfig {
             enable_multipass: false,
             multipass_min_extractions: 1,
             multipass_quality_threshold: 0.3,
+            fuzzy_alignment_threshold: default_fuzzy_alignment_threshold(),
+            class_descriptions: HashMap::new(),
+            skip_chunking: false,
+            enable_cache: false,
+            cache_dir: None,
+            cache_nonzero_temperature: true,
+            strict_validation: false,
+            system_prompt: None,
+            require_grounding: false,
+            allow_fuzzy_grounding: false,
+            example_selection: prompting::ExampleSelection::All,
+            max_examples: None,
+            keep_raw_outputs: false,
+            normalize_input: true,
+            max_output_tokens: None,
+            seed: None,
+            pass_prompts: Vec::new(),
+            response_parser: None,
+            per_chunk_timeout_ms: None,
+            context_provider: None,
+            sort_order: ExtractionSortOrder::AsFound,
+            merge_adjacent_same_class: false,
+            multipass_quality_scorer: None,
+            auto_shrink_on_truncation: false,
+            prompt_variables: HashMap::new(),
+            strict_prompt_variables: false,
+            json_mode: false,
+            checkpoint_path: None,
+            allowed_classes: None,
+            post_process: None,
+            tokenizer: None,
+            reformat_on_parse_failure: false,
+            max_reformat_attempts: 1,
+            min_chunks: None,
+            table_mode: false,
+            prompt_language: prompting::PromptLanguage::English,
             progress_handler: None,
         }
     }
 }
 
+fn default_fuzzy_alignment_threshold() -> f32 {
+    alignment::AlignmentConfig::default().fuzzy_alignment_threshold
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_reformat_attempts() -> usize {
+    1
+}
+
 impl std::fmt::Debug for ExtractConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ExtractConfig")
@@ -185,18 +359,99 @@ impl std::fmt::Debug for ExtractConfig {
             .field("enable_multipass", &self.enable_multipass)
             .field("multipass_min_extractions", &self.multipass_min_extractions)
             .field("multipass_quality_threshold", &self.multipass_quality_threshold)
+            .field("fuzzy_alignment_threshold", &self.fuzzy_alignment_threshold)
+            .field("class_descriptions", &self.class_descriptions)
+            .field("skip_chunking", &self.skip_chunking)
+            .field("enable_cache", &self.enable_cache)
+            .field("cache_dir", &self.cache_dir)
+            .field("cache_nonzero_temperature", &self.cache_nonzero_temperature)
+            .field("strict_validation", &self.strict_validation)
+            .field("system_prompt", &self.system_prompt)
+            .field("require_grounding", &self.require_grounding)
+            .field("allow_fuzzy_grounding", &self.allow_fuzzy_grounding)
+            .field("example_selection", &self.example_selection)
+            .field("max_examples", &self.max_examples)
+            .field("keep_raw_outputs", &self.keep_raw_outputs)
+            .field("normalize_input", &self.normalize_input)
+            .field("max_output_tokens", &self.max_output_tokens)
+            .field("seed", &self.seed)
+            .field("pass_prompts", &self.pass_prompts)
+            .field("response_parser", &self.response_parser.as_ref().map(|_| "<ResponseParser>"))
+            .field("per_chunk_timeout_ms", &self.per_chunk_timeout_ms)
+            .field("context_provider", &self.context_provider.as_ref().map(|_| "<ContextProvider>"))
+            .field("sort_order", &self.sort_order)
+            .field("merge_adjacent_same_class", &self.merge_adjacent_same_class)
+            .field("multipass_quality_scorer", &self.multipass_quality_scorer.as_ref().map(|_| "<QualityScorer>"))
+            .field("auto_shrink_on_truncation", &self.auto_shrink_on_truncation)
+            .field("prompt_variables", &self.prompt_variables)
+            .field("strict_prompt_variables", &self.strict_prompt_variables)
+            .field("json_mode", &self.json_mode)
+            .field("checkpoint_path", &self.checkpoint_path)
+            .field("allowed_classes", &self.allowed_classes)
+            .field("post_process", &self.post_process.as_ref().map(|_| "<PostProcessor>"))
+            .field("tokenizer", &self.tokenizer.as_ref().map(|_| "<Tokenizer>"))
+            .field("reformat_on_parse_failure", &self.reformat_on_parse_failure)
+            .field("max_reformat_attempts", &self.max_reformat_attempts)
+            .field("min_chunks", &self.min_chunks)
+            .field("table_mode", &self.table_mode)
+            .field("prompt_language", &self.prompt_language)
             .field("progress_handler", &"<ProgressHandler>")
             .finish()
     }
 }
 
 impl ExtractConfig {
+    /// Start a builder that validates the configuration when built
+    pub fn builder() -> ExtractConfigBuilder {
+        ExtractConfigBuilder::new()
+    }
+
     /// Set a progress handler for this configuration
     pub fn with_progress_handler(mut self, handler: std::sync::Arc<dyn ProgressHandler>) -> Self {
         self.progress_handler = Some(handler);
         self
     }
 
+    /// Ask the model to reformat unparseable responses, up to `max_attempts` times per chunk
+    pub fn with_reformat_on_parse_failure(mut self, max_attempts: usize) -> Self {
+        self.reformat_on_parse_failure = max_attempts > 0;
+        self.max_reformat_attempts = max_attempts;
+        self
+    }
+
+    /// Split large documents into chunks with a custom tokenizer
+    pub fn with_tokenizer(mut self, tokenizer: std::sync::Arc<dyn tokenizer::Tokenize>) -> Self {
+        self.tokenizer = Some(tokenizer);
+        self
+    }
+
+    /// Build each chunk's prompt context with a custom provider instead of `additional_context`
+    pub fn with_context_provider(mut self, provider: std::sync::Arc<dyn ContextProvider>) -> Self {
+        self.context_provider = Some(provider);
+        self
+    }
+
+    /// Score chunks with a custom scorer when deciding which to re-process in multi-pass mode
+    pub fn with_quality_scorer(mut self, scorer: std::sync::Arc<dyn QualityScorer>) -> Self {
+        self.multipass_quality_scorer = Some(scorer);
+        self
+    }
+
+    /// Transform or drop each extraction before it is returned
+    pub fn with_post_process(
+        mut self,
+        post_process: impl Fn(Extraction) -> Option<Extraction> + Send + Sync + 'static,
+    ) -> Self {
+        self.post_process = Some(std::sync::Arc::new(post_process));
+        self
+    }
+
+    /// Parse model output with a custom parser instead of the built-in JSON/YAML parsing
+    pub fn with_response_parser(mut self, parser: std::sync::Arc<dyn ResponseParser>) -> Self {
+        self.response_parser = Some(parser);
+        self
+    }
+
     /// Enable console progress output with default settings
     pub fn with_console_progress(mut self) -> Self {
         self.progress_handler = Some(std::sync::Arc::new(ConsoleProgressHandler::new()));
@@ -248,6 +503,7 @@ pub async fn extract_with_config(
 ///
 /// Returns an error if:
 /// * Examples are empty
+/// * An example fails validation while `strict_validation` is set
 /// * No API key is provided
 /// * URL download fails
 /// * Language model inference fails
@@ -263,9 +519,10 @@ pub async fn extract(
             "Examples are required for reliable extraction. Please provide at least one ExampleData object with sample extractions.".to_string()
         ));
     }
+    check_examples(examples, &config)?;
 
     if config.batch_length < config.max_workers {
-        log::warn!(
+        tracing::warn!(
             "batch_length ({}) < max_workers ({}). Only {} workers will be used. Set batch_length >= max_workers for optimal parallelization.",
             config.batch_length,
             config.max_workers,
@@ -276,7 +533,185 @@ pub async fn extract(
     // Load environment variables
     dotenvy::dotenv().ok();
 
-    // Initialize progress handler
+    init_progress(&config);
+
+    // Handle URL input
+    let text = if io::is_url(text_or_documents) {
+        io::download_text_from_url(text_or_documents).await?
+    } else {
+        text_or_documents.to_string()
+    };
+
+    let runner = ExtractionRunner::build(prompt_description, examples, &config).await?;
+    runner.run(&text, &config).await
+}
+
+/// Extract from many documents while reusing a single language model
+///
+/// `config.max_workers` bounds the concurrent model calls across all documents:
+/// the budget is split between concurrently processed documents and the chunk
+/// workers inside each one. Results are returned in input order, each tagged
+/// with its document id; a failure on one document does not abort the others.
+pub async fn extract_documents(
+    documents: &[io::JsonlDocument],
+    prompt_description: Option<&str>,
+    examples: &[ExampleData],
+    config: ExtractConfig,
+) -> LangExtractResult<Vec<LangExtractResult<AnnotatedDocument>>> {
+    use futures::stream::{self, StreamExt};
+
+    if examples.is_empty() {
+        return Err(LangExtractError::InvalidInput(
+            "Examples are required for reliable extraction. Please provide at least one ExampleData object with sample extractions.".to_string()
+        ));
+    }
+    check_examples(examples, &config)?;
+
+    dotenvy::dotenv().ok();
+    init_progress(&config);
+
+    let runner = ExtractionRunner::build(prompt_description, examples, &config).await?;
+    let max_workers = config.max_workers.max(1);
+    let concurrent_documents = max_workers.min(documents.len()).max(1);
+    let document_config = ExtractConfig {
+        max_workers: max_workers / concurrent_documents,
+        ..config
+    };
+    let results = stream::iter(documents)
+        .map(|document| {
+            let runner = &runner;
+            let config = &document_config;
+            async move {
+                let mut result = runner.run(&document.text, config).await?;
+                result.document_id = Some(document.id.clone());
+                Ok(result)
+            }
+        })
+        .buffered(concurrent_documents)
+        .collect()
+        .await;
+
+    Ok(results)
+}
+
+/// Attribute recording which prompt produced an extraction in [`extract_multi_prompt`]
+pub const SOURCE_PROMPT_ATTRIBUTE: &str = "source_prompt";
+
+/// Extract with several prompts, each with its own examples, and union the results
+///
+/// Every prompt runs over the whole text. Each extraction is tagged with its
+/// prompt description under [`SOURCE_PROMPT_ATTRIBUTE`], and duplicates found
+/// by more than one prompt are removed, keeping the earliest prompt's copy.
+pub async fn extract_multi_prompt(
+    text_or_documents: &str,
+    prompts: &[(&str, &[ExampleData])],
+    config: ExtractConfig,
+) -> LangExtractResult<AnnotatedDocument> {
+    if prompts.is_empty() {
+        return Err(LangExtractError::invalid_input("At least one prompt is required"));
+    }
+    for (description, examples) in prompts {
+        if examples.is_empty() {
+            return Err(LangExtractError::invalid_input(format!(
+                "Examples are required for prompt '{}'",
+                description
+            )));
+        }
+        check_examples(examples, &config)?;
+    }
+
+    dotenvy::dotenv().ok();
+    init_progress(&config);
+
+    let text = if io::is_url(text_or_documents) {
+        io::download_text_from_url(text_or_documents).await?
+    } else {
+        text_or_documents.to_string()
+    };
+
+    let mut extractions = Vec::new();
+    let mut document_text = None;
+    for (description, examples) in prompts {
+        let runner = ExtractionRunner::build(Some(description), examples, &config).await?;
+        let result = runner.run(&text, &config).await?;
+        for mut extraction in result.extractions.unwrap_or_default() {
+            extraction
+                .attributes
+                .get_or_insert_with(std::collections::HashMap::new)
+                .insert(SOURCE_PROMPT_ATTRIBUTE.to_string(), serde_json::json!(description));
+            extractions.push(extraction);
+        }
+        document_text = result.text;
+    }
+
+    let mut extractions = chunking::ResultAggregator::new().deduplicate_extractions(extractions)?;
+    config.sort_order.sort(&mut extractions);
+    Ok(AnnotatedDocument::with_extractions(extractions, document_text.unwrap_or(text)))
+}
+
+/// Text standing in for the document when the prompt is sent with an image
+const IMAGE_INPUT_TEXT: &str = "[See the attached image]";
+
+/// Extract structured data from an image, such as a scanned form, with a multimodal model
+///
+/// The image is sent alongside the usual prompt (base64 data URL for
+/// OpenAI-compatible APIs, `images` for Ollama); providers without image
+/// support return a configuration error. The returned document has no source
+/// text, so extractions carry no character intervals.
+pub async fn extract_from_image(
+    image_bytes: &[u8],
+    mime_type: &str,
+    prompt_description: Option<&str>,
+    examples: &[ExampleData],
+    config: ExtractConfig,
+) -> LangExtractResult<AnnotatedDocument> {
+    if examples.is_empty() {
+        return Err(LangExtractError::InvalidInput(
+            "Examples are required for reliable extraction. Please provide at least one ExampleData object with sample extractions.".to_string()
+        ));
+    }
+    if !mime_type.starts_with("image/") {
+        return Err(LangExtractError::invalid_input(format!("Unsupported image MIME type '{}'", mime_type)));
+    }
+    check_examples(examples, &config)?;
+
+    dotenvy::dotenv().ok();
+    init_progress(&config);
+
+    let prompt_template = build_prompt_template(prompt_description, examples, &config);
+    let prompt = prompt_template.render(IMAGE_INPUT_TEXT, config.additional_context.as_deref())?;
+    let language_model = factory::create_model(&config, Some(&prompt_template.examples)).await?;
+    let resolver = resolver::Resolver::new(&config, language_model.requires_fence_output())?;
+
+    let mut kwargs = HashMap::new();
+    kwargs.insert("temperature".to_string(), serde_json::json!(config.temperature));
+    let image = ImageInput::new(image_bytes, mime_type);
+    let outputs = language_model.infer_with_image(&prompt, &image, &kwargs).await?;
+    let response = outputs
+        .first()
+        .map(|output| output.text().to_string())
+        .ok_or_else(|| LangExtractError::inference_simple("Model returned no output for the image"))?;
+
+    let expected_fields: Vec<String> = examples
+        .iter()
+        .flat_map(|example| &example.extractions)
+        .map(|extraction| extraction.extraction_class.clone())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let (mut extractions, validation_result) = resolver.validate_and_parse(&response, &expected_fields)?;
+    if resolver.strict_validation() && !validation_result.is_valid {
+        return Err(LangExtractError::ValidationFailed {
+            errors: validation_result.errors,
+        });
+    }
+
+    finish_extractions("", &mut extractions, &config)?;
+    Ok(AnnotatedDocument::with_extractions(extractions, String::new()))
+}
+
+/// Install the configured progress handler
+fn init_progress(config: &ExtractConfig) {
     if let Some(handler) = &config.progress_handler {
         logging::init_progress_handler(handler.clone());
     } else {
@@ -288,80 +723,215 @@ pub async fn extract(
         };
         logging::init_progress_handler(default_handler);
     }
+}
 
-    // Handle URL input
-    let text = if io::is_url(text_or_documents) {
-        io::download_text_from_url(text_or_documents).await?
-    } else {
-        text_or_documents.to_string()
-    };
+/// Validate the examples, warning about problems or failing under strict validation
+fn check_examples(examples: &[ExampleData], config: &ExtractConfig) -> LangExtractResult<()> {
+    for (index, example) in examples.iter().enumerate() {
+        for warning in example.validate() {
+            if config.strict_validation {
+                return Err(LangExtractError::invalid_input(format!("Example {}: {}", index, warning)));
+            }
+            tracing::warn!("Example {}: {}", index, warning);
+        }
+    }
+    Ok(())
+}
 
-    // Create prompt template
-    let mut prompt_template = prompting::PromptTemplateStructured::new(prompt_description);
+/// Create the prompt template used for every chunk of an extraction
+pub(crate) fn build_prompt_template(
+    prompt_description: Option<&str>,
+    examples: &[ExampleData],
+    config: &ExtractConfig,
+) -> prompting::PromptTemplateStructured {
+    let mut prompt_template = prompting::PromptTemplateStructured::new(prompt_description)
+        .with_class_descriptions(config.class_descriptions.clone())
+        .with_type_hints(config.use_schema_constraints)
+        .with_example_selection(config.example_selection, config.max_examples)
+        .with_variables(config.prompt_variables.clone(), config.strict_prompt_variables)
+        .with_allowed_classes(config.allowed_classes.clone())
+        .with_table_mode(config.table_mode)
+        .with_language(config.prompt_language);
     prompt_template.examples.extend(examples.iter().cloned());
+    prompt_template.seed = config.seed;
+    prompt_template
+}
 
-    // Create language model
-    let language_model = factory::create_model(&config, Some(&prompt_template.examples)).await?;
+/// Create the annotator for `config` around an already created language model
+pub(crate) fn build_annotator(
+    language_model: Box<dyn inference::BaseLanguageModel>,
+    prompt_template: prompting::PromptTemplateStructured,
+    config: &ExtractConfig,
+    fence_output: bool,
+) -> annotation::Annotator {
+    annotation::Annotator::new(language_model, prompt_template, config.format_type, fence_output)
+        .with_alignment_config(alignment::AlignmentConfig {
+            fuzzy_alignment_threshold: config.fuzzy_alignment_threshold,
+            ..Default::default()
+        })
+        .with_temperature(config.temperature)
+        .with_skip_chunking(config.skip_chunking)
+        .with_chunk_timeout(config.per_chunk_timeout_ms.map(std::time::Duration::from_millis))
+        .with_auto_shrink_on_truncation(config.auto_shrink_on_truncation)
+        .with_checkpoint(config.checkpoint_path.clone().map(checkpoint::ChunkCheckpoint::new))
+        .with_context_provider(config.context_provider.clone())
+        .with_tokenizer(config.tokenizer.clone())
+        .with_context_window(factory::create_provider_config(config).ok().and_then(|provider| provider.context_window))
+        .with_min_chunks(config.min_chunks)
+        .with_reformat_on_parse_failure(if config.reformat_on_parse_failure {
+            config.max_reformat_attempts
+        } else {
+            0
+        })
+        .with_raw_outputs(config.keep_raw_outputs)
+        .with_grounding(config.require_grounding.then_some(annotation::GroundingPolicy {
+            allow_fuzzy: config.allow_fuzzy_grounding,
+        }))
+}
 
-    // Create resolver
-    let resolver = resolver::Resolver::new(&config, language_model.requires_fence_output())?;
+/// Annotator (or multi-pass processor) built once per configuration
+enum ExtractionRunner {
+    SinglePass {
+        annotator: annotation::Annotator,
+        resolver: resolver::Resolver,
+    },
+    MultiPass(multipass::MultiPassProcessor),
+}
 
-    // Create annotator
-    let annotator = annotation::Annotator::new(
-        language_model,
-        prompt_template,
-        config.format_type,
-        resolver.fence_output(),
-    );
-
-    // Perform annotation - use multi-pass if enabled
-    if config.enable_multipass && config.extraction_passes > 1 {
-        // Use multi-pass extraction
-        let multipass_config = multipass::MultiPassConfig {
-            max_passes: config.extraction_passes,
-            min_extractions_per_chunk: config.multipass_min_extractions,
-            enable_targeted_reprocessing: true,
-            enable_refinement_passes: true,
-            quality_threshold: config.multipass_quality_threshold,
-            max_reprocess_chunks: 10,
-            temperature_decay: 0.9,
-        };
+impl ExtractionRunner {
+    /// Create the language model, resolver and annotator for `config`
+    async fn build(
+        prompt_description: Option<&str>,
+        examples: &[ExampleData],
+        config: &ExtractConfig,
+    ) -> LangExtractResult<Self> {
+        let prompt_template = build_prompt_template(prompt_description, examples, config);
 
-        let processor = multipass::MultiPassProcessor::new(
-            multipass_config,
-            annotator,
-            resolver,
-        );
+        // Create language model
+        let language_model = factory::create_model(config, Some(&prompt_template.examples)).await?;
 
-        let (result, _stats) = processor.extract_multipass(
-            &text,
-            config.additional_context.as_deref(),
-            config.debug,
-        ).await?;
+        // Create resolver
+        let resolver = resolver::Resolver::new(config, language_model.requires_fence_output())?;
 
-        if config.debug {
-            println!("🎯 Multi-pass extraction completed with {} total extractions", 
-                result.extraction_count());
+        // Create annotator
+        let annotator = build_annotator(language_model, prompt_template, config, resolver.fence_output());
+
+        // Use multi-pass if enabled
+        let max_passes = config.extraction_passes.max(config.pass_prompts.len());
+        if config.enable_multipass && max_passes > 1 {
+            let multipass_config = multipass::MultiPassConfig {
+                max_passes,
+                min_extractions_per_chunk: config.multipass_min_extractions,
+                enable_targeted_reprocessing: true,
+                enable_refinement_passes: true,
+                quality_threshold: config.multipass_quality_threshold,
+                max_reprocess_chunks: 10,
+                temperature_decay: 0.9,
+                pass_prompts: config.pass_prompts.clone(),
+                quality_scorer: config.multipass_quality_scorer.clone(),
+                ..Default::default()
+            };
+
+            Ok(Self::MultiPass(multipass::MultiPassProcessor::new(
+                multipass_config,
+                annotator,
+                resolver,
+            )))
+        } else {
+            Ok(Self::SinglePass { annotator, resolver })
         }
+    }
 
+    /// Extract from a single text
+    async fn run(&self, text: &str, config: &ExtractConfig) -> LangExtractResult<AnnotatedDocument> {
+        let normalized;
+        let mut offset_map = None;
+        let text = if config.normalize_input {
+            let (normalized_text, offsets) = io::normalize_input_text_with_offsets(text);
+            if normalized_text != text {
+                offset_map = Some(offsets);
+            }
+            normalized = normalized_text;
+            normalized.as_str()
+        } else {
+            text
+        };
+
+        let mut result = match self {
+            Self::MultiPass(processor) => {
+                let (result, _stats) = processor.extract_multipass(
+                    text,
+                    config.additional_context.as_deref(),
+                    config.debug,
+                ).await?;
+
+                if config.debug {
+                    tracing::info!(extractions = result.extraction_count(), "Multi-pass extraction completed");
+                }
+
+                result
+            }
+            Self::SinglePass { annotator, resolver } => {
+                annotator
+                    .annotate_text(
+                        text,
+                        resolver,
+                        config.max_char_buffer,
+                        config.batch_length,
+                        config.additional_context.as_deref(),
+                        config.debug,
+                        config.extraction_passes,
+                        config.max_workers,
+                    )
+                    .await?
+            }
+        };
+
+        if let Some(extractions) = result.extractions.as_mut() {
+            finish_extractions(text, extractions, config)?;
+        }
+        let annotator = self.annotator();
+        result.run_metadata = Some(RunMetadata::new(
+            annotator.model_id(),
+            annotator.temperature(),
+            annotator.prompt_description().as_deref(),
+        ));
+        result.offset_map = offset_map;
         Ok(result)
-    } else {
-        // Use single-pass extraction
-        annotator
-            .annotate_text(
-                &text,
-                &resolver,
-                config.max_char_buffer,
-                config.batch_length,
-                config.additional_context.as_deref(),
-                config.debug,
-                config.extraction_passes,
-                config.max_workers,
-            )
-            .await
+    }
+
+    /// Annotator for the (first) extraction pass
+    fn annotator(&self) -> &annotation::Annotator {
+        match self {
+            Self::MultiPass(processor) => processor.annotator(),
+            Self::SinglePass { annotator, .. } => annotator,
+        }
     }
 }
 
+/// Apply the class allowlist, merging, post-processing and ordering configured for the returned extractions
+fn finish_extractions(text: &str, extractions: &mut Vec<Extraction>, config: &ExtractConfig) -> LangExtractResult<()> {
+    if let Some(allowed) = &config.allowed_classes {
+        let before = extractions.len();
+        extractions.retain(|e| allowed.contains(&e.extraction_class));
+        if extractions.len() < before {
+            tracing::warn!(dropped = before - extractions.len(), "Dropped extractions with classes outside allowed_classes");
+        }
+    }
+    // A class found anywhere in the document is not absent, even if one chunk reported it missing
+    let found: HashSet<String> = extractions.iter().filter(|e| e.present).map(|e| e.extraction_class.clone()).collect();
+    extractions.retain(|e| e.present || !found.contains(&e.extraction_class));
+    if config.merge_adjacent_same_class {
+        data::merge_adjacent_same_class(text, extractions);
+        alignment::populate_token_intervals(text, extractions)?;
+    }
+    if let Some(post_process) = &config.post_process {
+        *extractions = std::mem::take(extractions).into_iter().filter_map(post_process.as_ref()).collect();
+    }
+    config.sort_order.sort(extractions);
+    Ok(())
+}
+
 /// Visualize function that mirrors the Python API
 pub fn visualize(
     annotated_document: &AnnotatedDocument,
@@ -399,4 +969,302 @@ mod tests {
             }
         });
     }
+
+    /// Quiet, non-debug config routed to the offline mock `provider`
+    fn mock_config(provider: providers::ProviderConfig) -> ExtractConfig {
+        let mut config = ExtractConfig { debug: false, ..Default::default() }.with_quiet_mode();
+        config
+            .language_model_params
+            .insert("provider_config".to_string(), serde_json::to_value(provider).unwrap());
+        config
+    }
+
+    /// Parses `class: text` lines, one extraction per line
+    struct LineParser;
+
+    impl ResponseParser for LineParser {
+        fn parse(&self, raw: &str) -> LangExtractResult<Vec<Extraction>> {
+            Ok(raw
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(class, text)| Extraction::new(class.trim().to_string(), text.trim().to_string()))
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_with_custom_response_parser() {
+        let response = serde_json::json!({"response": "person: Alice\norganization: Initech"});
+        let (url, _) = providers::test_server::spawn_mock_server(response).await;
+        let provider = providers::ProviderConfig::ollama("test-model", Some(url));
+        let mut config = ExtractConfig {
+            debug: false,
+            normalize_input: false,
+            ..Default::default()
+        }
+        .with_response_parser(std::sync::Arc::new(LineParser))
+        .with_quiet_mode();
+        config
+            .language_model_params
+            .insert("provider_config".to_string(), serde_json::to_value(provider).unwrap());
+        let examples = vec![ExampleData::new(
+            "Bob works at Acme".to_string(),
+            vec![Extraction::new("person".to_string(), "Bob".to_string())],
+        )];
+
+        let document = extract("Alice works at Initech.", Some("Extract people"), &examples, config)
+            .await
+            .unwrap();
+
+        let found: Vec<_> = document
+            .extractions
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.extraction_class, e.extraction_text, e.char_interval.and_then(|i| i.start_pos)))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("person".to_string(), "Alice".to_string(), Some(0)),
+                ("organization".to_string(), "Initech".to_string(), Some(15)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zero_temperature_extraction_is_cached() {
+        let response = serde_json::json!({"response": r#"[{"person": "Alice"}]"#});
+        let (url, requests) = providers::test_server::spawn_mock_server(response).await;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let provider = providers::ProviderConfig::ollama("test-model", Some(url));
+        let mut config = ExtractConfig {
+            debug: false,
+            temperature: 0.0,
+            enable_cache: true,
+            cache_dir: Some(temp_dir.path().to_path_buf()),
+            cache_nonzero_temperature: false,
+            ..Default::default()
+        }
+        .with_quiet_mode();
+        config
+            .language_model_params
+            .insert("provider_config".to_string(), serde_json::to_value(provider).unwrap());
+        let examples = vec![ExampleData::new(
+            "Bob works at Acme".to_string(),
+            vec![Extraction::new("person".to_string(), "Bob".to_string())],
+        )];
+
+        for _ in 0..2 {
+            let document = extract("Alice works at Initech.", Some("Extract people"), &examples, config.clone())
+                .await
+                .unwrap();
+            assert_eq!(document.extraction_count(), 1);
+        }
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_extract_end_to_end_with_mock_provider() {
+        let provider = providers::ProviderConfig::mock("[]").with_mock_response(
+            "Alice works at Initech",
+            r#"[{"person": "Alice"}, {"organization": "Initech"}]"#,
+        );
+        let config = mock_config(provider);
+        let examples = vec![ExampleData::new(
+            "Bob works at Acme".to_string(),
+            vec![
+                Extraction::new("person".to_string(), "Bob".to_string()),
+                Extraction::new("organization".to_string(), "Acme".to_string()),
+            ],
+        )];
+
+        let document = extract("Alice works at Initech.", Some("Extract people"), &examples, config.clone())
+            .await
+            .unwrap();
+        let found: Vec<_> = document
+            .extractions
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.extraction_class, e.extraction_text, e.alignment_status))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("person".to_string(), "Alice".to_string(), Some(AlignmentStatus::MatchExact)),
+                ("organization".to_string(), "Initech".to_string(), Some(AlignmentStatus::MatchExact)),
+            ]
+        );
+
+        let unmatched = extract("Nobody here.", Some("Extract people"), &examples, config).await.unwrap();
+        assert_eq!(unmatched.extraction_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_require_grounding_drops_fuzzy_matches_by_default() {
+        let provider = providers::ProviderConfig::mock(
+            r#"[{"person": "Alice"}, {"person": "Alice Smith"}]"#,
+        );
+        let config = ExtractConfig {
+            require_grounding: true,
+            ..mock_config(provider)
+        };
+        let examples = vec![ExampleData::new(
+            "Bob works at Acme".to_string(),
+            vec![Extraction::new("person".to_string(), "Bob".to_string())],
+        )];
+
+        let document = extract("Alice met Bob in Paris.", Some("Extract people"), &examples, config)
+            .await
+            .unwrap();
+        let found: Vec<_> = document
+            .extractions
+            .unwrap()
+            .into_iter()
+            .map(|e| e.extraction_text)
+            .collect();
+        assert_eq!(found, vec!["Alice".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_extract_multi_prompt_unions_results() {
+        let provider = providers::ProviderConfig::mock("[]")
+            .with_mock_response("Extract people", r#"[{"person": "Alice"}]"#)
+            .with_mock_response(
+                "Extract organizations",
+                r#"[{"organization": "Initech"}, {"person": "Alice"}]"#,
+            );
+        let config = mock_config(provider);
+        let people = vec![ExampleData::new(
+            "Bob works at Acme".to_string(),
+            vec![Extraction::new("person".to_string(), "Bob".to_string())],
+        )];
+        let organizations = vec![ExampleData::new(
+            "Bob works at Acme".to_string(),
+            vec![Extraction::new("organization".to_string(), "Acme".to_string())],
+        )];
+
+        let document = extract_multi_prompt(
+            "Alice works at Initech.",
+            &[("Extract people", &people), ("Extract organizations", &organizations)],
+            config,
+        )
+        .await
+        .unwrap();
+
+        let found: Vec<_> = document
+            .extractions
+            .unwrap()
+            .into_iter()
+            .map(|e| {
+                let source = e.attributes.unwrap()[SOURCE_PROMPT_ATTRIBUTE].as_str().unwrap().to_string();
+                (e.extraction_class, e.extraction_text, source)
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("person".to_string(), "Alice".to_string(), "Extract people".to_string()),
+                ("organization".to_string(), "Initech".to_string(), "Extract organizations".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_allowed_classes_drop_off_schema_extractions() {
+        let provider = providers::ProviderConfig::mock(
+            r#"[{"person": "Alice"}, {"job_title": "engineer"}, {"profession": "engineer"}]"#,
+        );
+        let mut config = mock_config(provider);
+        config.allowed_classes = Some(vec!["person".to_string(), "age".to_string(), "profession".to_string()]);
+        let examples = vec![ExampleData::new(
+            "Bob, 42, is a teacher".to_string(),
+            vec![
+                Extraction::new("person".to_string(), "Bob".to_string()),
+                Extraction::new("age".to_string(), "42".to_string()),
+                Extraction::new("profession".to_string(), "teacher".to_string()),
+            ],
+        )];
+
+        let prompt = build_prompt_template(Some("Extract people"), &examples, &config)
+            .render("Alice is an engineer.", None)
+            .unwrap();
+        assert!(prompt.contains("Use only these extraction classes: person, age, profession"));
+
+        let document = extract("Alice is an engineer.", Some("Extract people"), &examples, config.clone())
+            .await
+            .unwrap();
+        let classes: Vec<_> = document.extractions.unwrap().into_iter().map(|e| e.extraction_class).collect();
+        assert_eq!(classes, vec!["person", "profession"]);
+
+        config.allowed_classes = None;
+        let unrestricted = extract("Alice is an engineer.", Some("Extract people"), &examples, config).await.unwrap();
+        assert_eq!(unrestricted.extraction_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_post_process_drops_and_transforms_extractions() {
+        let provider = providers::ProviderConfig::mock(
+            r#"[{"email": "Alice@Example.COM"}, {"age": "212"}, {"age": "42"}]"#,
+        );
+        let config = mock_config(provider).with_post_process(|mut extraction| match extraction.extraction_class.as_str() {
+            "email" => {
+                extraction.extraction_text = extraction.extraction_text.to_lowercase();
+                Some(extraction)
+            }
+            "age" if extraction.extraction_text.parse::<u32>().is_ok_and(|age| age > 150) => None,
+            _ => Some(extraction),
+        });
+        let examples = vec![ExampleData::new(
+            "bob@acme.com is 30".to_string(),
+            vec![
+                Extraction::new("email".to_string(), "bob@acme.com".to_string()),
+                Extraction::new("age".to_string(), "30".to_string()),
+            ],
+        )];
+
+        let document = extract("Alice@Example.COM is 42, not 212.", Some("Extract contacts"), &examples, config)
+            .await
+            .unwrap();
+        let found: Vec<_> = document
+            .extractions
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.extraction_class, e.extraction_text, e.char_interval.and_then(|i| i.start_pos)))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("email".to_string(), "alice@example.com".to_string(), Some(0)),
+                ("age".to_string(), "42".to_string(), Some(21)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_metadata_in_json_export() {
+        let provider = providers::ProviderConfig::mock(r#"[{"person": "Alice"}]"#);
+        let mut config = ExtractConfig {
+            temperature: 0.2,
+            ..mock_config(provider)
+        };
+        config.prompt_variables.insert("kind".to_string(), "people".to_string());
+        let examples = vec![ExampleData::new(
+            "Bob arrived.".to_string(),
+            vec![Extraction::new("person".to_string(), "Bob".to_string())],
+        )];
+
+        let document = extract("Alice arrived.", Some("Extract {kind}"), &examples, config).await.unwrap();
+        let metadata = document.run_metadata.as_ref().unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(&metadata.timestamp).is_ok());
+
+        let export_config = ExportConfig { format: ExportFormat::Json, ..Default::default() };
+        let exported: serde_json::Value = serde_json::from_str(&export_document(&document, &export_config).unwrap()).unwrap();
+        assert_eq!(exported["run_metadata"]["model_id"], "mock");
+        assert_eq!(exported["run_metadata"]["temperature"].as_f64().map(|t| (t * 10.0).round()), Some(2.0));
+        assert_eq!(exported["run_metadata"]["prompt"], "Extract people");
+
+        let export_config = ExportConfig { format: ExportFormat::Html, ..Default::default() };
+        let html = export_document(&document, &export_config).unwrap();
+        assert!(html.contains("Run Metadata") && html.contains("Extract people"));
+    }
 }
@@ -17,12 +17,20 @@ mod cli {
     use console::style;
     use indicatif::{ProgressBar, ProgressStyle};
     use langextract_rust::{
-        extract, ExampleData, Extraction, ExtractConfig, FormatType,
-        ProviderConfig, ProviderType, LangExtractError,
-        visualization::{export_document, ExportConfig, ExportFormat},
+        extract, extract_documents, BaseLanguageModel, ExampleData, Extraction, ExtractConfig, FormatType,
+        ProviderConfig, ProviderType, LangExtractError, LangExtractResult, UniversalProvider,
+        bench::run_bench,
+        exceptions::InferenceError,
+        factory::create_model,
+        io::{delimiter_for_path, load_examples_dir, parse_delimited, parse_jsonl_documents, write_delimited, DelimitedTable},
+        visualization::{
+            export_document, export_ndjson, extraction_to_ndjson_line, visualize_ansi,
+            ExportConfig, ExportFormat,
+        },
     };
 
     use std::fs;
+    use std::io::Write;
     use std::path::PathBuf;
     use std::time::Instant;
 
@@ -52,6 +60,8 @@ mod cli {
         Init(InitArgs),
         /// Test provider connectivity
         Test(TestArgs),
+        /// Benchmark provider latency, throughput and parse success
+        Bench(BenchArgs),
         /// List available models and providers
         Providers,
         /// Show example configurations
@@ -60,6 +70,8 @@ mod cli {
         Convert(ConvertArgs),
         /// Execute a multi-step extraction pipeline
         Pipeline(PipelineArgs),
+        /// Check an examples or pipeline file without running it
+        Validate(ValidateArgs),
     }
 
     #[derive(Args)]
@@ -76,6 +88,10 @@ mod cli {
         #[arg(short, long)]
         pub examples: Option<PathBuf>,
 
+        /// Directory of example files (JSON/YAML, one or more examples each), read in file name order
+        #[arg(long, value_name = "DIR")]
+        pub examples_dir: Option<PathBuf>,
+
         /// Prompt description for extraction
         #[arg(short, long)]
         pub prompt: Option<String>,
@@ -108,10 +124,24 @@ mod cli {
         #[arg(long, default_value = "8000")]
         pub max_chars: usize,
 
+        /// Send the whole document in a single prompt instead of chunking it
+        #[arg(long)]
+        pub no_chunking: bool,
+
         /// Number of parallel workers
         #[arg(long, default_value = "6")]
         pub workers: usize,
 
+        /// Treat INPUT as a JSON Lines file of `{"id": ..., "text": ...}` documents
+        /// and write one JSON result per line
+        #[arg(long)]
+        pub jsonl_input: bool,
+
+        /// Treat INPUT as a CSV/TSV file, extract from this column of every row and
+        /// write the rows back with `extractions` and `error` columns appended
+        #[arg(long, value_name = "NAME", conflicts_with = "jsonl_input")]
+        pub csv_column: Option<String>,
+
         /// Batch size for processing
         #[arg(long, default_value = "4")]
         pub batch_size: usize,
@@ -179,9 +209,40 @@ mod cli {
         pub model_url: Option<String>,
     }
 
+    #[derive(Args)]
+    pub struct BenchArgs {
+        /// Provider to benchmark (required)
+        #[arg(short, long, value_enum)]
+        pub provider: ProviderType,
+
+        /// Model to benchmark
+        #[arg(short, long)]
+        pub model: Option<String>,
+
+        /// API key to use
+        #[arg(long)]
+        pub api_key: Option<String>,
+
+        /// Model URL for custom/self-hosted models
+        #[arg(long)]
+        pub model_url: Option<String>,
+
+        /// Number of extractions to run
+        #[arg(short = 'n', long, default_value = "10")]
+        pub runs: usize,
+
+        /// Number of requests in flight at once
+        #[arg(long, default_value = "1")]
+        pub workers: usize,
+
+        /// Examples file (JSON/YAML format)
+        #[arg(short, long)]
+        pub examples: Option<PathBuf>,
+    }
+
     #[derive(Args)]
     pub struct ConvertArgs {
-        /// Input file to convert
+        /// Input file to convert (JSON, or YAML with a .yaml/.yml extension)
         pub input: PathBuf,
 
         /// Output file
@@ -238,6 +299,21 @@ mod cli {
         /// Export flattened JSON to file (Rust-exported, replaces python flatten)
         #[arg(long)]
         pub export_flattened: Option<PathBuf>,
+
+        /// Write the step dependency graph as GraphViz DOT to this file instead of executing
+        #[arg(long)]
+        pub export_dot: Option<PathBuf>,
+    }
+
+    #[derive(Args)]
+    pub struct ValidateArgs {
+        /// Examples file (JSON/YAML) to check
+        #[arg(value_name = "FILE", required_unless_present = "pipeline")]
+        pub file: Option<PathBuf>,
+
+        /// Pipeline configuration file (YAML) to check instead
+        #[arg(long, conflicts_with = "file")]
+        pub pipeline: Option<PathBuf>,
     }
 
     #[derive(ValueEnum, Clone, Debug)]
@@ -245,6 +321,10 @@ mod cli {
         Json,
         Yaml,
         Text,
+        /// Document text with extractions highlighted using ANSI colors
+        Ansi,
+        /// One compact JSON extraction per line (newline-delimited JSON)
+        Ndjson,
     }
 
     /// Initialize the CLI application
@@ -256,6 +336,11 @@ mod cli {
             colored::control::set_override(false);
         }
 
+        // Route the library's tracing events to the console
+        let max_level = if cli.verbose { tracing::Level::DEBUG } else { tracing::Level::INFO };
+        tracing::subscriber::set_global_default(ConsoleSubscriber::new(max_level))
+            .expect("tracing subscriber already set");
+
         // Initialize logging
         if cli.verbose {
             env_logger::Builder::from_default_env()
@@ -271,10 +356,84 @@ mod cli {
             Commands::Extract(args) => extract_command(args, cli.verbose).await,
             Commands::Init(args) => init_command(args).await,
             Commands::Test(args) => test_command(args).await,
+            Commands::Bench(args) => bench_command(args).await,
             Commands::Providers => providers_command().await,
             Commands::Examples => examples_command().await,
             Commands::Convert(args) => convert_command(args).await,
             Commands::Pipeline(args) => pipeline_command(args).await,
+            Commands::Validate(args) => validate_command(args).await,
+        }
+    }
+
+    /// Prints the library's `tracing` events to stderr, keeping stdout free for results
+    struct ConsoleSubscriber {
+        max_level: tracing::Level,
+        next_span_id: std::sync::atomic::AtomicU64,
+    }
+
+    impl ConsoleSubscriber {
+        fn new(max_level: tracing::Level) -> Self {
+            Self {
+                max_level,
+                next_span_id: std::sync::atomic::AtomicU64::new(1),
+            }
+        }
+    }
+
+    impl tracing::Subscriber for ConsoleSubscriber {
+        fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+            *metadata.level() <= self.max_level
+        }
+
+        fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let id = self.next_span_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            tracing::span::Id::from_u64(id)
+        }
+
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut fields = ConsoleFields::default();
+            event.record(&mut fields);
+
+            let marker = match *event.metadata().level() {
+                tracing::Level::ERROR => style("❌").red(),
+                tracing::Level::WARN => style("⚠️ ").yellow(),
+                tracing::Level::INFO => style("•").cyan(),
+                _ => style("·").dim(),
+            };
+            eprintln!("{} {}{}", marker, fields.message, style(fields.rest).dim());
+        }
+
+        fn enter(&self, _span: &tracing::span::Id) {}
+
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    /// Event message plus `key=value` pairs for the remaining fields
+    #[derive(Default)]
+    struct ConsoleFields {
+        message: String,
+        rest: String,
+    }
+
+    impl tracing::field::Visit for ConsoleFields {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            if field.name() == "message" {
+                self.message = value.to_string();
+            } else {
+                self.rest.push_str(&format!(" {}={}", field.name(), value));
+            }
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                self.message = format!("{:?}", value);
+            } else {
+                self.rest.push_str(&format!(" {}={:?}", field.name(), value));
+            }
         }
     }
 
@@ -301,11 +460,18 @@ mod cli {
         };
 
         // Load examples
-        let examples = if let Some(examples_path) = &args.examples {
+        let examples = if args.examples.is_some() || args.examples_dir.is_some() {
             if let Some(ref pb) = pb {
                 pb.set_message("Loading examples...");
             }
-            load_examples(examples_path)?
+            let mut examples = match &args.examples {
+                Some(examples_path) => load_examples(examples_path)?,
+                None => Vec::new(),
+            };
+            if let Some(examples_dir) = &args.examples_dir {
+                examples.extend(load_examples_dir(examples_dir)?);
+            }
+            examples
         } else {
             if !args.quiet {
                 println!("{}", style("⚠️  No examples provided. Using default person extraction examples.").yellow());
@@ -321,16 +487,27 @@ mod cli {
         if let Some(ref pb) = pb {
             pb.set_message("Reading input...");
         }
+        if args.jsonl_input && !std::path::Path::new(&args.input).exists() {
+            return Err(format!("JSONL input file not found: {}", args.input).into());
+        }
+        if args.csv_column.is_some() && !std::path::Path::new(&args.input).exists() {
+            return Err(format!("CSV input file not found: {}", args.input).into());
+        }
         let text = if args.input.starts_with("http://") || args.input.starts_with("https://") {
             if !args.quiet {
                 println!("📥 Downloading from URL: {}", args.input);
             }
             langextract_rust::io::download_text_from_url(&args.input).await?
+        } else if langextract_rust::io::is_word_document(&args.input) {
+            if !args.quiet {
+                println!("📖 Reading Word document: {}", args.input);
+            }
+            read_word_document(&args.input)?
         } else if std::path::Path::new(&args.input).exists() {
             if !args.quiet {
                 println!("📖 Reading file: {}", args.input);
             }
-            fs::read_to_string(&args.input)?
+            langextract_rust::io::read_text_file(&args.input)?
         } else {
             // Treat as literal text
             args.input.clone()
@@ -351,9 +528,10 @@ mod cli {
             format_type: match args.format {
                 OutputFormat::Json => FormatType::Json,
                 OutputFormat::Yaml => FormatType::Yaml,
-                OutputFormat::Text => FormatType::Json, // Default to JSON for processing
+                OutputFormat::Text | OutputFormat::Ansi | OutputFormat::Ndjson => FormatType::Json, // Default to JSON for processing
             },
             max_char_buffer: args.max_chars,
+            skip_chunking: args.no_chunking,
             max_workers: args.workers,
             batch_length: args.batch_size,
             temperature: args.temperature,
@@ -374,20 +552,7 @@ mod cli {
         }
 
         // Set up provider configuration (required)
-        let provider_config = match args.provider {
-            ProviderType::OpenAI => {
-                let mut config = ProviderConfig::openai(&args.model, args.api_key.clone());
-                if let Some(model_url) = &args.model_url {
-                    config = config.with_base_url(model_url.clone());
-                }
-                config
-            },
-            ProviderType::Ollama => ProviderConfig::ollama(&args.model, args.model_url.clone()),
-            ProviderType::Custom => ProviderConfig::custom(
-                &args.model_url.clone().unwrap_or_else(|| "http://localhost:8000".to_string()),
-                &args.model
-            ),
-        };
+        let provider_config = provider_config_for(args.provider, &args.model, args.api_key.clone(), args.model_url.clone());
 
         config.language_model_params.insert(
             "provider_config".to_string(),
@@ -398,6 +563,13 @@ mod cli {
             pb.set_message("Performing extraction...");
         }
 
+        if args.jsonl_input {
+            return extract_jsonl(&text, &examples, config, &args, pb, start_time).await;
+        }
+        if let Some(column) = &args.csv_column {
+            return extract_csv(&text, column, &examples, config, &args, pb, start_time).await;
+        }
+
         // Perform extraction
         let result = match extract(
             &text,
@@ -469,6 +641,173 @@ mod cli {
         Ok(())
     }
 
+    /// Extract from each line of a JSON Lines input and emit one result per line
+    async fn extract_jsonl(
+        content: &str,
+        examples: &[ExampleData],
+        config: ExtractConfig,
+        args: &ExtractArgs,
+        pb: Option<ProgressBar>,
+        start_time: Instant,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (documents, warnings) = parse_jsonl_documents(content);
+        for warning in &warnings {
+            eprintln!("{} Skipping malformed input {}", style("⚠️").yellow(), warning);
+        }
+
+        let results = match extract_documents(&documents, args.prompt.as_deref(), examples, config).await {
+            Ok(results) => results,
+            Err(e) => {
+                if let Some(ref pb) = pb {
+                    pb.finish_with_message("❌ Extraction failed");
+                }
+                return Err(handle_extraction_error(e));
+            }
+        };
+        if let Some(ref pb) = pb {
+            pb.finish_with_message("✅ Extraction completed");
+        }
+
+        let mut lines = Vec::new();
+        let mut failed = 0;
+        for (document, result) in documents.iter().zip(results) {
+            match result {
+                Ok(result) => lines.push(serde_json::to_string(&result)?),
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("{} Extraction failed for document '{}': {}", style("⚠️").yellow(), document.id, e);
+                }
+            }
+        }
+
+        if let Some(output_path) = &args.output {
+            let mut content = lines.join("\n");
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            fs::write(output_path, content)?;
+        } else {
+            let mut stdout = std::io::stdout().lock();
+            for line in &lines {
+                writeln!(stdout, "{}", line)?;
+            }
+        }
+
+        if !args.quiet {
+            eprintln!("{} Processed {} documents ({} failed, {} malformed lines skipped) in {:.2}s",
+                style("🎯").green(),
+                lines.len(),
+                failed,
+                warnings.len(),
+                start_time.elapsed().as_secs_f64()
+            );
+            if let Some(output_path) = &args.output {
+                eprintln!("💾 Results saved to: {}", output_path.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run extraction on one column of every CSV/TSV row and write the rows back out
+    async fn extract_csv(
+        content: &str,
+        column: &str,
+        examples: &[ExampleData],
+        config: ExtractConfig,
+        args: &ExtractArgs,
+        pb: Option<ProgressBar>,
+        start_time: Instant,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let delimiter = delimiter_for_path(&args.input);
+        let table = parse_delimited(content, delimiter)?;
+
+        let (headers, rows, failed) = match extract_table(&table, column, args.prompt.as_deref(), examples, config).await {
+            Ok(output) => output,
+            Err(e) => {
+                if let Some(ref pb) = pb {
+                    pb.finish_with_message("❌ Extraction failed");
+                }
+                return Err(handle_extraction_error(e));
+            }
+        };
+        if let Some(ref pb) = pb {
+            pb.finish_with_message("✅ Extraction completed");
+        }
+
+        let output = write_delimited(&headers, &rows, delimiter);
+        if let Some(output_path) = &args.output {
+            fs::write(output_path, output)?;
+        } else {
+            print!("{}", output);
+        }
+
+        if !args.quiet {
+            eprintln!("{} Processed {} rows ({} failed) in {:.2}s",
+                style("🎯").green(),
+                rows.len(),
+                failed,
+                start_time.elapsed().as_secs_f64()
+            );
+            if let Some(output_path) = &args.output {
+                eprintln!("💾 Results saved to: {}", output_path.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Extract from `column` of every row, returning the headers, the rows with
+    /// `extractions` and `error` columns appended, and the number of failed rows
+    async fn extract_table(
+        table: &DelimitedTable,
+        column: &str,
+        prompt: Option<&str>,
+        examples: &[ExampleData],
+        config: ExtractConfig,
+    ) -> LangExtractResult<(Vec<String>, Vec<Vec<String>>, usize)> {
+        let documents = table.column_documents(column)?;
+        let results = extract_documents(&documents, prompt, examples, config).await?;
+
+        let mut headers = table.headers.clone();
+        headers.extend(["extractions".to_string(), "error".to_string()]);
+
+        let mut rows = Vec::with_capacity(table.rows.len());
+        let mut failed = 0;
+        for (row, result) in table.rows.iter().zip(results) {
+            let mut row = row.clone();
+            match result {
+                Ok(result) => {
+                    let extractions: Vec<serde_json::Value> = result
+                        .extractions
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|e| {
+                            let mut value = serde_json::json!({
+                                "class": e.extraction_class,
+                                "text": e.extraction_text,
+                            });
+                            if let Some(interval) = &e.char_interval {
+                                value["start"] = serde_json::json!(interval.start_pos);
+                                value["end"] = serde_json::json!(interval.end_pos);
+                            }
+                            value
+                        })
+                        .collect();
+                    row.push(serde_json::to_string(&extractions)?);
+                    row.push(String::new());
+                }
+                Err(e) => {
+                    failed += 1;
+                    row.push(String::new());
+                    row.push(e.to_string());
+                }
+            }
+            rows.push(row);
+        }
+        Ok((headers, rows, failed))
+    }
+
     async fn init_command(args: InitArgs) -> Result<(), Box<dyn std::error::Error>> {
         println!("{}", style("🔧 Initializing LangExtract configuration...").bold().cyan());
 
@@ -496,6 +835,9 @@ OPENAI_API_KEY=your_openai_api_key_here
 # Gemini Configuration  
 GEMINI_API_KEY=your_gemini_api_key_here
 
+# Cohere Configuration
+COHERE_API_KEY=your_cohere_api_key_here
+
 # Custom provider configuration
 CUSTOM_API_KEY=your_custom_api_key_here
 CUSTOM_MODEL_URL=http://localhost:8000
@@ -531,11 +873,7 @@ OLLAMA_BASE_URL=http://localhost:11434
         dotenvy::dotenv().ok();
 
         let provider = args.provider;
-        let model = args.model.unwrap_or_else(|| match provider {
-            ProviderType::OpenAI => "gpt-3.5-turbo".to_string(),
-            ProviderType::Ollama => "mistral".to_string(),
-            ProviderType::Custom => "test-model".to_string(),
-        });
+        let model = args.model.unwrap_or_else(|| default_model(provider));
 
         println!("Provider: {}", style(format!("{:?}", provider)).cyan());
         println!("Model: {}", style(&model).cyan());
@@ -552,20 +890,7 @@ OLLAMA_BASE_URL=http://localhost:11434
         };
 
         // Set up provider configuration (required)
-        let provider_config = match args.provider {
-            ProviderType::OpenAI => {
-                let mut config = ProviderConfig::openai(&model, args.api_key.clone());
-                if let Some(model_url) = &args.model_url {
-                    config = config.with_base_url(model_url.clone());
-                }
-                config
-            },
-            ProviderType::Ollama => ProviderConfig::ollama(&model, args.model_url.clone()),
-            ProviderType::Custom => ProviderConfig::custom(
-                &args.model_url.clone().unwrap_or_else(|| "http://localhost:8000".to_string()),
-                &model
-            ),
-        };
+        let provider_config = provider_config_for(args.provider, &model, args.api_key.clone(), args.model_url.clone());
 
         config.language_model_params.insert(
             "provider_config".to_string(),
@@ -583,7 +908,34 @@ OLLAMA_BASE_URL=http://localhost:11434
         pb.set_style(ProgressStyle::default_spinner()
             .template("{spinner:.green} {msg}")
             .expect("Failed to set progress bar template"));
-        pb.set_message("Testing connection...");
+        pb.set_message("Checking provider health...");
+
+        // Cheap connectivity/auth/model check before spending tokens on an extraction
+        let health = match UniversalProvider::new(provider_config) {
+            Ok(provider) => provider.health_check().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = health {
+            pb.finish_with_message("❌ Provider health check failed");
+            match e.inference_kind() {
+                Some(InferenceError::ServerUnreachable { .. }) => {
+                    println!("{}", style("🌐 Server unreachable:").red().bold());
+                }
+                Some(InferenceError::AuthenticationFailed { .. }) => {
+                    println!("{}", style("🔑 Authentication failed:").red().bold());
+                }
+                Some(InferenceError::ModelNotFound { .. }) => {
+                    println!("{}", style("🤖 Model not found:").red().bold());
+                }
+                _ => {
+                    println!("{}", style("❌ Provider health check failed:").red().bold());
+                }
+            }
+            println!("{}", e);
+            return Err(e.into());
+        }
+
+        pb.set_message("Testing extraction...");
 
         match extract("This is a test message", Some("Extract test information"), &examples, config).await {
             Ok(_) => {
@@ -607,11 +959,17 @@ OLLAMA_BASE_URL=http://localhost:11434
                         println!("1. Set API key: {}", style("export OPENAI_API_KEY=your_key").cyan());
                         println!("2. Check account: https://platform.openai.com/account/api-keys");
                     }
+                    ProviderType::Cohere => {
+                        println!("\n{}", style("💡 Troubleshooting tips for Cohere:").yellow());
+                        println!("1. Set API key: {}", style("export COHERE_API_KEY=your_key").cyan());
+                        println!("2. Check account: https://dashboard.cohere.com/api-keys");
+                    }
                     ProviderType::Custom => {
                         println!("\n{}", style("💡 Troubleshooting tips for Custom provider:").yellow());
                         println!("1. Check URL: {}", style("--model-url http://your-server").cyan());
                         println!("2. Verify API compatibility with OpenAI format");
                     }
+                    ProviderType::Mock => {}
                 }
                 
                 return Err(e.into());
@@ -621,6 +979,103 @@ OLLAMA_BASE_URL=http://localhost:11434
         Ok(())
     }
 
+    /// Fixed input sent on every benchmark run
+    const BENCH_TEXT: &str = "Maria Garcia, 42, is a pediatric nurse at Boston Children's Hospital. \
+        Her colleague Tom Chen is 29 and works there as a radiologist.";
+
+    async fn bench_command(args: BenchArgs) -> Result<(), Box<dyn std::error::Error>> {
+        println!("{}", style("⏱️  Benchmarking provider...").bold().cyan());
+
+        dotenvy::dotenv().ok();
+
+        let model = args.model.unwrap_or_else(|| default_model(args.provider));
+        println!("Provider: {}", style(format!("{:?}", args.provider)).cyan());
+        println!("Model: {}", style(&model).cyan());
+
+        let examples = match &args.examples {
+            Some(path) => load_examples(path)?,
+            None => get_default_examples(),
+        };
+        let mut config = ExtractConfig {
+            model_id: model.clone(),
+            api_key: args.api_key.clone(),
+            model_url: args.model_url.clone(),
+            max_workers: args.workers,
+            debug: false,
+            ..Default::default()
+        };
+        let provider_config = provider_config_for(args.provider, &model, args.api_key.clone(), args.model_url.clone());
+        config.language_model_params.insert(
+            "provider_config".to_string(),
+            serde_json::to_value(&provider_config)?
+        );
+
+        let pb = ProgressBar::new_spinner();
+        pb.set_style(ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .expect("Failed to set progress bar template"));
+        pb.set_message(format!("Running {} extractions with {} workers...", args.runs, args.workers.max(1)));
+
+        let language_model = create_model(&config, Some(&examples)).await?;
+        let report = run_bench(
+            language_model.as_ref(),
+            BENCH_TEXT,
+            Some("Extract people, ages, professions and workplaces"),
+            &examples,
+            &config,
+            args.runs,
+        )
+        .await?;
+        pb.finish_with_message("✅ Benchmark completed");
+
+        println!();
+        print!("{}", report.to_table());
+        Ok(())
+    }
+
+    /// Provider configuration for the `--provider`, `--model`, `--api-key` and `--model-url` options
+    fn provider_config_for(
+        provider: ProviderType,
+        model: &str,
+        api_key: Option<String>,
+        model_url: Option<String>,
+    ) -> ProviderConfig {
+        match provider {
+            ProviderType::OpenAI => {
+                let mut config = ProviderConfig::openai(model, api_key);
+                if let Some(model_url) = model_url {
+                    config = config.with_base_url(model_url);
+                }
+                config
+            },
+            ProviderType::Ollama => ProviderConfig::ollama(model, model_url),
+            ProviderType::Cohere => {
+                let api_key = api_key.or_else(|| std::env::var("COHERE_API_KEY").ok());
+                let mut config = ProviderConfig::cohere(model, api_key);
+                if let Some(model_url) = model_url {
+                    config = config.with_base_url(model_url);
+                }
+                config
+            },
+            ProviderType::Custom => ProviderConfig::custom(
+                &model_url.unwrap_or_else(|| "http://localhost:8000".to_string()),
+                model
+            ),
+            ProviderType::Mock => ProviderConfig::mock("[]"),
+        }
+    }
+
+    /// Default model for `provider` when `--model` is not given
+    fn default_model(provider: ProviderType) -> String {
+        match provider {
+            ProviderType::OpenAI => "gpt-3.5-turbo".to_string(),
+            ProviderType::Ollama => "mistral".to_string(),
+            ProviderType::Cohere => "command-r".to_string(),
+            ProviderType::Custom => "test-model".to_string(),
+            ProviderType::Mock => "mock".to_string(),
+        }
+    }
+
     async fn providers_command() -> Result<(), Box<dyn std::error::Error>> {
         println!("{}", style("🔌 Available Providers and Models").bold().cyan());
         println!();
@@ -628,7 +1083,9 @@ OLLAMA_BASE_URL=http://localhost:11434
         let providers = vec![
             ("OpenAI", vec!["gpt-4o", "gpt-4o-mini", "gpt-3.5-turbo"], "High accuracy, JSON mode support"),
             ("Ollama", vec!["mistral", "llama2", "qwen", "codellama"], "Local inference, privacy-focused"),
+            ("Cohere", vec!["command-r", "command-r-plus"], "Hosted chat models with JSON output"),
             ("Custom", vec!["any-model"], "OpenAI-compatible HTTP APIs"),
+            ("Mock", vec!["mock"], "Offline canned responses for testing"),
         ];
 
         for (provider, models, description) in providers {
@@ -639,9 +1096,23 @@ OLLAMA_BASE_URL=http://localhost:11434
             match provider {
                 "OpenAI" => println!("  🔑 Requires: OPENAI_API_KEY environment variable"),
                 "Ollama" => println!("  🏠 Requires: Local Ollama installation (ollama.ai)"),
+                "Cohere" => println!("  🔑 Requires: COHERE_API_KEY environment variable"),
                 "Custom" => println!("  🌐 Requires: --model-url parameter"),
                 _ => {}
             }
+
+            // Query the live model list when credentials are available
+            let config = match provider {
+                "OpenAI" => std::env::var("OPENAI_API_KEY").ok().map(|key| ProviderConfig::openai("gpt-4o", Some(key))),
+                "Cohere" => std::env::var("COHERE_API_KEY").ok().map(|key| ProviderConfig::cohere("command-r", Some(key))),
+                _ => None,
+            };
+            if let Some(config) = config {
+                match UniversalProvider::new(config)?.list_models().await {
+                    Ok(models) => println!("  🌐 Available: {}", models.join(", ")),
+                    Err(e) => println!("  ⚠️  Could not list models: {}", e),
+                }
+            }
             println!();
         }
 
@@ -685,7 +1156,11 @@ OLLAMA_BASE_URL=http://localhost:11434
 
         // Read input file
         let input_content = fs::read_to_string(&args.input)?;
-        let result: langextract_rust::AnnotatedDocument = serde_json::from_str(&input_content)?;
+        let result: langextract_rust::AnnotatedDocument =
+            match args.input.extension().and_then(|s| s.to_str()) {
+                Some("yaml") | Some("yml") => serde_yaml::from_str(&input_content)?,
+                _ => serde_json::from_str(&input_content)?,
+            };
 
         // Export to requested format
         let export_config = ExportConfig {
@@ -734,6 +1209,13 @@ OLLAMA_BASE_URL=http://localhost:11434
             return Ok(());
         }
 
+        if let Some(dot_path) = &args.export_dot {
+            let config = utils::load_pipeline_from_file(&args.config)?;
+            std::fs::write(dot_path, langextract_rust::pipeline::export_dot(&config))?;
+            println!("🕸️  Pipeline graph saved to: {}", dot_path.display());
+            return Ok(());
+        }
+
         // Execute pipeline
         println!("{}", style("🔬 Executing pipeline...").bold().cyan());
 
@@ -812,6 +1294,53 @@ OLLAMA_BASE_URL=http://localhost:11434
 
     // Helper functions
 
+    #[cfg(feature = "docx")]
+    fn read_word_document(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(langextract_rust::io::extract_text_from_docx(path)?)
+    }
+
+    #[cfg(not(feature = "docx"))]
+    fn read_word_document(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+        Err(format!("Reading Word documents requires the 'docx' feature: {}", path).into())
+    }
+
+    async fn validate_command(args: ValidateArgs) -> Result<(), Box<dyn std::error::Error>> {
+        use langextract_rust::pipeline::{PipelineExecutor, PipelineValidation};
+
+        let (path, report) = match (&args.pipeline, &args.file) {
+            (Some(path), _) => (path, PipelineExecutor::from_yaml_file(path)?.validate()),
+            (None, Some(path)) => {
+                let examples = load_examples(path)?;
+                let mut report = PipelineValidation::default();
+                if examples.is_empty() {
+                    report.warnings.push("No examples found".to_string());
+                }
+                for (index, example) in examples.iter().enumerate() {
+                    for warning in example.validate() {
+                        report.warnings.push(format!("Example {}: {}", index, warning));
+                    }
+                }
+                (path, report)
+            }
+            (None, None) => return Err("Pass an examples file or --pipeline".into()),
+        };
+
+        println!("{} {}", style("🔎 Validating").bold().cyan(), path.display());
+        for error in &report.errors {
+            println!("  ❌ {}", error);
+        }
+        for warning in &report.warnings {
+            println!("  ⚠️  {}", warning);
+        }
+
+        if report.is_valid() {
+            println!("{} Valid ({} warnings)", style("✅").green(), report.warnings.len());
+            Ok(())
+        } else {
+            Err(format!("{} errors found in {}", report.errors.len(), path.display()).into())
+        }
+    }
+
     fn load_examples(path: &PathBuf) -> Result<Vec<ExampleData>, Box<dyn std::error::Error>> {
         let content = fs::read_to_string(path)?;
         
@@ -863,6 +1392,22 @@ temperature: 0.3
 max_char_buffer: 8000
 max_workers: 6
 batch_length: 4
+"#,
+            ProviderType::Cohere => r#"# Cohere Configuration
+model: "command-r"
+provider: "cohere"
+temperature: 0.3
+max_char_buffer: 8000
+max_workers: 6
+batch_length: 4
+"#,
+            ProviderType::Mock => r#"# Offline Mock Configuration (returns no extractions)
+model: "mock"
+provider: "mock"
+temperature: 0.3
+max_char_buffer: 8000
+max_workers: 6
+batch_length: 4
 "#,
             ProviderType::Custom => r#"# Custom Provider Configuration
 model: "your-model"
@@ -894,6 +1439,14 @@ batch_length: 4
                     "No extractions found".to_string()
                 }
             }
+            OutputFormat::Ansi => visualize_ansi(result, true)?,
+            OutputFormat::Ndjson => {
+                let mut lines = export_ndjson(result, args.show_intervals);
+                if !lines.is_empty() {
+                    lines.push('\n');
+                }
+                lines
+            }
         };
 
         fs::write(path, content)?;
@@ -933,6 +1486,16 @@ batch_length: 4
                     println!("{}", style("No extractions found").yellow());
                 }
             }
+            OutputFormat::Ansi => {
+                println!("{}", visualize_ansi(result, true)?);
+            }
+            OutputFormat::Ndjson => {
+                let mut stdout = std::io::stdout().lock();
+                for extraction in result.extractions.iter().flatten() {
+                    writeln!(stdout, "{}", extraction_to_ndjson_line(extraction, args.show_intervals))?;
+                    stdout.flush()?;
+                }
+            }
         }
         Ok(())
     }
@@ -954,12 +1517,67 @@ batch_length: 4
                 }
                 eprintln!("   Check model availability and API limits");
             }
+            LangExtractError::ValidationFailed { errors } => {
+                eprintln!("{}", style("🧪 Validation Error:").red().bold());
+                for validation_error in errors {
+                    eprintln!("   {}", validation_error.message);
+                }
+            }
             _ => {
                 eprintln!("{}", style("❌ Extraction Error:").red().bold());
             }
         }
         Box::new(error)
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_extract_table_keeps_row_order() {
+            let table = parse_delimited("id,note\n1,Alice arrived.\n2,Bob left.\n3,Carol stayed.\n", ',').unwrap();
+            let provider = ProviderConfig::mock("[]")
+                .with_mock_response("Alice arrived", r#"[{"person": "Alice"}]"#)
+                .with_mock_response("Bob left", r#"[{"person": "Bob"}]"#)
+                .with_mock_response("Carol stayed", r#"[{"person": "Carol"}]"#);
+            let mut config = ExtractConfig {
+                debug: false,
+                max_workers: 3,
+                ..Default::default()
+            }
+            .with_quiet_mode();
+            config
+                .language_model_params
+                .insert("provider_config".to_string(), serde_json::to_value(provider).unwrap());
+            let examples = vec![ExampleData::new(
+                "Dan came home.".to_string(),
+                vec![Extraction::new("person".to_string(), "Dan".to_string())],
+            )];
+
+            let (headers, rows, failed) = extract_table(&table, "note", Some("Extract people"), &examples, config)
+                .await
+                .unwrap();
+
+            assert_eq!(headers, vec!["id", "note", "extractions", "error"]);
+            assert_eq!(failed, 0);
+            let people: Vec<(String, String)> = rows
+                .iter()
+                .map(|row| {
+                    let extractions: serde_json::Value = serde_json::from_str(&row[2]).unwrap();
+                    (row[0].clone(), extractions[0]["text"].as_str().unwrap().to_string())
+                })
+                .collect();
+            assert_eq!(
+                people,
+                vec![
+                    ("1".to_string(), "Alice".to_string()),
+                    ("2".to_string(), "Bob".to_string()),
+                    ("3".to_string(), "Carol".to_string()),
+                ]
+            );
+        }
+    }
 }
 
 #[cfg(feature = "cli")]
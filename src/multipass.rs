@@ -9,17 +9,57 @@
 use crate::{
     alignment::{AlignmentStats, TextAligner},
     annotation::Annotator,
-    chunking::{ChunkResult, TextChunk, TextChunker},
+    chunking::{ChunkResult, DedupStrategy, ResultAggregator, TextChunk, TextChunker},
     data::{AnnotatedDocument, Extraction},
     exceptions::LangExtractResult,
     resolver::Resolver,
 };
 use futures::future::join_all;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-/// Configuration for multi-pass extraction
+/// Scores a chunk's extractions to decide whether the chunk is re-processed
+///
+/// Chunks scoring below [`MultiPassConfig::quality_threshold`] are sent to the
+/// next pass when targeted re-processing is enabled.
+pub trait QualityScorer: Send + Sync {
+    /// Score between 0.0 (re-process) and 1.0 (good enough)
+    fn score(&self, chunk: &TextChunk, extractions: &[Extraction]) -> f32;
+}
+
+/// Default chunk scorer based on extraction count and empty extraction texts
+///
+/// A chunk with fewer extractions than expected scores 0.0; otherwise the
+/// score is the share of extractions whose text is not empty.
 #[derive(Debug, Clone)]
+pub struct DefaultQualityScorer {
+    /// Minimum number of extractions a chunk should yield
+    pub expected_extractions: usize,
+}
+
+impl DefaultQualityScorer {
+    /// Create a scorer expecting at least `expected_extractions` per chunk
+    pub fn new(expected_extractions: usize) -> Self {
+        Self { expected_extractions }
+    }
+}
+
+impl QualityScorer for DefaultQualityScorer {
+    fn score(&self, _chunk: &TextChunk, extractions: &[Extraction]) -> f32 {
+        if extractions.len() < self.expected_extractions {
+            return 0.0;
+        }
+        if extractions.is_empty() {
+            return 1.0;
+        }
+        let filled = extractions.iter().filter(|e| !e.extraction_text.trim().is_empty()).count();
+        filled as f32 / extractions.len() as f32
+    }
+}
+
+/// Configuration for multi-pass extraction
+#[derive(Clone)]
 pub struct MultiPassConfig {
     /// Number of extraction passes to perform
     pub max_passes: usize,
@@ -35,6 +75,30 @@ pub struct MultiPassConfig {
     pub max_reprocess_chunks: usize,
     /// Temperature adjustment for subsequent passes
     pub temperature_decay: f32,
+    /// How extractions found in different passes are recognized as duplicates
+    pub dedup_strategy: DedupStrategy,
+    /// Prompt description for each pass (pass `i` uses entry `i - 1`, falling back to the base prompt)
+    pub pass_prompts: Vec<String>,
+    /// Chunk scorer for targeted re-processing (defaults to [`DefaultQualityScorer`]
+    /// expecting `min_extractions_per_chunk` extractions)
+    pub quality_scorer: Option<Arc<dyn QualityScorer>>,
+}
+
+impl std::fmt::Debug for MultiPassConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultiPassConfig")
+            .field("max_passes", &self.max_passes)
+            .field("min_extractions_per_chunk", &self.min_extractions_per_chunk)
+            .field("enable_targeted_reprocessing", &self.enable_targeted_reprocessing)
+            .field("enable_refinement_passes", &self.enable_refinement_passes)
+            .field("quality_threshold", &self.quality_threshold)
+            .field("max_reprocess_chunks", &self.max_reprocess_chunks)
+            .field("temperature_decay", &self.temperature_decay)
+            .field("dedup_strategy", &self.dedup_strategy)
+            .field("pass_prompts", &self.pass_prompts)
+            .field("quality_scorer", &self.quality_scorer.as_ref().map(|_| "<QualityScorer>"))
+            .finish()
+    }
 }
 
 impl Default for MultiPassConfig {
@@ -47,6 +111,9 @@ impl Default for MultiPassConfig {
             quality_threshold: 0.3,
             max_reprocess_chunks: 10,
             temperature_decay: 0.9,
+            dedup_strategy: DedupStrategy::NormalizedText,
+            pass_prompts: Vec::new(),
+            quality_scorer: None,
         }
     }
 }
@@ -58,6 +125,8 @@ pub struct MultiPassStats {
     pub total_passes: usize,
     /// Extractions found in each pass
     pub extractions_per_pass: Vec<usize>,
+    /// Extractions kept after deduplication, by the pass that first found them
+    pub new_extractions_per_pass: Vec<usize>,
     /// Chunks re-processed in each pass
     pub reprocessed_chunks_per_pass: Vec<usize>,
     /// Total processing time
@@ -102,6 +171,7 @@ pub struct ScoredExtraction {
 pub struct MultiPassProcessor {
     config: MultiPassConfig,
     annotator: Annotator,
+    pass_annotators: Vec<Annotator>,
     resolver: Resolver,
     aligner: TextAligner,
 }
@@ -113,14 +183,26 @@ impl MultiPassProcessor {
         annotator: Annotator,
         resolver: Resolver,
     ) -> Self {
+        let aligner = TextAligner::with_config(annotator.alignment_config().clone());
+        let pass_annotators = config
+            .pass_prompts
+            .iter()
+            .map(|prompt| annotator.for_prompt(prompt))
+            .collect();
         Self {
             config,
             annotator,
+            pass_annotators,
             resolver,
-            aligner: TextAligner::new(),
+            aligner,
         }
     }
 
+    /// Get the annotator used for the first pass
+    pub fn annotator(&self) -> &Annotator {
+        &self.annotator
+    }
+
     /// Perform multi-pass extraction on text
     pub async fn extract_multipass(
         &self,
@@ -132,6 +214,7 @@ impl MultiPassProcessor {
         let mut stats = MultiPassStats {
             total_passes: 0,
             extractions_per_pass: Vec::new(),
+            new_extractions_per_pass: Vec::new(),
             reprocessed_chunks_per_pass: Vec::new(),
             total_time: Duration::default(),
             time_per_pass: Vec::new(),
@@ -195,6 +278,16 @@ impl MultiPassProcessor {
         Ok((result, stats))
     }
 
+    /// Annotator for a 1-based pass number, using that pass's prompt when one is configured
+    fn annotator_for_pass(&self, pass_num: usize) -> &Annotator {
+        self.pass_annotators.get(pass_num - 1).unwrap_or(&self.annotator)
+    }
+
+    /// Whether a 1-based pass number runs with its own prompt
+    fn has_pass_prompt(&self, pass_num: usize) -> bool {
+        pass_num <= self.pass_annotators.len()
+    }
+
     /// Process a single text with multiple passes
     async fn process_single_text_multipass(
         &self,
@@ -221,7 +314,7 @@ impl MultiPassProcessor {
             };
 
             // Process the text
-            let result = self.annotator.annotate_text(
+            let result = self.annotator_for_pass(pass_num).annotate_text(
                 text,
                 &self.resolver,
                 2000, // max_char_buffer
@@ -235,11 +328,12 @@ impl MultiPassProcessor {
             // Score and collect new extractions
             let mut pass_extractions = Vec::new();
             if let Some(extractions) = result.extractions {
-                for extraction in extractions {
+                for mut extraction in extractions {
                     // Skip if we've already found this extraction
                     if !previous_extraction_texts.contains(&extraction.extraction_text) {
                         let quality_score = self.calculate_quality_score(&extraction, text);
                         if quality_score >= self.config.quality_threshold {
+                            extraction.source_pass = Some(pass_num);
                             pass_extractions.push(ScoredExtraction {
                                 extraction: extraction.clone(),
                                 quality_score,
@@ -261,8 +355,8 @@ impl MultiPassProcessor {
                     stats.extractions_per_pass.last().unwrap_or(&0), pass_num);
             }
 
-            // Early termination if no new extractions found
-            if stats.extractions_per_pass.last() == Some(&0) {
+            // Early termination if no new extractions found, unless the next pass has its own prompt
+            if stats.extractions_per_pass.last() == Some(&0) && !self.has_pass_prompt(pass_num + 1) {
                 if debug {
                     println!("   No new extractions found, terminating early");
                 }
@@ -286,7 +380,7 @@ impl MultiPassProcessor {
         let initial_chunks = chunker.chunk_text(text, None)?;
 
         let mut all_extractions = Vec::new();
-        let mut chunks_to_process = initial_chunks;
+        let mut chunks_to_process = initial_chunks.clone();
         let mut processed_extraction_texts = HashSet::new();
 
         for pass_num in 1..=self.config.max_passes {
@@ -312,13 +406,15 @@ impl MultiPassProcessor {
 
             for result in pass_results {
                 let extractions = result.extractions.unwrap_or_default();
-                let extraction_count = extractions.len();
+                let chunk = chunks_to_process.iter().find(|c| c.id == result.chunk_id);
+                let needs_reprocessing = chunk.is_some_and(|chunk| self.needs_reprocessing(chunk, &extractions));
                 
                 // Score and collect extractions
-                for extraction in extractions {
+                for mut extraction in extractions {
                     if !processed_extraction_texts.contains(&extraction.extraction_text) {
                         let quality_score = self.calculate_quality_score(&extraction, text);
                         if quality_score >= self.config.quality_threshold {
+                            extraction.source_pass = Some(pass_num);
                             pass_extractions.push(ScoredExtraction {
                                 extraction: extraction.clone(),
                                 quality_score,
@@ -331,13 +427,8 @@ impl MultiPassProcessor {
                 }
 
                 // Identify chunks for re-processing
-                if self.config.enable_targeted_reprocessing 
-                    && extraction_count < self.config.min_extractions_per_chunk
-                    && low_yield_chunks.len() < self.config.max_reprocess_chunks {
-                    
-                    // Find the original chunk for re-processing
-                    if let Some(chunk) = chunks_to_process.iter()
-                        .find(|c| c.id == result.chunk_id) {
+                if needs_reprocessing && low_yield_chunks.len() < self.config.max_reprocess_chunks {
+                    if let Some(chunk) = chunk {
                         low_yield_chunks.push(chunk.clone());
                     }
                 }
@@ -354,12 +445,16 @@ impl MultiPassProcessor {
                     stats.reprocessed_chunks_per_pass.last().unwrap_or(&0));
             }
 
-            // Prepare chunks for next pass
-            chunks_to_process = low_yield_chunks;
+            // Prepare chunks for next pass; a pass with its own prompt revisits every chunk
+            chunks_to_process = if self.has_pass_prompt(pass_num + 1) {
+                initial_chunks.clone()
+            } else {
+                low_yield_chunks
+            };
 
             // Early termination conditions
-            if chunks_to_process.is_empty() 
-                || stats.extractions_per_pass.last() == Some(&0) {
+            if chunks_to_process.is_empty()
+                || (stats.extractions_per_pass.last() == Some(&0) && !self.has_pass_prompt(pass_num + 1)) {
                 if debug {
                     println!("   No more chunks to process or no new extractions, terminating");
                 }
@@ -371,6 +466,18 @@ impl MultiPassProcessor {
         Ok(all_extractions)
     }
 
+    /// Whether a chunk's extractions score below the quality threshold
+    fn needs_reprocessing(&self, chunk: &TextChunk, extractions: &[Extraction]) -> bool {
+        if !self.config.enable_targeted_reprocessing {
+            return false;
+        }
+        let score = match &self.config.quality_scorer {
+            Some(scorer) => scorer.score(chunk, extractions),
+            None => DefaultQualityScorer::new(self.config.min_extractions_per_chunk).score(chunk, extractions),
+        };
+        score < self.config.quality_threshold
+    }
+
     /// Process chunks for a specific pass
     async fn process_chunks_for_pass(
         &self,
@@ -386,8 +493,9 @@ impl MultiPassProcessor {
             additional_context.map(String::from)
         };
 
+        let annotator = self.annotator_for_pass(pass_number);
         let chunk_futures = chunks.iter().map(|chunk| {
-            self.process_chunk_for_pass(chunk, enhanced_context.as_deref(), debug)
+            self.process_chunk_for_pass(annotator, chunk, enhanced_context.as_deref(), debug)
         });
 
         let results = join_all(chunk_futures).await;
@@ -411,13 +519,14 @@ impl MultiPassProcessor {
     /// Process a single chunk for a pass
     async fn process_chunk_for_pass(
         &self,
+        annotator: &Annotator,
         chunk: &TextChunk,
         additional_context: Option<&str>,
         _debug: bool,
     ) -> LangExtractResult<ChunkResult> {
         let start_time = Instant::now();
 
-        match self.annotator.annotate_text(&chunk.text, &self.resolver, 2000, 1, additional_context, false, 1, 1).await {
+        match annotator.annotate_text(&chunk.text, &self.resolver, 2000, 1, additional_context, false, 1, 1).await {
             Ok(annotated_doc) => {
                 let mut extractions = annotated_doc.extractions.unwrap_or_default();
                 
@@ -529,10 +638,11 @@ impl MultiPassProcessor {
         stats: &mut MultiPassStats,
         debug: bool,
     ) -> Vec<Extraction> {
-        // Filter by quality threshold
-        let high_quality: Vec<_> = scored_extractions.into_iter()
+        // Filter by quality threshold, earliest pass first so duplicates keep its provenance
+        let mut high_quality: Vec<_> = scored_extractions.into_iter()
             .filter(|se| se.quality_score >= self.config.quality_threshold)
             .collect();
+        high_quality.sort_by_key(|se| se.pass_number);
 
         // Calculate quality statistics
         let total_count = high_quality.len();
@@ -560,17 +670,22 @@ impl MultiPassProcessor {
             filtered_count: 0, // Will be calculated during deduplication
         };
 
-        // Deduplicate based on extraction text similarity
-        let mut deduplicated = Vec::new();
-        let mut seen_texts = HashSet::new();
-
-        for scored in high_quality {
-            let normalized_text = scored.extraction.extraction_text.to_lowercase().trim().to_string();
-            if !seen_texts.contains(&normalized_text) {
-                seen_texts.insert(normalized_text);
-                deduplicated.push(scored.extraction);
-            } else {
-                stats.quality_stats.filtered_count += 1;
+        // Deduplicate across passes; the aggregator keeps the first (earliest-pass) copy
+        let aggregator = ResultAggregator::with_settings(0.8, true, self.config.dedup_strategy.clone());
+        let candidates: Vec<Extraction> = high_quality.into_iter().map(|se| se.extraction).collect();
+        let candidate_count = candidates.len();
+        let deduplicated = aggregator
+            .deduplicate_extractions(candidates)
+            .unwrap_or_default();
+        stats.quality_stats.filtered_count += candidate_count - deduplicated.len();
+
+        stats.new_extractions_per_pass = vec![0; stats.total_passes];
+        for extraction in &deduplicated {
+            if let Some(count) = extraction
+                .source_pass
+                .and_then(|pass| stats.new_extractions_per_pass.get_mut(pass.wrapping_sub(1)))
+            {
+                *count += 1;
             }
         }
 
@@ -592,8 +707,9 @@ impl MultiPassProcessor {
         for (i, (&extractions, &time)) in stats.extractions_per_pass.iter()
             .zip(stats.time_per_pass.iter()).enumerate() {
             let reprocessed = stats.reprocessed_chunks_per_pass.get(i).unwrap_or(&0);
-            println!("Pass {}: {} extractions, {} chunks reprocessed, {:?}", 
-                i + 1, extractions, reprocessed, time);
+            let kept = stats.new_extractions_per_pass.get(i).unwrap_or(&0);
+            println!("Pass {}: {} extractions ({} new after dedup), {} chunks reprocessed, {:?}",
+                i + 1, extractions, kept, reprocessed, time);
         }
 
         println!("\nQuality Statistics:");
@@ -614,6 +730,44 @@ impl MultiPassProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        data::FormatType,
+        inference::{BaseLanguageModel, ScoredOutput},
+        prompting::PromptTemplateStructured,
+        ExtractConfig,
+    };
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Mock model returning the next canned response on each call
+    struct SequenceModel {
+        responses: Vec<String>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl BaseLanguageModel for SequenceModel {
+        async fn infer(
+            &self,
+            batch_prompts: &[String],
+            _kwargs: &HashMap<String, serde_json::Value>,
+        ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let response = &self.responses[call.min(self.responses.len() - 1)];
+            Ok(batch_prompts
+                .iter()
+                .map(|_| vec![ScoredOutput::from_text(response.clone())])
+                .collect())
+        }
+
+        fn model_id(&self) -> &str {
+            "sequence-model"
+        }
+
+        fn provider_name(&self) -> &str {
+            "test"
+        }
+    }
 
     #[test]
     fn test_multipass_config_default() {
@@ -657,4 +811,160 @@ mod tests {
         assert_eq!(extractions[0].extraction.extraction_class, "person");
         assert_eq!(extractions[1].extraction.extraction_class, "organization");
     }
+
+    #[tokio::test]
+    async fn test_overlapping_passes_keep_earliest_provenance() {
+        let model = SequenceModel {
+            responses: vec![
+                r#"[{"person": "Alice"}, {"person": "Bob"}]"#.to_string(),
+                r#"[{"person": "alice"}, {"person": "Bob"}, {"person": "Carol"}]"#.to_string(),
+            ],
+            calls: AtomicUsize::new(0),
+        };
+        let annotator = Annotator::new(
+            Box::new(model),
+            PromptTemplateStructured::new(Some("Extract people")),
+            FormatType::Json,
+            false,
+        );
+        let resolver = Resolver::new(&ExtractConfig { debug: false, ..Default::default() }, false).unwrap();
+        let config = MultiPassConfig {
+            max_passes: 2,
+            enable_refinement_passes: false,
+            ..Default::default()
+        };
+        let processor = MultiPassProcessor::new(config, annotator, resolver);
+
+        let (document, stats) = processor
+            .extract_multipass("Alice met Bob and Carol.", None, false)
+            .await
+            .unwrap();
+
+        let found: Vec<_> = document
+            .extractions
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.extraction_text, e.source_pass))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Alice".to_string(), Some(1)),
+                ("Bob".to_string(), Some(1)),
+                ("Carol".to_string(), Some(2)),
+            ]
+        );
+        assert_eq!(stats.new_extractions_per_pass, vec![2, 1]);
+    }
+
+    /// Mock model that only finds organizations when the prompt asks for them
+    struct PromptSensitiveModel;
+
+    #[async_trait]
+    impl BaseLanguageModel for PromptSensitiveModel {
+        async fn infer(
+            &self,
+            batch_prompts: &[String],
+            _kwargs: &HashMap<String, serde_json::Value>,
+        ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
+            Ok(batch_prompts
+                .iter()
+                .map(|prompt| {
+                    let response = if prompt.contains("Extract organizations") {
+                        r#"[{"organization": "Initech"}]"#
+                    } else {
+                        r#"[{"person": "Alice"}]"#
+                    };
+                    vec![ScoredOutput::from_text(response.to_string())]
+                })
+                .collect())
+        }
+
+        fn model_id(&self) -> &str {
+            "prompt-sensitive-model"
+        }
+
+        fn provider_name(&self) -> &str {
+            "test"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pass_prompts_catch_extractions_missed_by_first_prompt() {
+        let annotator = Annotator::new(
+            Box::new(PromptSensitiveModel),
+            PromptTemplateStructured::new(Some("Extract people")),
+            FormatType::Json,
+            false,
+        );
+        let resolver = Resolver::new(&ExtractConfig { debug: false, ..Default::default() }, false).unwrap();
+        let config = MultiPassConfig {
+            max_passes: 2,
+            enable_refinement_passes: false,
+            pass_prompts: vec!["Extract people".to_string(), "Extract organizations".to_string()],
+            ..Default::default()
+        };
+        let processor = MultiPassProcessor::new(config, annotator, resolver);
+
+        let (document, _) = processor
+            .extract_multipass("Alice works at Initech.", None, false)
+            .await
+            .unwrap();
+
+        let found: Vec<_> = document
+            .extractions
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.extraction_class, e.extraction_text, e.source_pass))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("person".to_string(), "Alice".to_string(), Some(1)),
+                ("organization".to_string(), "Initech".to_string(), Some(2)),
+            ]
+        );
+    }
+
+    /// Scorer that wants at least three extractions per chunk
+    struct DenseScorer;
+
+    impl QualityScorer for DenseScorer {
+        fn score(&self, _chunk: &TextChunk, extractions: &[Extraction]) -> f32 {
+            (extractions.len() as f32 / 3.0).min(1.0)
+        }
+    }
+
+    #[test]
+    fn test_quality_scorer_flags_sparse_chunk() {
+        let chunk = TextChunk::new(0, "Alice met Bob and Carol.".to_string(), 0, None);
+        let person = |name: &str| Extraction::new("person".to_string(), name.to_string());
+        let sparse = vec![person("Alice")];
+        let dense = vec![person("Alice"), person("Bob"), person("Carol")];
+
+        let default_scorer = DefaultQualityScorer::new(1);
+        assert_eq!(default_scorer.score(&chunk, &[]), 0.0);
+        assert_eq!(default_scorer.score(&chunk, &sparse), 1.0);
+        assert_eq!(default_scorer.score(&chunk, &[person("Alice"), person(" ")]), 0.5);
+
+        let processor = |scorer: Option<Arc<dyn QualityScorer>>| {
+            let annotator = Annotator::new(
+                Box::new(PromptSensitiveModel),
+                PromptTemplateStructured::new(Some("Extract people")),
+                FormatType::Json,
+                false,
+            );
+            let resolver = Resolver::new(&ExtractConfig { debug: false, ..Default::default() }, false).unwrap();
+            let config = MultiPassConfig { quality_threshold: 0.5, quality_scorer: scorer, ..Default::default() };
+            MultiPassProcessor::new(config, annotator, resolver)
+        };
+
+        let default_processor = processor(None);
+        assert!(!default_processor.needs_reprocessing(&chunk, &sparse));
+        assert!(default_processor.needs_reprocessing(&chunk, &[]));
+
+        let dense_processor = processor(Some(Arc::new(DenseScorer)));
+        assert!(dense_processor.needs_reprocessing(&chunk, &sparse));
+        assert!(!dense_processor.needs_reprocessing(&chunk, &dense));
+    }
 }
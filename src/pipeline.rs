@@ -11,6 +11,8 @@ use crate::{
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use futures::future::join_all;
+use std::future::Future;
+use tokio::sync::Semaphore;
 
 /// A single step in a processing pipeline
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +40,10 @@ pub struct PipelineStep {
 
     /// Dependencies - this step depends on output from these step IDs
     pub depends_on: Vec<String>,
+
+    /// Sampling temperature for this step, overriding `global_config.temperature`
+    #[serde(default)]
+    pub temperature: Option<f32>,
 }
 
 /// Filter configuration for processing specific extractions
@@ -74,6 +80,14 @@ pub struct PipelineConfig {
     /// Enable parallel execution of independent steps (default: false)
     #[serde(default)]
     pub enable_parallel_execution: bool,
+
+    /// Maximum number of steps run at once within a parallel wave (default: 4)
+    #[serde(default = "default_max_parallel_steps")]
+    pub max_parallel_steps: usize,
+}
+
+fn default_max_parallel_steps() -> usize {
+    4
 }
 
 /// Results from a single pipeline step
@@ -123,6 +137,22 @@ pub struct PipelineResult {
     pub error_message: Option<String>,
 }
 
+/// Issues found when checking a pipeline without running it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PipelineValidation {
+    /// Problems that stop the pipeline from running (cycles, unknown steps, bad filters)
+    pub errors: Vec<String>,
+    /// Example problems that may degrade extraction quality
+    pub warnings: Vec<String>,
+}
+
+impl PipelineValidation {
+    /// Whether no hard errors were found
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
 /// Pipeline executor
 pub struct PipelineExecutor {
     config: PipelineConfig,
@@ -166,13 +196,12 @@ impl PipelineExecutor {
     pub async fn execute(&self, input_text: &str) -> LangExtractResult<PipelineResult> {
         let start_time = std::time::Instant::now();
 
-        println!("🚀 Starting pipeline execution: {}", self.config.name);
-        println!("📝 Description: {}", self.config.description);
+        tracing::info!(pipeline = %self.config.name, "Starting pipeline execution");
         
         if self.config.enable_parallel_execution {
-            println!("⚡ Parallel execution enabled - independent steps will run concurrently");
+            tracing::debug!("Parallel execution enabled - independent steps will run concurrently");
         } else {
-            println!("🔄 Sequential execution - steps will run one after another");
+            tracing::debug!("Sequential execution - steps will run one after another");
         }
 
         if self.config.enable_parallel_execution {
@@ -211,7 +240,7 @@ impl PipelineExecutor {
 
         let total_time = start_time.elapsed().as_millis() as u64;
 
-        println!("✅ Pipeline execution completed in {}ms", total_time);
+        tracing::info!(elapsed_ms = total_time, "Pipeline execution completed");
 
         Ok(PipelineResult {
             config: self.config.clone(),
@@ -232,7 +261,7 @@ impl PipelineExecutor {
         let execution_waves = self.resolve_execution_waves()?;
         
         for (wave_index, wave_steps) in execution_waves.iter().enumerate() {
-            println!("🌊 Executing wave {} with {} steps", wave_index + 1, wave_steps.len());
+            tracing::debug!(wave = wave_index + 1, steps = wave_steps.len(), "Executing wave");
             
             if wave_steps.len() == 1 {
                 // Single step - execute normally
@@ -251,13 +280,16 @@ impl PipelineExecutor {
                 }
             } else {
                 // Multiple independent steps - execute in parallel
-                println!("⚡ Running {} steps in parallel", wave_steps.len());
-                
-                let parallel_futures: Vec<_> = wave_steps.iter()
-                    .map(|step_id| self.execute_step(step_id, input_text, &context_data))
-                    .collect();
-                
-                let wave_results = join_all(parallel_futures).await;
+                tracing::debug!(
+                    steps = wave_steps.len(),
+                    max_parallel = self.config.max_parallel_steps.max(1),
+                    "Running steps in parallel"
+                );
+
+                let context = &context_data;
+                let wave_results = self.run_wave(wave_steps, |step_id| {
+                    self.execute_step(step_id, input_text, context)
+                }).await;
                 
                 // Process results
                 for (i, result) in wave_results.into_iter().enumerate() {
@@ -283,7 +315,7 @@ impl PipelineExecutor {
 
         let total_time = start_time.elapsed().as_millis() as u64;
 
-        println!("✅ Pipeline execution completed in {}ms", total_time);
+        tracing::info!(elapsed_ms = total_time, "Pipeline execution completed");
 
         Ok(PipelineResult {
             config: self.config.clone(),
@@ -295,8 +327,63 @@ impl PipelineExecutor {
         })
     }
 
+    /// Run the steps of a wave concurrently, bounded by `max_parallel_steps`
+    ///
+    /// Steps beyond the limit wait for a permit; results keep the wave's order.
+    async fn run_wave<'a, F, Fut>(&self, wave_steps: &'a [String], run_step: F) -> Vec<LangExtractResult<StepResult>>
+    where
+        F: Fn(&'a str) -> Fut,
+        Fut: Future<Output = LangExtractResult<StepResult>>,
+    {
+        let semaphore = Semaphore::new(self.config.max_parallel_steps.max(1));
+        let semaphore = &semaphore;
+        let run_step = &run_step;
+
+        let futures: Vec<_> = wave_steps.iter()
+            .map(|step_id| async move {
+                let _permit = semaphore.acquire().await
+                    .map_err(|e| LangExtractError::configuration(format!("Step scheduler closed: {}", e)))?;
+                run_step(step_id).await
+            })
+            .collect();
+
+        join_all(futures).await
+    }
+
+    /// Check step ids, dependencies, filters and examples without calling a model
+    pub fn validate(&self) -> PipelineValidation {
+        let mut report = PipelineValidation::default();
+        let mut seen = std::collections::HashSet::new();
+
+        for step in &self.config.steps {
+            if !seen.insert(step.id.as_str()) {
+                report.errors.push(format!("Duplicate step id '{}'", step.id));
+            }
+            for dep in &step.depends_on {
+                if !self.config.steps.iter().any(|s| &s.id == dep) {
+                    report.errors.push(format!("Step '{}' depends on unknown step '{}'", step.id, dep));
+                }
+            }
+            if let Some(pattern) = step.filter.as_ref().and_then(|f| f.text_pattern.as_deref()) {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    report.errors.push(format!("Step '{}' has an invalid text_pattern: {}", step.id, e));
+                }
+            }
+            for (index, example) in step.examples.iter().enumerate() {
+                for warning in example.validate() {
+                    report.warnings.push(format!("Step '{}', example {}: {}", step.id, index, warning));
+                }
+            }
+        }
+
+        if let Err(e) = self.resolve_execution_order() {
+            report.errors.push(e.to_string());
+        }
+        report
+    }
+
     /// Resolve the execution order based on dependencies
-    fn resolve_execution_order(&self) -> LangExtractResult<Vec<String>> {
+    pub fn resolve_execution_order(&self) -> LangExtractResult<Vec<String>> {
         let mut order = Vec::new();
         let mut visited = std::collections::HashSet::new();
         let mut visiting = std::collections::HashSet::new();
@@ -385,6 +472,7 @@ impl PipelineExecutor {
     }
 
     /// Execute a single pipeline step
+    #[tracing::instrument(skip_all, fields(step = %step_id))]
     async fn execute_step(
         &self,
         step_id: &str,
@@ -396,22 +484,22 @@ impl PipelineExecutor {
 
         let step_start = std::time::Instant::now();
 
-        println!("🔄 Executing step: {} ({})", step.name, step.id);
+        tracing::info!(name = %step.name, "Executing step");
 
         // Determine input text for this step with mapping context
         let step_input = self.prepare_step_input(step, input_text, context_data)?;
         let input_count = step_input.len();
 
-        println!("📥 Processing {} input items", input_count);
+        tracing::debug!(items = input_count, "Prepared step input");
 
         let mut all_extractions = Vec::new();
 
         // Process each input item
         for (i, input_item) in step_input.iter().enumerate() {
-            println!("  📄 Processing item {}/{}", i + 1, input_count);
+            tracing::trace!(item = i + 1, of = input_count, "Processing step input item");
 
             // Create extraction config for this step
-            let step_config = self.config.global_config.clone();
+            let step_config = self.step_config(step);
             // Use step-specific examples if provided, otherwise use global
             let examples = if step.examples.is_empty() {
                 vec![] // Will need to be provided externally
@@ -493,7 +581,7 @@ impl PipelineExecutor {
                     }
                 }
                 Err(e) => {
-                    println!("  ❌ Step '{}' failed on item {}/{}: {}", step.id, i + 1, input_count, e);
+                    tracing::error!(item = i + 1, of = input_count, error = %e, "Step failed");
                     return Ok(StepResult {
                         step_id: step.id.clone(),
                         step_name: step.name.clone(),
@@ -509,8 +597,11 @@ impl PipelineExecutor {
 
         let processing_time = step_start.elapsed().as_millis() as u64;
 
-        println!("  ✅ Step '{}' completed: {} extractions in {}ms",
-                step.name, all_extractions.len(), processing_time);
+        tracing::info!(
+            extractions = all_extractions.len(),
+            elapsed_ms = processing_time,
+            "Step completed"
+        );
 
         Ok(StepResult {
             step_id: step.id.clone(),
@@ -523,6 +614,15 @@ impl PipelineExecutor {
         })
     }
 
+    /// Global extraction config with the step's overrides applied
+    fn step_config(&self, step: &PipelineStep) -> ExtractConfig {
+        let mut config = self.config.global_config.clone();
+        if let Some(temperature) = step.temperature {
+            config.temperature = temperature;
+        }
+        config
+    }
+
     /// Prepare input text for a step based on its configuration
     fn prepare_step_input(
         &self,
@@ -537,7 +637,7 @@ impl PipelineExecutor {
             for dep_id in &step.depends_on {
                 if let Some(extractions) = context_data.get(dep_id) {
                     // Apply filter if specified
-                    let filtered_extractions = self.apply_filter(extractions, &step.filter);
+                    let filtered_extractions = self.apply_filter(extractions, &step.filter)?;
 
                     for extraction in filtered_extractions {
                         let parent_start = extraction.char_interval.as_ref().and_then(|ci| ci.start_pos);
@@ -554,6 +654,11 @@ impl PipelineExecutor {
                 }
             }
 
+            // `max_items` caps the step's total input, not each dependency's share
+            if let Some(max_items) = step.filter.as_ref().and_then(|f| f.max_items) {
+                inputs.truncate(max_items);
+            }
+
             Ok(inputs)
         } else {
             // First step - use original text
@@ -569,37 +674,39 @@ impl PipelineExecutor {
     }
 
     /// Apply filter to extractions
+    ///
+    /// Fails with a configuration error when `text_pattern` is not a valid regex,
+    /// rather than letting every extraction through.
     fn apply_filter<'a>(
         &self,
         extractions: &'a [Extraction],
         filter: &Option<PipelineFilter>,
-    ) -> Vec<&'a Extraction> {
-        if let Some(f) = filter {
-            extractions.iter()
-                .filter(|e| {
-                    // Check class filter
-                    if let Some(class) = &f.class_filter {
-                        if e.extraction_class != *class {
-                            return false;
-                        }
-                    }
+    ) -> LangExtractResult<Vec<&'a Extraction>> {
+        let Some(f) = filter else {
+            return Ok(extractions.iter().collect());
+        };
 
-                    // Check text pattern filter
-                    if let Some(pattern) = &f.text_pattern {
-                        if let Ok(regex) = regex::Regex::new(pattern) {
-                            if !regex.is_match(&e.extraction_text) {
-                                return false;
-                            }
-                        }
+        let regex = f
+            .text_pattern
+            .as_deref()
+            .map(regex::Regex::new)
+            .transpose()
+            .map_err(|e| LangExtractError::configuration(format!("Invalid filter text_pattern: {}", e)))?;
+
+        Ok(extractions.iter()
+            .filter(|e| {
+                // Check class filter
+                if let Some(class) = &f.class_filter {
+                    if e.extraction_class != *class {
+                        return false;
                     }
+                }
 
-                    true
-                })
-                .take(f.max_items.unwrap_or(usize::MAX))
-                .collect()
-        } else {
-            extractions.iter().collect()
-        }
+                // Check text pattern filter
+                regex.as_ref().is_none_or(|regex| regex.is_match(&e.extraction_text))
+            })
+            .take(f.max_items.unwrap_or(usize::MAX))
+            .collect())
     }
 
     /// Build the final nested output structure
@@ -637,6 +744,52 @@ impl PipelineExecutor {
     }
 }
 
+/// Render the step dependency graph of `config` as GraphViz DOT
+///
+/// Each step becomes a node labeled with its name, with an edge from every
+/// dependency to the step that uses it. Steps with a filter are filled orange,
+/// the rest light blue. Steps that can run in the same parallel wave share a rank.
+pub fn export_dot(config: &PipelineConfig) -> String {
+    fn quote(value: &str) -> String {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    let mut dot = format!("digraph {} {{\n", quote(&config.name));
+    dot.push_str("    rankdir=LR;\n");
+    dot.push_str("    node [shape=box, style=\"rounded,filled\"];\n");
+
+    for step in &config.steps {
+        let color = if step.filter.is_some() { "orange" } else { "lightblue" };
+        dot.push_str(&format!(
+            "    {} [label={}, fillcolor={}];\n",
+            quote(&step.id),
+            quote(&step.name),
+            color
+        ));
+    }
+
+    for step in &config.steps {
+        for dep in &step.depends_on {
+            dot.push_str(&format!("    {} -> {};\n", quote(dep), quote(&step.id)));
+        }
+    }
+
+    // Circular configurations still get nodes and edges, just no wave ranks
+    if let Ok(waves) = PipelineExecutor::new(config.clone()).resolve_execution_waves() {
+        for (index, wave) in waves.iter().enumerate().filter(|(_, wave)| wave.len() > 1) {
+            let members: Vec<String> = wave.iter().map(|id| quote(id)).collect();
+            dot.push_str(&format!(
+                "    subgraph wave_{} {{ rank=same; {}; }}\n",
+                index,
+                members.join("; ")
+            ));
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
 /// Utility functions for pipeline management
 pub mod utils {
     use super::*;
@@ -648,6 +801,7 @@ pub mod utils {
             description: "Extract requirements and sub-divide into values, units, and specifications".to_string(),
             version: "1.0.0".to_string(),
             enable_parallel_execution: false,
+            max_parallel_steps: 4,
             global_config: ExtractConfig {
                 model_id: "gemini-2.5-flash".to_string(),
                 api_key: None,
@@ -667,6 +821,42 @@ pub mod utils {
                 enable_multipass: false,
                 multipass_min_extractions: 1,
                 multipass_quality_threshold: 0.3,
+                fuzzy_alignment_threshold: 0.4,
+                class_descriptions: std::collections::HashMap::new(),
+                skip_chunking: false,
+                enable_cache: false,
+                cache_dir: None,
+                cache_nonzero_temperature: true,
+                strict_validation: false,
+                system_prompt: None,
+                require_grounding: false,
+                allow_fuzzy_grounding: false,
+                example_selection: crate::prompting::ExampleSelection::All,
+                max_examples: None,
+                keep_raw_outputs: false,
+                normalize_input: true,
+                max_output_tokens: None,
+                seed: None,
+                pass_prompts: Vec::new(),
+                response_parser: None,
+                per_chunk_timeout_ms: None,
+                context_provider: None,
+                sort_order: crate::ExtractionSortOrder::AsFound,
+                merge_adjacent_same_class: false,
+                multipass_quality_scorer: None,
+                auto_shrink_on_truncation: false,
+                prompt_variables: std::collections::HashMap::new(),
+                strict_prompt_variables: false,
+                json_mode: false,
+                checkpoint_path: None,
+                allowed_classes: None,
+                post_process: None,
+                tokenizer: None,
+                reformat_on_parse_failure: false,
+                max_reformat_attempts: 1,
+                min_chunks: None,
+                table_mode: false,
+                prompt_language: crate::prompting::PromptLanguage::English,
                 progress_handler: None,
             },
             steps: vec![
@@ -687,6 +877,7 @@ pub mod utils {
                     output_field: "requirements".to_string(),
                     filter: None,
                     depends_on: vec![],
+                    temperature: None,
                 },
                 PipelineStep {
                     id: "extract_values".to_string(),
@@ -711,6 +902,7 @@ pub mod utils {
                         max_items: None,
                     }),
                     depends_on: vec!["extract_requirements".to_string()],
+                    temperature: None,
                 },
                 PipelineStep {
                     id: "extract_specifications".to_string(),
@@ -733,6 +925,7 @@ pub mod utils {
                         max_items: None,
                     }),
                     depends_on: vec!["extract_requirements".to_string()],
+                    temperature: None,
                 },
             ],
         }
@@ -801,8 +994,236 @@ mod tests {
             max_items: None,
         };
 
-        let filtered = executor.apply_filter(&extractions, &Some(filter));
+        let filtered = executor.apply_filter(&extractions, &Some(filter)).unwrap();
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].extraction_class, "requirement");
     }
+
+    fn parent_extractions() -> Vec<Extraction> {
+        [
+            ("requirement", "The system shall respond within 200ms"),
+            ("note", "Latency is measured at the gateway"),
+            ("requirement", "The system shall store 10GB per user"),
+            ("requirement", "Users should be able to log in"),
+            ("note", "Storage is billed monthly"),
+        ]
+        .iter()
+        .map(|(class, text)| Extraction::new(class.to_string(), text.to_string()))
+        .collect()
+    }
+
+    fn step_input_texts(filter: PipelineFilter) -> Vec<String> {
+        let config = utils::create_requirements_pipeline();
+        let mut step = config.steps[1].clone();
+        step.filter = Some(filter);
+        let executor = PipelineExecutor::new(config);
+
+        let mut context = HashMap::new();
+        context.insert("extract_requirements".to_string(), parent_extractions());
+
+        executor
+            .prepare_step_input(&step, "", &context)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_step_input_class_filter() {
+        let texts = step_input_texts(PipelineFilter {
+            class_filter: Some("note".to_string()),
+            text_pattern: None,
+            max_items: None,
+        });
+        assert_eq!(texts, vec!["Latency is measured at the gateway", "Storage is billed monthly"]);
+    }
+
+    #[test]
+    fn test_step_input_text_pattern_and_max_items() {
+        let texts = step_input_texts(PipelineFilter {
+            class_filter: None,
+            text_pattern: Some(r"\bshall\b".to_string()),
+            max_items: None,
+        });
+        assert_eq!(texts.len(), 2);
+        assert!(texts.iter().all(|t| t.contains("shall")));
+
+        let texts = step_input_texts(PipelineFilter {
+            class_filter: Some("requirement".to_string()),
+            text_pattern: None,
+            max_items: Some(2),
+        });
+        assert_eq!(texts, vec![
+            "The system shall respond within 200ms",
+            "The system shall store 10GB per user",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_step_with_no_matching_inputs_is_empty() {
+        let mut config = utils::create_requirements_pipeline();
+        config.steps[1].filter = Some(PipelineFilter {
+            class_filter: Some("missing".to_string()),
+            text_pattern: None,
+            max_items: None,
+        });
+        let executor = PipelineExecutor::new(config);
+
+        let mut context = HashMap::new();
+        context.insert("extract_requirements".to_string(), parent_extractions());
+
+        let result = executor.execute_step("extract_values", "", &context).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.input_count, 0);
+        assert!(result.extractions.is_empty());
+
+        let invalid = Some(PipelineFilter {
+            class_filter: None,
+            text_pattern: Some("(".to_string()),
+            max_items: None,
+        });
+        assert!(executor.apply_filter(&parent_extractions(), &invalid).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parallel_wave_respects_step_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut config = utils::create_requirements_pipeline();
+        let template = config.steps[0].clone();
+        config.steps = (0..6)
+            .map(|i| PipelineStep {
+                id: format!("step_{}", i),
+                depends_on: Vec::new(),
+                temperature: None,
+                ..template.clone()
+            })
+            .collect();
+        config.enable_parallel_execution = true;
+        config.max_parallel_steps = 2;
+        let executor = PipelineExecutor::new(config);
+
+        let waves = executor.resolve_execution_waves().unwrap();
+        assert_eq!(waves.len(), 1);
+        assert_eq!(waves[0].len(), 6);
+
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let results = executor.run_wave(&waves[0], |step_id| {
+            let running = &running;
+            let max_running = &max_running;
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+
+                Ok(StepResult {
+                    step_id: step_id.to_string(),
+                    step_name: step_id.to_string(),
+                    extractions: Vec::new(),
+                    processing_time_ms: 20,
+                    input_count: 1,
+                    success: true,
+                    error_message: None,
+                })
+            }
+        }).await;
+
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+        let ids: Vec<String> = results.into_iter().map(|r| r.unwrap().step_id).collect();
+        assert_eq!(ids, waves[0]);
+    }
+
+    #[test]
+    fn test_max_parallel_steps_defaults_when_missing() {
+        let config = utils::create_requirements_pipeline();
+        let mut value = serde_json::to_value(&config).unwrap();
+        value.as_object_mut().unwrap().remove("max_parallel_steps");
+
+        let deserialized: PipelineConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(deserialized.max_parallel_steps, 4);
+    }
+
+    #[test]
+    fn test_export_dot() {
+        let mut config = utils::create_requirements_pipeline();
+        config.steps[2].filter = None;
+        let dot = export_dot(&config);
+
+        assert!(dot.starts_with("digraph \"Requirements Extraction Pipeline\" {"));
+        for step in &config.steps {
+            assert!(dot.contains(&format!("\"{}\" [label=\"{}\"", step.id, step.name)));
+        }
+        assert!(dot.contains("\"extract_requirements\" -> \"extract_values\";"));
+        assert!(dot.contains("\"extract_requirements\" -> \"extract_specifications\";"));
+        assert_eq!(dot.matches(" -> ").count(), 2);
+        assert!(dot.contains("\"extract_values\" [label=\"Extract Values\", fillcolor=orange]"));
+        assert!(dot.contains("\"extract_specifications\" [label=\"Extract Specifications\", fillcolor=lightblue]"));
+        assert!(dot.contains("rank=same; \"extract_values\"; \"extract_specifications\";"));
+    }
+
+    #[test]
+    fn test_step_temperature_overrides_global() {
+        let mut config = utils::create_requirements_pipeline();
+        config.global_config.temperature = 0.5;
+        config.steps.truncate(2);
+        config.steps[0].temperature = Some(0.0);
+        config.steps[1].temperature = Some(0.9);
+        let mut inherits = config.steps[1].clone();
+        inherits.temperature = None;
+
+        let executor = PipelineExecutor::new(config);
+        assert_eq!(executor.step_config(&executor.config.steps[0]).temperature, 0.0);
+        assert_eq!(executor.step_config(&executor.config.steps[1]).temperature, 0.9);
+        assert_eq!(executor.step_config(&inherits).temperature, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_step_temperature_sent_to_model() {
+        use crate::providers::test_server::{request_json, spawn_mock_server};
+
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "[]"})).await;
+        let mut config = utils::create_requirements_pipeline();
+        config.global_config.temperature = 0.5;
+        config.global_config.debug = false;
+        config.global_config.language_model_params.insert(
+            "provider_config".to_string(),
+            serde_json::to_value(crate::providers::ProviderConfig::ollama("test-model", Some(url))).unwrap(),
+        );
+        config.steps[0].temperature = Some(0.0);
+        let executor = PipelineExecutor::new(config);
+
+        let result = executor
+            .execute_step("extract_requirements", "The system shall respond within 2 seconds.", &HashMap::new())
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let body = request_json(&requests.lock().unwrap()[0]);
+        assert_eq!(body["options"]["temperature"], 0.0);
+    }
+
+    #[test]
+    fn test_validate_reports_cycle_and_misaligned_example() {
+        let mut config = utils::create_requirements_pipeline();
+        assert!(PipelineExecutor::new(config.clone()).validate().is_valid());
+
+        let first_id = config.steps[0].id.clone();
+        let last_id = config.steps.last().unwrap().id.clone();
+        config.steps[0].depends_on.push(last_id);
+        config.steps[1].depends_on.push("missing_step".to_string());
+        config.steps[0].examples = vec![ExampleData::new(
+            "The system shall respond within 2 seconds.".to_string(),
+            vec![Extraction::new("requirement".to_string(), "respond within 5 seconds".to_string())],
+        )];
+
+        let report = PipelineExecutor::new(config).validate();
+        assert!(!report.is_valid());
+        assert!(report.errors.iter().any(|e| e.contains("Circular dependency")));
+        assert!(report.errors.iter().any(|e| e.contains("unknown step 'missing_step'")));
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with(&format!("Step '{}', example 0:", first_id)));
+    }
 }
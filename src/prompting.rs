@@ -4,8 +4,11 @@ use crate::{
     data::{ExampleData, FormatType},
     exceptions::{LangExtractError, LangExtractResult},
     providers::ProviderType,
+    templates::PromptStrings,
+    schema::{infer_attribute_keys, infer_class_types, infer_table_columns, ValueType, ATTRIBUTES_SUFFIX, TABLE_KEY},
 };
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Error types for template operations
 #[derive(Debug, thiserror::Error)]
@@ -39,6 +42,14 @@ pub struct PromptContext {
     pub schema_hint: Option<String>,
     /// Custom variables for template substitution
     pub variables: HashMap<String, String>,
+    /// Guidance for individual extraction classes, keyed by class name
+    pub class_descriptions: HashMap<String, String>,
+    /// Expected value type per extraction class
+    pub expected_types: BTreeMap<String, ValueType>,
+    /// Closed set of extraction classes the model may use
+    pub allowed_classes: Option<Vec<String>>,
+    /// Columns of the `table` row objects the model should return (empty outside table mode)
+    pub table_columns: Vec<String>,
 }
 
 impl PromptContext {
@@ -51,6 +62,10 @@ impl PromptContext {
             additional_context: None,
             schema_hint: None,
             variables: HashMap::new(),
+            class_descriptions: HashMap::new(),
+            expected_types: BTreeMap::new(),
+            allowed_classes: None,
+            table_columns: Vec::new(),
         }
     }
 
@@ -77,6 +92,24 @@ impl PromptContext {
         self.schema_hint = Some(hint);
         self
     }
+
+    /// Add per-class guidance (e.g. "person" -> "full legal name, not nicknames")
+    pub fn with_class_descriptions(mut self, class_descriptions: HashMap<String, String>) -> Self {
+        self.class_descriptions = class_descriptions;
+        self
+    }
+
+    /// Add the expected value type of each extraction class
+    pub fn with_expected_types(mut self, expected_types: BTreeMap<String, ValueType>) -> Self {
+        self.expected_types = expected_types;
+        self
+    }
+
+    /// Restrict the model to a closed set of extraction classes
+    pub fn with_allowed_classes(mut self, allowed_classes: Option<Vec<String>>) -> Self {
+        self.allowed_classes = allowed_classes;
+        self
+    }
 }
 
 /// Trait for rendering prompt templates
@@ -108,12 +141,14 @@ pub struct PromptTemplate {
     pub max_examples: Option<usize>,
     /// Whether to include reasoning instructions
     pub include_reasoning: bool,
+    /// Language of the instruction text the library generates
+    pub language: PromptLanguage,
 }
 
 impl PromptTemplate {
     /// Create a new prompt template
     pub fn new(format_type: FormatType, provider_type: ProviderType) -> Self {
-        let base_template = Self::default_base_template(format_type, provider_type);
+        let base_template = Self::default_base_template(format_type, provider_type, PromptLanguage::default());
         let example_template = Self::default_example_template(format_type);
         
         Self {
@@ -124,6 +159,7 @@ impl PromptTemplate {
             provider_type,
             max_examples: Some(5),
             include_reasoning: false,
+            language: PromptLanguage::default(),
         }
     }
 
@@ -132,16 +168,17 @@ impl PromptTemplate {
         let mut template = Self::new(format_type, provider_type);
         
         match provider_type {
-            ProviderType::OpenAI => {
+            ProviderType::OpenAI | ProviderType::Cohere => {
                 template.system_message = Some(
                     "You are an expert information extraction assistant. Extract structured information exactly as shown in the examples.".to_string()
                 );
-                template.include_reasoning = false; // OpenAI is good with direct instructions
+                template.include_reasoning = false; // Hosted chat models are good with direct instructions
             }
             ProviderType::Ollama => {
                 template.include_reasoning = true; // Local models benefit from reasoning steps
                 template.max_examples = Some(3); // Keep prompts shorter for local models
             }
+            ProviderType::Mock => {}
             ProviderType::Custom => {
                 // Conservative defaults for unknown providers
                 template.max_examples = Some(3);
@@ -170,6 +207,15 @@ impl PromptTemplate {
         self
     }
 
+    /// Generate the fixed instruction text in `language`
+    ///
+    /// Replaces the base template with the default one for that language.
+    pub fn with_language(mut self, language: PromptLanguage) -> Self {
+        self.language = language;
+        self.base_template = Self::default_base_template(self.format_type, self.provider_type, language);
+        self
+    }
+
     /// Set custom base template
     pub fn with_base_template(mut self, template: String) -> Self {
         self.base_template = template;
@@ -177,12 +223,12 @@ impl PromptTemplate {
     }
 
     /// Default base template for different formats and providers
-    fn default_base_template(format_type: FormatType, provider_type: ProviderType) -> String {
+    fn default_base_template(format_type: FormatType, provider_type: ProviderType, language: PromptLanguage) -> String {
         use crate::templates::TemplateBuilder;
         
         let include_reasoning = matches!(provider_type, ProviderType::Ollama | ProviderType::Custom);
         
-        TemplateBuilder::new(format_type)
+        TemplateBuilder::for_language(format_type, language)
             .with_reasoning(include_reasoning)
             .build()
     }
@@ -199,15 +245,17 @@ impl PromptTemplate {
         }
     }
 
-    /// Format examples according to the template
-    fn format_examples(&self, examples: &[ExampleData]) -> LangExtractResult<String> {
+    /// Format examples according to the template, as table rows when `table_mode` is set
+    fn format_examples(&self, examples: &[ExampleData], table_mode: bool) -> LangExtractResult<String> {
         use crate::templates::ExampleFormatter;
         
         let formatter = if let Some(max) = self.max_examples {
             ExampleFormatter::new(self.format_type).with_max_examples(max)
         } else {
             ExampleFormatter::new(self.format_type)
-        };
+        }
+        .with_language(self.language)
+        .with_table_mode(table_mode);
         
         formatter.format_examples(examples)
     }
@@ -220,6 +268,7 @@ impl PromptTemplate {
         use crate::templates::TemplateEngine;
         use std::collections::HashMap;
         
+        let strings = self.language.strings();
         let mut variables = HashMap::new();
         
         // Built-in variables
@@ -229,19 +278,84 @@ impl PromptTemplate {
         // Additional context
         if let Some(context_text) = &context.additional_context {
             variables.insert("additional_context".to_string(), 
-                format!("\n\nAdditional Context: {}\n", context_text));
+                format!("\n\n{}: {}\n", strings.additional_context, context_text));
         } else {
             variables.insert("additional_context".to_string(), String::new());
         }
 
+        // Per-class guidance, sorted so prompts are reproducible
+        if context.class_descriptions.is_empty() {
+            variables.insert("class_descriptions".to_string(), String::new());
+        } else {
+            let mut classes: Vec<_> = context.class_descriptions.iter().collect();
+            classes.sort();
+            let mut section = format!("\n\n{}:", strings.class_guidance);
+            for (class, description) in classes {
+                section.push_str(&format!("\n- {}: {}", class, description));
+            }
+            variables.insert("class_descriptions".to_string(), section);
+        }
+
+        // Closed set of extraction classes
+        match context.allowed_classes.as_deref() {
+            Some(classes) if !classes.is_empty() => {
+                variables.insert(
+                    "allowed_classes".to_string(),
+                    format!("\n\n{}: {}", strings.allowed_classes, classes.join(", ")),
+                );
+            }
+            _ => {
+                variables.insert("allowed_classes".to_string(), String::new());
+            }
+        }
+
+        // Expected value types per class
+        if context.expected_types.is_empty() {
+            variables.insert("type_hints".to_string(), String::new());
+        } else {
+            let mut section = format!("\n\n{}:", strings.expected_types);
+            for (class, value_type) in &context.expected_types {
+                section.push_str(&format!("\n- {}: {}", class, value_type));
+            }
+            variables.insert("type_hints".to_string(), section);
+        }
+
+        // Attribute keys shown by the examples, in the "<class>_attributes" shape they use
+        let attribute_keys = infer_attribute_keys(&context.examples);
+        if attribute_keys.is_empty() {
+            variables.insert("attribute_hints".to_string(), String::new());
+        } else {
+            let mut section = format!("\n\n{}", strings.attributes.replace("{suffix}", ATTRIBUTES_SUFFIX));
+            for (class, keys) in &attribute_keys {
+                section.push_str(&format!("\n- {}: {}", class, keys.iter().cloned().collect::<Vec<_>>().join(", ")));
+            }
+            variables.insert("attribute_hints".to_string(), section);
+        }
+
+        // Row shape for table mode, with the columns the examples use
+        if context.table_columns.is_empty() {
+            variables.insert("table_hint".to_string(), String::new());
+        } else {
+            variables.insert(
+                "table_hint".to_string(),
+                format!(
+                    "\n\n{}",
+                    strings
+                        .table_rows
+                        .replace("{key}", TABLE_KEY)
+                        .replace("{columns}", &context.table_columns.join(", "))
+                ),
+            );
+        }
+
         // Examples
-        let examples_text = self.format_examples(&context.examples)?;
+        let examples_text = self.format_examples(&context.examples, !context.table_columns.is_empty())?;
         variables.insert("examples".to_string(), examples_text);
 
         // Reasoning section
         if self.include_reasoning {
             variables.insert("reasoning".to_string(), 
-                "\n\nPlease think through this step by step before providing your answer.".to_string());
+                format!("\n\n{}", strings.step_by_step));
         } else {
             variables.insert("reasoning".to_string(), String::new());
         }
@@ -249,7 +363,7 @@ impl PromptTemplate {
         // Schema hint
         if let Some(hint) = &context.schema_hint {
             variables.insert("schema_hint".to_string(), 
-                format!("\n\nSchema guidance: {}\n", hint));
+                format!("\n\n{}: {}\n", strings.schema_guidance, hint));
         } else {
             variables.insert("schema_hint".to_string(), String::new());
         }
@@ -314,6 +428,111 @@ impl TemplateRenderer for PromptTemplate {
     }
 }
 
+/// Language of the instruction text the library adds around the description and examples
+///
+/// The prompt description, class descriptions and examples are used as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptLanguage {
+    #[default]
+    #[serde(alias = "en")]
+    English,
+    #[serde(alias = "de")]
+    German,
+    #[serde(alias = "es")]
+    Spanish,
+    #[serde(alias = "fr")]
+    French,
+}
+
+impl PromptLanguage {
+    /// The fixed prompt text in this language
+    pub fn strings(self) -> &'static PromptStrings {
+        match self {
+            Self::English => &PromptStrings::ENGLISH,
+            Self::German => &PromptStrings::GERMAN,
+            Self::Spanish => &PromptStrings::SPANISH,
+            Self::French => &PromptStrings::FRENCH,
+        }
+    }
+}
+
+/// Strategy for choosing which few-shot examples are rendered into a prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExampleSelection {
+    /// Include every example in the order given (default)
+    #[default]
+    All,
+    /// Keep the first `max_examples` examples
+    FirstN,
+    /// Pick `max_examples` examples at random, seeded by the input text so prompts stay reproducible
+    RandomN,
+    /// Pick the `max_examples` examples whose text shares the most words with the input
+    MostSimilarN,
+}
+
+impl ExampleSelection {
+    /// Choose up to `max_examples` examples for `input_text`
+    ///
+    /// `max_examples` of `None` keeps every example, though `MostSimilarN`
+    /// still reorders them with the closest first. `seed` only affects `RandomN`.
+    pub fn select(
+        self,
+        examples: &[ExampleData],
+        input_text: &str,
+        max_examples: Option<usize>,
+        seed: Option<u64>,
+    ) -> Vec<ExampleData> {
+        let limit = max_examples.unwrap_or(examples.len());
+        match self {
+            ExampleSelection::All => examples.to_vec(),
+            ExampleSelection::FirstN => examples.iter().take(limit).cloned().collect(),
+            ExampleSelection::RandomN => {
+                // xorshift seeded with an FNV-1a hash of the input text, mixed with the seed
+                let offset = 0xcbf2_9ce4_8422_2325_u64 ^ seed.unwrap_or(0);
+                let mut state = input_text.bytes().fold(offset, |hash, byte| {
+                    (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+                }) | 1;
+                let mut indices: Vec<usize> = (0..examples.len()).collect();
+                for i in (1..indices.len()).rev() {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    indices.swap(i, (state % (i as u64 + 1)) as usize);
+                }
+                indices.into_iter().take(limit).map(|i| examples[i].clone()).collect()
+            }
+            ExampleSelection::MostSimilarN => {
+                let input_words = word_set(input_text);
+                let mut scored: Vec<(f64, &ExampleData)> = examples
+                    .iter()
+                    .map(|example| (jaccard(&input_words, &word_set(&example.text)), example))
+                    .collect();
+                // Stable sort keeps the original order among equally similar examples
+                scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+                scored.into_iter().take(limit).map(|(_, example)| example.clone()).collect()
+            }
+        }
+    }
+}
+
+/// Lowercased alphanumeric words of `text`
+fn word_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
 /// Backward compatibility - simplified prompt template
 #[derive(Debug, Clone)]
 pub struct PromptTemplateStructured {
@@ -321,6 +540,24 @@ pub struct PromptTemplateStructured {
     pub description: Option<String>,
     /// Example data for guidance
     pub examples: Vec<ExampleData>,
+    /// Guidance for individual extraction classes, keyed by class name
+    pub class_descriptions: HashMap<String, String>,
+    /// Whether to tell the model the value type inferred for each class from the examples
+    pub include_type_hints: bool,
+    /// How examples are chosen for each rendered prompt
+    pub example_selection: ExampleSelection,
+    /// Maximum number of examples rendered per prompt (`None` keeps all)
+    pub max_examples: Option<usize>,
+    /// Seed mixed into `RandomN` example selection
+    pub seed: Option<u64>,
+    /// Values for `{key}` placeholders in the description
+    pub variables: HashMap<String, String>,
+    /// Fail on placeholders without a value instead of leaving them in the prompt
+    pub strict_variables: bool,
+    /// Closed set of extraction classes listed in the prompt
+    pub allowed_classes: Option<Vec<String>>,
+    /// Whether to describe the `table` row shape inferred from the examples
+    pub table_mode: bool,
     /// Advanced template for rendering
     template: PromptTemplate,
 }
@@ -331,6 +568,15 @@ impl PromptTemplateStructured {
         Self {
             description: description.map(|s| s.to_string()),
             examples: Vec::new(),
+            class_descriptions: HashMap::new(),
+            include_type_hints: false,
+            example_selection: ExampleSelection::All,
+            max_examples: None,
+            seed: None,
+            variables: HashMap::new(),
+            strict_variables: false,
+            allowed_classes: None,
+            table_mode: false,
             template: PromptTemplate::new(FormatType::Json, ProviderType::Ollama),
         }
     }
@@ -344,18 +590,100 @@ impl PromptTemplateStructured {
         Self {
             description: description.map(|s| s.to_string()),
             examples: Vec::new(),
+            class_descriptions: HashMap::new(),
+            include_type_hints: false,
+            example_selection: ExampleSelection::All,
+            max_examples: None,
+            seed: None,
+            variables: HashMap::new(),
+            strict_variables: false,
+            allowed_classes: None,
+            table_mode: false,
             template: PromptTemplate::for_provider(provider_type, format_type),
         }
     }
 
+    /// Set per-class guidance rendered alongside the description
+    pub fn with_class_descriptions(mut self, class_descriptions: HashMap<String, String>) -> Self {
+        self.class_descriptions = class_descriptions;
+        self
+    }
+
+    /// Enable or disable the "Expected types" section inferred from the examples
+    pub fn with_type_hints(mut self, enable: bool) -> Self {
+        self.include_type_hints = enable;
+        self
+    }
+
+    /// Choose how examples are selected for each prompt and how many are kept
+    pub fn with_example_selection(mut self, selection: ExampleSelection, max_examples: Option<usize>) -> Self {
+        self.example_selection = selection;
+        self.max_examples = max_examples;
+        self
+    }
+
+    /// Fill `{key}` placeholders in the description from `variables`
+    ///
+    /// Placeholders without a value are left as written, or rejected when `strict` is set.
+    pub fn with_variables(mut self, variables: HashMap<String, String>, strict: bool) -> Self {
+        self.variables = variables;
+        self.strict_variables = strict;
+        self
+    }
+
+    /// Tell the model to use only the given extraction classes
+    pub fn with_allowed_classes(mut self, allowed_classes: Option<Vec<String>>) -> Self {
+        self.allowed_classes = allowed_classes;
+        self
+    }
+
+    /// Ask for table rows in the `table` shape, with columns taken from the examples
+    pub fn with_table_mode(mut self, table_mode: bool) -> Self {
+        self.table_mode = table_mode;
+        self
+    }
+
+    /// Generate the fixed instruction text in `language`
+    pub fn with_language(mut self, language: PromptLanguage) -> Self {
+        self.template = self.template.with_language(language);
+        self
+    }
+
+    /// The description with its `{key}` placeholders substituted
+    pub fn render_description(&self) -> LangExtractResult<String> {
+        let description = self.description.as_deref().unwrap_or_default();
+        let placeholder = regex::Regex::new(r"\{([A-Za-z_][A-Za-z0-9_]*)\}").expect("valid placeholder pattern");
+
+        if self.strict_variables {
+            if let Some(missing) = placeholder
+                .captures_iter(description)
+                .map(|caps| caps[1].to_string())
+                .find(|name| !self.variables.contains_key(name))
+            {
+                return Err(TemplateError::MissingVariable { variable: missing }.into());
+            }
+        }
+
+        Ok(placeholder
+            .replace_all(description, |caps: &regex::Captures| {
+                self.variables.get(&caps[1]).cloned().unwrap_or_else(|| caps[0].to_string())
+            })
+            .into_owned())
+    }
+
     /// Render the prompt for given text
     pub fn render(&self, input_text: &str, additional_context: Option<&str>) -> LangExtractResult<String> {
-        let mut context = PromptContext::new(
-            self.description.clone().unwrap_or_default(),
-            input_text.to_string(),
-        );
+        let mut context = PromptContext::new(self.render_description()?, input_text.to_string());
         
-        context.examples = self.examples.clone();
+        context.examples = self.example_selection.select(&self.examples, input_text, self.max_examples, self.seed);
+        context.class_descriptions = self.class_descriptions.clone();
+        context.allowed_classes = self.allowed_classes.clone();
+        if self.include_type_hints {
+            context.expected_types = infer_class_types(&self.examples);
+        }
+        if self.table_mode {
+            context.table_columns = infer_table_columns(&self.examples);
+        }
         
         if let Some(ctx) = additional_context {
             context.additional_context = Some(ctx.to_string());
@@ -452,6 +780,53 @@ mod tests {
         assert!(rendered.contains("JSON format"));
     }
 
+    #[test]
+    fn test_class_descriptions_rendered() {
+        let mut class_descriptions = HashMap::new();
+        class_descriptions.insert("person".to_string(), "full legal name, not nicknames".to_string());
+        class_descriptions.insert("company".to_string(), "registered company name".to_string());
+
+        let template = PromptTemplateStructured::new(Some("Extract people and companies"))
+            .with_class_descriptions(class_descriptions);
+        let rendered = template.render("Bob works at Acme", None).unwrap();
+
+        assert!(rendered.contains("Extract people and companies"));
+        assert!(rendered.contains("Extraction class guidance:"));
+        assert!(rendered.contains("- person: full legal name, not nicknames"));
+        assert!(rendered.contains("- company: registered company name"));
+        assert!(rendered.find("- company").unwrap() < rendered.find("- person").unwrap());
+
+        let plain = PromptTemplateStructured::new(Some("Extract people")).render("Bob", None).unwrap();
+        assert!(!plain.contains("Extraction class guidance"));
+    }
+
+    #[test]
+    fn test_type_hints_rendered_from_examples() {
+        let mut template = PromptTemplateStructured::new(Some("Extract person details"))
+            .with_type_hints(true);
+        template.examples.push(ExampleData::new(
+            "John, 30, active since 2021-03-01, rating 4.5".to_string(),
+            vec![
+                Extraction::new("name".to_string(), "John".to_string()),
+                Extraction::new("age".to_string(), "30".to_string()),
+                Extraction::new("active".to_string(), "true".to_string()),
+                Extraction::new("since".to_string(), "2021-03-01".to_string()),
+                Extraction::new("rating".to_string(), "4.5".to_string()),
+            ],
+        ));
+
+        let rendered = template.render("Jane, 41", None).unwrap();
+        assert!(rendered.contains("Expected types:"));
+        assert!(rendered.contains("- age: integer"));
+        assert!(rendered.contains("- active: boolean"));
+        assert!(rendered.contains("- since: date"));
+        assert!(rendered.contains("- rating: float"));
+        assert!(rendered.contains("- name: string"));
+
+        let rendered = template.with_type_hints(false).render("Jane, 41", None).unwrap();
+        assert!(!rendered.contains("Expected types:"));
+    }
+
     #[test]
     fn test_provider_specific_templates() {
         let openai_template = PromptTemplate::for_provider(ProviderType::OpenAI, FormatType::Json);
@@ -476,4 +851,155 @@ mod tests {
         assert!(rendered.contains("Extract info"));
         assert!(rendered.contains("Input text"));
     }
+
+    fn example(text: &str) -> ExampleData {
+        ExampleData::new(
+            text.to_string(),
+            vec![Extraction::new("entity".to_string(), text.to_string())],
+        )
+    }
+
+    #[test]
+    fn test_example_selection_first_n_truncates() {
+        let examples = vec![example("alpha"), example("beta"), example("gamma")];
+        let selected = ExampleSelection::FirstN.select(&examples, "anything", Some(2), None);
+        let texts: Vec<&str> = selected.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["alpha", "beta"]);
+
+        assert_eq!(ExampleSelection::All.select(&examples, "anything", Some(1), None).len(), 3);
+
+        let random = ExampleSelection::RandomN.select(&examples, "anything", Some(2), None);
+        assert_eq!(random.len(), 2);
+        assert_eq!(
+            random.iter().map(|e| &e.text).collect::<Vec<_>>(),
+            ExampleSelection::RandomN
+                .select(&examples, "anything", Some(2), None)
+                .iter()
+                .map(|e| &e.text)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_example_selection_random_n_is_seedable() {
+        let examples: Vec<ExampleData> = (0..10).map(|i| example(&format!("example {}", i))).collect();
+        let texts = |seed| {
+            ExampleSelection::RandomN
+                .select(&examples, "anything", Some(5), seed)
+                .into_iter()
+                .map(|e| e.text)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(texts(Some(7)), texts(Some(7)));
+        assert!((1..20).any(|seed| texts(Some(seed)) != texts(Some(0))));
+    }
+
+    #[test]
+    fn test_example_selection_most_similar_n() {
+        let examples = vec![
+            example("The invoice total is $500 due March 1"),
+            example("Patient was prescribed 20mg of lisinopril daily"),
+            example("Alice joined Acme Corp as an engineer"),
+        ];
+
+        let selected = ExampleSelection::MostSimilarN.select(
+            &examples,
+            "The patient was prescribed ibuprofen twice daily",
+            Some(1),
+            None,
+        );
+        assert_eq!(selected.len(), 1);
+        assert!(selected[0].text.contains("lisinopril"));
+
+        let mut template = PromptTemplateStructured::new(Some("Extract entities"))
+            .with_example_selection(ExampleSelection::MostSimilarN, Some(1));
+        template.examples = examples;
+        let rendered = template.render("Bob joined Initech as an engineer", None).unwrap();
+        assert!(rendered.contains("Acme Corp"));
+        assert!(!rendered.contains("lisinopril"));
+    }
+
+    #[test]
+    fn test_attribute_hints_rendered_from_examples() {
+        let mut person = Extraction::new("person".to_string(), "John".to_string());
+        person.set_attribute("role".to_string(), serde_json::json!("CEO"));
+        person.set_attribute("age".to_string(), serde_json::json!("52"));
+        let mut template = PromptTemplateStructured::new(Some("Extract people"));
+        template.examples.push(ExampleData::new(
+            "John, 52, is the CEO of Acme".to_string(),
+            vec![person, Extraction::new("company".to_string(), "Acme".to_string())],
+        ));
+
+        let rendered = template.render("Jane runs Initech", None).unwrap();
+        assert!(rendered.contains("\"<class>_attributes\" object"));
+        assert!(rendered.contains("- person: age, role"));
+        assert!(!rendered.contains("- company:"));
+
+        let plain = PromptTemplateStructured::new(Some("Extract people")).render("Bob", None).unwrap();
+        assert!(!plain.contains("Attributes ("));
+    }
+
+    #[test]
+    fn test_table_hint_lists_example_columns() {
+        let mut template = PromptTemplateStructured::new(Some("Extract line items")).with_table_mode(true);
+        template.examples.push(ExampleData::new(
+            "2 x Widget at $5".to_string(),
+            vec![
+                Extraction::new("item".to_string(), "Widget".to_string()),
+                Extraction::new("qty".to_string(), "2".to_string()),
+                Extraction::new("price".to_string(), "$5".to_string()),
+            ],
+        ));
+
+        let rendered = template.render("1 x Gadget at $9", None).unwrap();
+        assert!(rendered.contains(r#"return {"table": [...]} with one object per row, keyed by these columns: item, qty, price"#));
+        assert!(rendered.contains("\"table\": [\n"));
+
+        template.table_mode = false;
+        assert!(!template.render("1 x Gadget at $9", None).unwrap().contains("Table rows:"));
+    }
+
+    #[test]
+    fn test_prompt_language_localizes_scaffolding() {
+        let mut template = PromptTemplateStructured::new(Some("Personen und Orte extrahieren"))
+            .with_allowed_classes(Some(vec!["person".to_string()]))
+            .with_language(PromptLanguage::German);
+        template.examples.push(ExampleData::new(
+            "Anna wohnt in Berlin".to_string(),
+            vec![Extraction::new("person".to_string(), "Anna".to_string())],
+        ));
+
+        let rendered = template.render("Max trifft Lena", Some("Ein Brief")).unwrap();
+        assert!(rendered.starts_with("Personen und Orte extrahieren"));
+        assert!(rendered.contains("Verwende nur diese Extraktionsklassen: person"));
+        assert!(rendered.contains("Antworte mit gültigem JSON"));
+        assert!(rendered.contains("Zusätzlicher Kontext: Ein Brief"));
+        assert!(rendered.contains("Beispiel 1:\nEingabe: Anna wohnt in Berlin"));
+        assert!(rendered.contains("Extrahiere nun die Informationen aus diesem Text:\n\nEingabe: Max trifft Lena"));
+        assert!(!rendered.contains("Now extract information"));
+
+        let language: PromptLanguage = serde_json::from_str("\"de\"").unwrap();
+        assert_eq!(language, PromptLanguage::German);
+    }
+
+    #[test]
+    fn test_description_placeholders() {
+        let variables = HashMap::from([
+            ("entity_types".to_string(), "people and places".to_string()),
+            ("doc_type".to_string(), "news article".to_string()),
+        ]);
+        let template = PromptTemplateStructured::new(Some("Extract {entity_types} from the following {doc_type}."))
+            .with_variables(variables.clone(), false);
+        assert_eq!(template.render_description().unwrap(), "Extract people and places from the following news article.");
+        assert!(template.render("Alice visited Rome.", None).unwrap().contains("Extract people and places"));
+
+        let template = PromptTemplateStructured::new(Some("Extract {entity_types} dated {year}."));
+        let lenient = template.clone().with_variables(variables.clone(), false);
+        assert_eq!(lenient.render_description().unwrap(), "Extract people and places dated {year}.");
+
+        let strict = template.with_variables(variables, true);
+        let err = strict.render("Alice visited Rome.", None).unwrap_err();
+        assert!(err.to_string().contains("year"));
+    }
 }
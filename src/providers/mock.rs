@@ -0,0 +1,189 @@
+//! Offline mock language model for deterministic tests.
+
+use super::config::ProviderConfig;
+use crate::{
+    exceptions::{LangExtractError, LangExtractResult},
+    inference::{BaseLanguageModel, ScoredOutput},
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Response function used by [`MockModel::from_fn`]
+type ResponseFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Language model returning canned responses without any network access
+///
+/// Responses are chosen by the first configured substring contained in the
+/// prompt, then the default response. A model built with [`MockModel::from_fn`]
+/// computes every response from the prompt instead.
+///
+/// ```
+/// use langextract_rust::providers::MockModel;
+///
+/// let model = MockModel::new()
+///     .with_response("John", r#"[{"person": "John"}]"#)
+///     .with_default_response("[]");
+/// ```
+pub struct MockModel {
+    model_id: String,
+    responses: Vec<(String, String)>,
+    default_response: Option<String>,
+    response_fn: Option<ResponseFn>,
+    fence_output: Option<bool>,
+    prompts: Mutex<Vec<String>>,
+}
+
+impl MockModel {
+    /// Create a mock with no responses configured
+    pub fn new() -> Self {
+        Self {
+            model_id: "mock".to_string(),
+            responses: Vec::new(),
+            default_response: None,
+            response_fn: None,
+            fence_output: None,
+            prompts: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Create a mock that computes each response from the prompt
+    pub fn from_fn(response_fn: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Self {
+            response_fn: Some(Arc::new(response_fn)),
+            ..Self::new()
+        }
+    }
+
+    /// Create a mock from a [`ProviderConfig::mock`] configuration
+    ///
+    /// Reads `mock_responses` (a list of `{"contains", "response"}` objects)
+    /// and `default_response` from the extra parameters.
+    pub fn from_config(config: &ProviderConfig) -> LangExtractResult<Self> {
+        let mut model = Self::new();
+        model.model_id = config.model.clone();
+
+        if let Some(responses) = config.extra_params.get("mock_responses") {
+            let responses: Vec<HashMap<String, String>> = serde_json::from_value(responses.clone())
+                .map_err(|e| LangExtractError::configuration(format!("Invalid mock_responses: {}", e)))?;
+            for entry in responses {
+                match (entry.get("contains"), entry.get("response")) {
+                    (Some(contains), Some(response)) => model = model.with_response(contains, response),
+                    _ => {
+                        return Err(LangExtractError::configuration(
+                            "Each mock response needs 'contains' and 'response' fields",
+                        ))
+                    }
+                }
+            }
+        }
+        if let Some(default_response) = config.extra_params.get("default_response").and_then(|v| v.as_str()) {
+            model = model.with_default_response(default_response);
+        }
+
+        Ok(model)
+    }
+
+    /// Return `response` for prompts containing `contains`
+    pub fn with_response(mut self, contains: &str, response: &str) -> Self {
+        self.responses.push((contains.to_string(), response.to_string()));
+        self
+    }
+
+    /// Return `response` for prompts no other response matches
+    pub fn with_default_response(mut self, response: &str) -> Self {
+        self.default_response = Some(response.to_string());
+        self
+    }
+
+    /// Set the model id reported by the mock
+    pub fn with_model_id(mut self, model_id: &str) -> Self {
+        self.model_id = model_id.to_string();
+        self
+    }
+
+    /// Prompts received so far, in call order
+    pub fn prompts(&self) -> Vec<String> {
+        self.prompts.lock().map(|prompts| prompts.clone()).unwrap_or_default()
+    }
+
+    fn respond(&self, prompt: &str) -> LangExtractResult<String> {
+        if let Some(response_fn) = &self.response_fn {
+            return Ok(response_fn(prompt));
+        }
+
+        self.responses
+            .iter()
+            .find(|(contains, _)| prompt.contains(contains.as_str()))
+            .map(|(_, response)| response.clone())
+            .or_else(|| self.default_response.clone())
+            .ok_or_else(|| LangExtractError::inference_simple("Mock model has no response for this prompt"))
+    }
+}
+
+impl Default for MockModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl BaseLanguageModel for MockModel {
+    fn set_fence_output(&mut self, fence_output: Option<bool>) {
+        self.fence_output = fence_output;
+    }
+
+    fn requires_fence_output(&self) -> bool {
+        self.fence_output.unwrap_or(false)
+    }
+
+    async fn infer(
+        &self,
+        batch_prompts: &[String],
+        _kwargs: &HashMap<String, serde_json::Value>,
+    ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
+        if let Ok(mut prompts) = self.prompts.lock() {
+            prompts.extend(batch_prompts.iter().cloned());
+        }
+
+        batch_prompts
+            .iter()
+            .map(|prompt| Ok(vec![ScoredOutput::from_text(self.respond(prompt)?)]))
+            .collect()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn provider_name(&self) -> &str {
+        "mock"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_model_picks_matching_response() {
+        let model = MockModel::new()
+            .with_response("John", "john")
+            .with_default_response("default");
+        let kwargs = HashMap::new();
+
+        let outputs = model
+            .infer(&["Who is John?".to_string(), "Who is Jane?".to_string()], &kwargs)
+            .await
+            .unwrap();
+        assert_eq!(outputs[0][0].output.as_deref(), Some("john"));
+        assert_eq!(outputs[1][0].output.as_deref(), Some("default"));
+        assert_eq!(model.prompts().len(), 2);
+
+        let strict = MockModel::new().with_response("John", "john");
+        assert!(strict.infer_single("Who is Jane?", &kwargs).await.is_err());
+
+        let echo = MockModel::from_fn(|prompt| prompt.to_uppercase());
+        assert_eq!(echo.infer_single("abc", &kwargs).await.unwrap()[0].output.as_deref(), Some("ABC"));
+    }
+}
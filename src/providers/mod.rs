@@ -1,9 +1,14 @@
 //! Language model provider implementations.
 
 pub mod config;
+pub mod mock;
 pub mod universal;
 
-pub use config::{ProviderConfig, ProviderType};
+#[cfg(test)]
+pub(crate) mod test_server;
+
+pub use config::{ProviderCapabilities, ProviderConfig, ProviderType};
+pub use mock::MockModel;
 pub use universal::UniversalProvider;
 
 use crate::exceptions::LangExtractResult;
@@ -0,0 +1,67 @@
+//! Minimal HTTP server for exercising providers in tests.
+
+use std::sync::{Arc, Mutex};
+
+/// Raw HTTP requests received by a mock server, in arrival order
+pub(crate) type RecordedRequests = Arc<Mutex<Vec<String>>>;
+
+/// Minimal HTTP server answering every request with `body`, recording raw requests
+pub(crate) async fn spawn_mock_server(body: serde_json::Value) -> (String, RecordedRequests) {
+    spawn_mock_server_with_status("200 OK", body).await
+}
+
+/// Like [`spawn_mock_server`], answering with the given HTTP status line
+pub(crate) async fn spawn_mock_server_with_status(
+    status: &'static str,
+    body: serde_json::Value,
+) -> (String, RecordedRequests) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let body = body.to_string();
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Read headers, then the declared body length
+            loop {
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                if n == 0 {
+                    break;
+                }
+                raw.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&raw).to_string();
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap_or(0)))
+                        .unwrap_or(0);
+                    if raw.len() >= header_end + 4 + content_length {
+                        break;
+                    }
+                }
+            }
+            recorded.lock().unwrap().push(String::from_utf8_lossy(&raw).to_string());
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+
+    (url, requests)
+}
+
+/// JSON body of a recorded raw HTTP request
+pub(crate) fn request_json(raw: &str) -> serde_json::Value {
+    let body = raw.split_once("\r\n\r\n").map(|(_, body)| body).unwrap_or_default();
+    serde_json::from_str(body).unwrap()
+}
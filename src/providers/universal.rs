@@ -1,10 +1,11 @@
 //! Universal provider implementation.
 
-use super::config::{ProviderConfig, ProviderType};
+use super::config::{ProviderCapabilities, ProviderConfig, ProviderType};
 use crate::{
+    cache::ResponseCache,
     data::FormatType,
-    exceptions::{LangExtractError, LangExtractResult},
-    inference::{BaseLanguageModel, ScoredOutput},
+    exceptions::{InferenceError, LangExtractError, LangExtractResult},
+    inference::{BaseLanguageModel, ImageInput, ScoredOutput},
     logging::{report_progress, ProgressEvent},
     schema,
     schema::BaseSchema,
@@ -19,10 +20,15 @@ pub struct UniversalProvider {
     config: ProviderConfig,
     format_type: FormatType,
     client: reqwest::Client,
-    #[cfg(feature = "openai")]
-    openai_client: Option<async_openai::Client<async_openai::config::OpenAIConfig>>,
     schema: Option<Box<dyn BaseSchema>>,
     fence_output_override: Option<bool>,
+    cache: Option<ResponseCache>,
+    system_prompt: Option<String>,
+    max_output_tokens: Option<u32>,
+    seed: Option<u64>,
+    json_mode: bool,
+    output_schema: Option<serde_json::Value>,
+    grammar: Option<String>,
 }
 
 impl UniversalProvider {
@@ -75,37 +81,293 @@ impl UniversalProvider {
         unreachable!("Should have returned from the loop")
     }
 
+    /// Build the default header map from the configured custom headers
+    ///
+    /// These are sent as client defaults, so headers set per request by the
+    /// provider itself (e.g. `Authorization` from `api_key`) take precedence.
+    fn build_default_headers(headers: &HashMap<String, String>) -> LangExtractResult<reqwest::header::HeaderMap> {
+        use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+        let mut header_map = HeaderMap::new();
+        for (key, value) in headers {
+            let name = HeaderName::from_bytes(key.as_bytes()).map_err(|e| {
+                LangExtractError::configuration(format!("Invalid header name '{}': {}", key, e))
+            })?;
+            let value = HeaderValue::from_str(value).map_err(|e| {
+                LangExtractError::configuration(format!("Invalid value for header '{}': {}", key, e))
+            })?;
+            header_map.insert(name, value);
+        }
+        Ok(header_map)
+    }
+
+    /// Add the configured proxies to the client builder
+    ///
+    /// Without any proxy in the config, reqwest reads the proxy environment
+    /// variables itself. Once one is configured, the other scheme falls back to
+    /// `HTTP_PROXY`/`HTTPS_PROXY` explicitly, since reqwest then stops reading them.
+    fn configure_proxies(
+        mut builder: reqwest::ClientBuilder,
+        config: &ProviderConfig,
+    ) -> LangExtractResult<reqwest::ClientBuilder> {
+        if config.http_proxy.is_none() && config.https_proxy.is_none() {
+            return Ok(builder);
+        }
+
+        let from_env = |name: &str| {
+            std::env::var(name)
+                .or_else(|_| std::env::var(name.to_lowercase()))
+                .ok()
+                .filter(|value| !value.is_empty())
+        };
+        let invalid = |proxy: &str, e: reqwest::Error| {
+            LangExtractError::configuration(format!("Invalid proxy URL '{}': {}", proxy, e))
+        };
+
+        if let Some(proxy) = config.http_proxy.clone().or_else(|| from_env("HTTP_PROXY")) {
+            builder = builder.proxy(reqwest::Proxy::http(&proxy).map_err(|e| invalid(&proxy, e))?);
+        }
+        if let Some(proxy) = config.https_proxy.clone().or_else(|| from_env("HTTPS_PROXY")) {
+            builder = builder.proxy(reqwest::Proxy::https(&proxy).map_err(|e| invalid(&proxy, e))?);
+        }
+        Ok(builder)
+    }
+
     /// Create a new universal provider
     pub fn new(config: ProviderConfig) -> LangExtractResult<Self> {
-        let client = reqwest::Client::new();
-
-        #[cfg(feature = "openai")]
-        let openai_client = if config.provider_type == ProviderType::OpenAI {
-            if let Some(api_key) = &config.api_key {
-                let openai_config = async_openai::config::OpenAIConfig::new()
-                    .with_api_key(api_key)
-                    .with_api_base(&config.base_url);
-                Some(async_openai::Client::with_config(openai_config))
-            } else {
-                return Err(LangExtractError::configuration(
-                    "API key is required for OpenAI provider",
-                ));
-            }
-        } else {
-            None
-        };
+        let builder = reqwest::Client::builder().default_headers(Self::build_default_headers(&config.headers)?);
+        let client = Self::configure_proxies(builder, &config)?
+            .build()
+            .map_err(|e| LangExtractError::configuration(format!("Failed to build HTTP client: {}", e)))?;
 
-        #[cfg(not(feature = "openai"))]
-        let openai_client = None;
+        if config.provider_type == ProviderType::OpenAI && config.api_key.is_none() {
+            return Err(LangExtractError::configuration(
+                "API key is required for OpenAI provider",
+            ));
+        }
+
+        if config.provider_type == ProviderType::Cohere && config.api_key.is_none() {
+            return Err(LangExtractError::configuration(
+                "API key is required for Cohere provider",
+            ));
+        }
 
         Ok(Self {
             config,
             format_type: FormatType::Json,
             client,
-            openai_client,
             schema: None,
             fence_output_override: None,
+            cache: None,
+            system_prompt: None,
+            max_output_tokens: None,
+            seed: None,
+            json_mode: false,
+            output_schema: None,
+            grammar: None,
+        })
+    }
+
+    /// Serve repeated identical requests from an on-disk response cache
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Send a system prompt ahead of every extraction prompt
+    ///
+    /// Chat providers receive it as a `system` message; others get it prepended to the prompt.
+    pub fn with_system_prompt(mut self, system_prompt: String) -> Self {
+        self.system_prompt = Some(system_prompt);
+        self
+    }
+
+    /// Cap the completion length of every request
+    ///
+    /// Sent as `max_tokens` to OpenAI-compatible APIs and `num_predict` to Ollama,
+    /// taking precedence over a `max_tokens` inference kwarg.
+    pub fn with_max_output_tokens(mut self, max_output_tokens: u32) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+
+    /// Request deterministic sampling with a fixed seed
+    ///
+    /// Sent as `seed` to OpenAI-compatible APIs and `options.seed` to Ollama.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Ask the provider for a JSON object response, independent of any schema
+    ///
+    /// Sent as `response_format: {"type": "json_object"}` to OpenAI-compatible APIs.
+    /// Ollama and Cohere already request JSON output for the JSON format type.
+    pub fn with_json_mode(mut self, json_mode: bool) -> Self {
+        self.json_mode = json_mode;
+        self
+    }
+
+    /// Constrain Ollama responses to a JSON schema, sent as `format` in place of `"json"`
+    ///
+    /// See [`crate::schema::json_output_schema`] for a schema matching the example output shape.
+    pub fn with_output_schema(mut self, output_schema: serde_json::Value) -> Self {
+        self.output_schema = Some(output_schema);
+        self
+    }
+
+    /// Constrain every response to a GBNF grammar, sent as `grammar` with OpenAI-compatible
+    /// requests for llama.cpp-style servers
+    ///
+    /// See [`crate::schema::gbnf_grammar`] for a grammar matching the example output shape.
+    pub fn with_grammar(mut self, grammar: String) -> Self {
+        self.grammar = Some(grammar);
+        self
+    }
+
+    /// Features supported by this provider's API
+    ///
+    /// The hosted OpenAI API rejects unknown fields, so only other OpenAI-compatible
+    /// servers are treated as accepting a grammar.
+    pub fn capabilities(&self) -> ProviderCapabilities {
+        let mut capabilities = ProviderCapabilities::for_provider(self.config.provider_type);
+        capabilities.grammar &= !self.config.base_url.contains("api.openai.com");
+        capabilities
+    }
+
+    /// Completion token limit for a request, if any
+    fn max_tokens(&self, kwargs: &HashMap<String, serde_json::Value>) -> Option<u64> {
+        self.max_output_tokens
+            .map(u64::from)
+            .or_else(|| kwargs.get("max_tokens").and_then(|v| v.as_u64()))
+    }
+
+    /// The prompt as seen by providers without a separate system role
+    fn prompt_with_system(&self, prompt: &str) -> String {
+        match &self.system_prompt {
+            Some(system_prompt) => format!("{}\n\n{}", system_prompt, prompt),
+            None => prompt.to_string(),
+        }
+    }
+
+    /// Request settings besides model, prompt and temperature, serialized for the cache key
+    fn cache_settings(&self, kwargs: &HashMap<String, serde_json::Value>) -> String {
+        let schema = self.schema.as_ref().map(|s| s.to_provider_config());
+        serde_json::json!({
+            "schema": schema,
+            "output_schema": self.output_schema,
+            "grammar": self.grammar,
+            "system_prompt": self.system_prompt,
+            "max_tokens": self.max_tokens(kwargs),
+            "seed": self.seed,
+            "json_mode": self.json_mode,
+            "format_type": self.format_type,
+            "extra_body": self.config.extra_body,
+            "extra_body_override": self.config.extra_body_override,
         })
+        .to_string()
+    }
+
+    /// Dispatch inference to the configured provider backend, bypassing the cache
+    async fn infer_uncached(
+        &self,
+        batch_prompts: &[String],
+        kwargs: &HashMap<String, serde_json::Value>,
+    ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
+        match self.config.provider_type {
+            #[cfg(feature = "openai")]
+            ProviderType::OpenAI => self.infer_openai(batch_prompts, kwargs, None).await,
+            ProviderType::Ollama => self.infer_ollama(batch_prompts, kwargs, None).await,
+            ProviderType::Cohere => self.infer_cohere(batch_prompts, kwargs).await,
+            ProviderType::Custom => Err(LangExtractError::configuration(
+                "Custom provider inference not yet implemented",
+            )),
+            ProviderType::Mock => super::MockModel::from_config(&self.config)?.infer(batch_prompts, kwargs).await,
+            #[cfg(not(feature = "openai"))]
+            ProviderType::OpenAI => Err(LangExtractError::configuration(
+                "OpenAI feature not enabled. Enable with --features openai",
+            )),
+        }
+    }
+
+    /// Ping a provider endpoint, mapping transport and HTTP failures to inference errors
+    async fn health_check_request(&self, url: &str) -> LangExtractResult<reqwest::Response> {
+        let provider = self.config.provider_type.to_string();
+        let mut request = self.client.get(url);
+        if let Some(api_key) = &self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await.map_err(|e| InferenceError::ServerUnreachable {
+            provider: provider.clone(),
+            message: format!("{}: {}", url, e),
+        })?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            let message = response.text().await.unwrap_or_default();
+            return Err(InferenceError::AuthenticationFailed {
+                provider,
+                message: format!("HTTP {}: {}", status, message),
+            }
+            .into());
+        }
+        if status.is_server_error() {
+            return Err(InferenceError::ServiceUnavailable { provider }.into());
+        }
+        if !status.is_success() && self.config.provider_type != ProviderType::Custom {
+            return Err(InferenceError::InferenceFailed {
+                message: format!("Health check request to {} returned HTTP {}", url, status),
+            }
+            .into());
+        }
+
+        Ok(response)
+    }
+
+    /// Whether a listed model name matches the configured model (Ollama adds ":latest")
+    fn model_listed(&self, name: &str) -> bool {
+        let model = &self.config.model;
+        name == model || (!model.contains(':') && name.split(':').next() == Some(model.as_str()))
+    }
+
+    /// Model listing endpoint with the keys of its model list and model names, if the provider has one
+    fn models_endpoint(&self) -> Option<(String, &'static str, &'static str)> {
+        let base_url = self.config.base_url.trim_end_matches('/');
+        match self.config.provider_type {
+            ProviderType::Ollama => Some((format!("{}/api/tags", base_url), "models", "name")),
+            ProviderType::OpenAI => Some((format!("{}/models", base_url), "data", "id")),
+            ProviderType::Cohere => Some((format!("{}/models", base_url), "models", "name")),
+            ProviderType::Custom | ProviderType::Mock => None,
+        }
+    }
+
+    /// List the models the provider exposes (`/v1/models` for OpenAI-compatible APIs, `/api/tags` for Ollama)
+    ///
+    /// Providers without a listing endpoint return an empty list and log a warning.
+    pub async fn list_models(&self) -> LangExtractResult<Vec<String>> {
+        let Some((url, list_key, name_key)) = self.models_endpoint() else {
+            tracing::warn!(provider = %self.config.provider_type, "Provider does not support listing models");
+            return Ok(Vec::new());
+        };
+
+        let response = self.health_check_request(&url).await?;
+        Self::listed_models(response, list_key, name_key).await
+    }
+
+    /// Collect the model names listed under `list_key`/`name_key` in a models response
+    async fn listed_models(response: reqwest::Response, list_key: &str, name_key: &str) -> LangExtractResult<Vec<String>> {
+        let body: serde_json::Value = response.json().await?;
+        Ok(body
+            .get(list_key)
+            .and_then(|models| models.as_array())
+            .map(|models| {
+                models
+                    .iter()
+                    .filter_map(|m| m.get(name_key).and_then(|n| n.as_str()).map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default())
     }
 
     /// Inference implementation for OpenAI-compatible APIs
@@ -114,24 +376,40 @@ impl UniversalProvider {
         &self,
         batch_prompts: &[String],
         kwargs: &HashMap<String, serde_json::Value>,
+        image: Option<&ImageInput>,
     ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
         use async_openai::types::{
-            ChatCompletionRequestMessage, ChatCompletionRequestSystemMessage,
-            ChatCompletionRequestSystemMessageContent, CreateChatCompletionRequest,
+            ChatCompletionRequestMessage, ChatCompletionRequestMessageContentPartImage,
+            ChatCompletionRequestMessageContentPartText, ChatCompletionRequestSystemMessage,
+            ChatCompletionRequestSystemMessageContent, ChatCompletionRequestUserMessageContent,
+            ChatCompletionRequestUserMessageContentPart, CreateChatCompletionRequest,
+            CreateChatCompletionResponse, ImageUrl,
         };
 
-        let client = self
-            .openai_client
-            .as_ref()
-            .ok_or_else(|| LangExtractError::configuration("OpenAI client not initialized"))?;
-
+        let url = format!("{}/chat/completions", self.config.base_url);
         let mut results = Vec::new();
 
         for prompt in batch_prompts {
-            // Create system message for format instructions
-            let system_message = match self.format_type {
-                FormatType::Json => "You are a helpful assistant that responds in JSON format. Always return valid JSON that matches the expected structure from the examples.",
-                FormatType::Yaml => "You are a helpful assistant that responds in YAML format. Always return valid YAML that matches the expected structure from the examples.",
+            // Create system message, defaulting to format instructions
+            let system_message = match (&self.system_prompt, self.format_type) {
+                (Some(system_prompt), _) => system_prompt.as_str(),
+                (None, FormatType::Json) => "You are a helpful assistant that responds in JSON format. Always return valid JSON that matches the expected structure from the examples.",
+                (None, FormatType::Yaml) => "You are a helpful assistant that responds in YAML format. Always return valid YAML that matches the expected structure from the examples.",
+            };
+
+            // Images travel as a base64 data URL part next to the prompt text
+            let content = match image {
+                Some(image) => ChatCompletionRequestUserMessageContent::Array(vec![
+                    ChatCompletionRequestUserMessageContentPart::Text(
+                        ChatCompletionRequestMessageContentPartText { text: prompt.clone() },
+                    ),
+                    ChatCompletionRequestUserMessageContentPart::ImageUrl(
+                        ChatCompletionRequestMessageContentPartImage {
+                            image_url: ImageUrl { url: image.data_url(), detail: None },
+                        },
+                    ),
+                ]),
+                None => ChatCompletionRequestUserMessageContent::Text(prompt.clone()),
             };
 
             // Create messages for the chat completion
@@ -144,9 +422,7 @@ impl UniversalProvider {
                 }),
                 ChatCompletionRequestMessage::User(
                     async_openai::types::ChatCompletionRequestUserMessage {
-                        content: async_openai::types::ChatCompletionRequestUserMessageContent::Text(
-                            prompt.clone(),
-                        ),
+                        content,
                         name: None,
                     },
                 ),
@@ -168,11 +444,24 @@ impl UniversalProvider {
                 }
             }
 
-            if let Some(max_tokens) = kwargs.get("max_tokens") {
-                if let Some(max_tokens_u64) = max_tokens.as_u64() {
-                    request.max_tokens = Some(max_tokens_u64 as u32);
-                }
+            if let Some(max_tokens) = self.max_tokens(kwargs) {
+                request.max_tokens = Some(max_tokens as u32);
+            }
+
+            if let Some(seed) = self.seed {
+                request.seed = Some(seed as i64);
+            }
+
+            if self.json_mode && self.format_type == FormatType::Json {
+                request.response_format = Some(async_openai::types::ResponseFormat::JsonObject);
+            }
+
+            // Extra fields go into the serialized body, so ones the typed request lacks survive
+            let mut request_body = serde_json::to_value(&request)?;
+            if let Some(grammar) = &self.grammar {
+                request_body["grammar"] = serde_json::json!(grammar);
             }
+            self.config.apply_extra_body(&mut request_body);
 
             // Make the API call with retry logic
             report_progress(ProgressEvent::ModelCall {
@@ -184,14 +473,36 @@ impl UniversalProvider {
             let response = self
                 .retry_with_backoff(
                     || async {
-                        let result = client.chat().create(request.clone()).await.map_err(|e| {
+                        // Custom headers from the config are applied as client defaults
+                        let mut request = self.client.post(&url).json(&request_body);
+                        if let Some(api_key) = &self.config.api_key {
+                            request = request.bearer_auth(api_key);
+                        }
+
+                        let response = request.send().await.map_err(|e| {
                             report_progress(ProgressEvent::Error {
                                 operation: "OpenAI API request".to_string(),
                                 error: format!("OpenAI API error: {}", e),
                             });
-                            LangExtractError::inference_simple(format!("OpenAI API error: {}", e))
-                        });
-                        result
+                            LangExtractError::NetworkError(e)
+                        })?;
+
+                        if !response.status().is_success() {
+                            let status = response.status();
+                            let detail = response.text().await.unwrap_or_default();
+                            report_progress(ProgressEvent::Error {
+                                operation: "OpenAI HTTP status".to_string(),
+                                error: format!("HTTP error status: {}", status),
+                            });
+                            return Err(LangExtractError::inference_simple(format!(
+                                "OpenAI API error: HTTP {}: {}",
+                                status, detail
+                            )));
+                        }
+
+                        response.json::<CreateChatCompletionResponse>().await.map_err(|e| {
+                            LangExtractError::parsing(format!("Failed to parse OpenAI response: {}", e))
+                        })
                     },
                     &format!("OpenAI API call for prompt batch {}", prompt.len()),
                 )
@@ -215,19 +526,27 @@ impl UniversalProvider {
         &self,
         batch_prompts: &[String],
         kwargs: &HashMap<String, serde_json::Value>,
+        image: Option<&ImageInput>,
     ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
         let mut results = Vec::new();
 
         for prompt in batch_prompts {
             let mut request_body = serde_json::json!({
                 "model": self.config.model,
-                "prompt": prompt,
+                "prompt": self.prompt_with_system(prompt),
                 "stream": false,
             });
 
-            // Set format for JSON output if needed
+            // Set format for JSON output if needed, preferring the example-derived schema
             if self.format_type == FormatType::Json {
-                request_body["format"] = serde_json::json!("json");
+                request_body["format"] = match &self.output_schema {
+                    Some(output_schema) => output_schema.clone(),
+                    None => serde_json::json!("json"),
+                };
+            }
+
+            if let Some(image) = image {
+                request_body["images"] = serde_json::json!([image.to_base64()]);
             }
 
             // Apply parameters from kwargs
@@ -235,22 +554,30 @@ impl UniversalProvider {
                 if let Some(temp) = kwargs.get("temperature") {
                     options["temperature"] = temp.clone();
                 }
-                if let Some(max_tokens) = kwargs.get("max_tokens") {
-                    options["num_predict"] = max_tokens.clone();
+                if let Some(max_tokens) = self.max_tokens(kwargs) {
+                    options["num_predict"] = serde_json::json!(max_tokens);
+                }
+                if let Some(seed) = self.seed {
+                    options["seed"] = serde_json::json!(seed);
                 }
             } else {
                 let mut options = serde_json::Map::new();
                 if let Some(temp) = kwargs.get("temperature") {
                     options.insert("temperature".to_string(), temp.clone());
                 }
-                if let Some(max_tokens) = kwargs.get("max_tokens") {
-                    options.insert("num_predict".to_string(), max_tokens.clone());
+                if let Some(max_tokens) = self.max_tokens(kwargs) {
+                    options.insert("num_predict".to_string(), serde_json::json!(max_tokens));
+                }
+                if let Some(seed) = self.seed {
+                    options.insert("seed".to_string(), serde_json::json!(seed));
                 }
                 if !options.is_empty() {
                     request_body["options"] = serde_json::Value::Object(options);
                 }
             }
 
+            self.config.apply_extra_body(&mut request_body);
+
             let url = format!("{}/api/generate", self.config.base_url);
 
             // Make the API call with retry logic
@@ -263,11 +590,10 @@ impl UniversalProvider {
             let response_body = self
                 .retry_with_backoff(
                     || async {
+                        // Custom headers from the config are applied as client defaults
                         let mut request = self.client.post(&url).json(&request_body);
-
-                        // Add headers
-                        for (key, value) in &self.config.headers {
-                            request = request.header(key, value);
+                        if let Some(api_key) = &self.config.api_key {
+                            request = request.bearer_auth(api_key);
                         }
 
                         let response = request.send().await.map_err(|e| {
@@ -320,6 +646,99 @@ impl UniversalProvider {
 
         Ok(results)
     }
+
+    /// Request body for Cohere's `/chat` endpoint
+    fn cohere_request_body(&self, prompt: &str, kwargs: &HashMap<String, serde_json::Value>) -> serde_json::Value {
+        let mut request_body = serde_json::json!({
+            "model": self.config.model,
+            "message": prompt,
+            "chat_history": [],
+        });
+
+        if let Some(system_prompt) = &self.system_prompt {
+            request_body["preamble"] = serde_json::json!(system_prompt);
+        }
+        if self.format_type == FormatType::Json {
+            request_body["response_format"] = serde_json::json!({"type": "json_object"});
+        }
+        if let Some(temp) = kwargs.get("temperature") {
+            request_body["temperature"] = temp.clone();
+        }
+        if let Some(max_tokens) = self.max_tokens(kwargs) {
+            request_body["max_tokens"] = serde_json::json!(max_tokens);
+        }
+        if let Some(seed) = self.seed {
+            request_body["seed"] = serde_json::json!(seed);
+        }
+
+        self.config.apply_extra_body(&mut request_body);
+        request_body
+    }
+
+    /// Inference implementation for Cohere's chat API
+    async fn infer_cohere(
+        &self,
+        batch_prompts: &[String],
+        kwargs: &HashMap<String, serde_json::Value>,
+    ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
+        let url = format!("{}/chat", self.config.base_url.trim_end_matches('/'));
+        let mut results = Vec::new();
+
+        for prompt in batch_prompts {
+            let request_body = self.cohere_request_body(prompt, kwargs);
+
+            report_progress(ProgressEvent::ModelCall {
+                provider: "Cohere".to_string(),
+                model: self.config.model.clone(),
+                input_length: prompt.len(),
+            });
+
+            let response_body = self
+                .retry_with_backoff(
+                    || async {
+                        let mut request = self.client.post(&url).json(&request_body);
+                        if let Some(api_key) = &self.config.api_key {
+                            request = request.bearer_auth(api_key);
+                        }
+
+                        let response = request.send().await.map_err(|e| {
+                            report_progress(ProgressEvent::Error {
+                                operation: "Cohere HTTP request".to_string(),
+                                error: format!("HTTP request failed: {}", e),
+                            });
+                            LangExtractError::NetworkError(e)
+                        })?;
+
+                        if !response.status().is_success() {
+                            let status = response.status();
+                            report_progress(ProgressEvent::Error {
+                                operation: "Cohere HTTP status".to_string(),
+                                error: format!("HTTP error status: {}", status),
+                            });
+                            return Err(LangExtractError::inference_simple(format!(
+                                "Cohere API error: HTTP {}",
+                                status
+                            )));
+                        }
+
+                        response.json::<serde_json::Value>().await.map_err(|e| {
+                            LangExtractError::parsing(format!("Failed to parse Cohere response: {}", e))
+                        })
+                    },
+                    &format!("Cohere API call for prompt batch {}", prompt.len()),
+                )
+                .await?;
+
+            let content = response_body
+                .get("text")
+                .and_then(|text| text.as_str())
+                .ok_or_else(|| LangExtractError::parsing("Missing 'text' field in Cohere response"))?;
+
+            results.push(vec![ScoredOutput::from_text(content.to_string())]);
+        }
+
+        Ok(results)
+    }
 }
 
 #[async_trait]
@@ -342,30 +761,111 @@ impl BaseLanguageModel for UniversalProvider {
             return override_val;
         }
 
-        // OpenAI with JSON mode doesn't need fences, Ollama might
-        match self.config.provider_type {
-            ProviderType::OpenAI if self.schema.is_some() => false,
-            _ => true,
+        // Constrained providers return bare JSON when a schema, JSON mode or grammar is in use
+        let schema_constrained = self.capabilities().json_schema
+            && (self.schema.is_some() || self.json_mode || self.output_schema.is_some());
+        let grammar_constrained = self.capabilities().grammar && self.grammar.is_some();
+        !(schema_constrained || grammar_constrained)
+    }
+
+    async fn health_check(&self) -> LangExtractResult<()> {
+        let models = match self.config.provider_type {
+            ProviderType::Custom => {
+                // No known listing endpoint; reaching the server is the best we can check
+                self.health_check_request(self.config.base_url.trim_end_matches('/')).await?;
+                return Ok(());
+            }
+            ProviderType::Mock => return Ok(()),
+            _ => self.list_models().await?,
+        };
+
+        if models.iter().any(|name| self.model_listed(name)) {
+            Ok(())
+        } else {
+            Err(InferenceError::ModelNotFound {
+                model_id: self.config.model.clone(),
+            }
+            .into())
         }
     }
 
+    /// Image input is sent as a data URL part to OpenAI-compatible APIs and in `images` to Ollama
+    async fn infer_with_image(
+        &self,
+        prompt: &str,
+        image: &ImageInput,
+        kwargs: &HashMap<String, serde_json::Value>,
+    ) -> LangExtractResult<Vec<ScoredOutput>> {
+        if !self.capabilities().vision {
+            return Err(LangExtractError::configuration(format!(
+                "Provider '{}' does not support image input",
+                self.config.provider_type
+            )));
+        }
+
+        let prompts = [prompt.to_string()];
+        let results = match self.config.provider_type {
+            #[cfg(feature = "openai")]
+            ProviderType::OpenAI => self.infer_openai(&prompts, kwargs, Some(image)).await?,
+            ProviderType::Ollama => self.infer_ollama(&prompts, kwargs, Some(image)).await?,
+            // OpenAI without the `openai` feature
+            provider => {
+                return Err(LangExtractError::configuration(format!(
+                    "Image input for provider '{}' is not enabled in this build",
+                    provider
+                )))
+            }
+        };
+        Ok(results.into_iter().next().unwrap_or_default())
+    }
+
     async fn infer(
         &self,
         batch_prompts: &[String],
         kwargs: &HashMap<String, serde_json::Value>,
     ) -> LangExtractResult<Vec<Vec<ScoredOutput>>> {
-        match self.config.provider_type {
-            #[cfg(feature = "openai")]
-            ProviderType::OpenAI => self.infer_openai(batch_prompts, kwargs).await,
-            ProviderType::Ollama => self.infer_ollama(batch_prompts, kwargs).await,
-            ProviderType::Custom => Err(LangExtractError::configuration(
-                "Custom provider inference not yet implemented",
-            )),
-            #[cfg(not(feature = "openai"))]
-            ProviderType::OpenAI => Err(LangExtractError::configuration(
-                "OpenAI feature not enabled. Enable with --features openai",
-            )),
+        let temperature = kwargs.get("temperature").and_then(|t| t.as_f64());
+        let cache = match &self.cache {
+            Some(cache) if cache.accepts_temperature(temperature) => cache,
+            _ => return self.infer_uncached(batch_prompts, kwargs).await,
+        };
+
+        let settings = self.cache_settings(kwargs);
+        let keys: Vec<String> = batch_prompts
+            .iter()
+            .map(|prompt| ResponseCache::key(&self.config.model, prompt, temperature, Some(&settings)))
+            .collect();
+
+        let mut results: Vec<Option<Vec<ScoredOutput>>> = keys
+            .iter()
+            .map(|key| cache.get(key).map(|output| vec![ScoredOutput::from_text(output)]))
+            .collect();
+
+        let misses: Vec<usize> = (0..results.len()).filter(|&i| results[i].is_none()).collect();
+        report_progress(ProgressEvent::Debug {
+            operation: "cache".to_string(),
+            details: format!(
+                "{} of {} prompts served from cache",
+                batch_prompts.len() - misses.len(),
+                batch_prompts.len()
+            ),
+        });
+
+        if !misses.is_empty() {
+            let miss_prompts: Vec<String> = misses.iter().map(|&i| batch_prompts[i].clone()).collect();
+            let fresh = self.infer_uncached(&miss_prompts, kwargs).await?;
+
+            for (&i, outputs) in misses.iter().zip(fresh) {
+                if let Some(output) = outputs.first().and_then(|o| o.output.as_deref()) {
+                    if let Err(e) = cache.put(&keys[i], &self.config.model, output) {
+                        tracing::warn!(error = %e, "Failed to write response cache entry");
+                    }
+                }
+                results[i] = Some(outputs);
+            }
         }
+
+        Ok(results.into_iter().map(Option::unwrap_or_default).collect())
     }
 
     fn format_type(&self) -> FormatType {
@@ -380,7 +880,9 @@ impl BaseLanguageModel for UniversalProvider {
         match self.config.provider_type {
             ProviderType::OpenAI => "openai",
             ProviderType::Ollama => "ollama",
+            ProviderType::Cohere => "cohere",
             ProviderType::Custom => "custom",
+            ProviderType::Mock => "mock",
         }
     }
 }
@@ -389,6 +891,7 @@ impl BaseLanguageModel for UniversalProvider {
 mod tests {
     use super::*;
     use crate::providers::config::ProviderConfig;
+    use crate::providers::test_server::{request_json, spawn_mock_server, spawn_mock_server_with_status};
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
 
@@ -450,4 +953,623 @@ mod tests {
         assert_eq!(result.unwrap(), "Immediate success!");
         assert_eq!(attempt_count.load(Ordering::SeqCst), 1);
     }
+
+    #[tokio::test]
+    async fn test_cache_serves_repeated_request() {
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "{\"name\": \"John\"}"})).await;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let provider = UniversalProvider::new(ProviderConfig::ollama("test-model", Some(url)))
+            .unwrap()
+            .with_cache(ResponseCache::new(temp_dir.path()).unwrap());
+
+        let prompts = vec!["Extract the name from: John is here".to_string()];
+        let mut kwargs = HashMap::new();
+        kwargs.insert("temperature".to_string(), serde_json::json!(0.0));
+
+        let first = provider.infer(&prompts, &kwargs).await.unwrap();
+        let second = provider.infer(&prompts, &kwargs).await.unwrap();
+
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert_eq!(first[0][0].text(), "{\"name\": \"John\"}");
+        assert_eq!(second[0][0].text(), first[0][0].text());
+    }
+
+    #[tokio::test]
+    async fn test_cache_key_covers_request_settings() {
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "[]"})).await;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let provider = || {
+            UniversalProvider::new(ProviderConfig::ollama("test-model", Some(url.clone())))
+                .unwrap()
+                .with_cache(ResponseCache::new(temp_dir.path()).unwrap())
+        };
+        let prompts = vec!["Extract the name from: John is here".to_string()];
+        let mut kwargs = HashMap::new();
+        kwargs.insert("temperature".to_string(), serde_json::json!(0.0));
+
+        provider().infer(&prompts, &kwargs).await.unwrap();
+        provider().infer(&prompts, &kwargs).await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        provider().with_max_output_tokens(64).infer(&prompts, &kwargs).await.unwrap();
+        provider().with_seed(7).infer(&prompts, &kwargs).await.unwrap();
+        provider().with_system_prompt("Be terse".to_string()).infer(&prompts, &kwargs).await.unwrap();
+        provider().with_json_mode(true).infer(&prompts, &kwargs).await.unwrap();
+        provider().with_grammar("root ::= \"[]\"".to_string()).infer(&prompts, &kwargs).await.unwrap();
+        let extra_body = ProviderConfig::ollama("test-model", Some(url.clone()))
+            .with_extra_body("top_p".to_string(), serde_json::json!(0.5));
+        UniversalProvider::new(extra_body)
+            .unwrap()
+            .with_cache(ResponseCache::new(temp_dir.path()).unwrap())
+            .infer(&prompts, &kwargs)
+            .await
+            .unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers_sent_with_requests() {
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "{}"})).await;
+
+        let config = ProviderConfig::ollama("test-model", Some(url))
+            .with_api_key("secret-key".to_string())
+            .with_header("X-Org-Id".to_string(), "acme".to_string())
+            .with_header("X-Title".to_string(), "LangExtract".to_string())
+            .with_header("Authorization".to_string(), "Bearer other".to_string());
+        let provider = UniversalProvider::new(config).unwrap();
+
+        provider
+            .infer(&["Extract something".to_string()], &HashMap::new())
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let request = requests[0].to_lowercase();
+        assert!(request.contains("x-org-id: acme"));
+        assert!(request.contains("x-title: langextract"));
+        // Provider auth header is not clobbered by the custom one
+        assert!(request.contains("authorization: bearer secret-key"));
+        assert!(!request.contains("bearer other"));
+    }
+
+    #[test]
+    fn test_invalid_custom_header_rejected() {
+        let config = ProviderConfig::ollama("test-model", None)
+            .with_header("Bad Header".to_string(), "value".to_string());
+        assert!(UniversalProvider::new(config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cache_bypassed_for_nonzero_temperature() {
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "{}"})).await;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let provider = UniversalProvider::new(ProviderConfig::ollama("test-model", Some(url)))
+            .unwrap()
+            .with_cache(ResponseCache::new(temp_dir.path()).unwrap().with_nonzero_temperature(false));
+
+        let prompts = vec!["Extract something".to_string()];
+        let mut kwargs = HashMap::new();
+        kwargs.insert("temperature".to_string(), serde_json::json!(0.8));
+
+        provider.infer(&prompts, &kwargs).await.unwrap();
+        provider.infer(&prompts, &kwargs).await.unwrap();
+
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    /// URL of a local port with nothing listening on it
+    async fn unreachable_url() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        url
+    }
+
+    #[tokio::test]
+    async fn test_ollama_health_check() {
+        let tags = serde_json::json!({"models": [{"name": "mistral:latest"}, {"name": "llama3:8b"}]});
+        let (url, requests) = spawn_mock_server(tags.clone()).await;
+
+        let provider = UniversalProvider::new(ProviderConfig::ollama("mistral", Some(url.clone()))).unwrap();
+        provider.health_check().await.unwrap();
+        assert!(requests.lock().unwrap()[0].starts_with("GET /api/tags"));
+
+        let provider = UniversalProvider::new(ProviderConfig::ollama("llama3:8b", Some(url.clone()))).unwrap();
+        provider.health_check().await.unwrap();
+
+        let provider = UniversalProvider::new(ProviderConfig::ollama("phi3", Some(url))).unwrap();
+        assert!(matches!(
+            provider.health_check().await.unwrap_err().inference_kind(),
+            Some(InferenceError::ModelNotFound { model_id }) if model_id == "phi3"
+        ));
+
+        let provider = UniversalProvider::new(ProviderConfig::ollama("mistral", Some(unreachable_url().await))).unwrap();
+        assert!(matches!(
+            provider.health_check().await.unwrap_err().inference_kind(),
+            Some(InferenceError::ServerUnreachable { .. })
+        ));
+
+        let (url, _) = spawn_mock_server_with_status("401 Unauthorized", serde_json::json!({"error": "unauthorized"})).await;
+        let provider = UniversalProvider::new(ProviderConfig::ollama("mistral", Some(url))).unwrap();
+        assert!(matches!(
+            provider.health_check().await.unwrap_err().inference_kind(),
+            Some(InferenceError::AuthenticationFailed { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_openai_health_check() {
+        let models = serde_json::json!({"object": "list", "data": [{"id": "gpt-4o-mini"}, {"id": "gpt-4o"}]});
+        let (url, requests) = spawn_mock_server(models).await;
+
+        let config = ProviderConfig::openai_compatible(&url, "gpt-4o", Some("test-key".to_string()));
+        UniversalProvider::new(config).unwrap().health_check().await.unwrap();
+        let request = requests.lock().unwrap()[0].to_lowercase();
+        assert!(request.starts_with("get /models"));
+        assert!(request.contains("authorization: bearer test-key"));
+
+        let config = ProviderConfig::openai_compatible(&url, "gpt-5-unknown", Some("test-key".to_string()));
+        assert!(matches!(
+            UniversalProvider::new(config).unwrap().health_check().await.unwrap_err().inference_kind(),
+            Some(InferenceError::ModelNotFound { .. })
+        ));
+
+        let (url, _) = spawn_mock_server_with_status("401 Unauthorized", serde_json::json!({"error": {"message": "Incorrect API key"}})).await;
+        let config = ProviderConfig::openai_compatible(&url, "gpt-4o", Some("bad-key".to_string()));
+        assert!(matches!(
+            UniversalProvider::new(config).unwrap().health_check().await.unwrap_err().inference_kind(),
+            Some(InferenceError::AuthenticationFailed { message, .. }) if message.contains("Incorrect API key")
+        ));
+
+        let config = ProviderConfig::openai_compatible(&unreachable_url().await, "gpt-4o", Some("test-key".to_string()));
+        assert!(matches!(
+            UniversalProvider::new(config).unwrap().health_check().await.unwrap_err().inference_kind(),
+            Some(InferenceError::ServerUnreachable { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_models_per_provider_shape() {
+        let (url, requests) = spawn_mock_server(serde_json::json!({"data": [{"id": "gpt-4o"}, {"id": "gpt-4o-mini"}]})).await;
+        let config = ProviderConfig::openai_compatible(&format!("{}/v1", url), "gpt-4o", Some("test-key".to_string()));
+        let models = UniversalProvider::new(config).unwrap().list_models().await.unwrap();
+        assert_eq!(models, vec!["gpt-4o", "gpt-4o-mini"]);
+        assert!(requests.lock().unwrap()[0].starts_with("GET /v1/models"));
+
+        let (url, requests) = spawn_mock_server(serde_json::json!({"models": [{"name": "mistral:latest"}]})).await;
+        let models = UniversalProvider::new(ProviderConfig::ollama("mistral", Some(url))).unwrap().list_models().await.unwrap();
+        assert_eq!(models, vec!["mistral:latest"]);
+        assert!(requests.lock().unwrap()[0].starts_with("GET /api/tags"));
+
+        let (url, _) = spawn_mock_server(serde_json::json!({"models": [{"name": "command-r"}, {"name": "command-r-plus"}]})).await;
+        let config = ProviderConfig::cohere("command-r", Some("test-key".to_string())).with_base_url(url);
+        let models = UniversalProvider::new(config).unwrap().list_models().await.unwrap();
+        assert_eq!(models, vec!["command-r", "command-r-plus"]);
+
+        let (url, requests) = spawn_mock_server(serde_json::json!({})).await;
+        let models = UniversalProvider::new(ProviderConfig::custom(&url, "any")).unwrap().list_models().await.unwrap();
+        assert!(models.is_empty());
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "openai")]
+    #[tokio::test]
+    async fn test_system_prompt_sent_as_system_message() {
+        let completion = serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "[]"},
+                "finish_reason": "stop"
+            }]
+        });
+        let (url, requests) = spawn_mock_server(completion).await;
+
+        let config = ProviderConfig::openai_compatible(&url, "gpt-4o", Some("test-key".to_string()));
+        let provider = UniversalProvider::new(config)
+            .unwrap()
+            .with_system_prompt("You are a precise information extraction engine.".to_string());
+        let prompts = vec!["Extract people from: Alice met Bob".to_string()];
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+
+        let body = request_json(&requests.lock().unwrap()[0]);
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages[0]["role"], "system");
+        assert_eq!(messages[0]["content"], "You are a precise information extraction engine.");
+        assert_eq!(messages[1]["role"], "user");
+        assert_eq!(messages[1]["content"], "Extract people from: Alice met Bob");
+    }
+
+    #[tokio::test]
+    async fn test_system_prompt_prepended_for_generate_api() {
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "[]"})).await;
+
+        let provider = UniversalProvider::new(ProviderConfig::ollama("test-model", Some(url)))
+            .unwrap()
+            .with_system_prompt("You are a precise information extraction engine.".to_string());
+        let prompts = vec!["Extract people from: Alice met Bob".to_string()];
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+
+        let body = request_json(&requests.lock().unwrap()[0]);
+        assert_eq!(
+            body["prompt"],
+            "You are a precise information extraction engine.\n\nExtract people from: Alice met Bob"
+        );
+    }
+
+    #[cfg(feature = "openai")]
+    #[tokio::test]
+    async fn test_max_output_tokens_sent_as_max_tokens() {
+        let completion = serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "[]"},
+                "finish_reason": "stop"
+            }]
+        });
+        let (url, requests) = spawn_mock_server(completion).await;
+        let config = ProviderConfig::openai_compatible(&url, "gpt-4o", Some("test-key".to_string()));
+        let prompts = vec!["Extract people from: Alice met Bob".to_string()];
+
+        let provider = UniversalProvider::new(config.clone()).unwrap();
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+        let provider = UniversalProvider::new(config).unwrap().with_max_output_tokens(256);
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert!(request_json(&requests[0]).get("max_tokens").is_none());
+        assert_eq!(request_json(&requests[1])["max_tokens"], 256);
+    }
+
+    #[tokio::test]
+    async fn test_max_output_tokens_sent_as_num_predict() {
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "[]"})).await;
+        let config = ProviderConfig::ollama("test-model", Some(url));
+        let prompts = vec!["Extract people from: Alice met Bob".to_string()];
+
+        let provider = UniversalProvider::new(config.clone()).unwrap();
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+        let provider = UniversalProvider::new(config).unwrap().with_max_output_tokens(256);
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert!(request_json(&requests[0]).get("options").is_none());
+        assert_eq!(request_json(&requests[1])["options"]["num_predict"], 256);
+    }
+
+    #[tokio::test]
+    async fn test_extra_body_merged_into_ollama_request() {
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "[]"})).await;
+        let config = ProviderConfig::ollama("test-model", Some(url))
+            .with_extra_body("keep_alive".to_string(), serde_json::json!("5m"))
+            .with_extra_body(
+                "options".to_string(),
+                serde_json::json!({"num_ctx": 8192, "top_p": 0.9, "temperature": 0.9}),
+            );
+        let prompts = vec!["Extract people from: Alice met Bob".to_string()];
+        let mut kwargs = HashMap::new();
+        kwargs.insert("temperature".to_string(), serde_json::json!(0.2));
+
+        UniversalProvider::new(config.clone()).unwrap().infer(&prompts, &kwargs).await.unwrap();
+        UniversalProvider::new(config.with_extra_body_override(true))
+            .unwrap()
+            .infer(&prompts, &kwargs)
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let body = request_json(&requests[0]);
+        assert_eq!(body["keep_alive"], "5m");
+        assert_eq!(body["options"]["num_ctx"], 8192);
+        assert_eq!(body["options"]["top_p"], 0.9);
+        assert_eq!(body["options"]["temperature"], 0.2);
+        assert_eq!(request_json(&requests[1])["options"]["temperature"], 0.9);
+    }
+
+    #[cfg(feature = "openai")]
+    #[tokio::test]
+    async fn test_extra_body_merged_into_openai_request() {
+        let completion = serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "[]"},
+                "finish_reason": "stop"
+            }]
+        });
+        let (url, requests) = spawn_mock_server(completion).await;
+        let config = ProviderConfig::openai_compatible(&url, "gpt-4o", Some("test-key".to_string()))
+            .with_extra_body("top_p".to_string(), serde_json::json!(0.5))
+            .with_extra_body("min_p".to_string(), serde_json::json!(0.05))
+            .with_extra_body("temperature".to_string(), serde_json::json!(1.5));
+        let mut kwargs = HashMap::new();
+        kwargs.insert("temperature".to_string(), serde_json::json!(0.25));
+
+        let provider = UniversalProvider::new(config).unwrap();
+        provider.infer(&["Extract people from: Alice met Bob".to_string()], &kwargs).await.unwrap();
+
+        let body = request_json(&requests.lock().unwrap()[0]);
+        assert_eq!(body["top_p"], 0.5);
+        assert_eq!(body["min_p"], 0.05);
+        assert_eq!(body["temperature"], 0.25);
+    }
+
+    #[test]
+    fn test_capabilities_differ_per_provider() {
+        let openai = UniversalProvider::new(ProviderConfig::openai("gpt-4o", Some("test-key".to_string())))
+            .unwrap()
+            .capabilities();
+        let ollama = UniversalProvider::new(ProviderConfig::ollama("mistral", None)).unwrap().capabilities();
+
+        assert!(openai.json_schema && ollama.json_schema);
+        assert!(!openai.grammar && !ollama.grammar);
+        assert!(openai.json_mode && ollama.json_mode);
+        assert!(openai.vision && ollama.vision);
+        assert_eq!(
+            UniversalProvider::new(ProviderConfig::custom("http://localhost:8000", "model"))
+                .unwrap()
+                .capabilities(),
+            ProviderCapabilities::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_output_schema_sent_to_ollama_as_format() {
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "[]"})).await;
+        let examples = vec![crate::data::ExampleData::new(
+            "John is 30".to_string(),
+            vec![
+                crate::data::Extraction::new("person".to_string(), "John".to_string()),
+                crate::data::Extraction::new("age".to_string(), "30".to_string()),
+            ],
+        )];
+        let mut config = crate::ExtractConfig {
+            use_schema_constraints: true,
+            ..Default::default()
+        };
+        config.language_model_params.insert(
+            "provider_config".to_string(),
+            serde_json::to_value(ProviderConfig::ollama("test-model", Some(url))).unwrap(),
+        );
+
+        let model = crate::factory::create_model(&config, Some(&examples)).await.unwrap();
+        assert!(!model.requires_fence_output());
+        model.infer(&["Extract from: Alice is 25".to_string()], &HashMap::new()).await.unwrap();
+
+        let body = request_json(&requests.lock().unwrap()[0]);
+        assert_eq!(body["format"], crate::schema::json_output_schema(&examples).unwrap());
+        assert!(body.get("grammar").is_none());
+    }
+
+    #[cfg(feature = "openai")]
+    #[tokio::test]
+    async fn test_grammar_sent_to_openai_compatible_server() {
+        let completion = serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "llama",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "[]"},
+                "finish_reason": "stop"
+            }]
+        });
+        let (url, requests) = spawn_mock_server(completion).await;
+        let examples = vec![crate::data::ExampleData::new(
+            "John is 30".to_string(),
+            vec![
+                crate::data::Extraction::new("person".to_string(), "John".to_string()),
+                crate::data::Extraction::new("age".to_string(), "30".to_string()),
+            ],
+        )];
+        let mut config = crate::ExtractConfig {
+            use_schema_constraints: true,
+            ..Default::default()
+        };
+        config.language_model_params.insert(
+            "provider_config".to_string(),
+            serde_json::to_value(ProviderConfig::openai_compatible(&url, "llama", Some("test-key".to_string()))).unwrap(),
+        );
+
+        let model = crate::factory::create_model(&config, Some(&examples)).await.unwrap();
+        assert!(!model.requires_fence_output());
+        model.infer(&["Extract people from: Alice".to_string()], &HashMap::new()).await.unwrap();
+
+        let body = request_json(&requests.lock().unwrap()[0]);
+        assert_eq!(body["grammar"], crate::schema::gbnf_grammar(&examples).unwrap());
+        assert!(body.get("format").is_none());
+    }
+
+    #[cfg(feature = "openai")]
+    #[tokio::test]
+    async fn test_seed_sent_to_openai() {
+        let completion = serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "[]"},
+                "finish_reason": "stop"
+            }]
+        });
+        let (url, requests) = spawn_mock_server(completion).await;
+        let config = ProviderConfig::openai_compatible(&url, "gpt-4o", Some("test-key".to_string()));
+        let prompts = vec!["Extract people from: Alice met Bob".to_string()];
+
+        let provider = UniversalProvider::new(config.clone()).unwrap();
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+        let provider = UniversalProvider::new(config).unwrap().with_seed(42);
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert!(request_json(&requests[0]).get("seed").is_none());
+        assert_eq!(request_json(&requests[1])["seed"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_requests_routed_through_configured_proxy() {
+        let (proxy_url, requests) = spawn_mock_server(serde_json::json!({"response": "[]"})).await;
+        let proxy_with_auth = proxy_url.replacen("http://", "http://user:secret@", 1);
+        let config = ProviderConfig::ollama("mistral", Some("http://ollama.internal:11434".to_string()))
+            .with_http_proxy(&proxy_with_auth);
+
+        let provider = UniversalProvider::new(config).unwrap();
+        provider.infer_single("Extract people", &HashMap::new()).await.unwrap();
+
+        let raw = requests.lock().unwrap()[0].clone();
+        assert!(raw.starts_with("POST http://ollama.internal:11434/api/generate "));
+        // base64("user:secret")
+        assert!(raw.to_lowercase().contains("proxy-authorization: basic dxnlcjpzzwnyzxq="));
+
+        let invalid = ProviderConfig::ollama("mistral", None).with_https_proxy("not a url");
+        assert!(UniversalProvider::new(invalid).is_err());
+    }
+
+    #[cfg(feature = "openai")]
+    #[tokio::test]
+    async fn test_image_input_sent_as_content_part() {
+        let completion = serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "[{\"name\": \"Alice\"}]"},
+                "finish_reason": "stop"
+            }]
+        });
+        let (url, requests) = spawn_mock_server(completion).await;
+        let provider = UniversalProvider::new(ProviderConfig::openai_compatible(&url, "gpt-4o", Some("test-key".to_string()))).unwrap();
+        let image = ImageInput::new(vec![0x89, b'P', b'N', b'G'], "image/png");
+
+        let output = provider.infer_with_image("Extract the name", &image, &HashMap::new()).await.unwrap();
+        assert_eq!(output[0].text(), r#"[{"name": "Alice"}]"#);
+
+        let body = request_json(&requests.lock().unwrap()[0]);
+        let content = &body["messages"][1]["content"];
+        assert_eq!(content[0], serde_json::json!({"type": "text", "text": "Extract the name"}));
+        assert_eq!(content[1]["type"], "image_url");
+        assert_eq!(content[1]["image_url"]["url"], "data:image/png;base64,iVBORw==");
+    }
+
+    #[tokio::test]
+    async fn test_image_input_sent_to_ollama_and_rejected_by_cohere() {
+        let image = ImageInput::new(vec![0x89, b'P', b'N', b'G'], "image/png");
+
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "[]"})).await;
+        let provider = UniversalProvider::new(ProviderConfig::ollama("llava", Some(url))).unwrap();
+        provider.infer_with_image("Extract the name", &image, &HashMap::new()).await.unwrap();
+        assert_eq!(request_json(&requests.lock().unwrap()[0])["images"], serde_json::json!(["iVBORw=="]));
+
+        let cohere = UniversalProvider::new(ProviderConfig::cohere("command-r", Some("key".to_string()))).unwrap();
+        let err = cohere.infer_with_image("Extract the name", &image, &HashMap::new()).await.unwrap_err();
+        assert!(err.to_string().contains("does not support image input"));
+    }
+
+    #[cfg(feature = "openai")]
+    #[tokio::test]
+    async fn test_json_mode_sets_response_format_without_schema() {
+        let completion = serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "[]"},
+                "finish_reason": "stop"
+            }]
+        });
+        let (url, requests) = spawn_mock_server(completion).await;
+        let config = ProviderConfig::openai_compatible(&url, "gpt-4o", Some("test-key".to_string()));
+        let prompts = vec!["Extract people from: Alice met Bob".to_string()];
+
+        let provider = UniversalProvider::new(config.clone()).unwrap();
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+        let provider = UniversalProvider::new(config).unwrap().with_json_mode(true);
+        assert!(provider.schema.is_none());
+        assert!(!provider.requires_fence_output());
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert!(request_json(&requests[0]).get("response_format").is_none());
+        assert_eq!(request_json(&requests[1])["response_format"], serde_json::json!({"type": "json_object"}));
+    }
+
+    #[tokio::test]
+    async fn test_seed_sent_to_ollama() {
+        let (url, requests) = spawn_mock_server(serde_json::json!({"response": "[]"})).await;
+        let config = ProviderConfig::ollama("test-model", Some(url));
+        let prompts = vec!["Extract people from: Alice met Bob".to_string()];
+
+        let provider = UniversalProvider::new(config).unwrap().with_seed(42);
+        provider.infer(&prompts, &HashMap::new()).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(request_json(&requests[0])["options"]["seed"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_cohere_chat_request_and_response() {
+        // Trimmed response captured from Cohere's v1 chat endpoint
+        let response = serde_json::json!({
+            "response_id": "5b8a2a36-3f4e-4d3c-9d5e-0c1f2b1a7e11",
+            "text": "[{\"person\": \"Alice\"}]",
+            "generation_id": "b4e6c1d0-8f0e-4f6a-a0b5-61a6e2f3c9a4",
+            "chat_history": [
+                {"role": "USER", "message": "Extract people from: Alice met Bob"},
+                {"role": "CHATBOT", "message": "[{\"person\": \"Alice\"}]"}
+            ],
+            "finish_reason": "COMPLETE",
+            "meta": {
+                "api_version": {"version": "1"},
+                "billed_units": {"input_tokens": 12, "output_tokens": 8}
+            }
+        });
+        let (url, requests) = spawn_mock_server(response).await;
+        let config = ProviderConfig::cohere("command-r", Some("test-key".to_string())).with_base_url(url);
+        let provider = UniversalProvider::new(config)
+            .unwrap()
+            .with_system_prompt("Answer in JSON.".to_string());
+        let mut kwargs = HashMap::new();
+        kwargs.insert("temperature".to_string(), serde_json::json!(0.3));
+
+        let results = provider
+            .infer(&["Extract people from: Alice met Bob".to_string()], &kwargs)
+            .await
+            .unwrap();
+        assert_eq!(results[0][0].text(), r#"[{"person": "Alice"}]"#);
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("POST /chat "));
+        assert!(requests[0].to_lowercase().contains("authorization: bearer test-key"));
+        let body = request_json(&requests[0]);
+        assert_eq!(body["model"], "command-r");
+        assert_eq!(body["message"], "Extract people from: Alice met Bob");
+        assert_eq!(body["chat_history"], serde_json::json!([]));
+        assert_eq!(body["temperature"], 0.3);
+        assert_eq!(body["preamble"], "Answer in JSON.");
+    }
+
+    #[test]
+    fn test_cohere_requires_api_key() {
+        let config = ProviderConfig::cohere("command-r", None);
+        assert!(UniversalProvider::new(config).is_err_and(|e| e.is_configuration_error()));
+    }
 }
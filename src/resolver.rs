@@ -3,15 +3,30 @@
 use crate::{
     data::{Extraction, FormatType},
     exceptions::{LangExtractError, LangExtractResult},
-    schema::ATTRIBUTES_SUFFIX,
+    schema::{ABSENT_CLASS_KEY, ATTRIBUTES_KEY, ATTRIBUTES_SUFFIX, PRESENT_KEY, TABLE_COLUMN_ATTRIBUTE, TABLE_KEY},
     ExtractConfig,
 };
 use regex::Regex;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Attribute holding the model's original text after an allowed-value correction
+pub const ORIGINAL_TEXT_ATTRIBUTE: &str = "original_text";
+
+/// Turns raw model output into extractions
+///
+/// Implement this to support output formats the built-in resolver does not
+/// understand (XML, bespoke delimited lines, regex captures, ...) and pass it
+/// via `ExtractConfig::with_response_parser`.
+pub trait ResponseParser: Send + Sync {
+    /// Parse a raw model response into extractions
+    fn parse(&self, raw: &str) -> LangExtractResult<Vec<Extraction>>;
+}
+
 /// Configuration for validation behavior
 #[derive(Debug, Clone)]
 pub struct ValidationConfig {
@@ -27,6 +42,180 @@ pub struct ValidationConfig {
     pub raw_outputs_dir: String,
     /// Quality threshold for extractions (0.0 to 1.0)
     pub quality_threshold: f32,
+    /// How to read numeric dates such as "01/05/2024" during date coercion
+    pub date_order: DateOrder,
+    /// Decimal and grouping separators used when coercing integers and floats
+    pub number_locale: NumberLocale,
+    /// Coerce whole numbers in scientific notation (`1e3`, `1.5e2`) to integers
+    pub scientific_integers: bool,
+    /// Permitted extraction texts per extraction class (e.g. `status` -> active/inactive)
+    pub allowed_values: HashMap<String, Vec<String>>,
+    /// Whether allowed values are compared case-insensitively
+    pub allowed_values_case_insensitive: bool,
+    /// Maximum edit distance for correcting a disallowed value to the nearest allowed one
+    pub allowed_values_max_edit_distance: Option<usize>,
+    /// Minimum/maximum extraction text length (in characters) per extraction class
+    pub field_length_constraints: HashMap<String, (Option<usize>, Option<usize>)>,
+    /// Whether length constraint violations are errors (true) or only warnings (false)
+    pub length_violations_as_errors: bool,
+    /// Renames model output keys to canonical extraction classes (e.g. `full_name` -> `person`)
+    pub field_aliases: HashMap<String, String>,
+    /// Whether field aliases must match keys exactly instead of case-insensitively
+    pub field_aliases_exact_match: bool,
+    /// Flatten nested objects/arrays into dotted-path classes (`address.city`, `items[0].name`)
+    /// instead of stringifying them into a single extraction
+    pub flatten_nested: bool,
+    /// Read a `table` key holding an array of row objects as table cells, one group per row
+    pub table_mode: bool,
+    /// JSON Pointer (e.g. `/result/entities`) to the part of the model output holding the extractions
+    pub root_path: Option<String>,
+    /// Object keys whose array value holds the extractions (`{"entities": [...]}`), tried in order
+    ///
+    /// An object with none of these keys is parsed as a single flat item.
+    pub wrapper_keys: Vec<String>,
+    /// Region (ISO 3166 alpha-2, e.g. `GB`) for phone numbers written without a country prefix
+    ///
+    /// When unset, such numbers are only recognised in the US 10-digit format.
+    pub default_country: Option<String>,
+    /// Whether a coerced percentage's `value` is the ratio (`50%` -> 0.5) or the raw percent (50.0)
+    ///
+    /// The coerced object always carries both as `ratio` and `percent`.
+    pub percentage_as_ratio: bool,
+    /// Reject responses with anything but whitespace around the top-level JSON value (after fence stripping)
+    ///
+    /// Prose-wrapped output such as `Sure! Here's your data: {...}` becomes a parse
+    /// error instead of being brace-scanned.
+    pub strict_json_only: bool,
+}
+
+/// Decimal and digit-grouping conventions for numbers like "1.234,56"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberLocale {
+    /// `1,234.56`: period decimal separator, comma grouping (US/UK style)
+    #[default]
+    UsEn,
+    /// `1.234,56`: comma decimal separator, period grouping (German, Italian, Spanish, ...)
+    EuDe,
+    /// `1 234,56`: comma decimal separator, space or no-break space grouping (French style)
+    EuFr,
+}
+
+impl NumberLocale {
+    fn decimal_separator(self) -> char {
+        match self {
+            NumberLocale::UsEn => '.',
+            NumberLocale::EuDe | NumberLocale::EuFr => ',',
+        }
+    }
+
+    /// Regex fragment matching one grouping separator, if the locale groups digits
+    fn grouping_pattern(self) -> Option<&'static str> {
+        match self {
+            NumberLocale::UsEn => Some(","),
+            NumberLocale::EuDe => Some(r"\."),
+            NumberLocale::EuFr => Some("[ \u{a0}\u{202f}]"),
+        }
+    }
+
+    fn is_grouping_separator(self, c: char) -> bool {
+        match self {
+            NumberLocale::UsEn => c == ',',
+            NumberLocale::EuDe => c == '.',
+            NumberLocale::EuFr => matches!(c, ' ' | '\u{a0}' | '\u{202f}'),
+        }
+    }
+
+    /// Rewrite a number written in this locale into the `1234.56` form Rust parses
+    fn normalize(self, value: &str) -> String {
+        value
+            .chars()
+            .filter(|&c| !self.is_grouping_separator(c))
+            .map(|c| if c == self.decimal_separator() { '.' } else { c })
+            .collect()
+    }
+
+    /// Integer and float patterns for this locale
+    fn number_regexes(self) -> (Regex, Regex) {
+        let whole = match self.grouping_pattern() {
+            Some(group) => format!(r"(?:\d{{1,3}}(?:{}\d{{3}})+|\d+)", group),
+            None => r"\d+".to_string(),
+        };
+        let decimal = regex::escape(&self.decimal_separator().to_string());
+
+        (
+            Regex::new(&format!(r"^[+-]?{}$", whole)).unwrap(),
+            Regex::new(&format!(r"^[+-]?(?:{})?{}?\d+([eE][+-]?\d+)?$", whole, decimal)).unwrap(),
+        )
+    }
+}
+
+/// Dialing rules of a region used to validate and normalize phone numbers
+#[derive(Debug, Clone, Copy)]
+struct PhoneRegion {
+    /// ISO 3166 alpha-2 region code
+    region: &'static str,
+    /// Country calling code, without the leading `+`
+    calling_code: &'static str,
+    /// Prefix dialed before national numbers inside the region
+    trunk_prefix: Option<&'static str>,
+    /// Shortest and longest valid national significant number
+    lengths: (usize, usize),
+}
+
+impl PhoneRegion {
+    fn accepts(&self, national: &str) -> bool {
+        (self.lengths.0..=self.lengths.1).contains(&national.len())
+    }
+}
+
+/// Regions recognised by phone coercion; shared calling codes resolve to the first entry
+const PHONE_REGIONS: &[PhoneRegion] = &[
+    PhoneRegion { region: "US", calling_code: "1", trunk_prefix: Some("1"), lengths: (10, 10) },
+    PhoneRegion { region: "CA", calling_code: "1", trunk_prefix: Some("1"), lengths: (10, 10) },
+    PhoneRegion { region: "GB", calling_code: "44", trunk_prefix: Some("0"), lengths: (9, 10) },
+    PhoneRegion { region: "IE", calling_code: "353", trunk_prefix: Some("0"), lengths: (7, 9) },
+    PhoneRegion { region: "FR", calling_code: "33", trunk_prefix: Some("0"), lengths: (9, 9) },
+    PhoneRegion { region: "DE", calling_code: "49", trunk_prefix: Some("0"), lengths: (6, 13) },
+    PhoneRegion { region: "NL", calling_code: "31", trunk_prefix: Some("0"), lengths: (9, 9) },
+    PhoneRegion { region: "BE", calling_code: "32", trunk_prefix: Some("0"), lengths: (8, 9) },
+    PhoneRegion { region: "CH", calling_code: "41", trunk_prefix: Some("0"), lengths: (9, 9) },
+    PhoneRegion { region: "AT", calling_code: "43", trunk_prefix: Some("0"), lengths: (4, 13) },
+    PhoneRegion { region: "ES", calling_code: "34", trunk_prefix: None, lengths: (9, 9) },
+    PhoneRegion { region: "IT", calling_code: "39", trunk_prefix: None, lengths: (6, 11) },
+    PhoneRegion { region: "PT", calling_code: "351", trunk_prefix: None, lengths: (9, 9) },
+    PhoneRegion { region: "SE", calling_code: "46", trunk_prefix: Some("0"), lengths: (7, 10) },
+    PhoneRegion { region: "NO", calling_code: "47", trunk_prefix: None, lengths: (8, 8) },
+    PhoneRegion { region: "DK", calling_code: "45", trunk_prefix: None, lengths: (8, 8) },
+    PhoneRegion { region: "FI", calling_code: "358", trunk_prefix: Some("0"), lengths: (5, 12) },
+    PhoneRegion { region: "PL", calling_code: "48", trunk_prefix: None, lengths: (9, 9) },
+    PhoneRegion { region: "RU", calling_code: "7", trunk_prefix: Some("8"), lengths: (10, 10) },
+    PhoneRegion { region: "IL", calling_code: "972", trunk_prefix: Some("0"), lengths: (8, 9) },
+    PhoneRegion { region: "AE", calling_code: "971", trunk_prefix: Some("0"), lengths: (8, 9) },
+    PhoneRegion { region: "ZA", calling_code: "27", trunk_prefix: Some("0"), lengths: (9, 9) },
+    PhoneRegion { region: "NG", calling_code: "234", trunk_prefix: Some("0"), lengths: (8, 10) },
+    PhoneRegion { region: "IN", calling_code: "91", trunk_prefix: Some("0"), lengths: (10, 10) },
+    PhoneRegion { region: "CN", calling_code: "86", trunk_prefix: Some("0"), lengths: (7, 11) },
+    PhoneRegion { region: "JP", calling_code: "81", trunk_prefix: Some("0"), lengths: (9, 10) },
+    PhoneRegion { region: "KR", calling_code: "82", trunk_prefix: Some("0"), lengths: (8, 10) },
+    PhoneRegion { region: "SG", calling_code: "65", trunk_prefix: None, lengths: (8, 8) },
+    PhoneRegion { region: "HK", calling_code: "852", trunk_prefix: None, lengths: (8, 8) },
+    PhoneRegion { region: "AU", calling_code: "61", trunk_prefix: Some("0"), lengths: (9, 9) },
+    PhoneRegion { region: "NZ", calling_code: "64", trunk_prefix: Some("0"), lengths: (8, 10) },
+    PhoneRegion { region: "BR", calling_code: "55", trunk_prefix: Some("0"), lengths: (10, 11) },
+    PhoneRegion { region: "MX", calling_code: "52", trunk_prefix: None, lengths: (10, 10) },
+    PhoneRegion { region: "AR", calling_code: "54", trunk_prefix: Some("0"), lengths: (10, 10) },
+];
+
+/// Preferred day/month order for numeric dates like "01/05/2024"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateOrder {
+    /// MM/DD/YYYY (US style)
+    #[default]
+    MonthFirst,
+    /// DD/MM/YYYY (most other locales)
+    DayFirst,
+    /// No preference; dates readable both ways are left unparsed
+    Unspecified,
 }
 
 impl Default for ValidationConfig {
@@ -38,6 +227,26 @@ impl Default for ValidationConfig {
             save_raw_outputs: true,
             raw_outputs_dir: "./raw_outputs".to_string(),
             quality_threshold: 0.0,
+            date_order: DateOrder::default(),
+            number_locale: NumberLocale::default(),
+            scientific_integers: true,
+            allowed_values: HashMap::new(),
+            allowed_values_case_insensitive: false,
+            allowed_values_max_edit_distance: None,
+            field_length_constraints: HashMap::new(),
+            length_violations_as_errors: true,
+            field_aliases: HashMap::new(),
+            field_aliases_exact_match: false,
+            flatten_nested: false,
+            table_mode: false,
+            root_path: None,
+            wrapper_keys: ["data", "results", "extractions", "entities"]
+                .iter()
+                .map(|key| key.to_string())
+                .collect(),
+            default_country: None,
+            percentage_as_ratio: true,
+            strict_json_only: false,
         }
     }
 }
@@ -57,6 +266,8 @@ pub struct ValidationResult {
     pub raw_output_file: Option<String>,
     /// Type coercion details
     pub coercion_summary: Option<CoercionSummary>,
+    /// Whether the model output was cut off and only its complete items were recovered
+    pub truncated: bool,
 }
 
 /// Validation error details
@@ -126,6 +337,11 @@ pub enum CoercionTargetType {
 /// Type coercion engine
 pub struct TypeCoercer {
     enable_coercion: bool,
+    date_order: DateOrder,
+    number_locale: NumberLocale,
+    scientific_integers: bool,
+    default_country: Option<String>,
+    percentage_as_ratio: bool,
     // Pre-compiled regex patterns for performance
     integer_regex: Regex,
     float_regex: Regex,
@@ -140,10 +356,17 @@ pub struct TypeCoercer {
 impl TypeCoercer {
     /// Create a new type coercer
     pub fn new(enable_coercion: bool) -> Self {
+        let number_locale = NumberLocale::default();
+        let (integer_regex, float_regex) = number_locale.number_regexes();
         Self {
             enable_coercion,
-            integer_regex: Regex::new(r"^[+-]?\d+$").unwrap(),
-            float_regex: Regex::new(r"^[+-]?\d*\.?\d+([eE][+-]?\d+)?$").unwrap(),
+            date_order: DateOrder::default(),
+            number_locale,
+            scientific_integers: true,
+            default_country: None,
+            percentage_as_ratio: true,
+            integer_regex,
+            float_regex,
             currency_regex: Regex::new(r"^\$+([\d,]+(?:\.\d{1,2})?)\s*(?:million|M|billion|B|thousand|K)?$|^([\d,]+(?:\.\d{1,2})?)\s*(?:million|M|billion|B|thousand|K)$").unwrap(),
             percentage_regex: Regex::new(r"^(\d*\.?\d+)%$").unwrap(),
             email_regex: Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap(),
@@ -153,6 +376,39 @@ impl TypeCoercer {
         }
     }
 
+    /// Set the preferred day/month order for numeric dates
+    pub fn with_date_order(mut self, date_order: DateOrder) -> Self {
+        self.date_order = date_order;
+        self
+    }
+
+    /// Set the decimal/grouping convention for integer and float coercion
+    pub fn with_number_locale(mut self, number_locale: NumberLocale) -> Self {
+        let (integer_regex, float_regex) = number_locale.number_regexes();
+        self.number_locale = number_locale;
+        self.integer_regex = integer_regex;
+        self.float_regex = float_regex;
+        self
+    }
+
+    /// Set whether whole numbers in scientific notation (`1e3`) coerce to integers
+    pub fn with_scientific_integers(mut self, enabled: bool) -> Self {
+        self.scientific_integers = enabled;
+        self
+    }
+
+    /// Set the region assumed for phone numbers without a country prefix
+    pub fn with_default_country(mut self, default_country: Option<String>) -> Self {
+        self.default_country = default_country;
+        self
+    }
+
+    /// Set whether coerced percentages report the ratio (0.5) or the raw percent (50.0) as `value`
+    pub fn with_percentage_as_ratio(mut self, as_ratio: bool) -> Self {
+        self.percentage_as_ratio = as_ratio;
+        self
+    }
+
     /// Attempt to coerce a string value to a more appropriate type
     pub fn coerce_value(&self, field_name: &str, value: &str) -> CoercionDetail {
         if !self.enable_coercion {
@@ -227,8 +483,24 @@ impl TypeCoercer {
     }
 
     fn try_coerce_integer(&self, field_name: &str, value: &str) -> Option<CoercionDetail> {
+        if self.scientific_integers && value.contains(['e', 'E']) && self.float_regex.is_match(value) {
+            // Only whole numbers small enough to be exact as f64 become integers
+            let num = self.number_locale.normalize(value).parse::<f64>().ok()?;
+            if num.fract() != 0.0 || num.abs() > 9_007_199_254_740_992.0 {
+                return None;
+            }
+            return Some(CoercionDetail {
+                field_name: field_name.to_string(),
+                original_value: value.to_string(),
+                coerced_value: Some(Value::Number(serde_json::Number::from(num as i64))),
+                target_type: CoercionTargetType::Integer,
+                success: true,
+                error_message: None,
+            });
+        }
+
         if self.integer_regex.is_match(value) {
-            match value.parse::<i64>() {
+            match self.number_locale.normalize(value).parse::<i64>() {
                 Ok(num) => Some(CoercionDetail {
                     field_name: field_name.to_string(),
                     original_value: value.to_string(),
@@ -253,7 +525,7 @@ impl TypeCoercer {
 
     fn try_coerce_float(&self, field_name: &str, value: &str) -> Option<CoercionDetail> {
         if self.float_regex.is_match(value) {
-            match value.parse::<f64>() {
+            match self.number_locale.normalize(value).parse::<f64>() {
                 Ok(num) => Some(CoercionDetail {
                     field_name: field_name.to_string(),
                     original_value: value.to_string(),
@@ -338,13 +610,22 @@ impl TypeCoercer {
         if let Some(captures) = self.percentage_regex.captures(value) {
             if let Some(percent_str) = captures.get(1) {
                 if let Ok(percent) = percent_str.as_str().parse::<f64>() {
+                    let number = |n: f64| {
+                        Value::Number(serde_json::Number::from_f64(n).unwrap_or_else(|| serde_json::Number::from(0)))
+                    };
+                    let ratio = percent / 100.0;
+                    let primary = if self.percentage_as_ratio { ratio } else { percent };
                     return Some(CoercionDetail {
                         field_name: field_name.to_string(),
                         original_value: value.to_string(),
-                        coerced_value: Some(Value::Number(
-                            serde_json::Number::from_f64(percent / 100.0)
-                                .unwrap_or_else(|| serde_json::Number::from(0)),
-                        )),
+                        coerced_value: Some(Value::Object({
+                            let mut obj = serde_json::Map::new();
+                            obj.insert("value".to_string(), number(primary));
+                            obj.insert("ratio".to_string(), number(ratio));
+                            obj.insert("percent".to_string(), number(percent));
+                            obj.insert("type".to_string(), Value::String("percentage".to_string()));
+                            obj
+                        })),
                         target_type: CoercionTargetType::Percentage,
                         success: true,
                         error_message: None,
@@ -376,39 +657,67 @@ impl TypeCoercer {
     }
 
     fn try_coerce_phone(&self, field_name: &str, value: &str) -> Option<CoercionDetail> {
-        if let Some(captures) = self.phone_regex.captures(value) {
-            let area = captures.get(1)?.as_str();
-            let exchange = captures.get(2)?.as_str();
-            let number = captures.get(3)?.as_str();
-            let formatted = format!("({}) {}-{}", area, exchange, number);
+        let default_region = self
+            .default_country
+            .as_deref()
+            .map(|code| PHONE_REGIONS.iter().find(|r| r.region.eq_ignore_ascii_case(code)));
+        let (region, national) = if value.starts_with('+') || value.starts_with("00") {
+            parse_international_phone(value)?
+        } else {
+            match default_region {
+                Some(Some(region)) if region.calling_code != "1" => (region, parse_national_phone(value, region)?),
+                Some(None) => return None,
+                _ => {
+                    let captures = self.phone_regex.captures(value)?;
+                    let region = default_region.flatten().unwrap_or(&PHONE_REGIONS[0]);
+                    (region, captures.iter().skip(1).flatten().map(|m| m.as_str()).collect())
+                }
+            }
+        };
 
-            Some(CoercionDetail {
-                field_name: field_name.to_string(),
-                original_value: value.to_string(),
-                coerced_value: Some(Value::Object({
-                    let mut obj = serde_json::Map::new();
-                    obj.insert("phone".to_string(), Value::String(formatted));
-                    obj.insert("area_code".to_string(), Value::String(area.to_string()));
-                    obj.insert("type".to_string(), Value::String("phone".to_string()));
-                    obj
-                })),
-                target_type: CoercionTargetType::PhoneNumber,
-                success: true,
-                error_message: None,
-            })
+        let e164 = format!("+{}{}", region.calling_code, national);
+        let mut obj = serde_json::Map::new();
+        if region.calling_code == "1" {
+            let formatted = format!("({}) {}-{}", &national[..3], &national[3..6], &national[6..]);
+            obj.insert("phone".to_string(), Value::String(formatted));
+            obj.insert("area_code".to_string(), Value::String(national[..3].to_string()));
         } else {
-            None
+            obj.insert("phone".to_string(), Value::String(e164.clone()));
         }
+        obj.insert("e164".to_string(), Value::String(e164));
+        obj.insert("country_code".to_string(), Value::String(region.calling_code.to_string()));
+        obj.insert("region".to_string(), Value::String(region.region.to_string()));
+        obj.insert("type".to_string(), Value::String("phone".to_string()));
+
+        Some(CoercionDetail {
+            field_name: field_name.to_string(),
+            original_value: value.to_string(),
+            coerced_value: Some(Value::Object(obj)),
+            target_type: CoercionTargetType::PhoneNumber,
+            success: true,
+            error_message: None,
+        })
     }
 
     fn try_coerce_date(&self, field_name: &str, value: &str) -> Option<CoercionDetail> {
         if self.date_regex.is_match(value) {
+            let parsed = self.parse_date(value);
             Some(CoercionDetail {
                 field_name: field_name.to_string(),
                 original_value: value.to_string(),
                 coerced_value: Some(Value::Object({
                     let mut obj = serde_json::Map::new();
-                    obj.insert("date".to_string(), Value::String(value.to_string()));
+                    match parsed {
+                        Some(date) => {
+                            obj.insert("date".to_string(), Value::String(date.format("%Y-%m-%d").to_string()));
+                            obj.insert("original".to_string(), Value::String(value.to_string()));
+                            obj.insert("parsed".to_string(), Value::Bool(true));
+                        }
+                        None => {
+                            obj.insert("date".to_string(), Value::String(value.to_string()));
+                            obj.insert("parsed".to_string(), Value::Bool(false));
+                        }
+                    }
                     obj.insert("type".to_string(), Value::String("date".to_string()));
                     obj
                 })),
@@ -421,6 +730,33 @@ impl TypeCoercer {
         }
     }
 
+    /// Parse a date in one of the recognized formats, returning None when unparseable or ambiguous
+    fn parse_date(&self, value: &str) -> Option<chrono::NaiveDate> {
+        use chrono::NaiveDate;
+
+        for format in ["%Y-%m-%d", "%B %d, %Y", "%b %d, %Y"] {
+            if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+                return Some(date);
+            }
+        }
+
+        let month_first = NaiveDate::parse_from_str(value, "%m/%d/%Y").ok();
+        let day_first = NaiveDate::parse_from_str(value, "%d/%m/%Y").ok();
+
+        match (month_first, day_first) {
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(a), Some(b)) => match self.date_order {
+                DateOrder::MonthFirst => Some(a),
+                DateOrder::DayFirst => Some(b),
+                DateOrder::Unspecified => None,
+            },
+            // Only one reading is a valid calendar date, so there is no ambiguity
+            (Some(a), None) => Some(a),
+            (None, Some(b)) => Some(b),
+            (None, None) => None,
+        }
+    }
+
     fn try_coerce_url(&self, field_name: &str, value: &str) -> Option<CoercionDetail> {
         if self.url_regex.is_match(value) {
             Some(CoercionDetail {
@@ -452,6 +788,10 @@ pub struct Resolver {
     validation_config: ValidationConfig,
     /// Type coercion engine
     type_coercer: TypeCoercer,
+    /// Whether invalid output is a hard error
+    strict_validation: bool,
+    /// Custom parser used in place of the built-in parsing
+    custom_parser: Option<Arc<dyn ResponseParser>>,
 }
 
 impl Resolver {
@@ -459,23 +799,31 @@ impl Resolver {
     pub fn new(config: &ExtractConfig, fence_output: bool) -> LangExtractResult<Self> {
         let validation_config = ValidationConfig {
             save_raw_outputs: config.debug, // Enable for debug mode by default
+            table_mode: config.table_mode,
             ..Default::default()
         };
 
         // Create raw outputs directory if it doesn't exist
         if validation_config.save_raw_outputs {
             if let Err(e) = fs::create_dir_all(&validation_config.raw_outputs_dir) {
-                log::warn!("Failed to create raw outputs directory: {}", e);
+                tracing::warn!(error = %e, "Failed to create raw outputs directory");
             }
         }
 
-        let type_coercer = TypeCoercer::new(validation_config.enable_type_coercion);
+        let type_coercer = TypeCoercer::new(validation_config.enable_type_coercion)
+            .with_date_order(validation_config.date_order)
+            .with_number_locale(validation_config.number_locale)
+            .with_scientific_integers(validation_config.scientific_integers)
+            .with_default_country(validation_config.default_country.clone())
+            .with_percentage_as_ratio(validation_config.percentage_as_ratio);
 
         Ok(Self {
             fence_output,
             format_type: config.format_type,
             validation_config,
             type_coercer,
+            strict_validation: config.strict_validation,
+            custom_parser: config.response_parser.clone(),
         })
     }
 
@@ -488,17 +836,24 @@ impl Resolver {
         // Create raw outputs directory if it doesn't exist
         if validation_config.save_raw_outputs {
             if let Err(e) = fs::create_dir_all(&validation_config.raw_outputs_dir) {
-                log::warn!("Failed to create raw outputs directory: {}", e);
+                tracing::warn!(error = %e, "Failed to create raw outputs directory");
             }
         }
 
-        let type_coercer = TypeCoercer::new(validation_config.enable_type_coercion);
+        let type_coercer = TypeCoercer::new(validation_config.enable_type_coercion)
+            .with_date_order(validation_config.date_order)
+            .with_number_locale(validation_config.number_locale)
+            .with_scientific_integers(validation_config.scientific_integers)
+            .with_default_country(validation_config.default_country.clone())
+            .with_percentage_as_ratio(validation_config.percentage_as_ratio);
 
         Ok(Self {
             fence_output,
             format_type: config.format_type,
             validation_config,
             type_coercer,
+            strict_validation: config.strict_validation,
+            custom_parser: config.response_parser.clone(),
         })
     }
 
@@ -507,6 +862,11 @@ impl Resolver {
         self.fence_output
     }
 
+    /// Get whether invalid model output fails the extraction
+    pub fn strict_validation(&self) -> bool {
+        self.strict_validation
+    }
+
     /// Save raw model output to a file for debugging/recovery
     pub fn save_raw_output(
         &self,
@@ -545,11 +905,12 @@ impl Resolver {
         fs::write(&filepath, content).map_err(|e| LangExtractError::IoError(e))?;
 
         let path_str = filepath.to_string_lossy().to_string();
-        log::info!("Saved raw output to: {}", path_str);
+        tracing::debug!(path = %path_str, "Saved raw model output");
         Ok(path_str)
     }
 
     /// Validate and parse model response with raw data preservation
+    #[tracing::instrument(level = "debug", skip_all)]
     pub fn validate_and_parse(
         &self,
         raw_response: &str,
@@ -558,12 +919,9 @@ impl Resolver {
         // Step 1: Always save raw output first if enabled
         let raw_file_path = if self.validation_config.save_raw_outputs {
             match self.save_raw_output(raw_response, Some("validation_parse")) {
-                Ok(path) => {
-                    println!("💾 Raw output saved to: {}", path);
-                    Some(path)
-                }
+                Ok(path) => Some(path),
                 Err(e) => {
-                    log::warn!("Failed to save raw output: {}", e);
+                    tracing::warn!(error = %e, "Failed to save raw output");
                     None
                 }
             }
@@ -572,20 +930,28 @@ impl Resolver {
         };
 
         // Step 2: Attempt to parse the response with enhanced cleaning and repair
-        println!("🔍 Parsing model response...");
-        let parse_result = self.parse_response_with_repair(raw_response, expected_fields);
+        tracing::debug!(length = raw_response.len(), "Parsing model response");
+        let parse_result = match &self.custom_parser {
+            Some(parser) => parser.parse(raw_response).map(|extractions| (extractions, false)),
+            None => self.parse_response_with_recovery(raw_response, expected_fields),
+        };
 
         // Step 3: Validate the parsed data
         let mut validation_result = match &parse_result {
-            Ok(extractions) => {
-                println!(
-                    "✅ Successfully parsed {} potential extractions",
-                    extractions.len()
-                );
-                self.validate_extractions(extractions, expected_fields)
+            Ok((extractions, recovered)) => {
+                tracing::debug!(count = extractions.len(), "Parsed potential extractions");
+                let mut result = self.validate_extractions(extractions, expected_fields);
+                if *recovered {
+                    result.truncated = true;
+                    result.warnings.push(ValidationWarning {
+                        message: "Model output was truncated; recovered the complete items and dropped the partial trailing one".to_string(),
+                        field_path: None,
+                    });
+                }
+                result
             }
             Err(parse_error) => {
-                println!("❌ Failed to parse model response");
+                tracing::debug!(error = %parse_error, "Failed to parse model response");
                 // If parsing failed, create validation result with error
                 ValidationResult {
                     is_valid: false,
@@ -599,6 +965,7 @@ impl Resolver {
                     corrected_data: None,
                     raw_output_file: raw_file_path.clone(), // Set the path here
                     coercion_summary: None,
+                    truncated: false,
                 }
             }
         };
@@ -610,17 +977,18 @@ impl Resolver {
 
         // Step 5: Return results - even if validation fails, we preserve the raw data
         match parse_result {
-            Ok(extractions) => Ok((extractions, validation_result)),
+            Ok((mut extractions, _)) => {
+                self.correct_allowed_values(&mut extractions);
+                Ok((extractions, validation_result))
+            }
             Err(e) => {
                 // Improved error reporting
                 match &validation_result.raw_output_file {
                     Some(path) => {
-                        log::warn!("Parse failed but raw data saved to: {}", path);
-                        println!("⚠️  Parse failed - check raw output at: {}", path);
+                        tracing::warn!(raw_output = %path, "Parse failed; raw model output was saved");
                     }
                     None => {
-                        log::warn!("Parse failed and no raw data was saved");
-                        println!("⚠️  Parse failed and raw data could not be saved");
+                        tracing::warn!("Parse failed and no raw model output was saved");
                     }
                 }
                 Err(e)
@@ -681,8 +1049,11 @@ impl Resolver {
                         // If we found multiple expected fields in the single extraction_text,
                         // this is likely malformed and should be re-parsed
                         if found_fields.len() > 1 {
-                            println!("🔧 Detected malformed JSON: {} extraction classes found in single extraction_text '{}'",
-                                     found_fields.len(), single_key);
+                            tracing::debug!(
+                                classes = found_fields.len(),
+                                key = %single_key,
+                                "Detected several extraction classes in a single extraction_text"
+                            );
 
                             // Try to extract individual field values
                             let mut repaired_obj = serde_json::Map::new();
@@ -714,10 +1085,7 @@ impl Resolver {
                             }
 
                             if !repaired_obj.is_empty() {
-                                println!(
-                                    "✅ Successfully repaired malformed JSON, extracted {} fields",
-                                    repaired_obj.len()
-                                );
+                                tracing::debug!(fields = repaired_obj.len(), "Repaired malformed JSON");
                                 return Some(serde_json::Value::Object(repaired_obj));
                             }
                         }
@@ -729,48 +1097,74 @@ impl Resolver {
     }
 
     /// Parse response with enhanced cleaning and repair capabilities
+    #[cfg(test)]
     fn parse_response_with_repair(
         &self,
         response: &str,
         expected_fields: &[String],
     ) -> LangExtractResult<Vec<Extraction>> {
+        self.parse_response_with_recovery(response, expected_fields)
+            .map(|(extractions, _)| extractions)
+    }
+
+    /// Parse response, also reporting whether truncated JSON had to be recovered
+    fn parse_response_with_recovery(
+        &self,
+        response: &str,
+        expected_fields: &[String],
+    ) -> LangExtractResult<(Vec<Extraction>, bool)> {
         // First, clean the response (remove code fences, etc.)
         let cleaned_response = self.clean_response(response);
-        println!(
-            "🧹 Cleaned response length: {} chars",
-            cleaned_response.len()
-        );
+        tracing::trace!(length = cleaned_response.len(), "Cleaned model response");
 
         // Try to parse as JSON first
         if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&cleaned_response) {
-            println!("📄 Parsed JSON successfully");
+            tracing::trace!("Parsed response as JSON");
 
             // Check if the JSON needs repair (malformed case with multiple classes in single extraction_text)
             if let Some(repaired_json) =
                 self.detect_and_repair_malformed_json(&json_value, expected_fields)
             {
-                println!("🔧 Applied JSON repair logic");
-                return self.parse_json_response(&repaired_json);
+                tracing::debug!("Applied JSON repair");
+                return Ok((self.parse_json_response(&repaired_json)?, false));
             } else {
-                return self.parse_json_response(&json_value);
+                return Ok((self.parse_json_response(&json_value)?, false));
+            }
+        }
+
+        // Only a cut-off JSON value may get past strict mode; anything else is prose around the JSON
+        if self.validation_config.strict_json_only {
+            let mut values = serde_json::Deserializer::from_str(&cleaned_response).into_iter::<Value>();
+            if !matches!(values.next(), Some(Err(e)) if e.is_eof()) {
+                return Err(LangExtractError::parsing(format!(
+                    "Response has content outside the top-level JSON value (strict_json_only): {}",
+                    cleaned_response
+                )));
             }
         }
 
+        // If the output was cut off, keep the complete items. This must run before
+        // the wrapped-object fallback, which would keep only the first complete object
+        if let Some(json_value) = recover_truncated_json(&cleaned_response) {
+            tracing::warn!("Recovered truncated JSON model output; dropped the incomplete trailing item");
+            return Ok((self.parse_json_response(&json_value)?, true));
+        }
+
         // If that fails, try to extract JSON from the response (in case it's wrapped)
-        if let Some(json_start) = cleaned_response.find('{') {
+        if let Some(json_start) = cleaned_response.find('{').filter(|_| !self.validation_config.strict_json_only) {
             if let Some(json_end) = cleaned_response.rfind('}') {
                 let json_str = &cleaned_response[json_start..=json_end];
                 if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(json_str) {
-                    println!("📄 Extracted and parsed JSON from wrapped content");
+                    tracing::trace!("Parsed JSON extracted from wrapped content");
 
                     // Check if the extracted JSON needs repair
                     if let Some(repaired_json) =
                         self.detect_and_repair_malformed_json(&json_value, expected_fields)
                     {
-                        println!("🔧 Applied JSON repair logic to extracted content");
-                        return self.parse_json_response(&repaired_json);
+                        tracing::debug!("Applied JSON repair to extracted content");
+                        return Ok((self.parse_json_response(&repaired_json)?, false));
                     } else {
-                        return self.parse_json_response(&json_value);
+                        return Ok((self.parse_json_response(&json_value)?, false));
                     }
                 }
             }
@@ -784,6 +1178,27 @@ impl Resolver {
 
     /// Parse JSON response into extractions
     fn parse_json_response(&self, json: &serde_json::Value) -> LangExtractResult<Vec<Extraction>> {
+        let json = match &self.validation_config.root_path {
+            Some(pointer) => json.pointer(pointer).ok_or_else(|| {
+                LangExtractError::parsing(format!(
+                    "root_path '{}' does not resolve to a value in the model output",
+                    pointer
+                ))
+            })?,
+            None => json,
+        };
+        let mut extractions = self.parse_json_items(json)?;
+
+        // Record the order extractions appeared in the response
+        for (index, extraction) in extractions.iter_mut().enumerate() {
+            extraction.extraction_index = Some(index);
+        }
+
+        Ok(extractions)
+    }
+
+    /// Parse the top-level JSON shapes (array, wrapper object, flat object)
+    fn parse_json_items(&self, json: &serde_json::Value) -> LangExtractResult<Vec<Extraction>> {
         let mut extractions = Vec::new();
 
         // Handle array at top level
@@ -794,16 +1209,26 @@ impl Resolver {
             return Ok(extractions);
         }
 
-        // Handle object with data/results wrapper
+        // Handle object wrapping the array in one of the configured keys
         if let Some(obj) = json.as_object() {
-            if let Some(data_array) = obj.get("data").and_then(|v| v.as_array()) {
-                for (index, item) in data_array.iter().enumerate() {
-                    extractions.extend(self.parse_single_item(item, Some(index))?);
+            if let Some(rows) = obj.get(TABLE_KEY).and_then(|v| v.as_array()).filter(|_| self.validation_config.table_mode) {
+                for (row, item) in rows.iter().enumerate() {
+                    extractions.extend(self.parse_table_row(item, row)?);
                 }
+                // Fields next to the table, such as an invoice number, are parsed as a flat item
+                let mut rest = obj.clone();
+                rest.remove(TABLE_KEY);
+                extractions.extend(self.parse_single_item(&Value::Object(rest), None)?);
                 return Ok(extractions);
             }
-            if let Some(results_array) = obj.get("results").and_then(|v| v.as_array()) {
-                for (index, item) in results_array.iter().enumerate() {
+
+            let wrapped = self
+                .validation_config
+                .wrapper_keys
+                .iter()
+                .find_map(|key| obj.get(key).and_then(|v| v.as_array()));
+            if let Some(array) = wrapped {
+                for (index, item) in array.iter().enumerate() {
                     extractions.extend(self.parse_single_item(item, Some(index))?);
                 }
                 return Ok(extractions);
@@ -816,6 +1241,17 @@ impl Resolver {
         Ok(extractions)
     }
 
+    /// Parse one table row into cell extractions grouped under `row`, each tagged with its column
+    fn parse_table_row(&self, item: &serde_json::Value, row: usize) -> LangExtractResult<Vec<Extraction>> {
+        let mut cells = self.parse_single_item(item, Some(row))?;
+        for cell in &mut cells {
+            cell.attributes
+                .get_or_insert_with(Default::default)
+                .insert(TABLE_COLUMN_ATTRIBUTE.to_string(), Value::String(cell.extraction_class.clone()));
+        }
+        Ok(cells)
+    }
+
     /// Parse a single item (object or primitive) into extractions
     fn parse_single_item(
         &self,
@@ -826,31 +1262,73 @@ impl Resolver {
 
         match item {
             Value::Object(obj) => {
-                let mut extraction = Extraction::default();
+                // Absence assertion: the model looked for the class and found nothing
+                if let (Some(Value::String(class)), Some(Value::Bool(false))) =
+                    (obj.get(ABSENT_CLASS_KEY), obj.get(PRESENT_KEY))
+                {
+                    let mut extraction = Extraction::absent(self.canonical_class(class));
+                    extraction.group_index = index;
+                    return Ok(vec![extraction]);
+                }
 
-                if let Some(idx) = index {
-                    extraction.group_index = Some(idx);
+                // Attribute maps keyed by the class they belong to ("name_attributes" -> "name"),
+                // plus a plain "attributes" object shared by the item's extractions
+                let mut attributes_by_class = std::collections::HashMap::new();
+                let shared_attributes: Option<std::collections::HashMap<String, Value>> = obj
+                    .get(ATTRIBUTES_KEY)
+                    .and_then(|value| value.as_object())
+                    .map(|map| map.iter().map(|(k, v)| (k.clone(), v.clone())).collect());
+                for (key, value) in obj {
+                    if let Some(class) = key.strip_suffix(ATTRIBUTES_SUFFIX) {
+                        if let Some(serde_map) = value.as_object() {
+                            let attributes: std::collections::HashMap<String, Value> =
+                                serde_map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                            attributes_by_class.insert(self.canonical_class(class), attributes);
+                        }
+                    }
                 }
 
+                // One extraction per field; fields of the same object share a group
                 for (key, value) in obj {
-                    if key.ends_with(ATTRIBUTES_SUFFIX) {
-                        extraction.attributes = value.as_object().map(|serde_map| {
-                            // 将 serde_json::Map 转换为迭代器（消耗自身，逐个取出键值对）
-                            serde_map.into_iter().map(|(k, v)| (k.clone(), v.clone())).collect() // 直接收集为 HashMap<String, Value>
-                        })
-                    } else {
-                        let extraction_text = match value {
-                            Value::String(s) => s.clone(),
-                            Value::Number(n) => n.to_string(),
-                            Value::Bool(b) => b.to_string(),
-                            Value::Array(_) | Value::Object(_) => value.to_string(),
-                            Value::Null => continue,
-                        };
-                        extraction.extraction_text = extraction_text;
-                        extraction.extraction_class = key.clone();
+                    if key.ends_with(ATTRIBUTES_SUFFIX) || (key == ATTRIBUTES_KEY && shared_attributes.is_some()) {
+                        continue;
+                    }
+                    if self.validation_config.flatten_nested && (value.is_object() || value.is_array()) {
+                        let mut leaves = Vec::new();
+                        flatten_value(&self.canonical_class(key), value, &mut leaves);
+                        for (path, extraction_text) in leaves {
+                            let mut extraction = Extraction::new(path, extraction_text);
+                            extraction.group_index = index;
+                            extraction.attributes = attributes_by_class
+                                .remove(&extraction.extraction_class)
+                                .or_else(|| shared_attributes.clone());
+                            extractions.push(extraction);
+                        }
+                        continue;
+                    }
+                    let extraction_text = match value {
+                        Value::String(s) => s.clone(),
+                        Value::Number(n) => n.to_string(),
+                        Value::Bool(b) => b.to_string(),
+                        Value::Array(_) | Value::Object(_) => value.to_string(),
+                        Value::Null => continue,
+                    };
+
+                    let class = self.canonical_class(key);
+                    let mut extraction = Extraction::new(class, extraction_text);
+                    extraction.group_index = index;
+                    extraction.attributes = attributes_by_class
+                        .remove(&extraction.extraction_class)
+                        .or_else(|| shared_attributes.clone());
+                    extractions.push(extraction);
+                }
+
+                // Attributes whose class key is absent belong to a lone extraction
+                if extractions.len() == 1 && extractions[0].attributes.is_none() {
+                    if let Some((_, attributes)) = attributes_by_class.into_iter().next() {
+                        extractions[0].attributes = Some(attributes);
                     }
                 }
-                extractions.push(extraction);
             }
             Value::String(s) => {
                 let extraction_class = if let Some(idx) = index {
@@ -871,6 +1349,89 @@ impl Resolver {
         Ok(extractions)
     }
 
+    /// Whether `text` is one of the `allowed` values
+    fn is_allowed_value(&self, text: &str, allowed: &[String]) -> bool {
+        let text = text.trim();
+        if self.validation_config.allowed_values_case_insensitive {
+            let text = text.to_lowercase();
+            allowed.iter().any(|value| value.trim().to_lowercase() == text)
+        } else {
+            allowed.iter().any(|value| value.trim() == text)
+        }
+    }
+
+    /// Find the allowed value closest to `text`, if within the configured edit distance
+    fn nearest_allowed_value<'a>(&self, text: &str, allowed: &'a [String]) -> Option<&'a str> {
+        let max_distance = self.validation_config.allowed_values_max_edit_distance?;
+        let normalize = |value: &str| {
+            if self.validation_config.allowed_values_case_insensitive {
+                value.trim().to_lowercase()
+            } else {
+                value.trim().to_string()
+            }
+        };
+        let text = normalize(text);
+
+        allowed
+            .iter()
+            .map(|value| (value, edit_distance(&text, &normalize(value))))
+            .filter(|(_, distance)| *distance <= max_distance)
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(value, _)| value.as_str())
+    }
+
+    /// Describe how an extraction violates the length constraints for its class, if it does
+    fn check_length_constraint(&self, extraction: &Extraction) -> Option<String> {
+        let (min, max) = self
+            .validation_config
+            .field_length_constraints
+            .get(&extraction.extraction_class)?;
+        let length = extraction.extraction_text.trim().chars().count();
+
+        if min.is_some_and(|min| length < min) || max.is_some_and(|max| length > max) {
+            Some(format!(
+                "Extraction text for field '{}' has {} chars, expected {}",
+                extraction.extraction_class,
+                length,
+                describe_length_bounds(*min, *max)
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Map a model output key to its canonical extraction class via `field_aliases`
+    fn canonical_class(&self, key: &str) -> String {
+        let aliases = &self.validation_config.field_aliases;
+        let canonical = if self.validation_config.field_aliases_exact_match {
+            aliases.get(key)
+        } else {
+            aliases
+                .get(key)
+                .or_else(|| aliases.iter().find(|(alias, _)| alias.eq_ignore_ascii_case(key)).map(|(_, class)| class))
+        };
+        canonical.cloned().unwrap_or_else(|| key.to_string())
+    }
+
+    /// Replace disallowed values with the nearest allowed one, keeping the original text in [`ORIGINAL_TEXT_ATTRIBUTE`]
+    fn correct_allowed_values(&self, extractions: &mut [Extraction]) {
+        for extraction in extractions {
+            let Some(allowed) = self.validation_config.allowed_values.get(&extraction.extraction_class) else {
+                continue;
+            };
+            if self.is_allowed_value(&extraction.extraction_text, allowed) {
+                continue;
+            }
+            if let Some(corrected) = self.nearest_allowed_value(&extraction.extraction_text, allowed) {
+                let original = std::mem::replace(&mut extraction.extraction_text, corrected.to_string());
+                extraction
+                    .attributes
+                    .get_or_insert_with(HashMap::new)
+                    .insert(ORIGINAL_TEXT_ATTRIBUTE.to_string(), Value::String(original));
+            }
+        }
+    }
+
     /// Validate extractions against expected schema
     fn validate_extractions(
         &self,
@@ -903,7 +1464,7 @@ impl Resolver {
         // Validate individual extractions and attempt type coercion
         for extraction in extractions {
             // Check for empty extraction text
-            if extraction.extraction_text.trim().is_empty() {
+            if extraction.present && extraction.extraction_text.trim().is_empty() {
                 warnings.push(ValidationWarning {
                     message: format!(
                         "Empty extraction text for field '{}'",
@@ -925,6 +1486,59 @@ impl Resolver {
                 });
             }
 
+            // Check the extraction length against the constraints for its class
+            if let Some(message) = self.check_length_constraint(extraction) {
+                if self.validation_config.length_violations_as_errors {
+                    errors.push(ValidationError {
+                        message,
+                        field_path: Some(extraction.extraction_class.clone()),
+                        expected: self.validation_config.field_length_constraints
+                            .get(&extraction.extraction_class)
+                            .map(|(min, max)| describe_length_bounds(*min, *max)),
+                        actual: Some(format!("{} chars", extraction.extraction_text.trim().chars().count())),
+                    });
+                    is_valid = false;
+                } else {
+                    warnings.push(ValidationWarning {
+                        message,
+                        field_path: Some(extraction.extraction_class.clone()),
+                    });
+                }
+            }
+
+            // Check the extraction against the allowed values for its class
+            if let Some(allowed) = self
+                .validation_config
+                .allowed_values
+                .get(&extraction.extraction_class)
+            {
+                if !self.is_allowed_value(&extraction.extraction_text, allowed) {
+                    match self.nearest_allowed_value(&extraction.extraction_text, allowed) {
+                        Some(corrected) => {
+                            warnings.push(ValidationWarning {
+                                message: format!(
+                                    "Corrected '{}' to allowed value '{}' for field '{}'",
+                                    extraction.extraction_text, corrected, extraction.extraction_class
+                                ),
+                                field_path: Some(extraction.extraction_class.clone()),
+                            });
+                        }
+                        None => {
+                            errors.push(ValidationError {
+                                message: format!(
+                                    "Value '{}' is not allowed for field '{}'",
+                                    extraction.extraction_text, extraction.extraction_class
+                                ),
+                                field_path: Some(extraction.extraction_class.clone()),
+                                expected: Some(format!("One of: {}", allowed.join(", "))),
+                                actual: Some(extraction.extraction_text.clone()),
+                            });
+                            is_valid = false;
+                        }
+                    }
+                }
+            }
+
             // Attempt type coercion if enabled
             if self.validation_config.enable_type_coercion {
                 let coercion_result = self
@@ -991,8 +1605,156 @@ impl Resolver {
             corrected_data,
             raw_output_file: None, // Set by caller
             coercion_summary,
+            truncated: false,
+        }
+    }
+}
+
+impl ResponseParser for Resolver {
+    fn parse(&self, raw: &str) -> LangExtractResult<Vec<Extraction>> {
+        self.validate_and_parse(raw, &[]).map(|(extractions, _)| extractions)
+    }
+}
+
+/// Human-readable description of a min/max length range
+fn describe_length_bounds(min: Option<usize>, max: Option<usize>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("between {} and {} chars", min, max),
+        (Some(min), None) => format!("at least {} chars", min),
+        (None, Some(max)) => format!("at most {} chars", max),
+        (None, None) => "any length".to_string(),
+    }
+}
+
+/// Digits of a phone number written with the usual separators, or `None` if other characters appear
+fn phone_digits(value: &str) -> Option<String> {
+    value
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '(' | ')' | '.' | '-' | '/'))
+        .then(|| value.chars().filter(char::is_ascii_digit).collect())
+}
+
+/// Split a `+` or `00` prefixed number into its region and national significant number
+fn parse_international_phone(value: &str) -> Option<(&'static PhoneRegion, String)> {
+    let rest = value.strip_prefix('+').or_else(|| value.strip_prefix("00"))?;
+    // "+44 (0)20 ..." repeats the trunk prefix for national callers
+    let digits = phone_digits(&rest.replacen("(0)", "", 1))?;
+
+    (1..=3).filter(|&len| len < digits.len()).find_map(|len| {
+        let (code, national) = digits.split_at(len);
+        PHONE_REGIONS
+            .iter()
+            .find(|r| r.calling_code == code && r.accepts(national))
+            .map(|region| (region, national.to_string()))
+    })
+}
+
+/// National significant number of a number dialed inside `region`
+///
+/// Plain digit runs are only accepted with the region's trunk prefix, so that
+/// ordinary integers are not mistaken for phone numbers.
+fn parse_national_phone(value: &str, region: &PhoneRegion) -> Option<String> {
+    let digits = phone_digits(value)?;
+    let has_separator = digits.len() < value.len();
+    match region.trunk_prefix.and_then(|trunk| digits.strip_prefix(trunk)) {
+        Some(national) if region.accepts(national) => Some(national.to_string()),
+        _ if has_separator && region.accepts(&digits) => Some(digits),
+        _ => None,
+    }
+}
+
+/// Recover the complete part of JSON that was cut off mid-output
+///
+/// Truncates at the last point where an item of the enclosing array (or the
+/// top-level container) was complete, then closes any brackets left open.
+/// Partially written items are dropped rather than half-kept.
+fn recover_truncated_json(text: &str) -> Option<Value> {
+    let start = text.find(['[', '{'])?;
+    let text = &text[start..];
+
+    // (cut position, closers still open at that point)
+    let mut cut_points: Vec<(usize, Vec<char>)> = Vec::new();
+    let mut open: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '[' => open.push(']'),
+            '{' => open.push('}'),
+            ']' | '}' => {
+                if open.pop() != Some(c) {
+                    return None;
+                }
+                if open.is_empty() {
+                    // The document is complete; nothing to recover
+                    return None;
+                }
+                cut_points.push((i + 1, open.clone()));
+            }
+            ',' if open.len() == 1 || open.last() == Some(&']') => cut_points.push((i, open.clone())),
+            _ => {}
         }
     }
+
+    // Prefer the latest cut that yields valid JSON
+    cut_points.into_iter().rev().find_map(|(cut, still_open)| {
+        if still_open.len() > 1 && still_open.last() == Some(&'}') {
+            // Would leave an object of the enclosing array half-written
+            return None;
+        }
+        let mut candidate = text[..cut].to_string();
+        candidate.extend(still_open.iter().rev());
+        serde_json::from_str(&candidate).ok()
+    })
+}
+
+/// Collect the scalar leaves of `value` as (dotted path, text) pairs, skipping nulls
+fn flatten_value(path: &str, value: &Value, leaves: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(obj) => {
+            for (key, child) in obj {
+                flatten_value(&format!("{}.{}", path, key), child, leaves);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                flatten_value(&format!("{}[{}]", path, i), child, leaves);
+            }
+        }
+        Value::String(s) => leaves.push((path.to_string(), s.clone())),
+        Value::Number(n) => leaves.push((path.to_string(), n.to_string())),
+        Value::Bool(b) => leaves.push((path.to_string(), b.to_string())),
+        Value::Null => {}
+    }
+}
+
+/// Levenshtein edit distance between two strings, counted in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
 }
 
 #[cfg(test)]
@@ -1024,6 +1786,128 @@ mod tests {
         Resolver::with_validation_config(&config, true, validation_config).unwrap()
     }
 
+    fn create_allowed_values_resolver(
+        case_insensitive: bool,
+        max_edit_distance: Option<usize>,
+    ) -> Resolver {
+        let mut allowed_values = HashMap::new();
+        allowed_values.insert(
+            "status".to_string(),
+            vec!["active".to_string(), "inactive".to_string(), "pending".to_string()],
+        );
+        let validation_config = ValidationConfig {
+            save_raw_outputs: false,
+            enable_type_coercion: false,
+            allowed_values,
+            allowed_values_case_insensitive: case_insensitive,
+            allowed_values_max_edit_distance: max_edit_distance,
+            ..Default::default()
+        };
+        Resolver::with_validation_config(&create_test_config(), true, validation_config).unwrap()
+    }
+
+    #[test]
+    fn test_allowed_values_accepts_known_value() {
+        let resolver = create_allowed_values_resolver(true, None);
+        let extractions = vec![
+            Extraction::new("status".to_string(), "Active".to_string()),
+            Extraction::new("name".to_string(), "John".to_string()),
+        ];
+
+        let result = resolver.validate_extractions(&extractions, &[]);
+        assert!(result.is_valid);
+        assert!(result.errors.is_empty());
+
+        // Case matters unless the case-insensitive option is set
+        let resolver = create_allowed_values_resolver(false, None);
+        let result = resolver.validate_extractions(&extractions, &[]);
+        assert!(!result.is_valid);
+    }
+
+    #[test]
+    fn test_allowed_values_rejects_unknown_value() {
+        let resolver = create_allowed_values_resolver(false, Some(2));
+        let extractions = vec![Extraction::new("status".to_string(), "archived".to_string())];
+
+        let result = resolver.validate_extractions(&extractions, &[]);
+        assert!(!result.is_valid);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].field_path.as_deref(), Some("status"));
+        assert_eq!(result.errors[0].actual.as_deref(), Some("archived"));
+    }
+
+    #[test]
+    fn test_allowed_values_corrects_near_miss() {
+        let resolver = create_allowed_values_resolver(false, Some(2));
+
+        let (extractions, result) = resolver
+            .validate_and_parse(r#"[{"status": "pendng"}, {"status": "activ"}]"#, &[])
+            .unwrap();
+        assert!(result.is_valid);
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("'pendng' to allowed value 'pending'")));
+
+        let corrected: Vec<_> = extractions
+            .iter()
+            .map(|e| {
+                let original = &e.attributes.as_ref().unwrap()[ORIGINAL_TEXT_ATTRIBUTE];
+                (e.extraction_text.as_str(), original.as_str().unwrap())
+            })
+            .collect();
+        assert_eq!(corrected, vec![("pending", "pendng"), ("active", "activ")]);
+    }
+
+    fn create_length_constrained_resolver(as_errors: bool) -> Resolver {
+        let mut field_length_constraints = HashMap::new();
+        field_length_constraints.insert("name".to_string(), (Some(2), Some(40)));
+        let validation_config = ValidationConfig {
+            save_raw_outputs: false,
+            enable_type_coercion: false,
+            field_length_constraints,
+            length_violations_as_errors: as_errors,
+            ..Default::default()
+        };
+        Resolver::with_validation_config(&create_test_config(), true, validation_config).unwrap()
+    }
+
+    #[test]
+    fn test_length_constraint_accepts_value_within_bounds() {
+        let resolver = create_length_constrained_resolver(true);
+        let extractions = vec![Extraction::new("name".to_string(), "Dr. Jane Smith".to_string())];
+
+        let result = resolver.validate_extractions(&extractions, &[]);
+        assert!(result.is_valid);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_length_constraint_rejects_too_long_name() {
+        let paragraph = "Jane Smith joined the company in 2019 and has since led the platform team through two reorganizations.";
+        let extractions = vec![Extraction::new("name".to_string(), paragraph.to_string())];
+
+        let resolver = create_length_constrained_resolver(true);
+        let result = resolver.validate_extractions(&extractions, &[]);
+        assert!(!result.is_valid);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].expected.as_deref(), Some("between 2 and 40 chars"));
+
+        // As warnings the extraction is flagged but the result stays valid
+        let resolver = create_length_constrained_resolver(false);
+        let result = resolver.validate_extractions(&extractions, &[]);
+        assert!(result.is_valid);
+        assert!(result.warnings.iter().any(|w| w.message.contains("expected between 2 and 40 chars")));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("pending", "pending"), 0);
+        assert_eq!(edit_distance("pendng", "pending"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
     #[test]
     fn test_validation_config_default() {
         let config = ValidationConfig::default();
@@ -1119,6 +2003,302 @@ mod tests {
         assert_eq!(city_extraction.extraction_text, "NYC");
     }
 
+    #[test]
+    fn test_field_aliases_map_keys_to_canonical_class() {
+        let mut field_aliases = HashMap::new();
+        field_aliases.insert("full_name".to_string(), "person".to_string());
+        field_aliases.insert("name".to_string(), "person".to_string());
+        let validation_config = ValidationConfig {
+            save_raw_outputs: false,
+            field_aliases,
+            ..Default::default()
+        };
+        let resolver =
+            Resolver::with_validation_config(&create_test_config(), true, validation_config.clone()).unwrap();
+        let json_response = r#"[
+            {"full_name": "Alice Smith", "full_name_attributes": {"role": "engineer"}},
+            {"Name": "Bob"},
+            {"city": "Paris"}
+        ]"#;
+
+        let extractions = resolver.parse_response_with_repair(json_response, &[]).unwrap();
+        let classes: Vec<&str> = extractions.iter().map(|e| e.extraction_class.as_str()).collect();
+        assert_eq!(classes, vec!["person", "person", "city"]);
+        assert_eq!(
+            extractions[0].attributes.as_ref().unwrap().get("role"),
+            Some(&serde_json::json!("engineer"))
+        );
+
+        // Exact matching leaves differently-cased keys alone
+        let validation_config = ValidationConfig {
+            field_aliases_exact_match: true,
+            ..validation_config
+        };
+        let resolver = Resolver::with_validation_config(&create_test_config(), true, validation_config).unwrap();
+        let extractions = resolver.parse_response_with_repair(json_response, &[]).unwrap();
+        let classes: Vec<&str> = extractions.iter().map(|e| e.extraction_class.as_str()).collect();
+        assert_eq!(classes, vec!["person", "Name", "city"]);
+    }
+
+    fn create_flattening_resolver(flatten_nested: bool) -> Resolver {
+        let validation_config = ValidationConfig {
+            save_raw_outputs: false,
+            flatten_nested,
+            ..Default::default()
+        };
+        Resolver::with_validation_config(&create_test_config(), true, validation_config).unwrap()
+    }
+
+    fn class_text_pairs(extractions: &[Extraction]) -> Vec<(&str, &str)> {
+        extractions
+            .iter()
+            .map(|e| (e.extraction_class.as_str(), e.extraction_text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_flatten_nested_objects_into_dotted_paths() {
+        let json_response = r#"[{"name": "Alice", "address": {"city": "NYC", "zip": "10001", "geo": {"lat": 40.7}}}]"#;
+
+        let extractions = create_flattening_resolver(true)
+            .parse_response_with_repair(json_response, &[])
+            .unwrap();
+        let mut found = class_text_pairs(&extractions);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                ("address.city", "NYC"),
+                ("address.geo.lat", "40.7"),
+                ("address.zip", "10001"),
+                ("name", "Alice"),
+            ]
+        );
+        assert!(extractions.iter().all(|e| e.group_index == Some(0)));
+
+        // Default keeps the whole object as one stringified extraction
+        let extractions = create_flattening_resolver(false)
+            .parse_response_with_repair(json_response, &[])
+            .unwrap();
+        let address = extractions.iter().find(|e| e.extraction_class == "address").unwrap();
+        assert_eq!(extractions.len(), 2);
+        assert!(address.extraction_text.contains("\"city\":\"NYC\""));
+    }
+
+    #[test]
+    fn test_flatten_nested_arrays_use_bracket_indices() {
+        let json_response = r#"[{"order": "A1", "items": [{"name": "pen", "qty": 2}, {"name": "ink", "tags": ["blue", null]}]}]"#;
+
+        let extractions = create_flattening_resolver(true)
+            .parse_response_with_repair(json_response, &[])
+            .unwrap();
+        let mut found = class_text_pairs(&extractions);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                ("items[0].name", "pen"),
+                ("items[0].qty", "2"),
+                ("items[1].name", "ink"),
+                ("items[1].tags[0]", "blue"),
+                ("order", "A1"),
+            ]
+        );
+    }
+
+    fn create_root_path_resolver(root_path: &str) -> Resolver {
+        let validation_config = ValidationConfig {
+            save_raw_outputs: false,
+            root_path: Some(root_path.to_string()),
+            ..Default::default()
+        };
+        Resolver::with_validation_config(&create_test_config(), true, validation_config).unwrap()
+    }
+
+    #[test]
+    fn test_root_path_selects_nested_array() {
+        let json_response = r#"{"meta": {"model": "x"}, "result": {"entities": [{"person": "Alice"}, {"person": "Bob"}]}}"#;
+
+        let extractions = create_root_path_resolver("/result/entities")
+            .parse_response_with_repair(json_response, &[])
+            .unwrap();
+        assert_eq!(class_text_pairs(&extractions), vec![("person", "Alice"), ("person", "Bob")]);
+
+        let extractions = create_root_path_resolver("/result/entities/1")
+            .parse_response_with_repair(json_response, &[])
+            .unwrap();
+        assert_eq!(class_text_pairs(&extractions), vec![("person", "Bob")]);
+    }
+
+    #[test]
+    fn test_strict_json_only_rejects_prose_wrapped_output() {
+        let validation_config = ValidationConfig {
+            save_raw_outputs: false,
+            strict_json_only: true,
+            ..Default::default()
+        };
+        let strict = Resolver::with_validation_config(&create_test_config(), true, validation_config).unwrap();
+        let expected = vec!["person".to_string()];
+
+        let clean = strict.parse_response_with_repair(r#"[{"person": "Alice"}]"#, &expected).unwrap();
+        assert_eq!(clean[0].extraction_text, "Alice");
+        let fenced = strict.parse_response_with_repair("```json\n[{\"person\": \"Bob\"}]\n```\n", &expected).unwrap();
+        assert_eq!(fenced[0].extraction_text, "Bob");
+
+        let prose = r#"Sure! Here's your data: {"person": "Carol"}"#;
+        assert!(strict.parse_response_with_repair(prose, &expected).is_err());
+        assert!(strict.parse_response_with_repair(r#"{"person": "Carol"} Hope this helps!"#, &expected).is_err());
+        assert_eq!(create_test_resolver().parse_response_with_repair(prose, &expected).unwrap()[0].extraction_text, "Carol");
+    }
+
+    #[test]
+    fn test_root_path_that_does_not_resolve_is_an_error() {
+        let json_response = r#"{"result": {"entities": []}}"#;
+
+        let error = create_root_path_resolver("/result/people")
+            .parse_response_with_repair(json_response, &[])
+            .unwrap_err();
+        assert!(error.to_string().contains("/result/people"));
+    }
+
+    #[test]
+    fn test_wrapper_keys_unwrap_extraction_arrays() {
+        let validation_config = ValidationConfig {
+            save_raw_outputs: false,
+            ..Default::default()
+        };
+        let resolver = Resolver::with_validation_config(&create_test_config(), true, validation_config).unwrap();
+        for key in ["data", "results", "extractions", "entities"] {
+            let json_response = format!(r#"{{"{}": [{{"person": "Alice"}}, {{"person": "Bob"}}]}}"#, key);
+            let extractions = resolver.parse_response_with_repair(&json_response, &[]).unwrap();
+            assert_eq!(class_text_pairs(&extractions), vec![("person", "Alice"), ("person", "Bob")], "{}", key);
+        }
+
+        // Unknown wrappers fall through to flat parsing
+        let json_response = r#"{"people": [{"person": "Alice"}], "place": "Paris"}"#;
+        let extractions = resolver.parse_response_with_repair(json_response, &[]).unwrap();
+        assert!(extractions.iter().any(|e| e.extraction_class == "place" && e.extraction_text == "Paris"));
+        assert!(!extractions.iter().any(|e| e.extraction_class == "person"));
+
+        let validation_config = ValidationConfig {
+            save_raw_outputs: false,
+            wrapper_keys: vec!["people".to_string()],
+            ..Default::default()
+        };
+        let resolver = Resolver::with_validation_config(&create_test_config(), true, validation_config).unwrap();
+        let extractions = resolver.parse_response_with_repair(json_response, &[]).unwrap();
+        assert_eq!(class_text_pairs(&extractions), vec![("person", "Alice")]);
+    }
+
+    #[test]
+    fn test_absence_assertion_round_trip() {
+        let validation_config = ValidationConfig {
+            save_raw_outputs: false,
+            ..Default::default()
+        };
+        let resolver = Resolver::with_validation_config(&create_test_config(), true, validation_config).unwrap();
+        let json_response = r#"[{"person": "Alice"}, {"class": "ssn", "present": false}]"#;
+
+        let extractions = resolver.parse_response_with_repair(json_response, &[]).unwrap();
+        assert_eq!(extractions.len(), 2);
+        assert!(extractions[0].present);
+        assert_eq!(extractions[1].extraction_class, "ssn");
+        assert_eq!(extractions[1].extraction_text, "");
+        assert!(!extractions[1].present);
+
+        let serialized = serde_json::to_string(&extractions).unwrap();
+        let restored: Vec<Extraction> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(restored, extractions);
+        assert_eq!(serialized.matches("\"present\"").count(), 1);
+
+        let mut document = crate::data::AnnotatedDocument::with_extractions(extractions, "Alice has no number on file.".to_string());
+        crate::alignment::align_extractions(
+            document.text.as_deref().unwrap(),
+            document.extractions.as_mut().unwrap(),
+            crate::alignment::AlignmentMode::default(),
+        )
+        .unwrap();
+        let absent = &document.extractions.as_ref().unwrap()[1];
+        assert!(absent.char_interval.is_none());
+        assert!(crate::visualize(&document, false).unwrap().contains("[ssn] ✗ not present"));
+    }
+
+    #[test]
+    fn test_truncated_array_recovers_complete_objects() {
+        let resolver = create_flattening_resolver(false);
+        let truncated = r#"```json
+[
+  {"name": "Alice", "age": "30"},
+  {"name": "Bob, Jr.", "age": "25"},
+  {"name": "Carol", "ag"#;
+
+        let extractions = resolver.parse_response_with_repair(truncated, &[]).unwrap();
+        let names: Vec<&str> = extractions
+            .iter()
+            .filter(|e| e.extraction_class == "name")
+            .map(|e| e.extraction_text.as_str())
+            .collect();
+        assert_eq!(names, vec!["Alice", "Bob, Jr."]);
+        assert_eq!(extractions.len(), 4);
+
+        let (_, validation) = resolver.validate_and_parse(truncated, &[]).unwrap();
+        assert!(validation.warnings.iter().any(|w| w.message.contains("truncated")));
+    }
+
+    #[test]
+    fn test_truncated_wrapper_and_string_recovery() {
+        let temp_dir = TempDir::new().unwrap();
+        let resolver = create_test_resolver_with_temp_dir(&temp_dir);
+
+        // Cut off inside a string of the third object, within a data wrapper
+        let truncated = r#"{"data": [{"person": "Alice"}, {"person": "Bob"}, {"person": "Car"#;
+        let extractions = resolver.parse_response_with_repair(truncated, &[]).unwrap();
+        let texts: Vec<&str> = extractions.iter().map(|e| e.extraction_text.as_str()).collect();
+        assert_eq!(texts, vec!["Alice", "Bob"]);
+
+        // Nothing complete to keep
+        assert!(resolver.parse_response_with_repair(r#"[{"person": "Ali"#, &[]).is_err());
+
+        // A single complete object is recovered as truncated output, not as a wrapped object
+        let (extractions, validation) = resolver.validate_and_parse(r#"[{"person": "Alice"}, {"person": "Bo"#, &[]).unwrap();
+        assert_eq!(extractions.len(), 1);
+        assert!(validation.truncated);
+    }
+
+    #[test]
+    fn test_parse_grouped_multi_field_json() {
+        let resolver = create_test_resolver();
+        let json_response = r#"[
+            {"name": "Alice", "age": "30", "name_attributes": {"role": "engineer"}},
+            {"name": "Bob", "age": "25"}
+        ]"#;
+        let expected_fields = vec!["name".to_string(), "age".to_string()];
+
+        let extractions = resolver
+            .parse_response_with_repair(json_response, &expected_fields)
+            .unwrap();
+        assert_eq!(extractions.len(), 4);
+
+        let alice = extractions.iter().find(|e| e.extraction_text == "Alice").unwrap();
+        assert_eq!(alice.group_index, Some(0));
+        assert_eq!(
+            alice.attributes.as_ref().unwrap().get("role"),
+            Some(&serde_json::json!("engineer"))
+        );
+
+        let doc = crate::data::AnnotatedDocument::with_extractions(extractions, String::new());
+        let groups = doc.grouped_extractions();
+        assert_eq!(groups.len(), 2);
+
+        let group_texts: Vec<std::collections::HashSet<&str>> = groups
+            .iter()
+            .map(|g| g.iter().map(|e| e.extraction_text.as_str()).collect())
+            .collect();
+        assert_eq!(group_texts[0], ["Alice", "30"].into_iter().collect());
+        assert_eq!(group_texts[1], ["Bob", "25"].into_iter().collect());
+        assert!(groups.iter().flatten().all(|e| e.extraction_index.is_some()));
+    }
+
     #[test]
     fn test_parse_invalid_json() {
         let resolver = create_test_resolver();
@@ -1209,6 +2389,71 @@ mod tests {
         assert!(std::path::Path::new(&raw_file).exists());
     }
 
+    /// Records the span names and event messages emitted while it is the default subscriber
+    #[derive(Default)]
+    struct CapturingSubscriber {
+        spans: std::sync::Mutex<Vec<String>>,
+        events: std::sync::Mutex<Vec<(tracing::Level, String)>>,
+    }
+
+    struct MessageVisitor(String);
+
+    impl tracing::field::Visit for MessageVisitor {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl tracing::Subscriber for CapturingSubscriber {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(span.metadata().name().to_string());
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut visitor = MessageVisitor(String::new());
+            event.record(&mut visitor);
+            self.events.lock().unwrap().push((*event.metadata().level(), visitor.0));
+        }
+
+        fn enter(&self, _span: &tracing::span::Id) {}
+
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    #[test]
+    fn test_validate_and_parse_emits_tracing_events() {
+        let subscriber = std::sync::Arc::new(CapturingSubscriber::default());
+        let resolver = create_flattening_resolver(false);
+
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            resolver.validate_and_parse(r#"[{"person": "Alice"}]"#, &[]).unwrap();
+            resolver
+                .validate_and_parse(r#"{"data": [{"person": "Alice"}, {"person": "Bo"#, &[])
+                .unwrap();
+        });
+
+        assert!(subscriber.spans.lock().unwrap().iter().any(|name| name == "validate_and_parse"));
+        let events = subscriber.events.lock().unwrap();
+        assert!(events
+            .iter()
+            .any(|(level, message)| *level == tracing::Level::DEBUG && message == "Parsing model response"));
+        assert!(events
+            .iter()
+            .any(|(level, message)| *level == tracing::Level::WARN && message.contains("truncated")));
+    }
+
     #[test]
     fn test_validate_and_parse_parse_failure() {
         let temp_dir = TempDir::new().unwrap();
@@ -1574,17 +2819,21 @@ mod tests {
                 accuracy_coercion.target_type,
                 CoercionTargetType::Percentage
             );
-            assert!(
-                (accuracy_coercion
-                    .coerced_value
-                    .as_ref()
-                    .unwrap()
-                    .as_f64()
-                    .unwrap()
-                    - 0.947)
-                    .abs()
-                    < 0.001
-            );
+            let coerced = accuracy_coercion.coerced_value.as_ref().unwrap();
+            assert!((coerced["value"].as_f64().unwrap() - 0.947).abs() < 0.001);
+            assert!((coerced["ratio"].as_f64().unwrap() - 0.947).abs() < 0.001);
+            assert!((coerced["percent"].as_f64().unwrap() - 94.7).abs() < 0.001);
+        }
+
+        #[test]
+        fn test_percentage_as_raw_percent() {
+            let coercer = TypeCoercer::new(true).with_percentage_as_ratio(false);
+            let detail = coercer.coerce_value("accuracy", "94.7%");
+            assert_eq!(detail.target_type, CoercionTargetType::Percentage);
+            let coerced = detail.coerced_value.unwrap();
+            assert!((coerced["value"].as_f64().unwrap() - 94.7).abs() < 0.001);
+            assert!((coerced["ratio"].as_f64().unwrap() - 0.947).abs() < 0.001);
+            assert!((coerced["percent"].as_f64().unwrap() - 94.7).abs() < 0.001);
         }
 
         #[test]
@@ -1671,6 +2920,147 @@ mod tests {
             );
         }
 
+        fn coerced_phone(coercer: &TypeCoercer, value: &str) -> Option<(String, String, String)> {
+            let detail = coercer.coerce_value("phone", value);
+            (detail.target_type == CoercionTargetType::PhoneNumber).then(|| {
+                let obj = detail.coerced_value.unwrap();
+                let field = |key: &str| obj[key].as_str().unwrap().to_string();
+                (field("e164"), field("country_code"), field("region"))
+            })
+        }
+
+        #[test]
+        fn test_international_phone_coercion() {
+            let coercer = TypeCoercer::new(true);
+            let gb = Some(("+442079460958".to_string(), "44".to_string(), "GB".to_string()));
+
+            assert_eq!(coerced_phone(&coercer, "+44 20 7946 0958"), gb);
+            assert_eq!(coerced_phone(&coercer, "+44 (0)20 7946 0958"), gb);
+            assert_eq!(
+                coerced_phone(&coercer, "+1-617-555-1234"),
+                Some(("+16175551234".to_string(), "1".to_string(), "US".to_string()))
+            );
+            assert_eq!(coerced_phone(&coercer, "+33612345678").unwrap().2, "FR");
+            assert_eq!(coerced_phone(&coercer, "+44 20 79"), None);
+
+            // Without a default country only US-style local numbers are recognised
+            assert_eq!(coerced_phone(&coercer, "020 7946 0958"), None);
+            assert_eq!(coerced_phone(&coercer, "(617) 555-1234").unwrap().0, "+16175551234");
+
+            let coercer = TypeCoercer::new(true).with_default_country(Some("gb".to_string()));
+            assert_eq!(coerced_phone(&coercer, "020 7946 0958"), gb);
+            assert_eq!(coerced_phone(&coercer, "02079460958"), gb);
+            assert_eq!(coerced_phone(&coercer, "+1-617-555-1234").unwrap().2, "US");
+            assert_eq!(coercer.coerce_value("count", "20794609").target_type, CoercionTargetType::Integer);
+        }
+
+        fn coerced_date(resolver: &Resolver, value: &str) -> serde_json::Map<String, Value> {
+            let detail = resolver.type_coercer.coerce_value("date", value);
+            assert!(detail.success);
+            assert_eq!(detail.target_type, CoercionTargetType::Date);
+            detail.coerced_value.unwrap().as_object().unwrap().clone()
+        }
+
+        #[test]
+        fn test_date_normalization_formats() {
+            let resolver = create_coercion_resolver();
+
+            for input in ["January 5, 2024", "Jan 5, 2024", "01/05/2024", "2024-01-05"] {
+                let obj = coerced_date(&resolver, input);
+                assert_eq!(obj.get("date").unwrap(), "2024-01-05", "input: {}", input);
+                assert_eq!(obj.get("original").unwrap(), input);
+                assert_eq!(obj.get("parsed").unwrap(), &Value::Bool(true));
+            }
+        }
+
+        #[test]
+        fn test_date_normalization_day_first() {
+            let config = create_test_config();
+            let validation_config = ValidationConfig {
+                save_raw_outputs: false,
+                date_order: DateOrder::DayFirst,
+                ..Default::default()
+            };
+            let resolver = Resolver::with_validation_config(&config, true, validation_config).unwrap();
+
+            let obj = coerced_date(&resolver, "01/05/2024");
+            assert_eq!(obj.get("date").unwrap(), "2024-05-01");
+
+            // Only readable as month-first, so the preference does not apply
+            let obj = coerced_date(&resolver, "12/25/2024");
+            assert_eq!(obj.get("date").unwrap(), "2024-12-25");
+        }
+
+        #[test]
+        fn test_number_locale_changes_separators() {
+            let us = TypeCoercer::new(true);
+            let de = TypeCoercer::new(true).with_number_locale(NumberLocale::EuDe);
+            let fr = TypeCoercer::new(true).with_number_locale(NumberLocale::EuFr);
+
+            let detail = us.coerce_value("amount", "1.234");
+            assert_eq!(detail.target_type, CoercionTargetType::Float);
+            assert_eq!(detail.coerced_value.unwrap().as_f64(), Some(1.234));
+            let detail = de.coerce_value("amount", "1.234");
+            assert_eq!(detail.target_type, CoercionTargetType::Integer);
+            assert_eq!(detail.coerced_value.unwrap().as_i64(), Some(1234));
+
+            assert!(!us.coerce_value("amount", "1.234,56").success);
+            let detail = de.coerce_value("amount", "-1.234.567,5");
+            assert_eq!(detail.target_type, CoercionTargetType::Float);
+            assert_eq!(detail.coerced_value.unwrap().as_f64(), Some(-1234567.5));
+            let detail = fr.coerce_value("amount", "1\u{202f}234,56");
+            assert_eq!(detail.coerced_value.unwrap().as_f64(), Some(1234.56));
+
+            // Misplaced grouping is not a number in either locale
+            assert!(!de.coerce_value("amount", "12.34,5").success);
+        }
+
+        #[test]
+        fn test_thousands_and_scientific_integers() {
+            let coercer = TypeCoercer::new(true);
+            let integer = |value: &str| {
+                let detail = coercer.coerce_value("count", value);
+                assert_eq!(detail.target_type, CoercionTargetType::Integer, "{}", value);
+                detail.coerced_value.unwrap().as_i64().unwrap()
+            };
+
+            assert_eq!(integer("42"), 42);
+            assert_eq!(integer("1,000"), 1000);
+            assert_eq!(integer("-12,345,678"), -12345678);
+            assert_eq!(integer("1e3"), 1000);
+            assert_eq!(integer("1.5e2"), 150);
+
+            let detail = coercer.coerce_value("count", "1.5");
+            assert_eq!(detail.target_type, CoercionTargetType::Float);
+            assert_eq!(detail.coerced_value.unwrap().as_f64(), Some(1.5));
+            let detail = coercer.coerce_value("count", "1.5e-1");
+            assert_eq!(detail.target_type, CoercionTargetType::Float);
+            assert!(!coercer.coerce_value("count", "1,00").success);
+
+            let detail = TypeCoercer::new(true).with_scientific_integers(false).coerce_value("count", "1e3");
+            assert_eq!(detail.target_type, CoercionTargetType::Float);
+        }
+
+        #[test]
+        fn test_date_normalization_ambiguous_falls_back() {
+            let config = create_test_config();
+            let validation_config = ValidationConfig {
+                save_raw_outputs: false,
+                date_order: DateOrder::Unspecified,
+                ..Default::default()
+            };
+            let resolver = Resolver::with_validation_config(&config, true, validation_config).unwrap();
+
+            let obj = coerced_date(&resolver, "01/05/2024");
+            assert_eq!(obj.get("date").unwrap(), "01/05/2024");
+            assert_eq!(obj.get("parsed").unwrap(), &Value::Bool(false));
+            assert!(obj.get("original").is_none());
+
+            // Same day either way is not ambiguous
+            let obj = coerced_date(&resolver, "05/05/2024");
+            assert_eq!(obj.get("date").unwrap(), "2024-05-05");
+        }
+
         #[test]
         fn test_no_coercion_when_disabled() {
             let config = create_test_config();
@@ -1765,4 +3155,30 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_attributes_object_fills_extraction_attributes() {
+        let validation_config = ValidationConfig {
+            save_raw_outputs: false,
+            ..Default::default()
+        };
+        let resolver =
+            Resolver::with_validation_config(&create_test_config(), true, validation_config).unwrap();
+        let json_response = r#"[
+            {"person": "John", "attributes": {"role": "CEO", "tenure": 5}},
+            {"person": "Jane", "person_attributes": {"role": "CTO"}, "attributes": {"role": "ignored"}}
+        ]"#;
+
+        let (extractions, _) = resolver.validate_and_parse(json_response, &[]).unwrap();
+        assert_eq!(extractions.len(), 2);
+        let john = extractions.iter().find(|e| e.extraction_text == "John").unwrap();
+        let attributes = john.attributes.as_ref().unwrap();
+        assert_eq!(attributes.get("role"), Some(&serde_json::json!("CEO")));
+        assert_eq!(attributes.get("tenure"), Some(&serde_json::json!(5)));
+        let jane = extractions.iter().find(|e| e.extraction_text == "Jane").unwrap();
+        assert_eq!(
+            jane.attributes.as_ref().unwrap().get("role"),
+            Some(&serde_json::json!("CTO"))
+        );
+    }
 }
@@ -2,7 +2,7 @@
 
 use crate::{data::ExampleData, exceptions::LangExtractResult};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 /// Shared key for extraction arrays in JSON/YAML
 pub const EXTRACTIONS_KEY: &str = "extractions";
@@ -10,6 +10,21 @@ pub const EXTRACTIONS_KEY: &str = "extractions";
 /// Attributes for
 pub const ATTRIBUTES_SUFFIX:&str = "_attributes";
 
+/// Key of an attribute object shared by every extraction of an output item
+pub const ATTRIBUTES_KEY: &str = "attributes";
+
+/// Class key of an absence assertion item, `{"class": "ssn", "present": false}`
+pub const ABSENT_CLASS_KEY: &str = "class";
+
+/// Flag key of an absence assertion item
+pub const PRESENT_KEY: &str = "present";
+
+/// Key of the row array in table mode, `{"table": [{"item": "Widget", "qty": "2"}]}`
+pub const TABLE_KEY: &str = "table";
+
+/// Attribute naming the column a table cell extraction came from
+pub const TABLE_COLUMN_ATTRIBUTE: &str = "table_column";
+
 /// Enumeration of constraint types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -39,6 +54,214 @@ impl Constraint {
     }
 }
 
+/// Value type inferred for an extraction class from its example values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+    Integer,
+    Float,
+    Boolean,
+    Date,
+    String,
+}
+
+impl std::fmt::Display for ValueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueType::Integer => write!(f, "integer"),
+            ValueType::Float => write!(f, "float"),
+            ValueType::Boolean => write!(f, "boolean"),
+            ValueType::Date => write!(f, "date"),
+            ValueType::String => write!(f, "string"),
+        }
+    }
+}
+
+impl ValueType {
+    /// Infer the type of a single example value
+    pub fn infer(value: &str) -> Self {
+        let value = value.trim();
+
+        if value.parse::<i64>().is_ok() {
+            ValueType::Integer
+        } else if value.parse::<f64>().is_ok_and(|v| v.is_finite()) {
+            ValueType::Float
+        } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+            ValueType::Boolean
+        } else if ["%Y-%m-%d", "%m/%d/%Y", "%d/%m/%Y", "%B %d, %Y", "%b %d, %Y"]
+            .iter()
+            .any(|format| chrono::NaiveDate::parse_from_str(value, format).is_ok())
+        {
+            ValueType::Date
+        } else {
+            ValueType::String
+        }
+    }
+
+    /// Combine the types of two values of the same class
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (ValueType::Integer, ValueType::Float) | (ValueType::Float, ValueType::Integer) => ValueType::Float,
+            _ => ValueType::String,
+        }
+    }
+}
+
+/// Infer one value type per extraction class from the example extractions
+///
+/// Classes whose examples disagree (e.g. a number and a word) fall back to string;
+/// integers mixed with floats are reported as float.
+pub fn infer_class_types(examples: &[ExampleData]) -> BTreeMap<String, ValueType> {
+    let mut types: BTreeMap<String, ValueType> = BTreeMap::new();
+
+    for extraction in examples.iter().flat_map(|example| &example.extractions) {
+        let value_type = ValueType::infer(&extraction.extraction_text);
+        types
+            .entry(extraction.extraction_class.clone())
+            .and_modify(|existing| *existing = existing.merge(value_type))
+            .or_insert(value_type);
+    }
+
+    types
+}
+
+/// Collect the attribute keys used by each extraction class in the examples
+///
+/// Classes whose examples carry no attributes are left out.
+pub fn infer_attribute_keys(examples: &[ExampleData]) -> BTreeMap<String, BTreeSet<String>> {
+    let mut keys: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for extraction in examples.iter().flat_map(|example| &example.extractions) {
+        if let Some(attributes) = extraction.attributes.as_ref().filter(|a| !a.is_empty()) {
+            keys.entry(extraction.extraction_class.clone())
+                .or_default()
+                .extend(attributes.keys().cloned());
+        }
+    }
+
+    keys
+}
+
+/// Table columns shown by the examples: their extraction classes, in first-seen order
+pub fn infer_table_columns(examples: &[ExampleData]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for extraction in examples.iter().flat_map(|example| &example.extractions) {
+        if extraction.present && !columns.contains(&extraction.extraction_class) {
+            columns.push(extraction.extraction_class.clone());
+        }
+    }
+    columns
+}
+
+/// GBNF rules shared by every generated grammar: JSON values, strings and whitespace
+const GBNF_JSON_RULES: &str = r#"object ::= "{" ws ( string ws ":" ws value ( "," ws string ws ":" ws value )* )? ws "}"
+array ::= "[" ws ( value ( "," ws value )* )? ws "]"
+value ::= object | array | string | number | "true" | "false" | "null"
+string ::= "\"" ( [^"\\\x7F\x00-\x1F] | "\\" ( ["\\/bfnrt] | "u" [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F] ) )* "\""
+number ::= "-"? [0-9]+ ( "." [0-9]+ )? ( [eE] [-+]? [0-9]+ )?
+ws ::= [ \t\n]*
+"#;
+
+/// GBNF literal matching `key` as a quoted JSON object key
+fn gbnf_key_literal(key: &str) -> String {
+    let json_key = serde_json::to_string(key).unwrap_or_else(|_| format!("\"{}\"", key));
+    format!("\"{}\"", json_key.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Build a GBNF grammar (llama.cpp syntax) for JSON output in the shape the examples use
+///
+/// The output is an array of `{"<class>": "text", "<class>_attributes": {...}}`
+/// items, one alternative per example class, plus absence assertions when the
+/// examples show any. Returns `None` when the examples have no extractions.
+pub fn gbnf_grammar(examples: &[ExampleData]) -> Option<String> {
+    let extractions: Vec<_> = examples.iter().flat_map(|example| &example.extractions).collect();
+    let classes: BTreeSet<&str> = extractions
+        .iter()
+        .filter(|extraction| extraction.present)
+        .map(|extraction| extraction.extraction_class.as_str())
+        .collect();
+    let has_absences = extractions.iter().any(|extraction| !extraction.present);
+    if classes.is_empty() && !has_absences {
+        return None;
+    }
+
+    let mut item_rules: Vec<String> = (0..classes.len()).map(|index| format!("class-{}", index)).collect();
+    if has_absences {
+        item_rules.push("absent".to_string());
+    }
+
+    let mut grammar = String::from(r#"root ::= "[" ws ( item ( "," ws item )* )? ws "]""#);
+    grammar.push('\n');
+    grammar.push_str(&format!("item ::= {}\n", item_rules.join(" | ")));
+    for (index, class) in classes.iter().enumerate() {
+        grammar.push_str(&format!(
+            "class-{} ::= \"{{\" ws {} ws \":\" ws string ( \",\" ws {} ws \":\" ws ( object | \"null\" ) )? ws \"}}\"\n",
+            index,
+            gbnf_key_literal(class),
+            gbnf_key_literal(&format!("{}{}", class, ATTRIBUTES_SUFFIX)),
+        ));
+    }
+    if has_absences {
+        grammar.push_str(&format!(
+            "absent ::= \"{{\" ws {} ws \":\" ws string \",\" ws {} ws \":\" ws \"false\" ws \"}}\"\n",
+            gbnf_key_literal(ABSENT_CLASS_KEY),
+            gbnf_key_literal(PRESENT_KEY),
+        ));
+    }
+    grammar.push_str(GBNF_JSON_RULES);
+    Some(grammar)
+}
+
+/// Build a JSON Schema for output in the shape the examples use, as sent in Ollama's `format`
+///
+/// Mirrors [`gbnf_grammar`]: an array of `{"<class>": "text", "<class>_attributes": {...}}`
+/// items plus absence assertions when the examples show any. Returns `None`
+/// when the examples have no extractions.
+pub fn json_output_schema(examples: &[ExampleData]) -> Option<serde_json::Value> {
+    let extractions: Vec<_> = examples.iter().flat_map(|example| &example.extractions).collect();
+    let classes: BTreeSet<&str> = extractions
+        .iter()
+        .filter(|extraction| extraction.present)
+        .map(|extraction| extraction.extraction_class.as_str())
+        .collect();
+    let has_absences = extractions.iter().any(|extraction| !extraction.present);
+    if classes.is_empty() && !has_absences {
+        return None;
+    }
+
+    let mut items: Vec<serde_json::Value> = classes
+        .iter()
+        .map(|class| {
+            let mut properties = serde_json::Map::new();
+            properties.insert(class.to_string(), serde_json::json!({"type": "string"}));
+            properties.insert(
+                format!("{}{}", class, ATTRIBUTES_SUFFIX),
+                serde_json::json!({"type": ["object", "null"]}),
+            );
+            serde_json::json!({
+                "type": "object",
+                "properties": properties,
+                "required": [class],
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+    if has_absences {
+        let mut properties = serde_json::Map::new();
+        properties.insert(ABSENT_CLASS_KEY.to_string(), serde_json::json!({"type": "string"}));
+        properties.insert(PRESENT_KEY.to_string(), serde_json::json!({"const": false}));
+        items.push(serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "required": [ABSENT_CLASS_KEY, PRESENT_KEY],
+            "additionalProperties": false,
+        }));
+    }
+
+    Some(serde_json::json!({"type": "array", "items": {"anyOf": items}}))
+}
+
 /// Abstract base trait for generating structured constraints from examples
 pub trait BaseSchema: Send + Sync {
     /// Factory method to build a schema instance from example data
@@ -194,6 +417,83 @@ mod tests {
         assert!(schema.supports_strict_mode()); // Should default to JSON
     }
 
+    #[test]
+    fn test_gbnf_grammar_from_examples() {
+        let examples = vec![ExampleData::new(
+            "John is 30".to_string(),
+            vec![
+                Extraction::new("person".to_string(), "John".to_string()),
+                Extraction::new("age".to_string(), "30".to_string()),
+            ],
+        )];
+
+        let grammar = gbnf_grammar(&examples).unwrap();
+        assert!(grammar.starts_with("root ::= "));
+        assert!(grammar.contains("item ::= class-0 | class-1\n"));
+        assert!(grammar.contains(r#"class-0 ::= "{" ws "\"age\"" ws ":" ws string ( "," ws "\"age_attributes\"""#));
+        assert!(grammar.contains(r#"class-1 ::= "{" ws "\"person\"""#));
+        assert!(!grammar.contains("absent ::="));
+        assert_eq!(gbnf_grammar(&[]), None);
+    }
+
+    #[test]
+    fn test_json_output_schema_from_examples() {
+        let examples = vec![ExampleData::new(
+            "John is 30".to_string(),
+            vec![
+                Extraction::new("person".to_string(), "John".to_string()),
+                Extraction::new("age".to_string(), "30".to_string()),
+            ],
+        )];
+
+        let schema = json_output_schema(&examples).unwrap();
+        assert_eq!(schema["type"], "array");
+        let items = schema["items"]["anyOf"].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["required"], serde_json::json!(["age"]));
+        assert_eq!(items[1]["properties"]["person"]["type"], "string");
+        assert!(items[1]["properties"].get("person_attributes").is_some());
+        assert_eq!(json_output_schema(&[]), None);
+    }
+
+    #[test]
+    fn test_value_type_inference() {
+        assert_eq!(ValueType::infer("30"), ValueType::Integer);
+        assert_eq!(ValueType::infer("19.99"), ValueType::Float);
+        assert_eq!(ValueType::infer("True"), ValueType::Boolean);
+        assert_eq!(ValueType::infer("2024-01-15"), ValueType::Date);
+        assert_eq!(ValueType::infer("March 5, 2024"), ValueType::Date);
+        assert_eq!(ValueType::infer("John Smith"), ValueType::String);
+        assert_eq!(ValueType::infer("NaN"), ValueType::String);
+    }
+
+    #[test]
+    fn test_infer_class_types_merges_examples() {
+        let examples = vec![
+            ExampleData::new(
+                "John is 30".to_string(),
+                vec![
+                    Extraction::new("age".to_string(), "30".to_string()),
+                    Extraction::new("score".to_string(), "7".to_string()),
+                    Extraction::new("code".to_string(), "42".to_string()),
+                ],
+            ),
+            ExampleData::new(
+                "Jane is 25".to_string(),
+                vec![
+                    Extraction::new("age".to_string(), "25".to_string()),
+                    Extraction::new("score".to_string(), "8.5".to_string()),
+                    Extraction::new("code".to_string(), "A7".to_string()),
+                ],
+            ),
+        ];
+
+        let types = infer_class_types(&examples);
+        assert_eq!(types.get("age"), Some(&ValueType::Integer));
+        assert_eq!(types.get("score"), Some(&ValueType::Float));
+        assert_eq!(types.get("code"), Some(&ValueType::String));
+    }
+
     #[test]
     fn test_constraint_serialization() {
         let constraint = Constraint::none();
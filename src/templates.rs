@@ -8,9 +8,10 @@ use serde_json::Value;
 use crate::{
     data::{ExampleData, FormatType},
     exceptions::{LangExtractError, LangExtractResult},
+    prompting::PromptLanguage,
 };
 use std::collections::HashMap;
-use crate::schema::ATTRIBUTES_SUFFIX;
+use crate::schema::{ABSENT_CLASS_KEY, ATTRIBUTES_SUFFIX, PRESENT_KEY, TABLE_KEY};
 
 /// Template error types
 #[derive(Debug, thiserror::Error)]
@@ -157,46 +158,198 @@ impl TemplateEngine {
     }
 }
 
-/// Common template fragments for reuse
+/// Fixed prompt text generated by the library, in one language
+///
+/// `{suffix}`, `{key}` and `{columns}` are filled in where the text is used.
+#[derive(Debug, Clone, Copy)]
+pub struct PromptStrings {
+    /// Opening line of every prompt
+    pub instruction_prefix: &'static str,
+    /// Asks for JSON output
+    pub json_format_instruction: &'static str,
+    /// Asks for YAML output
+    pub yaml_format_instruction: &'static str,
+    /// Step-by-step checklist for local models
+    pub reasoning_instruction: &'static str,
+    /// One-line request to reason before answering
+    pub step_by_step: &'static str,
+    /// Heading of the examples section
+    pub examples_header: &'static str,
+    /// Label numbering each example
+    pub example_label: &'static str,
+    /// Label of an example's input text
+    pub input_label: &'static str,
+    /// Label of an example's expected output
+    pub output_label: &'static str,
+    /// Lead-in to the text to extract from
+    pub input_header: &'static str,
+    /// Heading where a JSON answer starts
+    pub json_output_header: &'static str,
+    /// Heading where a YAML answer starts
+    pub yaml_output_header: &'static str,
+    /// Label of the additional context
+    pub additional_context: &'static str,
+    /// Heading of the per-class descriptions
+    pub class_guidance: &'static str,
+    /// Lead-in to the list of allowed classes
+    pub allowed_classes: &'static str,
+    /// Heading of the expected value types
+    pub expected_types: &'static str,
+    /// Heading of the attribute keys per class
+    pub attributes: &'static str,
+    /// Description of the table row shape
+    pub table_rows: &'static str,
+    /// Label of the schema hint
+    pub schema_guidance: &'static str,
+}
+
+impl PromptStrings {
+    /// English (the default)
+    pub const ENGLISH: PromptStrings = PromptStrings {
+        instruction_prefix: "You are an expert information extraction assistant. ",
+        json_format_instruction: "Respond with valid JSON that matches the structure shown in the examples.",
+        yaml_format_instruction: "Respond with valid YAML that matches the structure shown in the examples.",
+        reasoning_instruction: "\n\nThink step by step:\n1. Read the text carefully\n2. Identify the requested information\n3. Extract it in the exact format shown in examples",
+        step_by_step: "Please think through this step by step before providing your answer.",
+        examples_header: "\n\nExamples:\n",
+        example_label: "Example",
+        input_label: "Input",
+        output_label: "Output",
+        input_header: "\n\nNow extract information from this text:\n\nInput: ",
+        json_output_header: "\n\nOutput (JSON format):",
+        yaml_output_header: "\n\nOutput (YAML format):",
+        additional_context: "Additional Context",
+        class_guidance: "Extraction class guidance",
+        allowed_classes: "Use only these extraction classes",
+        expected_types: "Expected types",
+        attributes: "Attributes (give each extraction a \"<class>{suffix}\" object with these keys):",
+        table_rows: "Table rows: return {\"{key}\": [...]} with one object per row, keyed by these columns: {columns}",
+        schema_guidance: "Schema guidance",
+    };
+
+    /// German
+    pub const GERMAN: PromptStrings = PromptStrings {
+        instruction_prefix: "Du bist ein Experte für Informationsextraktion. ",
+        json_format_instruction: "Antworte mit gültigem JSON, das der Struktur in den Beispielen entspricht.",
+        yaml_format_instruction: "Antworte mit gültigem YAML, das der Struktur in den Beispielen entspricht.",
+        reasoning_instruction: "\n\nGehe Schritt für Schritt vor:\n1. Lies den Text sorgfältig\n2. Finde die gesuchten Informationen\n3. Extrahiere sie genau im Format der Beispiele",
+        step_by_step: "Denke Schritt für Schritt nach, bevor du antwortest.",
+        examples_header: "\n\nBeispiele:\n",
+        example_label: "Beispiel",
+        input_label: "Eingabe",
+        output_label: "Ausgabe",
+        input_header: "\n\nExtrahiere nun die Informationen aus diesem Text:\n\nEingabe: ",
+        json_output_header: "\n\nAusgabe (JSON-Format):",
+        yaml_output_header: "\n\nAusgabe (YAML-Format):",
+        additional_context: "Zusätzlicher Kontext",
+        class_guidance: "Hinweise zu den Extraktionsklassen",
+        allowed_classes: "Verwende nur diese Extraktionsklassen",
+        expected_types: "Erwartete Typen",
+        attributes: "Attribute (gib jeder Extraktion ein Objekt \"<class>{suffix}\" mit diesen Schlüsseln):",
+        table_rows: "Tabellenzeilen: gib {\"{key}\": [...]} mit einem Objekt pro Zeile zurück, mit diesen Spalten als Schlüssel: {columns}",
+        schema_guidance: "Schema-Hinweise",
+    };
+
+    /// Spanish
+    pub const SPANISH: PromptStrings = PromptStrings {
+        instruction_prefix: "Eres un asistente experto en extracción de información. ",
+        json_format_instruction: "Responde con JSON válido que siga la estructura de los ejemplos.",
+        yaml_format_instruction: "Responde con YAML válido que siga la estructura de los ejemplos.",
+        reasoning_instruction: "\n\nPiensa paso a paso:\n1. Lee el texto con atención\n2. Identifica la información solicitada\n3. Extráela exactamente en el formato de los ejemplos",
+        step_by_step: "Piensa paso a paso antes de dar tu respuesta.",
+        examples_header: "\n\nEjemplos:\n",
+        example_label: "Ejemplo",
+        input_label: "Entrada",
+        output_label: "Salida",
+        input_header: "\n\nAhora extrae la información de este texto:\n\nEntrada: ",
+        json_output_header: "\n\nSalida (formato JSON):",
+        yaml_output_header: "\n\nSalida (formato YAML):",
+        additional_context: "Contexto adicional",
+        class_guidance: "Indicaciones por clase de extracción",
+        allowed_classes: "Usa solo estas clases de extracción",
+        expected_types: "Tipos esperados",
+        attributes: "Atributos (da a cada extracción un objeto \"<class>{suffix}\" con estas claves):",
+        table_rows: "Filas de tabla: devuelve {\"{key}\": [...]} con un objeto por fila y estas columnas como claves: {columns}",
+        schema_guidance: "Guía de esquema",
+    };
+
+    /// French
+    pub const FRENCH: PromptStrings = PromptStrings {
+        instruction_prefix: "Tu es un assistant expert en extraction d'informations. ",
+        json_format_instruction: "Réponds avec du JSON valide qui suit la structure des exemples.",
+        yaml_format_instruction: "Réponds avec du YAML valide qui suit la structure des exemples.",
+        reasoning_instruction: "\n\nProcède étape par étape :\n1. Lis attentivement le texte\n2. Repère les informations demandées\n3. Extrais-les exactement au format des exemples",
+        step_by_step: "Réfléchis étape par étape avant de répondre.",
+        examples_header: "\n\nExemples :\n",
+        example_label: "Exemple",
+        input_label: "Entrée",
+        output_label: "Sortie",
+        input_header: "\n\nExtrais maintenant les informations de ce texte :\n\nEntrée: ",
+        json_output_header: "\n\nSortie (format JSON) :",
+        yaml_output_header: "\n\nSortie (format YAML) :",
+        additional_context: "Contexte supplémentaire",
+        class_guidance: "Consignes par classe d'extraction",
+        allowed_classes: "Utilise uniquement ces classes d'extraction",
+        expected_types: "Types attendus",
+        attributes: "Attributs (donne à chaque extraction un objet \"<class>{suffix}\" avec ces clés) :",
+        table_rows: "Lignes de tableau : renvoie {\"{key}\": [...]} avec un objet par ligne et ces colonnes comme clés : {columns}",
+        schema_guidance: "Indications de schéma",
+    };
+
+    /// Output section header for `format`
+    pub fn output_header(&self, format: FormatType) -> &'static str {
+        match format {
+            FormatType::Json => self.json_output_header,
+            FormatType::Yaml => self.yaml_output_header,
+        }
+    }
+
+    /// Format instruction for `format`
+    pub fn format_instruction(&self, format: FormatType) -> &'static str {
+        match format {
+            FormatType::Json => self.json_format_instruction,
+            FormatType::Yaml => self.yaml_format_instruction,
+        }
+    }
+}
+
+/// Common template fragments for reuse (English; see [`PromptStrings`] for other languages)
 pub struct TemplateFragments;
 
 impl TemplateFragments {
     /// Standard instruction prefix
     pub fn instruction_prefix() -> &'static str {
-        "You are an expert information extraction assistant. "
+        PromptStrings::ENGLISH.instruction_prefix
     }
 
     /// JSON format instruction
     pub fn json_format_instruction() -> &'static str {
-        "Respond with valid JSON that matches the structure shown in the examples."
+        PromptStrings::ENGLISH.json_format_instruction
     }
 
     /// YAML format instruction
     pub fn yaml_format_instruction() -> &'static str {
-        "Respond with valid YAML that matches the structure shown in the examples."
+        PromptStrings::ENGLISH.yaml_format_instruction
     }
 
     /// Reasoning instruction for local models
     pub fn reasoning_instruction() -> &'static str {
-        "\n\nThink step by step:\n1. Read the text carefully\n2. Identify the requested information\n3. Extract it in the exact format shown in examples"
+        PromptStrings::ENGLISH.reasoning_instruction
     }
 
     /// Example section header
     pub fn examples_header() -> &'static str {
-        "\n\nExamples:\n"
+        PromptStrings::ENGLISH.examples_header
     }
 
     /// Input section header
     pub fn input_header() -> &'static str {
-        "\n\nNow extract information from this text:\n\nInput: "
+        PromptStrings::ENGLISH.input_header
     }
 
     /// Output section header
     pub fn output_header(format: FormatType) -> String {
-        match format {
-            FormatType::Json => "\n\nOutput (JSON format):".to_string(),
-            FormatType::Yaml => "\n\nOutput (YAML format):".to_string(),
-        }
+        PromptStrings::ENGLISH.output_header(format).to_string()
     }
 }
 
@@ -204,6 +357,8 @@ impl TemplateFragments {
 pub struct ExampleFormatter {
     format_type: FormatType,
     max_examples: Option<usize>,
+    language: PromptLanguage,
+    table_mode: bool,
 }
 
 impl ExampleFormatter {
@@ -211,6 +366,8 @@ impl ExampleFormatter {
         Self {
             format_type,
             max_examples: None,
+            language: PromptLanguage::default(),
+            table_mode: false,
         }
     }
 
@@ -219,6 +376,18 @@ impl ExampleFormatter {
         self
     }
 
+    /// Label the examples in `language`
+    pub fn with_language(mut self, language: PromptLanguage) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Show example outputs as `{"table": [...]}` rows, one per `group_index`
+    pub fn with_table_mode(mut self, table_mode: bool) -> Self {
+        self.table_mode = table_mode;
+        self
+    }
+
     /// Format examples for inclusion in prompts
     pub fn format_examples(&self, examples: &[ExampleData]) -> LangExtractResult<String> {
         if examples.is_empty() {
@@ -231,13 +400,14 @@ impl ExampleFormatter {
             examples
         };
 
+        let strings = self.language.strings();
         let mut result = String::new();
-        result.push_str(TemplateFragments::examples_header());
+        result.push_str(strings.examples_header);
 
         for (i, example) in examples_to_use.iter().enumerate() {
-            result.push_str(&format!("\nExample {}:\n", i + 1));
-            result.push_str(&format!("Input: {}\n", example.text));
-            result.push_str("Output: ");
+            result.push_str(&format!("\n{} {}:\n", strings.example_label, i + 1));
+            result.push_str(&format!("{}: {}\n", strings.input_label, example.text));
+            result.push_str(&format!("{}: ", strings.output_label));
             result.push_str(&self.format_single_example(example)?);
             result.push('\n');
         }
@@ -250,9 +420,17 @@ impl ExampleFormatter {
         // let mut obj_map: BTreeMap<String, Value> = std::collections::BTreeMap::new();
 
         let mut items: Vec<Value> = Vec::new();
+        // Table rows in first-seen order, keyed by group index
+        let mut rows: Vec<(Option<usize>, serde_json::Map<String, Value>)> = Vec::new();
 
         for extraction in &example.extractions {
             let mut map = serde_json::Map::new();
+            if !extraction.present {
+                map.insert(ABSENT_CLASS_KEY.to_string(), Value::String(extraction.extraction_class.clone()));
+                map.insert(PRESENT_KEY.to_string(), Value::Bool(false));
+                items.push(Value::Object(map));
+                continue;
+            }
             map.insert(
                 extraction.extraction_class.clone(),
                 Value::String(extraction.extraction_text.clone()),
@@ -272,10 +450,24 @@ impl ExampleFormatter {
                 attributes_value,
             );
 
-            items.push(Value::Object(map));
+            if !self.table_mode {
+                items.push(Value::Object(map));
+                continue;
+            }
+            match rows.iter_mut().find(|(group, _)| *group == extraction.group_index) {
+                Some((_, row)) => row.extend(map),
+                None => rows.push((extraction.group_index, map)),
+            }
         }
 
-        let payload = Value::Array(items);
+        let payload = if self.table_mode {
+            let mut table = serde_json::Map::new();
+            let rows: Vec<Value> = rows.into_iter().map(|(_, row)| Value::Object(row)).collect();
+            table.insert(TABLE_KEY.to_string(), Value::Array(rows.into_iter().chain(items).collect()));
+            Value::Object(table)
+        } else {
+            Value::Array(items)
+        };
 
         match self.format_type {
             FormatType::Json => self.format_as_json(payload),
@@ -330,29 +522,32 @@ pub struct TemplateBuilder {
     input_section: String,
     output_section: String,
     engine: TemplateEngine,
+    language: PromptLanguage,
 }
 
 impl TemplateBuilder {
     pub fn new(format_type: FormatType) -> Self {
-        let format_instruction = match format_type {
-            FormatType::Json => TemplateFragments::json_format_instruction(),
-            FormatType::Yaml => TemplateFragments::yaml_format_instruction(),
-        };
+        Self::for_language(format_type, PromptLanguage::default())
+    }
 
+    /// Builder whose fixed instruction text is in `language`
+    pub fn for_language(format_type: FormatType, language: PromptLanguage) -> Self {
+        let strings = language.strings();
         Self {
-            instruction: TemplateFragments::instruction_prefix().to_string(),
-            format_instruction: format_instruction.to_string(),
+            instruction: strings.instruction_prefix.to_string(),
+            format_instruction: strings.format_instruction(format_type).to_string(),
             reasoning: String::new(),
             examples_section: "{examples}".to_string(),
             context_section: "{additional_context}".to_string(),
             input_section: format!(
                 "{}{}{}",
-                TemplateFragments::input_header(),
+                strings.input_header,
                 "{input_text}",
-                TemplateFragments::output_header(format_type)
+                strings.output_header(format_type)
             ),
             output_section: String::new(),
             engine: TemplateEngine::lenient(),
+            language,
         }
     }
 
@@ -363,7 +558,7 @@ impl TemplateBuilder {
 
     pub fn with_reasoning(mut self, include: bool) -> Self {
         if include {
-            self.reasoning = TemplateFragments::reasoning_instruction().to_string();
+            self.reasoning = self.language.strings().reasoning_instruction.to_string();
         } else {
             self.reasoning.clear();
         }
@@ -377,7 +572,7 @@ impl TemplateBuilder {
 
     pub fn build(&self) -> String {
         format!(
-            "{{task_description}}\n\n{}{}{}{}{}{}\n",
+            "{{task_description}}{{class_descriptions}}{{allowed_classes}}{{type_hints}}{{attribute_hints}}{{table_hint}}\n\n{}{}{}{}{}{}\n",
             self.instruction,
             self.format_instruction,
             self.context_section,
@@ -468,6 +663,34 @@ mod tests {
         assert!(result.contains("age"));
     }
 
+    #[test]
+    fn test_example_formatter_table_mode_groups_rows() {
+        let formatter = ExampleFormatter::new(FormatType::Json).with_table_mode(true);
+        let cell = |class: &str, text: &str, row: usize| {
+            let mut extraction = Extraction::new(class.to_string(), text.to_string());
+            extraction.group_index = Some(row);
+            extraction
+        };
+        let example = ExampleData::new(
+            "2 x Widget at $5, 1 x Gadget at $9".to_string(),
+            vec![
+                cell("item", "Widget", 0),
+                cell("qty", "2", 0),
+                cell("item", "Gadget", 1),
+                cell("qty", "1", 1),
+            ],
+        );
+
+        let result = formatter.format_examples(&[example]).unwrap();
+        let output = result.split_once("Output: ").unwrap().1;
+        let parsed: Value = serde_json::from_str(output.trim()).unwrap();
+        let rows = parsed[TABLE_KEY].as_array().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["item"], "Widget");
+        assert_eq!(rows[0]["qty"], "2");
+        assert_eq!(rows[1]["item"], "Gadget");
+    }
+
     #[test]
     fn test_template_builder() {
         let template = TemplateBuilder::new(FormatType::Json)
@@ -4,6 +4,10 @@
 //! punctuation-level) tokens. Tokenization is necessary for alignment
 //! between extracted data and the source text and for forming sentence
 //! boundaries for LLM information extraction.
+//!
+//! Chunking works against the [`Tokenize`] trait. [`Tokenizer`] is the default
+//! implementation; [`CjkTokenizer`] handles Chinese and Japanese text, which
+//! has no spaces between words.
 
 use crate::exceptions::{LangExtractError, LangExtractResult};
 use regex::Regex;
@@ -128,6 +132,35 @@ impl TokenizedText {
     }
 }
 
+/// Splits text into tokens and finds sentence boundaries for chunking
+pub trait Tokenize: Send + Sync {
+    /// Tokenize text into tokens
+    fn tokenize(&self, text: &str) -> LangExtractResult<TokenizedText>;
+
+    /// Find the sentence starting at `start_token_index`
+    fn find_sentence_range(
+        &self,
+        text: &str,
+        tokens: &[Token],
+        start_token_index: usize,
+    ) -> LangExtractResult<TokenInterval>;
+
+    /// Reconstruct text from a token interval
+    fn tokens_text(
+        &self,
+        tokenized_text: &TokenizedText,
+        token_interval: &TokenInterval,
+    ) -> LangExtractResult<String> {
+        interval_text(tokenized_text, token_interval)
+    }
+}
+
+/// Han ideographs and kana, each of which becomes a token of its own
+const CJK_CHARS: &str = r"\p{Han}\p{Hiragana}\p{Katakana}ー々〆";
+
+/// Full-width sentence-ending punctuation
+const CJK_SENTENCE_ENDS: &str = "。．！？";
+
 /// Text tokenizer for splitting text into tokens
 pub struct Tokenizer {
     letters_pattern: Regex,
@@ -144,6 +177,15 @@ impl Tokenizer {
     /// Create a new tokenizer
     pub fn new() -> LangExtractResult<Self> {
         // Regex patterns for tokenization (matching Python implementation)
+        Self::with_patterns(
+            r"[A-Za-z0-9]+(?:/[A-Za-z0-9]+)+|[A-Za-z]+|[0-9]+|[^A-Za-z0-9\s]+",
+            r"^(?:[A-Za-z]+|[0-9]+)$",
+            r"[.?!]$",
+        )
+    }
+
+    /// Create a tokenizer from its token, word and end-of-sentence patterns
+    fn with_patterns(token: &str, word: &str, end_of_sentence: &str) -> LangExtractResult<Self> {
         let letters_pattern = Regex::new(r"[A-Za-z]+").map_err(|e| {
             LangExtractError::configuration(format!("Failed to compile letters regex: {}", e))
         })?;
@@ -160,15 +202,15 @@ impl Tokenizer {
             LangExtractError::configuration(format!("Failed to compile slash abbreviation regex: {}", e))
         })?;
 
-        let token_pattern = Regex::new(r"[A-Za-z0-9]+(?:/[A-Za-z0-9]+)+|[A-Za-z]+|[0-9]+|[^A-Za-z0-9\s]+").map_err(|e| {
+        let token_pattern = Regex::new(token).map_err(|e| {
             LangExtractError::configuration(format!("Failed to compile token regex: {}", e))
         })?;
 
-        let word_pattern = Regex::new(r"^(?:[A-Za-z]+|[0-9]+)$").map_err(|e| {
+        let word_pattern = Regex::new(word).map_err(|e| {
             LangExtractError::configuration(format!("Failed to compile word regex: {}", e))
         })?;
 
-        let end_of_sentence_pattern = Regex::new(r"[.?!]$").map_err(|e| {
+        let end_of_sentence_pattern = Regex::new(end_of_sentence).map_err(|e| {
             LangExtractError::configuration(format!("Failed to compile end of sentence regex: {}", e))
         })?;
 
@@ -247,32 +289,7 @@ impl Tokenizer {
         tokenized_text: &TokenizedText,
         token_interval: &TokenInterval,
     ) -> LangExtractResult<String> {
-        if token_interval.start_index >= token_interval.end_index {
-            return Err(LangExtractError::invalid_input(format!(
-                "Invalid token interval: start_index={}, end_index={}",
-                token_interval.start_index, token_interval.end_index
-            )));
-        }
-
-        if token_interval.end_index > tokenized_text.tokens.len() {
-            return Err(LangExtractError::invalid_input(format!(
-                "Token interval end_index {} exceeds token count {}",
-                token_interval.end_index,
-                tokenized_text.tokens.len()
-            )));
-        }
-
-        if tokenized_text.tokens.is_empty() {
-            return Ok(String::new());
-        }
-
-        let start_token = &tokenized_text.tokens[token_interval.start_index];
-        let end_token = &tokenized_text.tokens[token_interval.end_index - 1];
-
-        let start_char = start_token.char_interval.start_pos;
-        let end_char = end_token.char_interval.end_pos;
-
-        Ok(tokenized_text.text[start_char..end_char].to_string())
+        interval_text(tokenized_text, token_interval)
     }
 
     /// Check if a punctuation token ends a sentence
@@ -375,13 +392,104 @@ impl Default for Tokenizer {
     }
 }
 
+impl Tokenize for Tokenizer {
+    fn tokenize(&self, text: &str) -> LangExtractResult<TokenizedText> {
+        Tokenizer::tokenize(self, text)
+    }
+
+    fn find_sentence_range(
+        &self,
+        text: &str,
+        tokens: &[Token],
+        start_token_index: usize,
+    ) -> LangExtractResult<TokenInterval> {
+        Tokenizer::find_sentence_range(self, text, tokens, start_token_index)
+    }
+}
+
+/// Tokenizer for Chinese and Japanese text
+///
+/// Every Han ideograph and kana character is its own word token, and
+/// full-width `。！？` end sentences. Latin words, numbers and other
+/// punctuation are tokenized as by [`Tokenizer`].
+pub struct CjkTokenizer {
+    inner: Tokenizer,
+}
+
+impl CjkTokenizer {
+    /// Create a new CJK-aware tokenizer
+    pub fn new() -> LangExtractResult<Self> {
+        let token = format!(
+            r"[{cjk}]|[A-Za-z0-9]+(?:/[A-Za-z0-9]+)+|[A-Za-z]+|[0-9]+|[{ends}]|[^A-Za-z0-9\s{cjk}{ends}]+",
+            cjk = CJK_CHARS,
+            ends = CJK_SENTENCE_ENDS
+        );
+        let word = format!(r"^(?:[A-Za-z]+|[0-9]+|[{}])$", CJK_CHARS);
+        let end_of_sentence = format!(r"[.?!{}]$", CJK_SENTENCE_ENDS);
+        Ok(Self {
+            inner: Tokenizer::with_patterns(&token, &word, &end_of_sentence)?,
+        })
+    }
+}
+
+impl Default for CjkTokenizer {
+    fn default() -> Self {
+        Self::new().expect("Failed to create CJK tokenizer")
+    }
+}
+
+impl Tokenize for CjkTokenizer {
+    fn tokenize(&self, text: &str) -> LangExtractResult<TokenizedText> {
+        self.inner.tokenize(text)
+    }
+
+    fn find_sentence_range(
+        &self,
+        text: &str,
+        tokens: &[Token],
+        start_token_index: usize,
+    ) -> LangExtractResult<TokenInterval> {
+        self.inner.find_sentence_range(text, tokens, start_token_index)
+    }
+}
+
+/// Text covered by `token_interval`, from the start of its first token to the end of its last
+fn interval_text(tokenized_text: &TokenizedText, token_interval: &TokenInterval) -> LangExtractResult<String> {
+    if token_interval.start_index >= token_interval.end_index {
+        return Err(LangExtractError::invalid_input(format!(
+            "Invalid token interval: start_index={}, end_index={}",
+            token_interval.start_index, token_interval.end_index
+        )));
+    }
+
+    if token_interval.end_index > tokenized_text.tokens.len() {
+        return Err(LangExtractError::invalid_input(format!(
+            "Token interval end_index {} exceeds token count {}",
+            token_interval.end_index,
+            tokenized_text.tokens.len()
+        )));
+    }
+
+    if tokenized_text.tokens.is_empty() {
+        return Ok(String::new());
+    }
+
+    let start_token = &tokenized_text.tokens[token_interval.start_index];
+    let end_token = &tokenized_text.tokens[token_interval.end_index - 1];
+
+    let start_char = start_token.char_interval.start_pos;
+    let end_char = end_token.char_interval.end_pos;
+
+    Ok(tokenized_text.text[start_char..end_char].to_string())
+}
+
 #[cfg(test)]
 mod tests;
 
 /// Iterator for processing sentences in tokenized text
 pub struct SentenceIterator<'a> {
     tokenized_text: &'a TokenizedText,
-    tokenizer: &'a Tokenizer,
+    tokenizer: &'a dyn Tokenize,
     current_token_pos: usize,
     token_len: usize,
 }
@@ -390,7 +498,7 @@ impl<'a> SentenceIterator<'a> {
     /// Create a new sentence iterator
     pub fn new(
         tokenized_text: &'a TokenizedText,
-        tokenizer: &'a Tokenizer,
+        tokenizer: &'a dyn Tokenize,
         current_token_pos: usize,
     ) -> LangExtractResult<Self> {
         let token_len = tokenized_text.tokens.len();
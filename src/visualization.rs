@@ -1,10 +1,11 @@
 //! Visualization utilities for annotated documents.
 
-use crate::{data::AnnotatedDocument, exceptions::LangExtractResult};
+use crate::{data::{AlignmentStatus, AnnotatedDocument}, exceptions::LangExtractResult};
 use crate::pipeline::PipelineResult;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use crate::Extraction;
+use crate::schema::TABLE_COLUMN_ATTRIBUTE;
 /// Export format options for visualization
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
@@ -21,6 +22,36 @@ pub enum ExportFormat {
     Csv,
 }
 
+/// How overlapping extractions are rendered in single-document HTML export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverlapPolicy {
+    /// Keep the extraction that starts first
+    #[default]
+    KeepFirst,
+    /// Keep the extraction covering the most characters
+    KeepLongest,
+    /// Keep the extraction with the highest `confidence` attribute,
+    /// falling back to its alignment quality
+    KeepHighestConfidence,
+    /// Render every extraction, nesting overlapping spans
+    Nest,
+}
+
+/// Shown in place of the text of an absence assertion
+const ABSENT_LABEL: &str = "✗ not present";
+
+/// How extraction attributes are written in CSV export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvAttributeMode {
+    /// One `attributes.<key>` column per attribute key found in the document
+    #[default]
+    Columns,
+    /// A single `attributes` column holding each extraction's attributes as JSON
+    Json,
+    /// Leave attributes out
+    Omit,
+}
+
 /// Configuration for visualization exports
 #[derive(Debug, Clone)]
 pub struct ExportConfig {
@@ -46,6 +77,22 @@ pub struct ExportConfig {
     pub allow_overlapping_highlights: bool,
     /// Show legend for pipeline steps/colors
     pub show_pipeline_legend: bool,
+    /// Emit extractions as nested groups (by group_index) in JSON export
+    pub group_extractions: bool,
+    /// How overlapping extractions are highlighted in HTML export
+    pub overlap_policy: OverlapPolicy,
+    /// Emit the text once plus the extractions as embedded JSON, highlighted client-side on load (HTML export)
+    ///
+    /// Keeps files for large documents small. `OverlapPolicy::Nest` is rendered as `KeepFirst`.
+    pub lazy_render: bool,
+    /// How extraction attributes are written in CSV export
+    pub csv_attributes: CsvAttributeMode,
+    /// Separator joining the items of list-valued attributes in `CsvAttributeMode::Columns`
+    pub attribute_separator: String,
+    /// Write JSON export on one line without null fields, using `start`/`end` for intervals
+    pub compact: bool,
+    /// Include the id of the chunk each extraction came from (JSON/CSV export; implied by `show_char_intervals`)
+    pub show_chunk_ids: bool,
 }
 
 impl Default for ExportConfig {
@@ -62,6 +109,13 @@ impl Default for ExportConfig {
             expand_nested_json: false,
             allow_overlapping_highlights: false,
             show_pipeline_legend: true,
+            group_extractions: false,
+            overlap_policy: OverlapPolicy::KeepFirst,
+            lazy_render: false,
+            csv_attributes: CsvAttributeMode::default(),
+            attribute_separator: "; ".to_string(),
+            compact: false,
+            show_chunk_ids: false,
         }
     }
 }
@@ -148,6 +202,94 @@ pub fn export_pipeline_html(
     Ok(html)
 }
 
+/// Export an HTML report of the differences between two runs over the same text
+///
+/// Extractions only in `new` are highlighted green, those only in `old` red,
+/// changed ones amber, and unchanged ones in a neutral color.
+pub fn export_diff_html(
+    old: &AnnotatedDocument,
+    new: &AnnotatedDocument,
+    original_text: &str,
+) -> LangExtractResult<String> {
+    let diff = crate::data::diff_documents(old, new);
+    let changed_after: Vec<&Extraction> = diff.changed.iter().map(|c| &c.after).collect();
+    let unchanged = new
+        .extractions
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .filter(|e| !diff.added.contains(e) && !changed_after.contains(e));
+
+    let tagged = unchanged
+        .map(|e| (e, "unchanged"))
+        .chain(diff.added.iter().map(|e| (e, "added")))
+        .chain(diff.removed.iter().map(|e| (e, "removed")))
+        .chain(changed_after.iter().map(|e| (*e, "changed")));
+
+    let mut spans: Vec<LayeredSpan> = tagged
+        .filter_map(|(e, status)| {
+            let interval = e.char_interval.as_ref()?;
+            let (start, end) = (interval.start_pos?, interval.end_pos?);
+            (start < end && end <= original_text.len()).then(|| LayeredSpan {
+                start,
+                end,
+                class_name: e.extraction_class.clone(),
+                text: e.extraction_text.clone(),
+                step_index: 0,
+                parent_step_id: None,
+                parent_class: None,
+                parent_text: None,
+                diff_status: Some(status),
+            })
+        })
+        .collect();
+    spans.sort_by_key(|s| (s.start, s.end));
+
+    let title = "LangExtract Diff";
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n");
+    html.push_str("<html lang=\"en\">\n");
+    html.push_str("<head>\n");
+    html.push_str("    <meta charset=\"UTF-8\">\n");
+    html.push_str(&format!("    <title>{}</title>\n", title));
+    html.push_str("    <style>\n");
+    html.push_str("        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; max-width: 1200px; margin: 0 auto; padding: 20px; background: #f8fafc; color: #334155; }\n");
+    html.push_str("        .document-text { background: #f1f5f9; border-radius: 8px; padding: 16px; font-family: 'Monaco', 'Menlo', monospace; line-height: 1.6; white-space: pre-wrap; }\n");
+    html.push_str("        .legend { display: flex; gap: 12px; flex-wrap: wrap; margin-bottom: 12px; }\n");
+    html.push_str("        .legend-item { display: inline-flex; align-items: center; gap: 8px; padding: 6px 10px; border: 1px solid #e2e8f0; border-radius: 6px; background: #fff; }\n");
+    html.push_str("        .badge { width: 12px; height: 12px; border-radius: 3px; display: inline-block; }\n");
+    html.push_str("        .extraction-highlight { border-radius: 3px; padding: 1px 2px; cursor: pointer; }\n");
+    html.push_str("        .added { background: rgba(34, 197, 94, 0.25); border: 1px solid rgba(34, 197, 94, 0.6); }\n");
+    html.push_str("        .removed { background: rgba(239, 68, 68, 0.25); border: 1px solid rgba(239, 68, 68, 0.6); text-decoration: line-through; }\n");
+    html.push_str("        .changed { background: rgba(234, 179, 8, 0.25); border: 1px solid rgba(234, 179, 8, 0.6); }\n");
+    html.push_str("        .unchanged { background: rgba(148, 163, 184, 0.2); border: 1px solid rgba(148, 163, 184, 0.5); }\n");
+    html.push_str("    </style>\n");
+    html.push_str("</head>\n");
+    html.push_str("<body>\n");
+    html.push_str(&format!("    <h1>{}</h1>\n", title));
+    html.push_str(&format!(
+        "    <p>{} added, {} removed, {} changed</p>\n",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    ));
+    html.push_str("    <div class=\"legend\">");
+    for status in ["added", "removed", "changed", "unchanged"] {
+        html.push_str(&format!(
+            r#"<span class="legend-item"><span class="badge {}"></span>{}</span>"#,
+            status, status
+        ));
+    }
+    html.push_str("</div>\n");
+    html.push_str("    <div class=\"document-text\">");
+    html.push_str(&highlight_text_html_with_layers(original_text, &spans, true)?);
+    html.push_str("</div>\n");
+    html.push_str("</body>\n");
+    html.push_str("</html>\n");
+
+    Ok(html)
+}
+
 /// Export a flattened JSON view of pipeline results (one item per atomic extraction)
 pub fn export_pipeline_flattened_json(
     pipeline_result: &PipelineResult,
@@ -172,8 +314,8 @@ pub fn export_pipeline_flattened_json(
         obj.insert("step_name".to_string(), Value::String(step_name.to_string()));
         if let (Some(s), Some(e)) = (start, end) {
             let mut ci = serde_json::Map::new();
-            ci.insert("start_pos".to_string(), Value::Number(serde_json::Number::from(s as u64)));
-            ci.insert("end_pos".to_string(), Value::Number(serde_json::Number::from(e as u64)));
+            ci.insert("start".to_string(), Value::Number(serde_json::Number::from(s as u64)));
+            ci.insert("end".to_string(), Value::Number(serde_json::Number::from(e as u64)));
             obj.insert("char_interval".to_string(), Value::Object(ci));
         }
         if let Some(attrs) = parent_attrs {
@@ -292,6 +434,17 @@ struct LayeredSpan {
     parent_step_id: Option<String>,
     parent_class: Option<String>,
     parent_text: Option<String>,
+    /// Diff status class ("added", "removed", ...) used instead of the step color
+    diff_status: Option<&'static str>,
+}
+
+impl LayeredSpan {
+    fn highlight_class(&self) -> String {
+        match self.diff_status {
+            Some(status) => status.to_string(),
+            None => format!("step-{}", self.step_index),
+        }
+    }
 }
 
 fn build_layered_spans(pipeline_result: &PipelineResult, original_text: &str, expand_nested_json: bool) -> Vec<LayeredSpan> {
@@ -318,6 +471,7 @@ fn build_layered_spans(pipeline_result: &PipelineResult, original_text: &str, ex
                             parent_step_id: e.attributes.as_ref().and_then(|m| m.get("parent_step_id")).and_then(|v| v.as_str()).map(|s| s.to_string()),
                             parent_class: e.attributes.as_ref().and_then(|m| m.get("parent_class")).and_then(|v| v.as_str()).map(|s| s.to_string()),
                             parent_text: e.attributes.as_ref().and_then(|m| m.get("parent_text")).and_then(|v| v.as_str()).map(|s| s.to_string()),
+                            diff_status: None,
                         });
                         added = true;
                     }
@@ -338,6 +492,7 @@ fn build_layered_spans(pipeline_result: &PipelineResult, original_text: &str, ex
                             parent_step_id: e.attributes.as_ref().and_then(|m| m.get("parent_step_id")).and_then(|v| v.as_str()).map(|s| s.to_string()),
                             parent_class: e.attributes.as_ref().and_then(|m| m.get("parent_class")).and_then(|v| v.as_str()).map(|s| s.to_string()),
                             parent_text: e.attributes.as_ref().and_then(|m| m.get("parent_text")).and_then(|v| v.as_str()).map(|s| s.to_string()),
+                            diff_status: None,
                         });
                     }
                 }
@@ -388,6 +543,7 @@ fn build_layered_spans(pipeline_result: &PipelineResult, original_text: &str, ex
                                         parent_step_id: parent_step_id.clone(),
                                         parent_class: e.attributes.as_ref().and_then(|m| m.get("parent_class")).and_then(|v| v.as_str()).map(|s| s.to_string()),
                                         parent_text: e.attributes.as_ref().and_then(|m| m.get("parent_text")).and_then(|v| v.as_str()).map(|s| s.to_string()),
+                                        diff_status: None,
                                     });
                                 }
                             }
@@ -434,7 +590,7 @@ fn build_extractions_list_html(spans: &[LayeredSpan]) -> String {
     html
 }
 
-/// Build HTML of text with layered spans. Currently uses non-overlapping simplification.
+/// Build HTML of text with layered spans, nesting overlapping spans when `allow_overlaps` is set.
 fn highlight_text_html_with_layers(
     text: &str,
     spans: &[LayeredSpan],
@@ -462,8 +618,8 @@ fn highlight_text_html_with_layers(
                 let s = &spans[idx];
                 let seg = &text[safe_start..safe_end];
                 result.push_str(&format!(
-                    r#"<span class="extraction-highlight step-{}" data-class="{}" data-text="{}" data-parent-class="{}">{}</span>"#,
-                    s.step_index,
+                    r#"<span class="extraction-highlight {}" data-class="{}" data-text="{}" data-parent-class="{}">{}</span>"#,
+                    s.highlight_class(),
                     html_escape(&s.class_name),
                     html_escape(&s.text),
                     html_escape(s.parent_class.as_deref().unwrap_or("")),
@@ -486,56 +642,49 @@ fn highlight_text_html_with_layers(
             events.push((s.end, false, i));
         }
     }
-    events.sort_by_key(|(pos, is_start, idx)| (*pos, !*is_start, spans[*idx].step_index));
+    // Close before opening at the same position; open wider spans first so they enclose narrower ones
+    events.sort_by_key(|(pos, is_start, idx)| {
+        let s = &spans[*idx];
+        (*pos, *is_start, std::cmp::Reverse(s.end), s.step_index)
+    });
+
+    let open_tag = |s: &LayeredSpan| {
+        format!(
+            r#"<span class="extraction-highlight {}" data-class="{}" data-text="{}" data-parent-class="{}">"#,
+            s.highlight_class(),
+            html_escape(&s.class_name),
+            html_escape(&s.text),
+            html_escape(s.parent_class.as_deref().unwrap_or(""))
+        )
+    };
 
     let mut result = String::new();
     let mut cursor = 0usize;
     let mut open: Vec<usize> = Vec::new();
 
-    let mut push_plain = |from: usize, to: usize, out: &mut String| {
-        if to > from {
-            out.push_str(&html_escape(&text[from..to]));
-        }
-    };
-
     for (pos, is_start, idx) in events {
-        let safe_pos = find_char_boundary(text, pos);
+        let safe_pos = find_char_boundary(text, pos).max(cursor);
+        if safe_pos > cursor {
+            result.push_str(&html_escape(&text[cursor..safe_pos]));
+            cursor = safe_pos;
+        }
         if is_start {
-            push_plain(cursor, safe_pos, &mut result);
-            let s = &spans[idx];
-            result.push_str(&format!(
-                r#"<span class="extraction-highlight step-{}" data-class="{}" data-text="{}" data-parent-class="{}">{}</span>"#,
-                s.step_index,
-                html_escape(&s.class_name),
-                html_escape(&s.text),
-                html_escape(s.parent_class.as_deref().unwrap_or("")),
-                html_escape(&text[cursor..safe_pos])
-            ));
+            result.push_str(&open_tag(&spans[idx]));
             open.push(idx);
-            cursor = safe_pos;
-        } else {
-            push_plain(cursor, safe_pos, &mut result);
-            cursor = safe_pos;
-            if let Some(pos_in_open) = open.iter().rposition(|&j| j == idx) {
-                for _ in pos_in_open..open.len() {
-                    result.push_str("</span>");
-                }
-                open.remove(pos_in_open);
-                for j in open.iter().copied() {
-                    let s = &spans[j];
-                    result.push_str(&format!(
-                        r#"<span class="extraction-highlight step-{}" data-class="{}" data-text="{}" data-parent-class="{}">{}</span>"#,
-                        s.step_index,
-                        html_escape(&s.class_name),
-                        html_escape(&s.text),
-                        html_escape(s.parent_class.as_deref().unwrap_or("")),
-                        html_escape(&text[cursor..safe_pos])
-                    ));
-                }
+        } else if let Some(pos_in_open) = open.iter().rposition(|&j| j == idx) {
+            // Close everything opened after this span, then reopen the ones still active
+            for _ in pos_in_open..open.len() {
+                result.push_str("</span>");
+            }
+            open.remove(pos_in_open);
+            for &j in &open[pos_in_open..] {
+                result.push_str(&open_tag(&spans[j]));
             }
         }
     }
-    push_plain(cursor, text.len(), &mut result);
+    if cursor < text.len() {
+        result.push_str(&html_escape(&text[cursor..]));
+    }
     for _ in 0..open.len() { result.push_str("</span>"); }
     Ok(result)
 }
@@ -548,6 +697,69 @@ pub fn visualize(
     visualize_text(annotated_document, show_char_intervals)
 }
 
+/// ANSI foreground colors cycled through for extraction classes
+const ANSI_CLASS_COLORS: [&str; 6] = ["32", "33", "34", "35", "36", "31"];
+
+/// ANSI color used for every extraction when not coloring by class
+const ANSI_DEFAULT_COLOR: &str = "33";
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Pick a stable ANSI color for an extraction class
+fn ansi_color_for_class(class: &str) -> &'static str {
+    // FNV-1a keeps the class -> color mapping stable across runs
+    let hash = class
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3));
+    ANSI_CLASS_COLORS[(hash % ANSI_CLASS_COLORS.len() as u64) as usize]
+}
+
+/// Render the document text for a terminal with extractions highlighted by ANSI colors
+///
+/// With `color_by_class` every extraction class gets its own stable color and a
+/// legend is appended; otherwise all extractions share one highlight color.
+/// Overlapping extractions are skipped, as in the HTML view.
+pub fn visualize_ansi(annotated_document: &AnnotatedDocument, color_by_class: bool) -> LangExtractResult<String> {
+    let text = annotated_document.text.as_deref().unwrap_or("");
+    let extractions = annotated_document.extractions.as_deref().unwrap_or(&[]);
+    let color_for = |class: &str| {
+        if color_by_class { ansi_color_for_class(class) } else { ANSI_DEFAULT_COLOR }
+    };
+
+    let mut result = String::new();
+    let mut last_pos = 0;
+
+    for (start, end, extraction) in non_overlapping_intervals(text, extractions) {
+        let safe_start = find_char_boundary(text, start).max(last_pos);
+        let safe_end = find_char_boundary(text, end);
+        if safe_start >= safe_end {
+            continue;
+        }
+
+        result.push_str(&text[last_pos..safe_start]);
+        result.push_str(&format!(
+            "\x1b[1;{}m{}{}",
+            color_for(&extraction.extraction_class),
+            &text[safe_start..safe_end],
+            ANSI_RESET
+        ));
+        last_pos = safe_end;
+    }
+    result.push_str(&text[last_pos..]);
+
+    if color_by_class && !extractions.is_empty() {
+        let classes: std::collections::BTreeSet<&str> =
+            extractions.iter().map(|e| e.extraction_class.as_str()).collect();
+        let legend: Vec<String> = classes
+            .into_iter()
+            .map(|class| format!("\x1b[1;{}m{}{}", color_for(class), class, ANSI_RESET))
+            .collect();
+        result.push_str(&format!("\n\nLegend: {}", legend.join(" ")));
+    }
+
+    Ok(result)
+}
+
 /// Export as simple text format (original implementation)
 fn visualize_text(
     annotated_document: &AnnotatedDocument,
@@ -574,7 +786,7 @@ fn visualize_text(
             result.push_str(&format!("{}. [{}] {}\n",
                 i + 1,
                 extraction.extraction_class,
-                extraction.extraction_text
+                display_text(extraction)
             ));
 
             if show_char_intervals {
@@ -708,6 +920,10 @@ fn export_html(
             padding: 15px;
             transition: all 0.2s ease;
         }}
+        .extraction-card.absent {{
+            border-style: dashed;
+            color: #94a3b8;
+        }}
         .extraction-card:hover {{
             border-color: #3b82f6;
             box-shadow: 0 4px 12px rgba(59, 130, 246, 0.15);
@@ -767,6 +983,17 @@ fn export_html(
         .class-count-item:last-child {{
             border-bottom: none;
         }}
+        .alignment-table {{
+            width: 100%;
+            border-collapse: collapse;
+            background: #f1f5f9;
+            border-radius: 8px;
+        }}
+        .alignment-table th, .alignment-table td {{
+            padding: 8px 12px;
+            text-align: left;
+            border-bottom: 1px solid #e2e8f0;
+        }}
         .class-badge {{
             background: #10b981;
             color: white;
@@ -795,8 +1022,8 @@ fn export_html(
                 <h2>📄 Document Text</h2>
                 <div class="document-text">"#);
 
-        if config.highlight_extractions {
-            html.push_str(&highlight_text_html(text, annotated_document)?);
+        if config.highlight_extractions && !config.lazy_render {
+            html.push_str(&highlight_text_html(text, annotated_document, config.overlap_policy)?);
         } else {
             html.push_str(&html_escape(text));
         }
@@ -804,18 +1031,26 @@ fn export_html(
         html.push_str("</div>\n            </div>\n");
     }
 
-    // Extractions section
-    if let Some(extractions) = &annotated_document.extractions {
+    // Extractions section, rendered from the embedded JSON in lazy mode
+    if let (true, Some(extractions)) = (config.lazy_render, &annotated_document.extractions) {
+        html.push_str(&format!(r#"            <div class="section">
+                <h2>🎯 Extractions ({} found)</h2>
+                <div class="extractions-grid" id="extractions-grid"></div>
+            </div>
+            <script type="application/json" id="extraction-data">{}</script>
+"#, extractions.len(), lazy_extraction_data(text, extractions, config)));
+    } else if let Some(extractions) = &annotated_document.extractions {
         html.push_str(&format!(r#"            <div class="section">
                 <h2>🎯 Extractions ({} found)</h2>
                 <div class="extractions-grid">
 "#, extractions.len()));
 
         for extraction in extractions {
-            html.push_str(&format!(r#"                    <div class="extraction-card">
+            html.push_str(&format!(r#"                    <div class="extraction-card{}">
                         <div class="extraction-class">{}</div>
                         <div class="extraction-text">{}</div>
-"#, html_escape(&extraction.extraction_class), html_escape(&extraction.extraction_text)));
+"#, if extraction.present { "" } else { " absent" },
+                html_escape(&extraction.extraction_class), html_escape(display_text(extraction))));
 
             if config.show_char_intervals {
                 if let Some(interval) = &extraction.char_interval {
@@ -829,12 +1064,30 @@ fn export_html(
 "#, html_escape(description)));
             }
 
+            if let Some(attributes) = &extraction.attributes {
+                let mut attributes: Vec<_> = attributes.iter().collect();
+                attributes.sort_by(|a, b| a.0.cmp(b.0));
+                for (key, value) in attributes {
+                    let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                    html.push_str(&format!(r#"                        <div class="extraction-meta">{}: {}</div>
+"#, html_escape(key), html_escape(&value)));
+                }
+            }
+
             html.push_str("                    </div>\n");
         }
 
         html.push_str("                </div>\n            </div>\n");
     }
 
+    // Table section for cells parsed in table mode
+    if let Some(table) = annotated_document.extractions.as_deref().and_then(table_html) {
+        html.push_str(&format!(r#"            <div class="section">
+                <h2>🧮 Table</h2>
+{}            </div>
+"#, table));
+    }
+
     // Statistics section
     if config.include_statistics {
         html.push_str(r#"            <div class="section">
@@ -877,6 +1130,18 @@ fn export_html(
             }
 
             html.push_str("                </div>\n");
+
+            html.push_str(r#"                <h3>Alignment by Class</h3>
+                <table class="alignment-table">
+                    <tr><th>Class</th><th>Exact</th><th>Fuzzy</th><th>Unaligned</th></tr>
+"#);
+            for (class, (exact, fuzzy, none)) in sorted_class_alignment(extractions) {
+                html.push_str(&format!(
+                    "                    <tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    html_escape(&class), exact, fuzzy, none
+                ));
+            }
+            html.push_str("                </table>\n");
         } else {
             html.push_str("                </div>\n");
         }
@@ -884,12 +1149,42 @@ fn export_html(
         html.push_str("            </div>\n");
     }
 
+    // Run metadata section
+    if let Some(metadata) = &annotated_document.run_metadata {
+        html.push_str(r#"            <div class="section">
+                <h2>🧾 Run Metadata</h2>
+                <div class="class-counts">
+"#);
+        let mut rows = vec![
+            ("Model", metadata.model_id.clone()),
+            ("Temperature", metadata.temperature.to_string()),
+            ("Timestamp", metadata.timestamp.clone()),
+        ];
+        if let Some(prompt) = &metadata.prompt {
+            rows.push(("Prompt", prompt.clone()));
+        }
+        for (label, value) in rows {
+            html.push_str(&format!(r#"                    <div class="class-count-item">
+                        <span>{}</span>
+                        <span>{}</span>
+                    </div>
+"#, label, html_escape(&value)));
+        }
+        html.push_str("                </div>
+            </div>
+");
+    }
+
     // Footer
     html.push_str(r#"        </div>
     </div>
 
     <script>
-        // Add interactivity for extraction highlights
+"#);
+    if config.lazy_render {
+        html.push_str(LAZY_RENDER_SCRIPT);
+    }
+    html.push_str(r#"        // Add interactivity for extraction highlights
         document.querySelectorAll('.extraction-highlight').forEach(element => {
             element.addEventListener('click', function() {
                 const className = this.getAttribute('data-class');
@@ -904,6 +1199,164 @@ fn export_html(
     Ok(html)
 }
 
+/// Client-side renderer for [`ExportConfig::lazy_render`] highlights and extraction cards
+const LAZY_RENDER_SCRIPT: &str = r#"        (() => {
+            const data = document.getElementById('extraction-data');
+            if (!data) return;
+            const { highlight, extractions } = JSON.parse(data.textContent);
+            const element = (tag, className, text) => {
+                const node = document.createElement(tag);
+                node.className = className;
+                if (text !== undefined) node.textContent = text;
+                return node;
+            };
+
+            const container = document.querySelector('.document-text');
+            if (highlight && container) {
+                const text = container.textContent;
+                const fragment = document.createDocumentFragment();
+                let pos = 0;
+                extractions.filter(e => e.highlight).sort((a, b) => a.start - b.start).forEach(e => {
+                    fragment.append(text.slice(pos, e.start));
+                    const span = element('span', 'extraction-highlight', text.slice(e.start, e.end));
+                    span.dataset.class = e.class;
+                    span.dataset.text = e.text;
+                    fragment.append(span);
+                    pos = e.end;
+                });
+                fragment.append(text.slice(pos));
+                container.replaceChildren(fragment);
+            }
+
+            const grid = document.getElementById('extractions-grid');
+            extractions.forEach(e => {
+                const card = element('div', 'extraction-card');
+                card.append(element('div', 'extraction-class', e.class), element('div', 'extraction-text', e.text));
+                (e.meta || []).forEach(line => card.append(element('div', 'extraction-meta', line)));
+                grid.append(card);
+            });
+        })();
+"#;
+
+/// Embedded JSON for [`ExportConfig::lazy_render`]
+///
+/// Offsets are UTF-16 code units, matching JavaScript string indexing. The
+/// JSON is safe to place inside a `<script>` element.
+fn lazy_extraction_data(text: &str, extractions: &[Extraction], config: &ExportConfig) -> String {
+    let highlighted = match config.overlap_policy {
+        OverlapPolicy::KeepFirst | OverlapPolicy::Nest => non_overlapping_intervals(text, extractions),
+        OverlapPolicy::KeepLongest => {
+            best_non_overlapping_intervals(text, extractions, |start, end, _| (end - start) as f64)
+        }
+        OverlapPolicy::KeepHighestConfidence => {
+            best_non_overlapping_intervals(text, extractions, |_, _, e| extraction_confidence(e))
+        }
+    };
+    let highlighted: Vec<(usize, usize, &Extraction)> = highlighted
+        .into_iter()
+        .map(|(start, end, e)| (find_char_boundary(text, start), find_char_boundary(text, end), e))
+        .filter(|(start, end, _)| start < end)
+        .collect();
+    let offsets: Vec<usize> = highlighted.iter().flat_map(|(start, end, _)| [*start, *end]).collect();
+    let utf16 = utf16_offsets(text, &offsets);
+
+    let entries: Vec<Value> = extractions
+        .iter()
+        .map(|extraction| {
+            let mut meta = Vec::new();
+            if config.show_char_intervals {
+                if let Some(interval) = &extraction.char_interval {
+                    meta.push(format!("Position: {}-{}", interval.start_pos.unwrap_or(0), interval.end_pos.unwrap_or(0)));
+                }
+            }
+            if let Some(description) = &extraction.description {
+                meta.push(format!("Description: {}", description));
+            }
+            if let Some(attributes) = &extraction.attributes {
+                let mut attributes: Vec<_> = attributes.iter().collect();
+                attributes.sort_by(|a, b| a.0.cmp(b.0));
+                for (key, value) in attributes {
+                    meta.push(format!("{}: {}", key, value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string())));
+                }
+            }
+
+            let mut entry = json!({
+                "class": extraction.extraction_class,
+                "text": display_text(extraction),
+                "meta": meta,
+            });
+            if let Some((start, end, _)) = highlighted.iter().find(|(_, _, e)| std::ptr::eq(*e, extraction)) {
+                entry["highlight"] = json!(true);
+                entry["start"] = json!(utf16[start]);
+                entry["end"] = json!(utf16[end]);
+            }
+            entry
+        })
+        .collect();
+
+    json!({ "highlight": config.highlight_extractions, "extractions": entries })
+        .to_string()
+        .replace("</", "<\\/")
+}
+
+/// Map byte offsets (on char boundaries) in `text` to UTF-16 offsets
+fn utf16_offsets(text: &str, byte_offsets: &[usize]) -> HashMap<usize, usize> {
+    let mut sorted = byte_offsets.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut offsets = HashMap::with_capacity(sorted.len());
+    let mut units = 0;
+    let mut chars = text.char_indices().peekable();
+    for offset in sorted {
+        while let Some((_, c)) = chars.next_if(|(i, _)| *i < offset) {
+            units += c.len_utf16();
+        }
+        offsets.insert(offset, units);
+    }
+    offsets
+}
+
+/// Render table-mode cells as an HTML table: one row per group, columns in first-seen order
+fn table_html(extractions: &[Extraction]) -> Option<String> {
+    let cells: Vec<(&str, &Extraction)> = extractions
+        .iter()
+        .filter_map(|extraction| {
+            let column = extraction.attributes.as_ref()?.get(TABLE_COLUMN_ATTRIBUTE)?.as_str()?;
+            Some((column, extraction))
+        })
+        .collect();
+    if cells.is_empty() {
+        return None;
+    }
+
+    let mut columns: Vec<&str> = Vec::new();
+    let mut rows: std::collections::BTreeMap<usize, HashMap<&str, &str>> = std::collections::BTreeMap::new();
+    for (column, extraction) in &cells {
+        if !columns.contains(column) {
+            columns.push(column);
+        }
+        rows.entry(extraction.group_index.unwrap_or(usize::MAX))
+            .or_default()
+            .insert(column, display_text(extraction));
+    }
+
+    let mut html = String::from("                <table class=\"alignment-table\">\n                    <tr>");
+    for column in &columns {
+        html.push_str(&format!("<th>{}</th>", html_escape(column)));
+    }
+    html.push_str("</tr>\n");
+    for row in rows.values() {
+        html.push_str("                    <tr>");
+        for column in &columns {
+            html.push_str(&format!("<td>{}</td>", html_escape(row.get(column).copied().unwrap_or(""))));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("                </table>\n");
+    Some(html)
+}
+
 /// Helper function to escape HTML characters
 fn html_escape(text: &str) -> String {
     text.replace('&', "&amp;")
@@ -933,39 +1386,139 @@ fn find_char_boundary(text: &str, mut index: usize) -> usize {
     index
 }
 
-/// Helper function to highlight extractions in text
-fn highlight_text_html(text: &str, annotated_document: &AnnotatedDocument) -> LangExtractResult<String> {
-    if let Some(extractions) = &annotated_document.extractions {
-        // Collect all valid intervals with their extraction info
-        let mut intervals: Vec<(usize, usize, &Extraction)> = Vec::new();
+/// Byte range for a character interval, clamped to `text` and widened to UTF-8 character boundaries
+///
+/// An offset inside a multi-byte character expands the range to cover the
+/// whole character, so slicing `text` with the result never panics.
+fn char_boundary_range(text: &str, start: usize, end: usize) -> (usize, usize) {
+    let start = find_char_boundary(text, start);
+    let mut end = end.min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    (start, end.max(start))
+}
 
-        for extraction in extractions {
-            if let Some(interval) = &extraction.char_interval {
-                if let (Some(start), Some(end)) = (interval.start_pos, interval.end_pos) {
-                    if start < end && end <= text.len() {
-                        intervals.push((start, end, extraction));
-                    }
+/// Collect the valid extraction intervals in `text`, sorted and with overlaps removed
+///
+/// When intervals overlap the first one (by start position) is kept.
+fn non_overlapping_intervals<'a>(text: &str, extractions: &'a [Extraction]) -> Vec<(usize, usize, &'a Extraction)> {
+    // Collect all valid intervals with their extraction info
+    let mut intervals: Vec<(usize, usize, &Extraction)> = Vec::new();
+
+    for extraction in extractions {
+        if let Some(interval) = &extraction.char_interval {
+            if let (Some(start), Some(end)) = (interval.start_pos, interval.end_pos) {
+                if start < end && end <= text.len() {
+                    intervals.push((start, end, extraction));
                 }
             }
         }
+    }
 
-        // Sort by start position
-        intervals.sort_by_key(|(start, _, _)| *start);
+    // Sort by start position
+    intervals.sort_by_key(|(start, _, _)| *start);
 
-        // Remove overlapping intervals - keep the first one when intervals overlap
-        let mut filtered_intervals = Vec::new();
-        let mut last_end = 0;
+    // Remove overlapping intervals - keep the first one when intervals overlap
+    let mut filtered_intervals = Vec::new();
+    let mut last_end = 0;
 
-        for (start, end, extraction) in intervals {
-            if start >= last_end {
-                filtered_intervals.push((start, end, extraction));
-                last_end = end;
-            } else {
-                // Skip overlapping interval, but log it for debugging
-                log::debug!("Skipping overlapping extraction: '{}' at {}-{} (overlaps with previous ending at {})",
-                    extraction.extraction_text, start, end, last_end);
-            }
+    for (start, end, extraction) in intervals {
+        if start >= last_end {
+            filtered_intervals.push((start, end, extraction));
+            last_end = end;
+        } else {
+            // Skip overlapping interval, but log it for debugging
+            log::debug!("Skipping overlapping extraction: '{}' at {}-{} (overlaps with previous ending at {})",
+                extraction.extraction_text, start, end, last_end);
         }
+    }
+
+    filtered_intervals
+}
+
+/// Confidence score used by [`OverlapPolicy::KeepHighestConfidence`]
+fn extraction_confidence(extraction: &Extraction) -> f64 {
+    let from_attribute = extraction
+        .attributes
+        .as_ref()
+        .and_then(|attributes| attributes.get("confidence"))
+        .and_then(|value| value.as_f64().or_else(|| value.as_str()?.parse().ok()));
+
+    from_attribute.unwrap_or(match extraction.alignment_status {
+        Some(AlignmentStatus::MatchExact) => 1.0,
+        Some(AlignmentStatus::MatchGreater) | Some(AlignmentStatus::MatchLesser) => 0.75,
+        Some(AlignmentStatus::MatchFuzzy) => 0.5,
+        None => 0.0,
+    })
+}
+
+/// Resolve overlapping intervals by keeping the best-scoring one of each overlapping pair
+fn best_non_overlapping_intervals<'a>(
+    text: &str,
+    extractions: &'a [Extraction],
+    score: impl Fn(usize, usize, &Extraction) -> f64,
+) -> Vec<(usize, usize, &'a Extraction)> {
+    let mut candidates: Vec<(usize, usize, &Extraction)> = extractions
+        .iter()
+        .filter_map(|extraction| {
+            let interval = extraction.char_interval.as_ref()?;
+            let (start, end) = (interval.start_pos?, interval.end_pos?);
+            (start < end && end <= text.len()).then_some((start, end, extraction))
+        })
+        .collect();
+    // Highest score first; ties go to the earlier extraction
+    candidates.sort_by(|a, b| {
+        score(b.0, b.1, b.2)
+            .total_cmp(&score(a.0, a.1, a.2))
+            .then(a.0.cmp(&b.0))
+    });
+
+    let mut kept: Vec<(usize, usize, &Extraction)> = Vec::new();
+    for (start, end, extraction) in candidates {
+        if kept.iter().all(|(s, e, _)| end <= *s || start >= *e) {
+            kept.push((start, end, extraction));
+        } else {
+            log::debug!("Skipping overlapping extraction: '{}' at {}-{}", extraction.extraction_text, start, end);
+        }
+    }
+    kept.sort_by_key(|(start, _, _)| *start);
+    kept
+}
+
+/// Helper function to highlight extractions in text
+fn highlight_text_html(text: &str, annotated_document: &AnnotatedDocument, policy: OverlapPolicy) -> LangExtractResult<String> {
+    if let Some(extractions) = &annotated_document.extractions {
+        let filtered_intervals = match policy {
+            OverlapPolicy::KeepFirst => non_overlapping_intervals(text, extractions),
+            OverlapPolicy::KeepLongest => {
+                best_non_overlapping_intervals(text, extractions, |start, end, _| (end - start) as f64)
+            }
+            OverlapPolicy::KeepHighestConfidence => {
+                best_non_overlapping_intervals(text, extractions, |_, _, e| extraction_confidence(e))
+            }
+            OverlapPolicy::Nest => {
+                let mut spans: Vec<LayeredSpan> = extractions
+                    .iter()
+                    .filter_map(|e| {
+                        let interval = e.char_interval.as_ref()?;
+                        Some(LayeredSpan {
+                            start: interval.start_pos?,
+                            end: interval.end_pos?,
+                            class_name: e.extraction_class.clone(),
+                            text: e.extraction_text.clone(),
+                            step_index: 0,
+                            parent_step_id: None,
+                            parent_class: None,
+                            parent_text: None,
+                            diff_status: None,
+                        })
+                    })
+                    .collect();
+                spans.sort_by_key(|s| (s.start, s.end));
+                return highlight_text_html_with_layers(text, &spans, true);
+            }
+        };
 
         // Now build the HTML with non-overlapping intervals
         let mut result = String::new();
@@ -973,8 +1526,7 @@ fn highlight_text_html(text: &str, annotated_document: &AnnotatedDocument) -> La
 
         for (start, end, extraction) in filtered_intervals {
             // Ensure we're at valid UTF-8 boundaries
-            let safe_start = find_char_boundary(text, start);
-            let safe_end = find_char_boundary(text, end);
+            let (safe_start, safe_end) = char_boundary_range(text, start, end);
 
             // Add text before this extraction
             if safe_start > last_pos {
@@ -1024,6 +1576,30 @@ fn count_extraction_classes(extractions: &[crate::data::Extraction]) -> HashMap<
     class_counts
 }
 
+/// Count how each extraction class aligned to the source text, as `(exact, fuzzy, none)`
+///
+/// `exact` includes the partial `MatchLesser` and `MatchGreater` matches, which
+/// still align on exact tokens; `none` counts extractions without an alignment.
+pub fn per_class_alignment(extractions: &[Extraction]) -> HashMap<String, (usize, usize, usize)> {
+    let mut counts: HashMap<String, (usize, usize, usize)> = HashMap::new();
+    for extraction in extractions {
+        let entry = counts.entry(extraction.extraction_class.clone()).or_default();
+        match extraction.alignment_status {
+            Some(AlignmentStatus::MatchFuzzy) => entry.1 += 1,
+            Some(_) => entry.0 += 1,
+            None => entry.2 += 1,
+        }
+    }
+    counts
+}
+
+/// [`per_class_alignment`] sorted by class name
+fn sorted_class_alignment(extractions: &[Extraction]) -> Vec<(String, (usize, usize, usize))> {
+    let mut counts: Vec<_> = per_class_alignment(extractions).into_iter().collect();
+    counts.sort();
+    counts
+}
+
 /// Export as structured markdown with extraction summaries
 fn export_markdown(
     annotated_document: &AnnotatedDocument,
@@ -1056,7 +1632,7 @@ fn export_markdown(
 
         for (i, extraction) in extractions.iter().enumerate() {
             md.push_str(&format!("### {}. {}\n\n", i + 1, extraction.extraction_class));
-            md.push_str(&format!("**Text:** {}\n\n", extraction.extraction_text));
+            md.push_str(&format!("**Text:** {}\n\n", display_text(extraction)));
 
             if config.show_char_intervals {
                 if let Some(interval) = &extraction.char_interval {
@@ -1089,6 +1665,14 @@ fn export_markdown(
             for (class, count) in class_counts {
                 md.push_str(&format!("| {} | {} |\n", class, count));
             }
+
+            md.push_str("\n### Alignment by Class\n\n");
+            md.push_str("| Class | Exact | Fuzzy | Unaligned |\n");
+            md.push_str("|-------|-------|-------|-----------|\n");
+
+            for (class, (exact, fuzzy, none)) in sorted_class_alignment(extractions) {
+                md.push_str(&format!("| {} | {} | {} | {} |\n", class, exact, fuzzy, none));
+            }
         }
 
         md.push_str("\n");
@@ -1113,16 +1697,19 @@ fn highlight_text_markdown(text: &str, annotated_document: &AnnotatedDocument) -
 
         for extraction in sorted_extractions {
             if let Some(interval) = &extraction.char_interval {
+                let (start, end) =
+                    char_boundary_range(text, interval.start_pos.unwrap_or(0), interval.end_pos.unwrap_or(0));
+                let start = start.max(last_pos);
+
                 // Add text before the extraction
-                if interval.start_pos.unwrap_or(0) > last_pos && interval.start_pos.unwrap_or(0) <= text.len() {
-                    result.push_str(&text[last_pos..interval.start_pos.unwrap_or(0)]);
+                if start > last_pos {
+                    result.push_str(&text[last_pos..start]);
                 }
 
                 // Add highlighted extraction with markdown bold
-                if interval.end_pos.unwrap_or(0) <= text.len() && interval.start_pos.unwrap_or(0) < interval.end_pos.unwrap_or(0) {
-                    let extraction_text = &text[interval.start_pos.unwrap_or(0)..interval.end_pos.unwrap_or(0)];
-                    result.push_str(&format!("**{}**", extraction_text));
-                    last_pos = interval.end_pos.unwrap_or(0);
+                if start < end {
+                    result.push_str(&format!("**{}**", &text[start..end]));
+                    last_pos = end;
                 }
             }
         }
@@ -1160,25 +1747,33 @@ fn export_json(
         json_data["text"] = json!(annotated_document.text);
     }
 
-    // Add extractions
-    if let Some(extractions) = &annotated_document.extractions {
-        let extractions_json: Vec<Value> = extractions.iter().map(|extraction| {
-            let mut ext_json = json!({
-                "extraction_class": extraction.extraction_class,
-                "extraction_text": extraction.extraction_text,
-                "description": extraction.description
-            });
-
-            if config.show_char_intervals {
-                if let Some(interval) = &extraction.char_interval {
-                    ext_json["char_interval"] = json!({
-                        "start_char": interval.start_pos.unwrap_or(0),
-                        "end_char": interval.end_pos.unwrap_or(0),
-                        "alignment_status": extraction.alignment_status.as_ref().map(|s| format!("{:?}", s)).unwrap_or_else(|| "None".to_string())
-                    });
+    // Add extractions, either nested by group or as a flat list
+    if config.group_extractions && annotated_document.extractions.is_some() {
+        let groups_json: Vec<Value> = annotated_document.grouped_extractions().iter().map(|group| {
+            let mut group_obj = serde_json::Map::new();
+            group_obj.insert("group_index".to_string(), json!(group.first().and_then(|e| e.group_index)));
+
+            for extraction in group {
+                let ext_json = extraction_to_json(extraction, config);
+                // Repeated classes within a group become an array
+                match group_obj.get_mut(&extraction.extraction_class) {
+                    Some(Value::Array(items)) => items.push(ext_json),
+                    Some(existing) => *existing = json!([existing.clone(), ext_json]),
+                    None => {
+                        group_obj.insert(extraction.extraction_class.clone(), ext_json);
+                    }
                 }
             }
 
+            Value::Object(group_obj)
+        }).collect();
+
+        json_data["groups"] = json!(groups_json);
+    } else if let Some(extractions) = &annotated_document.extractions {
+        let extractions_json: Vec<Value> = extractions.iter().map(|extraction| {
+            let mut ext_json = extraction_to_json(extraction, config);
+            ext_json["extraction_class"] = json!(extraction.extraction_class);
+
             if let Some(group_index) = extraction.group_index {
                 ext_json["group_index"] = json!(group_index);
             }
@@ -1201,56 +1796,224 @@ fn export_json(
             let class_counts = count_extraction_classes(extractions);
             stats["unique_classes"] = json!(class_counts.len());
             stats["extraction_classes"] = json!(class_counts);
+            stats["per_class_alignment"] = per_class_alignment(extractions)
+                .into_iter()
+                .map(|(class, (exact, fuzzy, none))| (class, json!({"exact": exact, "fuzzy": fuzzy, "none": none})))
+                .collect::<serde_json::Map<_, _>>()
+                .into();
         }
 
         json_data["statistics"] = stats;
     }
 
+    if let Some(metadata) = &annotated_document.run_metadata {
+        json_data["run_metadata"] = json!(metadata);
+    }
+
+    if config.compact {
+        if let Some(root) = json_data.as_object_mut() {
+            root.retain(|_, value| !value.is_null());
+        }
+        if let Some(export_config) = json_data["export_config"].as_object_mut() {
+            export_config.retain(|_, value| !value.is_null());
+        }
+        return Ok(serde_json::to_string(&json_data)?);
+    }
+
     Ok(serde_json::to_string_pretty(&json_data)?)
 }
 
+/// Convert a single extraction to its JSON export representation (without class)
+fn extraction_to_json(extraction: &Extraction, config: &ExportConfig) -> Value {
+    let mut ext_json = json!({
+        "extraction_text": extraction.extraction_text
+    });
+    if !config.compact || extraction.description.is_some() {
+        ext_json["description"] = json!(extraction.description);
+    }
+    if !extraction.present {
+        ext_json["present"] = json!(false);
+    }
+
+    if config.show_char_intervals {
+        if let Some(interval) = &extraction.char_interval {
+            ext_json["char_interval"] = char_interval_json(extraction, interval, config);
+        }
+    }
+
+    if config.show_char_intervals || config.show_chunk_ids {
+        if let Some(chunk_id) = extraction.chunk_id {
+            ext_json["chunk_id"] = json!(chunk_id);
+        }
+    }
+
+    if let Some(attributes) = &extraction.attributes {
+        if !attributes.is_empty() {
+            ext_json["attributes"] = json!(attributes);
+        }
+    }
+
+    ext_json
+}
+
+/// JSON for an extraction's interval; compact form uses `start`/`end` and omits missing fields
+fn char_interval_json(extraction: &Extraction, interval: &crate::data::CharInterval, config: &ExportConfig) -> Value {
+    let alignment_status = extraction.alignment_status.as_ref().map(|s| format!("{:?}", s));
+    if !config.compact {
+        return json!({
+            "start_char": interval.start_pos.unwrap_or(0),
+            "end_char": interval.end_pos.unwrap_or(0),
+            "alignment_status": alignment_status.unwrap_or_else(|| "None".to_string())
+        });
+    }
+
+    let mut interval_json = serde_json::Map::new();
+    if let Some(start) = interval.start_pos {
+        interval_json.insert("start".to_string(), json!(start));
+    }
+    if let Some(end) = interval.end_pos {
+        interval_json.insert("end".to_string(), json!(end));
+    }
+    if let Some(status) = alignment_status {
+        interval_json.insert("alignment_status".to_string(), json!(status));
+    }
+    Value::Object(interval_json)
+}
+
+/// Serialize one extraction as a compact single-line JSON object (one NDJSON record)
+pub fn extraction_to_ndjson_line(extraction: &Extraction, show_char_intervals: bool) -> String {
+    let config = ExportConfig {
+        show_char_intervals,
+        ..Default::default()
+    };
+    let mut ext_json = extraction_to_json(extraction, &config);
+    ext_json["extraction_class"] = json!(extraction.extraction_class);
+    ext_json.to_string()
+}
+
+/// Export all extractions as newline-delimited JSON, one extraction per line
+pub fn export_ndjson(annotated_document: &AnnotatedDocument, show_char_intervals: bool) -> String {
+    annotated_document
+        .extractions
+        .iter()
+        .flatten()
+        .map(|extraction| extraction_to_ndjson_line(extraction, show_char_intervals))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Export as CSV for spreadsheet analysis
 fn export_csv(
     annotated_document: &AnnotatedDocument,
     config: &ExportConfig,
 ) -> LangExtractResult<String> {
     let mut csv = String::new();
+    let extractions = annotated_document.extractions.as_deref().unwrap_or_default();
+    let attribute_keys: Vec<&String> = match config.csv_attributes {
+        CsvAttributeMode::Columns => extractions
+            .iter()
+            .filter_map(|e| e.attributes.as_ref())
+            .flat_map(|attributes| attributes.keys())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+        CsvAttributeMode::Json | CsvAttributeMode::Omit => Vec::new(),
+    };
 
     // CSV Header
+    let show_chunk_ids = config.show_char_intervals || config.show_chunk_ids;
     if config.show_char_intervals {
-        csv.push_str("extraction_class,extraction_text,description,start_char,end_char,alignment_status,group_index\n");
+        csv.push_str("extraction_class,extraction_text,description,start_char,end_char,alignment_status,group_index");
     } else {
-        csv.push_str("extraction_class,extraction_text,description,group_index\n");
+        csv.push_str("extraction_class,extraction_text,description,group_index");
+    }
+    if show_chunk_ids {
+        csv.push_str(",chunk_id");
+    }
+    for key in &attribute_keys {
+        csv.push_str(&format!(",{}", csv_escape(&format!("attributes.{}", key))));
     }
+    if config.csv_attributes == CsvAttributeMode::Json {
+        csv.push_str(",attributes");
+    }
+    csv.push('\n');
 
     // CSV Rows
-    if let Some(extractions) = &annotated_document.extractions {
-        for extraction in extractions {
-            let class = csv_escape(&extraction.extraction_class);
-            let text = csv_escape(&extraction.extraction_text);
-            let description = extraction.description.as_ref().map(|d| csv_escape(d)).unwrap_or_else(|| "".to_string());
-            let group_index = extraction.group_index.map(|i| i.to_string()).unwrap_or_else(|| "".to_string());
+    for extraction in extractions {
+        // Cells after group_index: the chunk id (when shown), then the attributes
+        let mut trailing_cells = String::new();
+        if show_chunk_ids {
+            trailing_cells.push_str(&format!(",{}", extraction.chunk_id.map(|id| id.to_string()).unwrap_or_default()));
+        }
+        for key in &attribute_keys {
+            let value = extraction.attributes.as_ref().and_then(|attributes| attributes.get(*key));
+            let cell = value.map(|v| attribute_cell_text(v, &config.attribute_separator)).unwrap_or_default();
+            trailing_cells.push_str(&format!(",{}", csv_escape(&cell)));
+        }
+        if config.csv_attributes == CsvAttributeMode::Json {
+            let json = extraction
+                .attributes
+                .as_ref()
+                .filter(|attributes| !attributes.is_empty())
+                .map(|attributes| json!(attributes).to_string())
+                .unwrap_or_default();
+            trailing_cells.push_str(&format!(",{}", csv_escape(&json)));
+        }
 
-            if config.show_char_intervals {
-                if let Some(interval) = &extraction.char_interval {
-                    csv.push_str(&format!("{},{},{},{},{},{:?},{}\n",
-                        class, text, description,
-                        interval.start_pos.unwrap_or(0), interval.end_pos.unwrap_or(0),
-                        extraction.alignment_status.as_ref().map(|s| format!("{:?}", s)).unwrap_or_else(|| "None".to_string()), group_index));
-                } else {
-                    csv.push_str(&format!("{},{},{},,,None,{}\n",
-                        class, text, description, group_index));
-                }
+        let class = csv_escape(&extraction.extraction_class);
+        let text = csv_escape(&extraction.extraction_text);
+        let description = extraction.description.as_ref().map(|d| csv_escape(d)).unwrap_or_else(|| "".to_string());
+        let group_index = extraction.group_index.map(|i| i.to_string()).unwrap_or_else(|| "".to_string());
+
+        if config.show_char_intervals {
+            if let Some(interval) = &extraction.char_interval {
+                // Report offsets that slice the document text cleanly, as the highlighters do
+                let (start, end) = (interval.start_pos.unwrap_or(0), interval.end_pos.unwrap_or(0));
+                let (start, end) = match &annotated_document.text {
+                    Some(document_text) => char_boundary_range(document_text, start, end),
+                    None => (start, end),
+                };
+                csv.push_str(&format!("{},{},{},{},{},{:?},{}{}\n",
+                    class, text, description,
+                    start, end,
+                    extraction.alignment_status.as_ref().map(|s| format!("{:?}", s)).unwrap_or_else(|| "None".to_string()), group_index,
+                    trailing_cells));
             } else {
-                csv.push_str(&format!("{},{},{},{}\n",
-                    class, text, description, group_index));
+                csv.push_str(&format!("{},{},{},,,None,{}{}\n",
+                    class, text, description, group_index, trailing_cells));
             }
+        } else {
+            csv.push_str(&format!("{},{},{},{}{}\n",
+                class, text, description, group_index, trailing_cells));
         }
     }
 
     Ok(csv)
 }
 
+/// Text of an attribute value for a CSV cell, joining list items with `separator`
+fn attribute_cell_text(value: &Value, separator: &str) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| attribute_cell_text(item, separator))
+            .collect::<Vec<_>>()
+            .join(separator),
+        other => other.to_string(),
+    }
+}
+
+/// Extraction text for display, marking absence assertions
+fn display_text(extraction: &Extraction) -> &str {
+    if extraction.present {
+        &extraction.extraction_text
+    } else {
+        ABSENT_LABEL
+    }
+}
+
 /// Helper function to escape CSV values
 fn csv_escape(text: &str) -> String {
     if text.contains(',') || text.contains('"') || text.contains('\n') {
@@ -1281,6 +2044,9 @@ mod tests {
                 description: Some("Person name".to_string()),
                 attributes: Some(HashMap::new()),
                 token_interval: None,
+                source_pass: None,
+                chunk_id: None,
+                present: true,
             },
             Extraction {
                 extraction_class: "company".to_string(),
@@ -1292,6 +2058,9 @@ mod tests {
                 description: None,
                 attributes: Some(HashMap::new()),
                 token_interval: None,
+                source_pass: None,
+                chunk_id: None,
+                present: true,
             },
             Extraction {
                 extraction_class: "salary".to_string(),
@@ -1303,6 +2072,9 @@ mod tests {
                 description: Some("Annual salary".to_string()),
                 attributes: Some(HashMap::new()),
                 token_interval: None,
+                source_pass: None,
+                chunk_id: None,
+                present: true,
             },
         ];
 
@@ -1310,6 +2082,9 @@ mod tests {
             document_id: Some("test_doc".to_string()),
             text: Some(text.to_string()),
             extractions: Some(extractions),
+            raw_outputs: None,
+            run_metadata: None,
+            offset_map: None,
         }
     }
 
@@ -1332,6 +2107,55 @@ mod tests {
         assert!(result.contains("Statistics:"));
     }
 
+    #[test]
+    fn test_ansi_visualization() {
+        let document = create_sample_document();
+
+        let result = visualize_ansi(&document, true).unwrap();
+        let person = format!("\x1b[1;{}mJohn Smith\x1b[0m", ansi_color_for_class("person"));
+        let company = format!("\x1b[1;{}mTechCorp\x1b[0m", ansi_color_for_class("company"));
+        let salary = format!("\x1b[1;{}m$50,000\x1b[0m", ansi_color_for_class("salary"));
+        assert!(result.starts_with(&format!("{} works at {} and earns {}.", person, company, salary)));
+        assert!(result.contains("Legend:"));
+        assert_eq!(ansi_color_for_class("person"), ansi_color_for_class("person"));
+
+        let plain = visualize_ansi(&document, false).unwrap();
+        assert!(plain.starts_with("\x1b[1;33mJohn Smith\x1b[0m works at \x1b[1;33mTechCorp\x1b[0m"));
+        assert!(!plain.contains("Legend:"));
+    }
+
+    #[test]
+    fn test_ansi_visualization_skips_overlaps() {
+        let mut document = create_sample_document();
+        let mut overlapping = Extraction::new("name".to_string(), "Smith".to_string());
+        overlapping.char_interval = Some(CharInterval::new(Some(5), Some(10)));
+        document.extractions.as_mut().unwrap().push(overlapping);
+
+        let result = visualize_ansi(&document, false).unwrap();
+        assert_eq!(result.matches("\x1b[0m").count(), 3);
+        assert!(result.contains("\x1b[1;33mJohn Smith\x1b[0m"));
+    }
+
+    #[test]
+    fn test_ndjson_export() {
+        let document = create_sample_document();
+
+        let output = export_ndjson(&document, true);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+
+        for (line, expected_class) in lines.iter().zip(["person", "company", "salary"]) {
+            let value: Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["extraction_class"], expected_class);
+            assert!(value["char_interval"]["start_char"].is_u64());
+        }
+
+        let without_intervals = export_ndjson(&document, false);
+        let first: Value = serde_json::from_str(without_intervals.lines().next().unwrap()).unwrap();
+        assert_eq!(first["extraction_text"], "John Smith");
+        assert!(first.get("char_interval").is_none());
+    }
+
     #[test]
     fn test_html_export() {
         let document = create_sample_document();
@@ -1393,6 +2217,44 @@ mod tests {
         assert!(result.contains("| person | 1 |"));
     }
 
+    #[test]
+    fn test_json_export_grouped() {
+        let text = "Alice is 30. Bob is 25.";
+        let make = |class: &str, value: &str, group: usize, index: usize| Extraction {
+            extraction_class: class.to_string(),
+            extraction_text: value.to_string(),
+            group_index: Some(group),
+            extraction_index: Some(index),
+            ..Default::default()
+        };
+        let document = AnnotatedDocument::with_extractions(
+            vec![
+                make("name", "Alice", 0, 0),
+                make("age", "30", 0, 1),
+                make("name", "Bob", 1, 2),
+                make("age", "25", 1, 3),
+            ],
+            text.to_string(),
+        );
+        let config = ExportConfig {
+            format: ExportFormat::Json,
+            group_extractions: true,
+            ..Default::default()
+        };
+
+        let result = export_document(&document, &config).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+
+        assert!(parsed.get("extractions").is_none());
+        let groups = parsed["groups"].as_array().unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0]["group_index"], 0);
+        assert_eq!(groups[0]["name"]["extraction_text"], "Alice");
+        assert_eq!(groups[0]["age"]["extraction_text"], "30");
+        assert_eq!(groups[1]["name"]["extraction_text"], "Bob");
+        assert_eq!(groups[1]["age"]["extraction_text"], "25");
+    }
+
     #[test]
     fn test_json_export() {
         let document = create_sample_document();
@@ -1425,6 +2287,73 @@ mod tests {
         assert_eq!(stats["unique_classes"], 3);
     }
 
+    #[test]
+    fn test_compact_json_export() {
+        let mut document = create_sample_document();
+        document.extractions.as_mut().unwrap()[1].alignment_status = None;
+        let verbose_config = ExportConfig {
+            format: ExportFormat::Json,
+            show_char_intervals: true,
+            include_text: false,
+            include_statistics: false,
+            ..Default::default()
+        };
+        let compact_config = ExportConfig { compact: true, ..verbose_config.clone() };
+
+        let verbose = export_document(&document, &verbose_config).unwrap();
+        let compact = export_document(&document, &compact_config).unwrap();
+        assert!(compact.len() < verbose.len());
+        assert!(!compact.contains("null") && !compact.contains('\n'));
+
+        let parsed: serde_json::Value = serde_json::from_str(&compact).unwrap();
+        let verbose: serde_json::Value = serde_json::from_str(&verbose).unwrap();
+        let compact_items = parsed["extractions"].as_array().unwrap();
+        let verbose_items = verbose["extractions"].as_array().unwrap();
+        assert_eq!(compact_items.len(), verbose_items.len());
+        for (compact, verbose) in compact_items.iter().zip(verbose_items) {
+            assert_eq!(compact["extraction_class"], verbose["extraction_class"]);
+            assert_eq!(compact["extraction_text"], verbose["extraction_text"]);
+            assert_eq!(compact.get("description").unwrap_or(&Value::Null), &verbose["description"]);
+            assert_eq!(compact["char_interval"]["start"], verbose["char_interval"]["start_char"]);
+            assert_eq!(compact["char_interval"]["end"], verbose["char_interval"]["end_char"]);
+        }
+        assert!(compact_items[1].get("description").is_none());
+        assert!(compact_items[1]["char_interval"].get("alignment_status").is_none());
+        assert_eq!(compact_items[0]["char_interval"]["alignment_status"], "MatchExact");
+    }
+
+    #[test]
+    fn test_per_class_alignment_counts() {
+        let mut document = create_sample_document();
+        let extractions = document.extractions.as_mut().unwrap();
+        let mut unaligned = Extraction::new("salary".to_string(), "fifty thousand".to_string());
+        unaligned.alignment_status = None;
+        extractions.push(unaligned);
+        let mut lesser = Extraction::new("person".to_string(), "Smith".to_string());
+        lesser.alignment_status = Some(AlignmentStatus::MatchLesser);
+        extractions.push(lesser);
+
+        let counts = per_class_alignment(extractions);
+        assert_eq!(counts["person"], (2, 0, 0));
+        assert_eq!(counts["company"], (1, 0, 0));
+        assert_eq!(counts["salary"], (0, 1, 1));
+
+        let json_config = ExportConfig { format: ExportFormat::Json, ..Default::default() };
+        let parsed: serde_json::Value = serde_json::from_str(&export_document(&document, &json_config).unwrap()).unwrap();
+        assert_eq!(
+            parsed["statistics"]["per_class_alignment"]["salary"],
+            serde_json::json!({"exact": 0, "fuzzy": 1, "none": 1})
+        );
+
+        let md_config = ExportConfig { format: ExportFormat::Markdown, ..Default::default() };
+        let markdown = export_document(&document, &md_config).unwrap();
+        assert!(markdown.contains("| salary | 0 | 1 | 1 |"));
+
+        let html_config = ExportConfig { format: ExportFormat::Html, ..Default::default() };
+        let html = export_document(&document, &html_config).unwrap();
+        assert!(html.contains("<tr><td>salary</td><td>0</td><td>1</td><td>1</td></tr>"));
+    }
+
     #[test]
     fn test_csv_export() {
         let document = create_sample_document();
@@ -1438,7 +2367,7 @@ mod tests {
         let lines: Vec<&str> = result.lines().collect();
 
         // Check header
-        assert_eq!(lines[0], "extraction_class,extraction_text,description,start_char,end_char,alignment_status,group_index");
+        assert_eq!(lines[0], "extraction_class,extraction_text,description,start_char,end_char,alignment_status,group_index,chunk_id");
 
         // Check data rows
         assert_eq!(lines.len(), 4); // Header + 3 data rows
@@ -1469,6 +2398,49 @@ mod tests {
         assert!(!result.contains("end_char"));
     }
 
+    #[test]
+    fn test_csv_export_attributes() {
+        let mut document = create_sample_document();
+        let extractions = document.extractions.as_mut().unwrap();
+        let attributes = extractions[0].attributes.as_mut().unwrap();
+        attributes.insert("roles".to_string(), json!(["engineer", "lead, \"platform\""]));
+        attributes.insert("age".to_string(), json!(42));
+        extractions[2].attributes.as_mut().unwrap().insert("currency".to_string(), json!("USD"));
+
+        let config = ExportConfig {
+            format: ExportFormat::Csv,
+            show_char_intervals: false,
+            attribute_separator: " | ".to_string(),
+            ..Default::default()
+        };
+        let result = export_document(&document, &config).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(
+            lines[0],
+            "extraction_class,extraction_text,description,group_index,attributes.age,attributes.currency,attributes.roles"
+        );
+        assert_eq!(lines[1], "person,John Smith,Person name,0,42,,\"engineer | lead, \"\"platform\"\"\"");
+        assert_eq!(lines[2], "company,TechCorp,,0,,,");
+        assert_eq!(lines[3], "salary,\"$50,000\",Annual salary,0,,USD,");
+
+        let config = ExportConfig {
+            csv_attributes: CsvAttributeMode::Json,
+            ..config
+        };
+        let result = export_document(&document, &config).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines[0], "extraction_class,extraction_text,description,group_index,attributes");
+        assert!(lines[1].ends_with(",\"{\"\"age\"\":42,\"\"roles\"\":[\"\"engineer\"\",\"\"lead, \\\"\"platform\\\"\"\"\"]}\""));
+        assert!(lines[2].ends_with(",0,"));
+
+        let config = ExportConfig {
+            csv_attributes: CsvAttributeMode::Omit,
+            ..config
+        };
+        let result = export_document(&document, &config).unwrap();
+        assert_eq!(result.lines().next().unwrap(), "extraction_class,extraction_text,description,group_index");
+    }
+
     #[test]
     fn test_csv_escape() {
         assert_eq!(csv_escape("simple"), "simple");
@@ -1505,6 +2477,9 @@ mod tests {
             document_id: Some("empty".to_string()),
             text: Some("".to_string()),
             extractions: None,
+            raw_outputs: None,
+            run_metadata: None,
+            offset_map: None,
         };
 
         let config = ExportConfig::default();
@@ -1519,6 +2494,9 @@ mod tests {
             document_id: Some("no_text".to_string()),
             text: None,
             extractions: None,
+            raw_outputs: None,
+            run_metadata: None,
+            offset_map: None,
         };
 
         let config = ExportConfig::default();
@@ -1547,7 +2525,7 @@ mod tests {
         let document = create_sample_document();
         let text = document.text.as_ref().unwrap();
 
-        let result = highlight_text_html(text, &document).unwrap();
+        let result = highlight_text_html(text, &document, OverlapPolicy::KeepFirst).unwrap();
 
         assert!(result.contains("extraction-highlight"));
         assert!(result.contains("data-class=\"person\""));
@@ -1568,6 +2546,9 @@ mod tests {
                 description: None,
                 attributes: Some(HashMap::new()),
                 token_interval: None,
+                source_pass: None,
+                chunk_id: None,
+                present: true,
             },
             Extraction {
                 extraction_class: "person".to_string(),
@@ -1579,6 +2560,9 @@ mod tests {
                 description: None,
                 attributes: Some(HashMap::new()),
                 token_interval: None,
+                source_pass: None,
+                chunk_id: None,
+                present: true,
             },
             Extraction {
                 extraction_class: "company".to_string(),
@@ -1590,6 +2574,9 @@ mod tests {
                 description: None,
                 attributes: Some(HashMap::new()),
                 token_interval: None,
+                source_pass: None,
+                chunk_id: None,
+                present: true,
             },
         ];
 
@@ -1616,6 +2603,7 @@ mod tests {
                 output_field: "requirements".to_string(),
                 filter: None,
                 depends_on: vec![],
+                temperature: None,
             },
             PipelineStep {
                 id: "s2".to_string(),
@@ -1626,6 +2614,7 @@ mod tests {
                 output_field: "values".to_string(),
                 filter: None,
                 depends_on: vec!["s1".to_string()],
+                temperature: None,
             },
         ];
 
@@ -1636,6 +2625,7 @@ mod tests {
             steps: steps.clone(),
             global_config: LibExtractConfig::default(),
             enable_parallel_execution: false,
+            max_parallel_steps: 4,
         };
 
         // Compute positions
@@ -1657,6 +2647,9 @@ mod tests {
                 description: None,
                 attributes: Some(HashMap::new()),
                 token_interval: None,
+                source_pass: None,
+                chunk_id: None,
+                present: true,
             }],
             processing_time_ms: 1,
             input_count: 1,
@@ -1678,6 +2671,9 @@ mod tests {
                     description: None,
                     attributes: Some(HashMap::new()),
                     token_interval: None,
+                    source_pass: None,
+                    chunk_id: None,
+                    present: true,
                 },
                 Extraction {
                     extraction_class: "unit".to_string(),
@@ -1689,6 +2685,9 @@ mod tests {
                     description: None,
                     attributes: Some(HashMap::new()),
                     token_interval: None,
+                    source_pass: None,
+                    chunk_id: None,
+                    present: true,
                 }
             ],
             processing_time_ms: 1,
@@ -1719,10 +2718,10 @@ mod tests {
         let text = "System uptime must be 99.9% for availability.";
 
         let steps = vec![
-            PipelineStep { id: "s1".to_string(), name: "Req".to_string(), description: "".to_string(), examples: vec![], prompt: "".to_string(), output_field: "req".to_string(), filter: None, depends_on: vec![] },
-            PipelineStep { id: "s2".to_string(), name: "Vals".to_string(), description: "".to_string(), examples: vec![], prompt: "".to_string(), output_field: "vals".to_string(), filter: None, depends_on: vec!["s1".to_string()] },
+            PipelineStep { id: "s1".to_string(), name: "Req".to_string(), description: "".to_string(), examples: vec![], prompt: "".to_string(), output_field: "req".to_string(), filter: None, depends_on: vec![], temperature: None },
+            PipelineStep { id: "s2".to_string(), name: "Vals".to_string(), description: "".to_string(), examples: vec![], prompt: "".to_string(), output_field: "vals".to_string(), filter: None, depends_on: vec!["s1".to_string()], temperature: None },
         ];
-        let cfg = PipelineConfig { name: "T".to_string(), description: "".to_string(), version: "0".to_string(), steps, global_config: LibExtractConfig::default(), enable_parallel_execution: false };
+        let cfg = PipelineConfig { name: "T".to_string(), description: "".to_string(), version: "0".to_string(), steps, global_config: LibExtractConfig::default(), enable_parallel_execution: false, max_parallel_steps: 4 };
 
         let step1_res = StepResult {
             step_id: "s1".to_string(),
@@ -1737,6 +2736,9 @@ mod tests {
                 description: None,
                 attributes: Some(HashMap::new()),
                 token_interval: None,
+                source_pass: None,
+                chunk_id: None,
+                present: true,
             }],
             processing_time_ms: 1,
             input_count: 1,
@@ -1757,6 +2759,9 @@ mod tests {
                 description: None,
                 attributes: Some(HashMap::new()),
                 token_interval: None,
+                source_pass: None,
+                chunk_id: None,
+                present: true,
             }],
             processing_time_ms: 1,
             input_count: 1,
@@ -1777,10 +2782,10 @@ mod tests {
         let text = "The system shall support 10 users concurrently.";
 
         let steps = vec![
-            PipelineStep { id: "s1".to_string(), name: "Req".to_string(), description: "".to_string(), examples: vec![], prompt: "".to_string(), output_field: "req".to_string(), filter: None, depends_on: vec![] },
-            PipelineStep { id: "s2".to_string(), name: "Vals".to_string(), description: "".to_string(), examples: vec![], prompt: "".to_string(), output_field: "vals".to_string(), filter: None, depends_on: vec!["s1".to_string()] },
+            PipelineStep { id: "s1".to_string(), name: "Req".to_string(), description: "".to_string(), examples: vec![], prompt: "".to_string(), output_field: "req".to_string(), filter: None, depends_on: vec![], temperature: None },
+            PipelineStep { id: "s2".to_string(), name: "Vals".to_string(), description: "".to_string(), examples: vec![], prompt: "".to_string(), output_field: "vals".to_string(), filter: None, depends_on: vec!["s1".to_string()], temperature: None },
         ];
-        let cfg = PipelineConfig { name: "T".to_string(), description: "".to_string(), version: "0".to_string(), steps, global_config: LibExtractConfig::default(), enable_parallel_execution: false };
+        let cfg = PipelineConfig { name: "T".to_string(), description: "".to_string(), version: "0".to_string(), steps, global_config: LibExtractConfig::default(), enable_parallel_execution: false, max_parallel_steps: 4 };
 
         let parent_start = 0usize;
         let parent_end = text.len();
@@ -1800,6 +2805,9 @@ mod tests {
                 description: None,
                 attributes: Some(HashMap::new()),
                 token_interval: None,
+                source_pass: None,
+                chunk_id: None,
+                present: true,
             }],
             processing_time_ms: 1,
             input_count: 1,
@@ -1821,6 +2829,9 @@ mod tests {
                     description: None,
                     attributes: Some(HashMap::new()),
                     token_interval: None,
+                    source_pass: None,
+                    chunk_id: None,
+                    present: true,
                 },
                 Extraction {
                     extraction_class: "phrase".to_string(),
@@ -1832,6 +2843,9 @@ mod tests {
                     description: None,
                     attributes: Some(HashMap::new()),
                     token_interval: None,
+                    source_pass: None,
+                    chunk_id: None,
+                    present: true,
                 },
             ],
             processing_time_ms: 1,
@@ -1849,4 +2863,205 @@ mod tests {
         assert!(html.contains("10"));
         assert!(html.contains("10 users"));
     }
+
+    #[test]
+    fn test_html_export_shows_attributes() {
+        let mut document = create_sample_document();
+        let person = &mut document.extractions.as_mut().unwrap()[0];
+        person.set_attribute("role".to_string(), serde_json::json!("CEO & founder"));
+        person.set_attribute("age".to_string(), serde_json::json!(52));
+        let config = ExportConfig {
+            format: ExportFormat::Html,
+            ..Default::default()
+        };
+
+        let result = export_document(&document, &config).unwrap();
+
+        assert!(result.contains(r#"<div class="extraction-meta">role: CEO &amp; founder</div>"#));
+        assert!(result.contains(r#"<div class="extraction-meta">age: 52</div>"#));
+        assert!(result.find("age: 52").unwrap() < result.find("role: CEO").unwrap());
+    }
+
+    #[test]
+    fn test_export_diff_html_marks_added_and_removed() {
+        let text = "John Smith works at Acme in Boston.";
+        let located = |class: &str, value: &str| {
+            let start = text.find(value).unwrap();
+            let mut extraction = Extraction::new(class.to_string(), value.to_string());
+            extraction.char_interval = Some(CharInterval::new(Some(start), Some(start + value.len())));
+            extraction
+        };
+        let old = AnnotatedDocument::with_extractions(
+            vec![located("person", "John Smith"), located("city", "Boston")],
+            text.to_string(),
+        );
+        let new = AnnotatedDocument::with_extractions(
+            vec![located("person", "John Smith"), located("company", "Acme")],
+            text.to_string(),
+        );
+
+        let html = export_diff_html(&old, &new, text).unwrap();
+        assert!(html.contains(r#"<span class="extraction-highlight added" data-class="company" data-text="Acme""#));
+        assert!(html.contains(r#"<span class="extraction-highlight removed" data-class="city" data-text="Boston""#));
+        assert!(html.contains(r#"<span class="extraction-highlight unchanged" data-class="person""#));
+        assert!(html.contains(r#"<div class="legend">"#));
+    }
+
+    fn overlapping_document() -> AnnotatedDocument {
+        let text = "Dr. John Smith arrived.";
+        let located = |class: &str, start: usize, end: usize| {
+            let mut extraction = Extraction::new(class.to_string(), text[start..end].to_string());
+            extraction.char_interval = Some(CharInterval::new(Some(start), Some(end)));
+            extraction
+        };
+        AnnotatedDocument::with_extractions(
+            vec![located("title", 0, 8), located("person", 4, 14)],
+            text.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_overlap_policy_keep_longest() {
+        let document = overlapping_document();
+        let text = document.text.as_deref().unwrap();
+
+        let first = highlight_text_html(text, &document, OverlapPolicy::KeepFirst).unwrap();
+        assert!(first.contains(r#"data-class="title""#));
+        assert!(!first.contains(r#"data-class="person""#));
+
+        let longest = highlight_text_html(text, &document, OverlapPolicy::KeepLongest).unwrap();
+        assert!(longest.contains(r#"data-text="John Smith">John Smith</span>"#));
+        assert!(!longest.contains(r#"data-class="title""#));
+        assert!(longest.starts_with("Dr. <span"));
+    }
+
+    #[test]
+    fn test_overlap_policy_keep_highest_confidence() {
+        let mut document = overlapping_document();
+        let extractions = document.extractions.as_mut().unwrap();
+        extractions[0].attributes = Some(HashMap::from([("confidence".to_string(), serde_json::json!(0.9))]));
+        extractions[1].attributes = Some(HashMap::from([("confidence".to_string(), serde_json::json!(0.4))]));
+        let text = document.text.clone().unwrap();
+
+        let html = highlight_text_html(&text, &document, OverlapPolicy::KeepHighestConfidence).unwrap();
+        assert!(html.contains(r#"data-class="title""#));
+        assert!(!html.contains(r#"data-class="person""#));
+    }
+
+    #[test]
+    fn test_overlap_policy_nest() {
+        let document = overlapping_document();
+        let text = document.text.as_deref().unwrap();
+
+        let html = highlight_text_html(text, &document, OverlapPolicy::Nest).unwrap();
+        let title = r#"<span class="extraction-highlight step-0" data-class="title" data-text="Dr. John" data-parent-class="">"#;
+        let person = r#"<span class="extraction-highlight step-0" data-class="person" data-text="John Smith" data-parent-class="">"#;
+        assert_eq!(
+            html,
+            format!("{title}Dr. {person}John</span></span>{person} Smith</span> arrived.")
+        );
+    }
+
+    #[test]
+    fn test_lazy_render_embeds_extractions_once() {
+        let text = "Zoë met Bob. Zoë left.";
+        let located = |class: &str, start: usize, end: usize| {
+            let mut extraction = Extraction::new(class.to_string(), text[start..end].to_string());
+            extraction.char_interval = Some(CharInterval::new(Some(start), Some(end)));
+            extraction
+        };
+        let document = AnnotatedDocument::with_extractions(
+            vec![located("person", 0, 4), located("person", 9, 12), located("person", 14, 18)],
+            text.to_string(),
+        );
+        let config = ExportConfig {
+            format: ExportFormat::Html,
+            lazy_render: true,
+            ..Default::default()
+        };
+
+        let html = export_document(&document, &config).unwrap();
+        assert_eq!(html.matches("Zoë met Bob").count(), 1);
+        assert!(!html.contains(r#"<span class="extraction-highlight""#));
+        assert!(!html.contains(r#"<div class="extraction-card">"#));
+
+        let start = html.find(r#"id="extraction-data">"#).unwrap() + r#"id="extraction-data">"#.len();
+        let end = start + html[start..].find("</script>").unwrap();
+        let data: Value = serde_json::from_str(&html[start..end]).unwrap();
+        let extractions = data["extractions"].as_array().unwrap();
+        assert_eq!(extractions.len(), 3);
+        assert_eq!(extractions[1]["text"], "Bob");
+        // UTF-16 offsets: "ë" is one code unit but two bytes
+        assert_eq!((extractions[1]["start"].as_u64(), extractions[1]["end"].as_u64()), (Some(8), Some(11)));
+        assert_eq!(extractions[2]["start"].as_u64(), Some(13));
+    }
+
+    #[test]
+    fn test_highlighting_with_intervals_inside_multibyte_characters() {
+        let text = "Café 🎉 in Zürich";
+        let located = |class: &str, value: &str, start: usize, end: usize| {
+            let mut extraction = Extraction::new(class.to_string(), value.to_string());
+            extraction.char_interval = Some(CharInterval::new(Some(start), Some(end)));
+            extraction
+        };
+        let zurich = text.find("Zürich").unwrap();
+        let document = AnnotatedDocument::with_extractions(
+            vec![
+                // "é" spans bytes 3..5 and "🎉" bytes 6..10; both intervals end mid-character
+                located("place", "Café", 0, 4),
+                located("emoji", "🎉", 7, 9),
+                located("city", "Zürich", zurich, zurich + 2),
+            ],
+            text.to_string(),
+        );
+        let export = |format: ExportFormat| {
+            let config = ExportConfig { format, show_char_intervals: true, ..Default::default() };
+            export_document(&document, &config).unwrap()
+        };
+
+        let markdown = export(ExportFormat::Markdown);
+        assert!(markdown.contains("**Café** **🎉** in **Zü**rich"));
+
+        let html = export(ExportFormat::Html);
+        assert!(html.contains(r#"data-text="Café">Café</span>"#));
+        assert!(html.contains(r#"data-text="🎉">🎉</span>"#));
+
+        let csv = export(ExportFormat::Csv);
+        assert!(csv.contains("place,Café,,0,5,"));
+        assert!(csv.contains("emoji,🎉,,6,10,"));
+        assert!(csv.contains(&format!("city,Zürich,,{},{},", zurich, zurich + 3)));
+    }
+
+    #[test]
+    fn test_table_mode_round_trips_rows_into_html_table() {
+        let config = crate::ExtractConfig {
+            debug: false,
+            table_mode: true,
+            ..Default::default()
+        };
+        let resolver = crate::resolver::Resolver::new(&config, false).unwrap();
+        let response = r#"{"invoice": "INV-7", "table": [
+            {"item": "Widget", "qty": "2"},
+            {"item": "Gadget", "qty": "5"}
+        ]}"#;
+
+        let (extractions, _) = resolver.validate_and_parse(response, &[]).unwrap();
+        let cells: Vec<_> = extractions.iter().filter(|e| e.group_index.is_some()).collect();
+        assert_eq!(cells.len(), 4);
+        let gadget = cells.iter().find(|e| e.extraction_text == "Gadget").unwrap();
+        assert_eq!(gadget.group_index, Some(1));
+        assert_eq!(gadget.attributes.as_ref().unwrap()["table_column"], serde_json::json!("item"));
+        assert!(extractions.iter().any(|e| e.extraction_class == "invoice" && e.group_index.is_none()));
+
+        let document = AnnotatedDocument::with_extractions(extractions, "Widget x2, Gadget x5".to_string());
+        let html = export_document(&document, &ExportConfig {
+            format: ExportFormat::Html,
+            ..Default::default()
+        })
+        .unwrap();
+        let table_start = html.find("🧮 Table").unwrap();
+        let table = &html[table_start..];
+        assert!(table.contains("<tr><th>item</th><th>qty</th></tr>"));
+        assert!(table.find("<tr><td>Widget</td><td>2</td></tr>").unwrap() < table.find("<tr><td>Gadget</td><td>5</td></tr>").unwrap());
+    }
 }